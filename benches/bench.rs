@@ -2,7 +2,10 @@ use std::fs;
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-use actson::{feeder::SliceJsonFeeder, JsonEvent, JsonParser};
+use actson::{
+    batch::EventBatch, feeder::SliceJsonFeeder, options::JsonParserOptionsBuilder, JsonEvent,
+    JsonParser,
+};
 
 fn make_large(json: &str) -> String {
     let mut large = String::from("{");
@@ -37,6 +40,27 @@ fn consume(json_bytes: &[u8]) {
     }
 }
 
+fn consume_batch(json_bytes: &[u8]) {
+    let feeder = SliceJsonFeeder::new(json_bytes);
+    let mut parser = JsonParser::new(feeder);
+    let mut batch = EventBatch::new(256);
+    while parser.drain_into(&mut batch, usize::MAX).unwrap().is_some() {
+        // values have already been copied, so just look at them once
+        for e in batch.drain() {
+            std::hint::black_box(e.value);
+        }
+    }
+}
+
+fn consume_structure(json_bytes: &[u8]) {
+    let feeder = SliceJsonFeeder::new(json_bytes);
+    let options = JsonParserOptionsBuilder::default()
+        .with_structure_only(true)
+        .build();
+    let mut parser = JsonParser::new_with_options(feeder, options);
+    while parser.next_event().unwrap().is_some() {}
+}
+
 fn actson_benchmark(c: &mut Criterion) {
     let json = fs::read_to_string("tests/fixtures/pass1.txt").unwrap();
     let json_bytes = json.as_bytes();
@@ -56,6 +80,18 @@ fn actson_benchmark(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("actson_batch", |b| {
+        b.iter(|| {
+            consume_batch(json_bytes);
+        })
+    });
+
+    c.bench_function("actson_batch_large", |b| {
+        b.iter(|| {
+            consume_batch(json_large_bytes);
+        })
+    });
+
     c.bench_function("actson_novalues", |b| {
         b.iter(|| {
             let feeder = SliceJsonFeeder::new(json_bytes);
@@ -72,6 +108,18 @@ fn actson_benchmark(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("actson_structure_only", |b| {
+        b.iter(|| {
+            consume_structure(json_bytes);
+        })
+    });
+
+    c.bench_function("actson_structure_only_large", |b| {
+        b.iter(|| {
+            consume_structure(json_large_bytes);
+        })
+    });
+
     #[cfg(feature = "serde_json")]
     c.bench_function("actson_serde", |b| {
         b.iter(|| {
@@ -0,0 +1,198 @@
+//! Incremental decoding of Base64-encoded string values (RFC 4648)
+//!
+//! See [`JsonParser::decode_base64_into()`](crate::JsonParser::decode_base64_into())
+use std::io::Write;
+
+use thiserror::Error;
+
+/// The Base64 alphabet a string value is encoded with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet with `+` and `/` (RFC 4648, Section 4). This is
+    /// the default.
+    #[default]
+    Standard,
+
+    /// The URL- and filename-safe alphabet with `-` and `_` (RFC 4648,
+    /// Section 5)
+    UrlSafe,
+}
+
+impl Base64Alphabet {
+    /// Get the 6-bit value of the given character or `None` if the character
+    /// does not belong to the alphabet
+    fn value(&self, c: u8) -> Option<u8> {
+        match (c, self) {
+            (b'A'..=b'Z', _) => Some(c - b'A'),
+            (b'a'..=b'z', _) => Some(c - b'a' + 26),
+            (b'0'..=b'9', _) => Some(c - b'0' + 52),
+            (b'+', Base64Alphabet::Standard) | (b'-', Base64Alphabet::UrlSafe) => Some(62),
+            (b'/', Base64Alphabet::Standard) | (b'_', Base64Alphabet::UrlSafe) => Some(63),
+            _ => None,
+        }
+    }
+}
+
+/// An error that can happen when decoding a Base64-encoded string value
+#[derive(Error, Debug)]
+pub enum Base64Error {
+    /// The string value contains a character that does not belong to the
+    /// selected alphabet (or padding in a place other than the end)
+    #[error("invalid Base64 character: `{0}'")]
+    InvalidCharacter(char),
+
+    /// The string value ends with an incomplete group of characters that
+    /// cannot be decoded
+    #[error("invalid Base64 length")]
+    InvalidLength,
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Decode the given Base64 characters and write the decoded bytes to the
+/// given writer.
+///
+/// If `last` is `false`, only complete groups of four characters are
+/// decoded. Incomplete groups and groups that contain padding are left
+/// over, because the next characters are needed to decide if they are
+/// valid. If `last` is `true`, all characters are decoded and padding is
+/// optional. Return the number of characters consumed and the number of
+/// bytes written.
+pub(crate) fn decode<W>(
+    input: &[u8],
+    alphabet: Base64Alphabet,
+    last: bool,
+    writer: &mut W,
+) -> Result<(usize, usize), Base64Error>
+where
+    W: Write + ?Sized,
+{
+    let mut data = input.len();
+    if last {
+        // strip up to two padding characters, but only if they complete a
+        // group of four
+        let padding = input.iter().rev().take(2).take_while(|c| **c == b'=');
+        let padding = padding.count();
+        if padding > 0 && input.len().is_multiple_of(4) {
+            data -= padding;
+        }
+        if data % 4 == 1 {
+            return Err(Base64Error::InvalidLength);
+        }
+    } else {
+        data -= data % 4;
+        if data > 0 && input[data - 1] == b'=' {
+            data -= 4;
+        }
+    }
+
+    let mut out = Vec::with_capacity(data / 4 * 3 + 2);
+    for (i, group) in input[..data].chunks(4).enumerate() {
+        let mut acc = 0u32;
+        for (j, &c) in group.iter().enumerate() {
+            let Some(v) = alphabet.value(c) else {
+                let character = String::from_utf8_lossy(&input[i * 4 + j..])
+                    .chars()
+                    .next()
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+                return Err(Base64Error::InvalidCharacter(character));
+            };
+            acc = acc << 6 | v as u32;
+        }
+
+        // a group of n characters carries n * 6 bits, i.e. n - 1 full bytes
+        acc <<= 6 * (4 - group.len());
+        out.extend_from_slice(&acc.to_be_bytes()[1..group.len()]);
+    }
+
+    writer.write_all(&out)?;
+    let consumed = if last { input.len() } else { data };
+    Ok((consumed, out.len()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base64::{decode, Base64Alphabet, Base64Error};
+
+    fn decode_all(input: &str, alphabet: Base64Alphabet) -> Result<Vec<u8>, Base64Error> {
+        let mut out = vec![];
+        decode(input.as_bytes(), alphabet, true, &mut out)?;
+        Ok(out)
+    }
+
+    /// Test the examples from RFC 4648, Section 10
+    #[test]
+    fn rfc4648() {
+        for (encoded, decoded) in [
+            ("", ""),
+            ("Zg==", "f"),
+            ("Zm8=", "fo"),
+            ("Zm9v", "foo"),
+            ("Zm9vYg==", "foob"),
+            ("Zm9vYmE=", "fooba"),
+            ("Zm9vYmFy", "foobar"),
+            ("Zm9vYg", "foob"),
+            ("Zm9vYmE", "fooba"),
+        ] {
+            assert_eq!(
+                decode_all(encoded, Base64Alphabet::Standard).unwrap(),
+                decoded.as_bytes()
+            );
+        }
+    }
+
+    /// Test that only the characters of the selected alphabet are accepted
+    #[test]
+    fn alphabets() {
+        assert_eq!(
+            decode_all("+/+/", Base64Alphabet::Standard).unwrap(),
+            [0xfb, 0xff, 0xbf]
+        );
+        assert_eq!(
+            decode_all("-_-_", Base64Alphabet::UrlSafe).unwrap(),
+            [0xfb, 0xff, 0xbf]
+        );
+        assert!(matches!(
+            decode_all("ab-_", Base64Alphabet::Standard),
+            Err(Base64Error::InvalidCharacter('-'))
+        ));
+        assert!(matches!(
+            decode_all("ab+/", Base64Alphabet::UrlSafe),
+            Err(Base64Error::InvalidCharacter('+'))
+        ));
+    }
+
+    /// Test that invalid input is rejected
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            decode_all("Zm9vY", Base64Alphabet::Standard),
+            Err(Base64Error::InvalidLength)
+        ));
+        assert!(matches!(
+            decode_all("Zg==Zg==", Base64Alphabet::Standard),
+            Err(Base64Error::InvalidCharacter('='))
+        ));
+        assert!(matches!(
+            decode_all("Zm9v\u{e4}", Base64Alphabet::Standard),
+            Err(Base64Error::InvalidCharacter('\u{e4}'))
+        ));
+    }
+
+    /// Test that incomplete groups and padding are left over if more input
+    /// may follow
+    #[test]
+    fn partial() {
+        let mut out = vec![];
+        assert_eq!(
+            decode(b"Zm9vYm", Base64Alphabet::Standard, false, &mut out).unwrap(),
+            (4, 3)
+        );
+        assert_eq!(
+            decode(b"Zm9vYg==", Base64Alphabet::Standard, false, &mut out).unwrap(),
+            (4, 3)
+        );
+        assert_eq!(out, b"foofoo");
+    }
+}
@@ -0,0 +1,108 @@
+//! Collect multiple events in one call for batch processing
+//!
+//! See [`JsonParser::drain_into()`](crate::JsonParser::drain_into())
+use std::collections::vec_deque::{Drain, Iter};
+use std::collections::VecDeque;
+
+use crate::JsonEvent;
+
+/// An event collected by [`JsonParser::drain_into()`](crate::JsonParser::drain_into())
+/// together with a copy of its value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchedEvent {
+    /// The event
+    pub event: JsonEvent,
+
+    /// The name of the field for [`JsonEvent::FieldName`], the string for
+    /// [`JsonEvent::ValueString`], the number exactly as it appeared in the
+    /// input for [`JsonEvent::ValueInt`] and [`JsonEvent::ValueFloat`], or
+    /// `None` for all other events
+    pub value: Option<Box<str>>,
+
+    /// The nesting depth of the event. Top-level values have a depth of 0.
+    /// Values, field names, and nested objects and arrays inside a top-level
+    /// object or array have a depth of 1, and so on.
+    /// [`JsonEvent::StartObject`], [`JsonEvent::EndObject`],
+    /// [`JsonEvent::StartArray`], and [`JsonEvent::EndArray`] have the depth
+    /// of the object or array they open or close (i.e. 1 for a top-level
+    /// object).
+    pub depth: usize,
+}
+
+/// A ring buffer with a fixed capacity that collects events for batch
+/// processing. Fill it with [`JsonParser::drain_into()`](crate::JsonParser::drain_into())
+/// and remove processed events with [`Self::pop()`] or [`Self::drain()`].
+/// The buffer never grows beyond its capacity, so it does not allocate after
+/// it has been created (except for the copied values).
+#[derive(Clone, Debug)]
+pub struct EventBatch {
+    events: VecDeque<BatchedEvent>,
+    capacity: usize,
+}
+
+impl EventBatch {
+    /// Create a new empty batch that can hold up to `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        EventBatch {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Return the maximum number of events the batch can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the number of events in the batch
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Return `true` if the batch does not contain any events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Return `true` if the batch cannot hold any more events
+    pub fn is_full(&self) -> bool {
+        self.events.len() >= self.capacity
+    }
+
+    /// Return an iterator over the events in the batch (oldest first)
+    /// without removing them
+    pub fn iter(&self) -> Iter<'_, BatchedEvent> {
+        self.events.iter()
+    }
+
+    /// Remove and return the oldest event in the batch
+    pub fn pop(&mut self) -> Option<BatchedEvent> {
+        self.events.pop_front()
+    }
+
+    /// Remove all events from the batch and return them (oldest first)
+    pub fn drain(&mut self) -> Drain<'_, BatchedEvent> {
+        self.events.drain(..)
+    }
+
+    /// Remove all events from the batch
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Add an event to the batch. The caller has to make sure the batch is
+    /// not full.
+    pub(crate) fn push(&mut self, event: BatchedEvent) {
+        debug_assert!(!self.is_full());
+        self.events.push_back(event);
+    }
+}
+
+impl<'a> IntoIterator for &'a EventBatch {
+    type Item = &'a BatchedEvent;
+    type IntoIter = Iter<'a, BatchedEvent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
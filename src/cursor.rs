@@ -0,0 +1,297 @@
+//! Navigate through a JSON text in memory without building a DOM
+//!
+//! See [`JsonCursor`]
+use thiserror::Error;
+
+use crate::event::JsonEventKind;
+use crate::feeder::SliceJsonFeeder;
+use crate::parser::{InvalidStringValueError, ParserError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while navigating with a [`JsonCursor`]
+#[derive(Error, Debug)]
+pub enum CursorError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+
+    /// [`JsonCursor::enter()`] was called but the current value is not an
+    /// object or an array (or the cursor is not positioned at a value)
+    #[error("the current value is not an object or an array")]
+    NotAContainer,
+
+    /// [`JsonCursor::enter()`] was called for an object or array that has
+    /// already been left with [`JsonCursor::exit()`]. Use
+    /// [`JsonCursor::reset()`] to navigate to it again.
+    #[error("the current object or array has already been left")]
+    ContainerLeft,
+
+    /// [`JsonCursor::exit()`] was called at the top level
+    #[error("the cursor is not inside an object or an array")]
+    NotInContainer,
+}
+
+/// The value a [`JsonCursor`] is positioned at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorValue<'c> {
+    /// An object. Use [`JsonCursor::enter()`] to navigate to its fields.
+    Object,
+
+    /// An array. Use [`JsonCursor::enter()`] to navigate to its elements.
+    Array,
+
+    /// A string
+    String(&'c str),
+
+    /// A number, exactly as it appeared in the JSON text
+    Number(&'c str),
+
+    /// `true` or `false`
+    Bool(bool),
+
+    /// `null`
+    Null,
+}
+
+/// A cursor that navigates through a JSON text in memory. It is built on the
+/// event stream of a [`JsonParser`] and offers a more DOM-like interface
+/// without building a DOM. The cursor is always positioned at a value (or
+/// behind the last value of an object or array). [`Self::enter()`] descends
+/// into the current object or array, [`Self::next_sibling()`] moves to the
+/// next value in the same container, and [`Self::exit()`] returns to the
+/// enclosing container. [`Self::value()`] and [`Self::key()`] return the
+/// current value and its field name.
+///
+/// The cursor only moves forward. Values that are passed over are skipped
+/// (but still validated) without being converted or kept in memory.
+/// Navigating backwards (e.g. to a previous sibling or into an object that
+/// has already been left) requires [`Self::reset()`], which re-scans the
+/// JSON text from the beginning. Each step backwards therefore costs time
+/// proportional to the size of the text before the target value. If you
+/// need to navigate back and forth a lot, build a DOM instead.
+///
+/// ```
+/// use actson::JsonCursor;
+/// use actson::cursor::CursorValue;
+///
+/// let json = r#"{"name": "Elvis", "albums": [{"title": "Elvis Is Back!", "year": 1960}], "alive": false}"#;
+/// let mut cursor = JsonCursor::new(json.as_bytes()).unwrap();
+///
+/// assert_eq!(cursor.value().unwrap(), Some(CursorValue::Object));
+/// assert!(cursor.enter().unwrap());
+/// assert_eq!(cursor.key(), Some("name"));
+/// assert_eq!(cursor.value().unwrap(), Some(CursorValue::String("Elvis")));
+///
+/// assert!(cursor.next_sibling().unwrap());
+/// assert_eq!(cursor.key(), Some("albums"));
+/// assert!(cursor.enter().unwrap());
+/// assert!(cursor.enter().unwrap());
+/// assert!(cursor.next_sibling().unwrap());
+/// assert_eq!(cursor.key(), Some("year"));
+/// assert_eq!(cursor.value().unwrap(), Some(CursorValue::Number("1960")));
+///
+/// // leave the album and the array of albums
+/// cursor.exit().unwrap();
+/// cursor.exit().unwrap();
+/// assert_eq!(cursor.key(), Some("albums"));
+///
+/// assert!(cursor.next_sibling().unwrap());
+/// assert_eq!(cursor.key(), Some("alive"));
+/// assert_eq!(cursor.value().unwrap(), Some(CursorValue::Bool(false)));
+/// assert!(!cursor.next_sibling().unwrap());
+/// ```
+pub struct JsonCursor<'a> {
+    json: &'a [u8],
+    parser: JsonParser<SliceJsonFeeder<'a>>,
+
+    /// The event that started the current value or `None` if the cursor is
+    /// behind the last value of an object or array (or behind the top-level
+    /// value)
+    current: Option<JsonEvent>,
+
+    /// `true` if the current value is an object or array that has already
+    /// been consumed completely (i.e. left with [`Self::exit()`])
+    consumed: bool,
+
+    /// The last event that ended an object or an array
+    last_end: JsonEvent,
+
+    /// The field name of the current value. The buffer is reused.
+    key: String,
+
+    /// `true` if the current value has a field name
+    has_key: bool,
+
+    /// The field names of the objects and arrays that have been entered
+    parent_keys: Vec<Option<String>>,
+}
+
+impl<'a> JsonCursor<'a> {
+    /// Create a new cursor for the given JSON text and position it at the
+    /// top-level value
+    pub fn new(json: &'a [u8]) -> Result<Self, CursorError> {
+        let mut cursor = JsonCursor {
+            json,
+            parser: JsonParser::new(SliceJsonFeeder::new(json)),
+            current: None,
+            consumed: false,
+            last_end: JsonEvent::NeedMoreInput,
+            key: String::new(),
+            has_key: false,
+            parent_keys: Vec::new(),
+        };
+        cursor.read_value()?;
+        Ok(cursor)
+    }
+
+    /// Position the cursor at the top-level value again. This re-scans the
+    /// JSON text from the beginning, so navigating to a value after a reset
+    /// takes time proportional to the size of the text before it.
+    pub fn reset(&mut self) -> Result<(), CursorError> {
+        self.parser = JsonParser::new(SliceJsonFeeder::new(self.json));
+        self.current = None;
+        self.consumed = false;
+        self.last_end = JsonEvent::NeedMoreInput;
+        self.has_key = false;
+        self.parent_keys.clear();
+        self.read_value()?;
+        Ok(())
+    }
+
+    /// Get the next event from the parser. Skipped values are consumed in
+    /// between.
+    fn read(&mut self) -> Result<Option<JsonEvent>, CursorError> {
+        loop {
+            match self.parser.next_event()? {
+                Some(JsonEvent::NeedMoreInput) => continue,
+                event => return Ok(event),
+            }
+        }
+    }
+
+    /// Read the next value (including its field name) after the current
+    /// value has been consumed. Return `false` if the enclosing container
+    /// (or the JSON text) has ended.
+    fn read_value(&mut self) -> Result<bool, CursorError> {
+        let mut event = self.read()?;
+        self.has_key = event == Some(JsonEvent::FieldName);
+        if self.has_key {
+            self.key.clear();
+            self.key.push_str(self.parser.current_str()?);
+            event = self.read()?;
+        }
+
+        self.consumed = false;
+        match event {
+            Some(e @ (JsonEvent::EndObject | JsonEvent::EndArray)) => {
+                self.last_end = e;
+                self.current = None;
+                Ok(false)
+            }
+            None => {
+                self.current = None;
+                Ok(false)
+            }
+            Some(e) => {
+                self.current = Some(e);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Descend into the current object or array and position the cursor at
+    /// its first value. Return `false` if the object or array is empty. In
+    /// this case, the cursor is positioned behind its (non-existent) last
+    /// value, so [`Self::exit()`] can be used to return to the enclosing
+    /// container.
+    pub fn enter(&mut self) -> Result<bool, CursorError> {
+        match self.current {
+            Some(JsonEvent::StartObject | JsonEvent::StartArray) if self.consumed => {
+                return Err(CursorError::ContainerLeft);
+            }
+            Some(JsonEvent::StartObject | JsonEvent::StartArray) => {}
+            _ => return Err(CursorError::NotAContainer),
+        }
+        self.parent_keys
+            .push(self.has_key.then(|| self.key.clone()));
+        self.read_value()
+    }
+
+    /// Move the cursor to the next value in the current object or array.
+    /// If the current value is an object or an array, it is skipped. Return
+    /// `false` if there are no more values. In this case, the cursor is
+    /// positioned behind the last value. At the top level, this method
+    /// moves behind the top-level value and returns `false`.
+    pub fn next_sibling(&mut self) -> Result<bool, CursorError> {
+        match self.current {
+            None => return Ok(false),
+            Some(JsonEvent::StartObject | JsonEvent::StartArray) if !self.consumed => {
+                self.parser.skip_value();
+            }
+            Some(_) => {}
+        }
+        self.read_value()
+    }
+
+    /// Skip the remaining values of the current object or array and position
+    /// the cursor at the object or array itself again. It cannot be entered
+    /// a second time, but [`Self::next_sibling()`] moves on to the value
+    /// after it.
+    pub fn exit(&mut self) -> Result<(), CursorError> {
+        let Some(key) = self.parent_keys.pop() else {
+            return Err(CursorError::NotInContainer);
+        };
+        while self.next_sibling()? {}
+
+        self.current = Some(if self.last_end == JsonEvent::EndObject {
+            JsonEvent::StartObject
+        } else {
+            JsonEvent::StartArray
+        });
+        self.consumed = true;
+        self.has_key = key.is_some();
+        if let Some(key) = key {
+            self.key = key;
+        }
+        Ok(())
+    }
+
+    /// Return the number of objects and arrays the cursor has entered
+    pub fn depth(&self) -> usize {
+        self.parent_keys.len()
+    }
+
+    /// Return the kind of the current value or `None` if the cursor is
+    /// behind the last value of an object or array
+    pub fn kind(&self) -> Option<JsonEventKind> {
+        self.current.and_then(JsonEventKind::of)
+    }
+
+    /// Return the field name of the current value or `None` if the value is
+    /// not inside an object (or if the cursor is behind the last value)
+    pub fn key(&self) -> Option<&str> {
+        (self.has_key && self.current.is_some()).then_some(self.key.as_str())
+    }
+
+    /// Return the current value or `None` if the cursor is behind the last
+    /// value of an object or array. Strings and numbers are borrowed from the
+    /// parser, so they are not copied.
+    pub fn value(&self) -> Result<Option<CursorValue<'_>>, CursorError> {
+        let Some(current) = self.current else {
+            return Ok(None);
+        };
+        Ok(Some(match current {
+            JsonEvent::StartObject => CursorValue::Object,
+            JsonEvent::StartArray => CursorValue::Array,
+            JsonEvent::ValueString => CursorValue::String(self.parser.current_str()?),
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                CursorValue::Number(self.parser.current_raw_number()?)
+            }
+            JsonEvent::ValueTrue => CursorValue::Bool(true),
+            JsonEvent::ValueFalse => CursorValue::Bool(false),
+            _ => CursorValue::Null,
+        }))
+    }
+}
@@ -1,5 +1,10 @@
 /// All possible JSON events returned by [`JsonParser::next_event()`](crate::JsonParser::next_event())
+///
+/// New events may be added in future versions (e.g. for new parser options),
+/// so matches on this enum need a wildcard arm.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum JsonEvent {
     /// The JSON parser needs more input before the next event can be returned.
     /// Invoke the parser's feeder to give it more input.
@@ -41,4 +46,90 @@ pub enum JsonEvent {
 
     /// A `null` value.
     ValueNull = 11,
+
+    /// A value (an object or an array) that has been skipped because it
+    /// would have exceeded the maximum stack depth. This event is only
+    /// generated if [`JsonParserOptionsBuilder::with_skip_beyond_max_depth()`](crate::options::JsonParserOptionsBuilder::with_skip_beyond_max_depth())
+    /// or [`JsonParserOptionsBuilder::with_clamp_depth()`](crate::options::JsonParserOptionsBuilder::with_clamp_depth())
+    /// is enabled. It replaces all events of the skipped value.
+    TruncatedValue = 12,
+
+    /// A string, number, boolean, or `null` value whose type and contents
+    /// are not reported. This event is only generated if
+    /// [`JsonParserOptionsBuilder::with_structure_only()`](crate::options::JsonParserOptionsBuilder::with_structure_only())
+    /// is enabled. It replaces [`Self::ValueString`], [`Self::ValueInt`],
+    /// [`Self::ValueFloat`], [`Self::ValueTrue`], [`Self::ValueFalse`], and
+    /// [`Self::ValueNull`].
+    Scalar = 13,
+}
+
+/// The kind of a JSON value. Used to describe which events a value may
+/// consist of (see [`check_top_level_types()`](crate::check_top_level_types())).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsonEventKind {
+    /// An object ([`JsonEvent::StartObject`])
+    Object,
+
+    /// An array ([`JsonEvent::StartArray`])
+    Array,
+
+    /// A string ([`JsonEvent::ValueString`])
+    String,
+
+    /// An integer or a floating point number ([`JsonEvent::ValueInt`] or
+    /// [`JsonEvent::ValueFloat`])
+    Number,
+
+    /// An integer ([`JsonEvent::ValueInt`])
+    Integer,
+
+    /// `true` or `false` ([`JsonEvent::ValueTrue`] or
+    /// [`JsonEvent::ValueFalse`])
+    Boolean,
+
+    /// `null` ([`JsonEvent::ValueNull`])
+    Null,
+}
+
+impl JsonEventKind {
+    /// Get the kind of the value that starts with the given event or `None`
+    /// if the event does not start a value. Integers are reported as
+    /// [`JsonEventKind::Integer`] and floating point numbers as
+    /// [`JsonEventKind::Number`].
+    pub fn of(event: JsonEvent) -> Option<Self> {
+        match event {
+            JsonEvent::StartObject => Some(JsonEventKind::Object),
+            JsonEvent::StartArray => Some(JsonEventKind::Array),
+            JsonEvent::ValueString => Some(JsonEventKind::String),
+            JsonEvent::ValueInt => Some(JsonEventKind::Integer),
+            JsonEvent::ValueFloat => Some(JsonEventKind::Number),
+            JsonEvent::ValueTrue | JsonEvent::ValueFalse => Some(JsonEventKind::Boolean),
+            JsonEvent::ValueNull => Some(JsonEventKind::Null),
+            JsonEvent::NeedMoreInput
+            | JsonEvent::EndObject
+            | JsonEvent::EndArray
+            | JsonEvent::FieldName
+            | JsonEvent::TruncatedValue
+            | JsonEvent::Scalar => None,
+        }
+    }
+
+    /// Check if a value that starts with the given event is of this kind
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonEventKind};
+    ///
+    /// assert!(JsonEventKind::Number.matches(JsonEvent::ValueInt));
+    /// assert!(JsonEventKind::Number.matches(JsonEvent::ValueFloat));
+    /// assert!(!JsonEventKind::Integer.matches(JsonEvent::ValueFloat));
+    /// assert!(!JsonEventKind::Object.matches(JsonEvent::EndObject));
+    /// ```
+    pub fn matches(&self, event: JsonEvent) -> bool {
+        match (self, JsonEventKind::of(event)) {
+            (JsonEventKind::Number, Some(JsonEventKind::Integer)) => true,
+            (k, Some(found)) => *k == found,
+            (_, None) => false,
+        }
+    }
 }
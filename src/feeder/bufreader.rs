@@ -1,12 +1,65 @@
-use std::io::{BufRead, BufReader, Read};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
 
-use super::{FillError, JsonFeeder};
+use crate::parser::{CollectKeysError, Fold, FoldError, NextEventError, Scalar};
+use crate::{JsonEvent, JsonParser};
+
+use super::{FeederError, JsonFeeder};
 
 /// A [`JsonFeeder`] that reads from a [`BufReader`].
+#[derive(Debug)]
 pub struct BufReaderJsonFeeder<T> {
     reader: BufReader<T>,
     filled: bool,
     pos: usize,
+    len: usize,
+    consumed: usize,
+    max_bytes: Option<usize>,
+    total_len: Option<usize>,
+}
+
+/// A [`BufReaderJsonFeeder`] that reads from a boxed trait object instead of
+/// a concrete reader type. Use it if the reader is only chosen at runtime
+/// (e.g. standard input, a file, or a decompressor, depending on the command
+/// line arguments), or if feeders for different readers should be stored
+/// under a single type. Since it is a [`BufReaderJsonFeeder`], it provides
+/// the same methods (e.g. [`fill_buf()`](BufReaderJsonFeeder::fill_buf()))
+/// and reports errors the same way. Each read goes through dynamic dispatch,
+/// but the [`BufReader`] ensures that this only happens once per buffer.
+///
+/// ```
+/// use std::io::{BufReader, Read};
+/// use actson::JsonParser;
+/// use actson::feeder::DynReadJsonFeeder;
+///
+/// let use_stdin = false;
+/// let reader: Box<dyn Read> = if use_stdin {
+///     Box::new(std::io::stdin())
+/// } else {
+///     Box::new(&br#"{"name": "Elvis"}"#[..])
+/// };
+///
+/// let mut parser = JsonParser::new(DynReadJsonFeeder::new(BufReader::new(reader)));
+/// let mut events = 0;
+/// while let Some(event) = parser.next_event_blocking().unwrap() {
+///     events += 1;
+/// }
+/// assert_eq!(events, 4);
+/// ```
+pub type DynReadJsonFeeder<'a> = BufReaderJsonFeeder<Box<dyn Read + 'a>>;
+
+impl<'a> DynReadJsonFeeder<'a> {
+    /// Box the given reader and create a new feeder that reads from it
+    /// through a [`BufReader`] with default capacity. If you already have a
+    /// `Box<dyn Read>`, pass it to [`BufReaderJsonFeeder::new()`] instead to
+    /// avoid boxing it twice.
+    pub fn new_dyn<R>(reader: R) -> Self
+    where
+        R: Read + 'a,
+    {
+        BufReaderJsonFeeder::new(BufReader::new(Box::new(reader)))
+    }
 }
 
 impl<T> BufReaderJsonFeeder<T>
@@ -19,15 +72,73 @@ where
             reader,
             filled: false,
             pos: 0,
+            len: 0,
+            consumed: 0,
+            max_bytes: None,
+            total_len: None,
         }
     }
 
+    /// Create a new feeder that reads from the given [`BufReader`] but
+    /// refuses to read more than `max_bytes` bytes in total. If the input is
+    /// larger, [`fill_buf()`](Self::fill_buf()) returns
+    /// [`FeederError::InputTooLarge`].
+    ///
+    /// This limit is enforced on the I/O side: the feeder stops reading from
+    /// the underlying reader as soon as the limit would be exceeded, which is
+    /// useful if you want to stop receiving data (e.g. from a socket)
+    /// altogether. The parser itself is not aware of the limit.
+    pub fn new_with_max_bytes(reader: BufReader<T>, max_bytes: usize) -> Self {
+        BufReaderJsonFeeder {
+            max_bytes: Some(max_bytes),
+            ..Self::new(reader)
+        }
+    }
+
+    /// Specify the total length of the input in bytes (e.g. the size of the
+    /// file the reader reads from), so the parser can report its
+    /// [`progress()`](JsonParser::progress()). The feeder cannot determine
+    /// the length itself because the reader might be a stream.
+    pub fn with_total_len(mut self, total_len: usize) -> Self {
+        self.total_len = Some(total_len);
+        self
+    }
+
+    /// Consume the feeder and return the underlying reader together with all
+    /// bytes that have already been read into the buffer but that have not
+    /// been consumed by the parser yet. This allows you to continue reading
+    /// from the reader after the JSON text (e.g. if the JSON text is followed
+    /// by other data).
+    ///
+    /// Note that the parser may have to consume one byte after the end of a
+    /// top-level number to detect where the number ends. This byte is not
+    /// part of the returned bytes.
+    pub fn into_remainder(self) -> (T, Vec<u8>) {
+        let rest = self
+            .reader
+            .buffer()
+            .get(self.pos..)
+            .unwrap_or_default()
+            .to_vec();
+        (self.reader.into_inner(), rest)
+    }
+
     /// Fill the feeder's internal buffer
-    pub fn fill_buf(&mut self) -> Result<(), FillError> {
+    pub fn fill_buf(&mut self) -> Result<(), FeederError> {
         self.reader.consume(self.pos);
-        self.reader.fill_buf()?;
-        self.filled = true;
+        self.consumed += self.pos;
         self.pos = 0;
+        self.len = 0;
+
+        let buf = self.reader.fill_buf()?;
+        if let Some(max_bytes) = self.max_bytes {
+            if self.consumed + buf.len() > max_bytes {
+                return Err(FeederError::InputTooLarge(max_bytes));
+            }
+        }
+
+        self.len = buf.len();
+        self.filled = true;
         Ok(())
     }
 }
@@ -37,7 +148,7 @@ where
     T: Read,
 {
     fn has_input(&self) -> bool {
-        self.pos < self.reader.buffer().len()
+        self.pos < self.len
     }
 
     fn is_done(&self) -> bool {
@@ -45,13 +156,153 @@ where
     }
 
     fn next_input(&mut self) -> Option<u8> {
-        let buf = self.reader.buffer();
-        if self.pos < buf.len() {
-            let r = Some(buf[self.pos]);
+        if self.pos < self.len {
+            let r = Some(self.reader.buffer()[self.pos]);
             self.pos += 1;
             r
         } else {
             None
         }
     }
+
+    fn total_len(&self) -> Option<usize> {
+        self.total_len
+    }
+
+    fn peek(&self) -> &[u8] {
+        self.reader
+            .buffer()
+            .get(self.pos..self.len)
+            .unwrap_or_default()
+    }
+
+    fn consume(&mut self, n: usize) {
+        debug_assert!(n <= self.len - self.pos);
+        self.pos += n;
+    }
+}
+
+impl<T> JsonParser<BufReaderJsonFeeder<T>>
+where
+    T: Read,
+{
+    /// Proceed parsing and return the next event that is not
+    /// [`JsonEvent::NeedMoreInput`]. This
+    /// method reads from the feeder's reader whenever the parser needs more
+    /// input (see [`JsonParser::next_event_blocking_on()`]).
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::BufReaderJsonFeeder;
+    ///
+    /// let json = r#"[1, 2, 3]"#.as_bytes();
+    /// let feeder = BufReaderJsonFeeder::new(BufReader::with_capacity(2, json));
+    /// let mut parser = JsonParser::new(feeder);
+    ///
+    /// let mut sum = 0;
+    /// while let Some(event) = parser.next_event_blocking().unwrap() {
+    ///     if event == JsonEvent::ValueInt {
+    ///         sum += parser.current_int::<i64>().unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn next_event_blocking(&mut self) -> Result<Option<JsonEvent>, NextEventError> {
+        self.next_event_blocking_on(|feeder| Ok(feeder.fill_buf()?))
+    }
+
+    /// Collect the field names of the object that has just started and skip
+    /// their values (see [`JsonParser::collect_keys_on()`]). This method
+    /// reads from the feeder's reader whenever the parser needs more input.
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::BufReaderJsonFeeder;
+    ///
+    /// let json = r#"[{"a": 1, "b": {"c": 2}}, {"d": []}]"#.as_bytes();
+    /// let feeder = BufReaderJsonFeeder::new(BufReader::with_capacity(4, json));
+    /// let mut parser = JsonParser::new(feeder);
+    ///
+    /// let mut keys = Vec::new();
+    /// while let Some(event) = parser.next_event_blocking().unwrap() {
+    ///     if event == JsonEvent::StartObject {
+    ///         keys.push(parser.collect_keys().unwrap());
+    ///     }
+    /// }
+    /// assert_eq!(keys, [vec!["a", "b"], vec!["d"]]);
+    /// ```
+    pub fn collect_keys(&mut self) -> Result<Vec<String>, CollectKeysError> {
+        self.collect_keys_on(|feeder| Ok(feeder.fill_buf()?))
+    }
+
+    /// Call the given closure with each number in the JSON text and
+    /// accumulate the results (see [`JsonParser::fold_numbers()`]). In
+    /// contrast to [`JsonParser::fold_numbers()`], this method reads from
+    /// the feeder's reader until the end of the JSON text has been reached.
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use actson::JsonParser;
+    /// use actson::feeder::BufReaderJsonFeeder;
+    /// use actson::parser::Scalar;
+    ///
+    /// let json = r#"{"values": [1, 2, 3.5], "other": {"n": -1}}"#.as_bytes();
+    /// let feeder = BufReaderJsonFeeder::new(BufReader::new(json));
+    /// let mut parser = JsonParser::new(feeder);
+    /// let sum = parser
+    ///     .fold_all_numbers(0.0, |acc, n| match n {
+    ///         Scalar::Int(i) => acc + i as f64,
+    ///         Scalar::Float(f) => acc + f,
+    ///         _ => acc,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(sum, 5.5);
+    /// ```
+    pub fn fold_all_numbers<A, F>(&mut self, init: A, mut f: F) -> Result<A, FoldError>
+    where
+        F: FnMut(A, Scalar<'_>) -> A,
+    {
+        let mut acc = init;
+        loop {
+            match self.fold_numbers(acc, &mut f)? {
+                Fold::NeedMoreInput(a) => {
+                    acc = a;
+                    self.feeder.fill_buf()?;
+                }
+                Fold::Done(a) => return Ok(a),
+            }
+        }
+    }
+}
+
+impl JsonParser<BufReaderJsonFeeder<File>> {
+    /// Open the file at the given path and create a parser that reads from
+    /// it through a [`BufReader`] with default capacity and default options.
+    /// If you need to configure the reader, the feeder, or the parser,
+    /// create them manually instead.
+    ///
+    /// The feeder knows the size of the file, so the parser can report its
+    /// [`progress()`](JsonParser::progress()).
+    ///
+    /// ```no_run
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let mut parser = JsonParser::from_path("data.json").unwrap();
+    /// loop {
+    ///     match parser.next_event().unwrap() {
+    ///         Some(JsonEvent::NeedMoreInput) => parser.feeder.fill_buf().unwrap(),
+    ///         Some(_) => {}
+    ///         None => break,
+    ///     }
+    /// }
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(JsonParser::new(
+            BufReaderJsonFeeder::new(BufReader::new(file)).with_total_len(len as usize),
+        ))
+    }
 }
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use ::bytes::{Buf, Bytes};
+
+use super::JsonFeeder;
+
+/// A push-based feeder that holds on to [`Bytes`] chunks instead of copying
+/// them into an internal buffer. This is useful if the input is already
+/// available as [`Bytes`] (or [`BytesMut`](::bytes::BytesMut)), e.g. when
+/// receiving a request body with Tokio or Hyper. Since [`Bytes`] is
+/// reference-counted, pushing a chunk is cheap and does not copy any data.
+///
+/// In contrast to [`PushJsonFeeder`](super::PushJsonFeeder), this feeder has
+/// no fixed capacity. Call [`JsonParser::next_event()`](crate::JsonParser::next_event())
+/// until it returns [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput)
+/// before pushing the next chunk to keep memory usage low. Call
+/// [`done()`](Self::done()) to indicate the end of the JSON text.
+///
+/// This feeder is only available if the `bytes` feature is enabled.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::BytesJsonFeeder;
+/// use bytes::Bytes;
+///
+/// let chunks = [Bytes::from_static(b"{\"name\": \"El"), Bytes::from_static(b"vis\"}")];
+///
+/// let mut parser = JsonParser::new(BytesJsonFeeder::new());
+/// let mut chunks = chunks.into_iter();
+/// while let Some(event) = parser.next_event().unwrap() {
+///     match event {
+///         JsonEvent::NeedMoreInput => match chunks.next() {
+///             Some(chunk) => parser.feeder.push(chunk),
+///             None => parser.feeder.done(),
+///         },
+///         JsonEvent::ValueString => assert_eq!(parser.current_str().unwrap(), "Elvis"),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BytesJsonFeeder {
+    chunks: VecDeque<Bytes>,
+    done: bool,
+}
+
+impl BytesJsonFeeder {
+    /// Create a new feeder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Provide another chunk of input to the [`JsonParser`](crate::JsonParser).
+    /// The chunk is not copied. Empty chunks are ignored.
+    pub fn push(&mut self, chunk: impl Into<Bytes>) {
+        let chunk = chunk.into();
+        if !chunk.is_empty() {
+            self.chunks.push_back(chunk);
+        }
+    }
+
+    /// Return the number of bytes that have been pushed but that have not
+    /// been consumed by the parser yet
+    pub fn buffered(&self) -> usize {
+        self.chunks.iter().map(Bytes::len).sum()
+    }
+
+    /// Call this method to indicate that the end of the JSON text has been
+    /// reached and that there is no more input to parse.
+    pub fn done(&mut self) {
+        self.done = true;
+    }
+}
+
+impl JsonFeeder for BytesJsonFeeder {
+    fn has_input(&self) -> bool {
+        !self.chunks.is_empty()
+    }
+
+    fn is_done(&self) -> bool {
+        self.done && !self.has_input()
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        let chunk = self.chunks.front_mut()?;
+        let b = chunk.get_u8();
+        if chunk.is_empty() {
+            self.chunks.pop_front();
+        }
+        Some(b)
+    }
+
+    /// Return the rest of the first chunk that has not been consumed yet
+    fn peek(&self) -> &[u8] {
+        self.chunks.front().map(|c| &c[..]).unwrap_or_default()
+    }
+
+    fn consume(&mut self, n: usize) {
+        if let Some(chunk) = self.chunks.front_mut() {
+            chunk.advance(n);
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::{ErrorKind, Read};
+
+use super::{FeederError, JsonFeeder};
+
+/// The default size of the internal buffer
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// A [`JsonFeeder`] that reads a stream of length-prefixed frames from a
+/// [`Read`]er. Each frame consists of a 4-byte big-endian length followed by
+/// exactly that many bytes containing a JSON text.
+///
+/// Call [`next_frame()`](Self::next_frame()) to start reading a frame. Then,
+/// parse the frame's JSON text with a new [`JsonParser`](crate::JsonParser)
+/// and call [`fill_buf()`](Self::fill_buf()) whenever the parser needs more
+/// input. The feeder never provides bytes beyond the end of the current
+/// frame. Bytes of the current frame that have not been parsed yet are
+/// discarded when the next frame is started.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::LengthPrefixedJsonFeeder;
+///
+/// let mut input = Vec::new();
+/// for json in [&b"[1, 2]"[..], &b"{\"a\": true}"[..]] {
+///     input.extend((json.len() as u32).to_be_bytes());
+///     input.extend(json);
+/// }
+///
+/// let mut feeder = LengthPrefixedJsonFeeder::new(input.as_slice());
+/// let mut frames = 0;
+/// while feeder.next_frame().unwrap() {
+///     let mut parser = JsonParser::new(&mut feeder);
+///     while let Some(event) = parser.next_event().unwrap() {
+///         if event == JsonEvent::NeedMoreInput {
+///             parser.feeder.fill_buf().unwrap();
+///         }
+///     }
+///     frames += 1;
+/// }
+/// assert_eq!(frames, 2);
+/// ```
+#[derive(Debug)]
+pub struct LengthPrefixedJsonFeeder<R> {
+    reader: R,
+
+    /// Buffer containing bytes of the current frame
+    buf: Box<[u8]>,
+
+    /// The position of the next byte to return from [`Self::buf`]
+    pos: usize,
+
+    /// The number of valid bytes in [`Self::buf`]
+    len: usize,
+
+    /// The number of bytes of the current frame that have not been read from
+    /// the reader yet
+    remaining: usize,
+
+    /// `true` if a frame has been started with [`Self::next_frame()`]
+    in_frame: bool,
+}
+
+impl<R> LengthPrefixedJsonFeeder<R>
+where
+    R: Read,
+{
+    /// Create a new feeder that reads frames from the given reader
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, reader)
+    }
+
+    /// Create a new feeder that reads frames from the given reader and uses
+    /// an internal buffer with the given capacity
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        LengthPrefixedJsonFeeder {
+            reader,
+            buf: vec![0; capacity.max(1)].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            remaining: 0,
+            in_frame: false,
+        }
+    }
+
+    /// Read the length prefix of the next frame. Bytes of the current frame
+    /// that have not been consumed yet are skipped. Returns `false` if the
+    /// reader is at its end and there are no more frames, or
+    /// [`FeederError::TruncatedFrame`] if the input ends inside the length
+    /// prefix.
+    pub fn next_frame(&mut self) -> Result<bool, FeederError> {
+        // skip the rest of the current frame
+        self.pos = 0;
+        self.len = 0;
+        while self.remaining > 0 {
+            self.read_chunk()?;
+            self.len = 0;
+        }
+        self.in_frame = false;
+
+        let mut prefix = [0u8; 4];
+        let mut n = 0;
+        while n < prefix.len() {
+            match self.reader.read(&mut prefix[n..]) {
+                Ok(0) if n == 0 => return Ok(false),
+                Ok(0) => return Err(FeederError::TruncatedFrame),
+                Ok(m) => n += m,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        self.remaining = u32::from_be_bytes(prefix) as usize;
+        self.in_frame = true;
+        Ok(true)
+    }
+
+    /// Fill the feeder's internal buffer with more bytes of the current
+    /// frame. Returns [`FeederError::TruncatedFrame`] if the input ends before
+    /// the end of the frame.
+    pub fn fill_buf(&mut self) -> Result<(), FeederError> {
+        if self.pos < self.len || self.remaining == 0 {
+            return Ok(());
+        }
+        self.read_chunk()
+    }
+
+    /// Read the next chunk of the current frame into the internal buffer
+    fn read_chunk(&mut self) -> Result<(), FeederError> {
+        let n = self.remaining.min(self.buf.len());
+        loop {
+            match self.reader.read(&mut self.buf[..n]) {
+                Ok(0) => return Err(FeederError::TruncatedFrame),
+                Ok(m) => {
+                    self.pos = 0;
+                    self.len = m;
+                    self.remaining -= m;
+                    return Ok(());
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl<R> JsonFeeder for LengthPrefixedJsonFeeder<R>
+where
+    R: Read,
+{
+    fn has_input(&self) -> bool {
+        self.pos < self.len
+    }
+
+    fn is_done(&self) -> bool {
+        self.in_frame && self.remaining == 0 && !self.has_input()
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        if self.pos < self.len {
+            let r = Some(self.buf[self.pos]);
+            self.pos += 1;
+            r
+        } else {
+            None
+        }
+    }
+
+    fn peek(&self) -> &[u8] {
+        &self.buf[self.pos..self.len]
+    }
+
+    fn consume(&mut self, n: usize) {
+        debug_assert!(n <= self.len - self.pos);
+        self.pos += n;
+    }
+}
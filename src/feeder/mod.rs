@@ -1,19 +1,61 @@
 mod bufreader;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod lengthprefixed;
 mod push;
 mod slice;
+mod transcode;
+mod vecdeque;
 
-pub use bufreader::BufReaderJsonFeeder;
+pub use bufreader::{BufReaderJsonFeeder, DynReadJsonFeeder};
+#[cfg(feature = "bytes")]
+pub use bytes::BytesJsonFeeder;
+pub use lengthprefixed::LengthPrefixedJsonFeeder;
 pub use push::{PushError, PushJsonFeeder};
-pub use slice::SliceJsonFeeder;
+pub use slice::{Events, IntoEvents, SliceJsonFeeder};
+pub use transcode::{Encoding, TranscodeError, TranscodingJsonFeeder};
+pub use vecdeque::VecDequeJsonFeeder;
 
 use thiserror::Error;
 
+/// An error that can happen if a feeder is unable to provide more input.
+/// In contrast to [`ParserError`](crate::parser::ParserError), this error
+/// does not mean that the input is invalid JSON but that the input could not
+/// be read.
 #[derive(Error, Debug)]
-pub enum FillError {
+pub enum FeederError {
     #[error("{0}")]
     Io(#[from] std::io::Error),
+
+    /// The feeder refused to read more input because the input exceeds the
+    /// configured maximum number of bytes
+    #[error("input exceeds the maximum size of {0} bytes")]
+    InputTooLarge(usize),
+
+    /// The input ended in the middle of a length-prefixed frame (see
+    /// [`LengthPrefixedJsonFeeder`])
+    #[error("input ended in the middle of a frame")]
+    TruncatedFrame,
+
+    /// The input could not be transcoded to UTF-8 (see
+    /// [`TranscodingJsonFeeder`])
+    #[error("{0}")]
+    Transcode(#[from] TranscodeError),
+
+    /// One of several chained readers could not be read. The error contains
+    /// the zero-based index of the reader and the underlying I/O error.
+    #[error("unable to read from reader {index}: {source}")]
+    Reader {
+        index: usize,
+        #[source]
+        source: std::io::Error,
+    },
 }
 
+/// The old name of [`FeederError`]
+#[deprecated(note = "use `FeederError` instead")]
+pub type FillError = FeederError;
+
 /// A feeder can be used to provide more input data to the
 /// [`JsonParser`](crate::JsonParser).
 pub trait JsonFeeder {
@@ -25,4 +67,96 @@ pub trait JsonFeeder {
 
     /// Decode and return the next character to be parsed
     fn next_input(&mut self) -> Option<u8>;
+
+    /// Check if the feeder is unable to provide more input because of an
+    /// error. The parser calls this method whenever the feeder does not have
+    /// more input and returns [`ParserError::Feeder`](crate::parser::ParserError::Feeder)
+    /// if the feeder reports an error. The default implementation never
+    /// reports an error. Feeders that are filled by the caller (e.g.
+    /// [`BufReaderJsonFeeder`]) return I/O errors from their `fill_buf()`
+    /// method instead.
+    fn check_error(&mut self) -> Result<(), FeederError> {
+        Ok(())
+    }
+
+    /// Return the total length of the input in bytes if it is known in
+    /// advance (e.g. because the input is a slice or a file), or `None` if
+    /// the input is unbounded or its length is unknown (e.g. a stream). The
+    /// parser uses this value to compute its
+    /// [`progress()`](crate::JsonParser::progress()). The default
+    /// implementation returns `None`.
+    fn total_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return bytes that are currently buffered but that have not been
+    /// consumed yet (i.e. the bytes the next calls of [`Self::next_input()`]
+    /// will return) without consuming them. This is useful for diagnostics
+    /// or to implement custom framing on top of the parser.
+    ///
+    /// The returned slice is only a snapshot of what the feeder has at hand.
+    /// Feeders that read from a reader only return what is left in their
+    /// current buffer, not what the reader could provide next. Feeders
+    /// backed by a ring buffer or by multiple chunks may only return a
+    /// prefix of the buffered bytes. An empty slice therefore does not mean
+    /// that the input has ended. Bytes the parser has already read but put
+    /// back (e.g. the byte that ended a number) are not included. The
+    /// default implementation returns an empty slice.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::{JsonFeeder, SliceJsonFeeder};
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"{"a": true}"#));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    /// assert_eq!(parser.feeder.peek(), br#""a": true}"#);
+    /// ```
+    fn peek(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Consume the given number of bytes without returning them. The bytes
+    /// must have been returned by [`Self::peek()`] before, so `n` must not be
+    /// larger than the length of the slice returned by it. The parser uses
+    /// this method to skip over bytes it does not have to look at one by
+    /// one (see [`JsonParserOptionsBuilder::with_structure_only()`](crate::options::JsonParserOptionsBuilder::with_structure_only())).
+    /// The default implementation calls [`Self::next_input()`] `n` times.
+    fn consume(&mut self, n: usize) {
+        for _ in 0..n {
+            self.next_input();
+        }
+    }
+}
+
+impl<F> JsonFeeder for &mut F
+where
+    F: JsonFeeder + ?Sized,
+{
+    fn has_input(&self) -> bool {
+        (**self).has_input()
+    }
+
+    fn is_done(&self) -> bool {
+        (**self).is_done()
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        (**self).next_input()
+    }
+
+    fn check_error(&mut self) -> Result<(), FeederError> {
+        (**self).check_error()
+    }
+
+    fn total_len(&self) -> Option<usize> {
+        (**self).total_len()
+    }
+
+    fn peek(&self) -> &[u8] {
+        (**self).peek()
+    }
+
+    fn consume(&mut self, n: usize) {
+        (**self).consume(n)
+    }
 }
@@ -1,5 +1,6 @@
 use std::cmp::min;
 use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
 
 use thiserror::Error;
 
@@ -9,6 +10,12 @@ use super::JsonFeeder;
 pub enum PushError {
     #[error("feeder is full")]
     Full,
+
+    /// The feeder became full before all bytes could be pushed (see
+    /// [`PushJsonFeeder::try_push_bytes()`]). The error contains the number
+    /// of bytes that have been accepted.
+    #[error("feeder is full after accepting {0} bytes")]
+    PartiallyAccepted(usize),
 }
 
 /// A feeder that can be used to provide more input data to the
@@ -27,11 +34,42 @@ pub struct PushJsonFeeder {
     done: bool,
 }
 
+impl Debug for PushJsonFeeder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PushJsonFeeder")
+            .field("buffered", &self.input.len())
+            .field("capacity", &self.input.capacity())
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl Clone for PushJsonFeeder {
+    fn clone(&self) -> Self {
+        // the capacity of the input buffer determines how many bytes can be
+        // pushed, so it has to be preserved
+        let mut input = VecDeque::with_capacity(self.input.capacity());
+        input.extend(self.input.iter());
+        PushJsonFeeder {
+            input,
+            done: self.done,
+        }
+    }
+}
+
 impl PushJsonFeeder {
     /// Create a new push-based feeder
     pub fn new() -> Self {
+        Self::with_capacity(1024)
+    }
+
+    /// Create a new push-based feeder that buffers at most the given number
+    /// of bytes. This bounds the memory the feeder uses regardless of how
+    /// fast data is produced. Use [`try_push_bytes()`](Self::try_push_bytes())
+    /// to find out when the producer has to wait for the parser.
+    pub fn with_capacity(capacity: usize) -> Self {
         PushJsonFeeder {
-            input: VecDeque::with_capacity(1024),
+            input: VecDeque::with_capacity(capacity),
             done: false,
         }
     }
@@ -58,6 +96,52 @@ impl PushJsonFeeder {
         n
     }
 
+    /// Provide more data to the [`JsonParser`](crate::JsonParser) but only
+    /// as much as fits into the feeder's buffer (see
+    /// [`with_capacity()`](Self::with_capacity())). Returns the number of
+    /// bytes pushed if all of them have been accepted, or
+    /// [`PushError::PartiallyAccepted`] with the number of accepted bytes if
+    /// the feeder became full. In the latter case, call
+    /// [`JsonParser::next_event()`](crate::JsonParser::next_event()) until it
+    /// returns [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput)
+    /// and then push the remaining bytes. This allows a producer to apply
+    /// backpressure.
+    ///
+    /// Only call [`done()`](Self::done()) after all bytes have been accepted.
+    /// Bytes that have been rejected are not part of the input, so if you
+    /// call `done()` too early, the parser will report an incomplete JSON
+    /// text.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::{PushError, PushJsonFeeder};
+    ///
+    /// let mut json: &[u8] = br#"{"a": [1, 2, 3]}"#;
+    /// let mut parser = JsonParser::new(PushJsonFeeder::with_capacity(4));
+    /// let mut events = 0;
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     if e == JsonEvent::NeedMoreInput {
+    ///         match parser.feeder.try_push_bytes(json) {
+    ///             Ok(n) | Err(PushError::PartiallyAccepted(n)) => json = &json[n..],
+    ///             Err(e) => panic!("{e}"),
+    ///         }
+    ///         if json.is_empty() {
+    ///             parser.feeder.done();
+    ///         }
+    ///     } else {
+    ///         events += 1;
+    ///     }
+    /// }
+    /// assert_eq!(events, 8);
+    /// ```
+    pub fn try_push_bytes(&mut self, buf: &[u8]) -> Result<usize, PushError> {
+        let n = self.push_bytes(buf);
+        if n < buf.len() {
+            return Err(PushError::PartiallyAccepted(n));
+        }
+        Ok(n)
+    }
+
     /// Checks if the parser accepts more input at the moment. If it doesn't,
     /// you have to call [`JsonParser::next_event()`](crate::JsonParser::next_event())
     /// until it returns [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput).
@@ -91,6 +175,16 @@ impl JsonFeeder for PushJsonFeeder {
     fn next_input(&mut self) -> Option<u8> {
         self.input.pop_front()
     }
+
+    /// Return the buffered bytes up to the point where the internal ring
+    /// buffer wraps around
+    fn peek(&self) -> &[u8] {
+        self.input.as_slices().0
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.input.drain(..n);
+    }
 }
 
 #[cfg(test)]
@@ -246,4 +340,55 @@ mod test {
             &mut feeder,
         );
     }
+
+    /// Test that bytes are only accepted up to the configured capacity
+    #[test]
+    fn try_push_bytes() {
+        let mut feeder = PushJsonFeeder::with_capacity(8);
+        assert_eq!(feeder.try_push_bytes(b"abc").unwrap(), 3);
+        assert!(matches!(
+            feeder.try_push_bytes(b"defghij"),
+            Err(PushError::PartiallyAccepted(5))
+        ));
+        assert!(feeder.is_full());
+        assert!(matches!(
+            feeder.try_push_bytes(b"j"),
+            Err(PushError::PartiallyAccepted(0))
+        ));
+        assert_eq!(feeder.try_push_bytes(b"").unwrap(), 0);
+
+        assert_eq!(feeder.next_input(), Some(b'a'));
+        assert_eq!(feeder.try_push_bytes(b"j").unwrap(), 1);
+        assert!(feeder.is_full());
+    }
+
+    /// Test that a clone has the same capacity and contents
+    #[test]
+    fn clone() {
+        let mut feeder = PushJsonFeeder {
+            input: VecDeque::with_capacity(16),
+            done: false,
+        };
+        feeder.push_bytes("abcd".as_bytes());
+
+        let mut clone = feeder.clone();
+        assert_eq!(clone.push_bytes(&[b'x'; 32]), 12);
+        assert!(clone.is_full());
+        assert_eq!(clone.next_input(), Some(b'a'));
+
+        assert!(!feeder.is_full());
+        assert_eq!(feeder.next_input(), Some(b'a'));
+    }
+
+    /// Test that buffered bytes can be inspected without consuming them
+    #[test]
+    fn peek() {
+        let mut feeder = PushJsonFeeder::with_capacity(4);
+        assert_eq!(feeder.peek(), b"");
+        assert_eq!(feeder.push_bytes(b"abc"), 3);
+        assert_eq!(feeder.peek(), b"abc");
+        assert_eq!(feeder.next_input(), Some(b'a'));
+        assert_eq!(feeder.peek(), b"bc");
+        assert!(feeder.has_input());
+    }
 }
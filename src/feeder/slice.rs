@@ -1,6 +1,12 @@
+use std::fmt::{Debug, Formatter};
+
+use crate::parser::ParserError;
+use crate::{JsonEvent, JsonParser};
+
 use super::JsonFeeder;
 
 /// A [`JsonFeeder`] that feeds the [`JsonParser`](crate::JsonParser) from a slice of bytes
+#[derive(Clone)]
 pub struct SliceJsonFeeder<'a> {
     slice: &'a [u8],
     pos: usize,
@@ -13,6 +19,15 @@ impl<'a> SliceJsonFeeder<'a> {
     }
 }
 
+impl Debug for SliceJsonFeeder<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SliceJsonFeeder")
+            .field("len", &self.slice.len())
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
 impl JsonFeeder for SliceJsonFeeder<'_> {
     fn has_input(&self) -> bool {
         self.pos < self.slice.len()
@@ -31,11 +46,190 @@ impl JsonFeeder for SliceJsonFeeder<'_> {
             r
         }
     }
+
+    fn total_len(&self) -> Option<usize> {
+        Some(self.slice.len())
+    }
+
+    fn peek(&self) -> &[u8] {
+        &self.slice[self.pos..]
+    }
+
+    fn consume(&mut self, n: usize) {
+        debug_assert!(n <= self.slice.len() - self.pos);
+        self.pos += n;
+    }
+}
+
+impl<'a> JsonParser<SliceJsonFeeder<'a>> {
+    /// Return the part of the input slice that the parser has not consumed
+    /// yet. This is useful to find out where parsing stopped (e.g. after an
+    /// error) or to parse a single value and keep the rest of the input for
+    /// something else. The byte that caused an error counts as consumed.
+    ///
+    /// The method is only available for [`SliceJsonFeeder`] because other
+    /// feeders do not keep their input in a contiguous buffer. It relies on
+    /// [`JsonParser::parsed_bytes()`], so the result is only meaningful if
+    /// the parser has read the slice from its beginning.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = br#"{"a": 1} rest"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default().with_streaming(true).build(),
+    /// );
+    /// while parser.next_event().unwrap() != Some(JsonEvent::EndObject) {}
+    /// assert_eq!(parser.remaining_input(), b" rest");
+    /// ```
+    pub fn remaining_input(&self) -> &'a [u8] {
+        let slice = self.feeder.slice;
+        &slice[self.parsed_bytes().min(slice.len())..]
+    }
+
+    /// Return an [`Iterator`] over the events of the JSON text that borrows
+    /// the parser. In contrast to [`IntoIterator::into_iter()`], the parser
+    /// remains accessible through [`Events::parser()`], so the values of
+    /// the events can be read (e.g. with
+    /// [`JsonParser::current_str()`]).
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = br#"{"name": "Elvis"}"#;
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    /// let mut events = parser.events();
+    /// let mut strings = Vec::new();
+    /// while let Some(event) = events.next() {
+    ///     if event.unwrap() == JsonEvent::ValueString {
+    ///         strings.push(events.parser().current_str().unwrap().to_string());
+    ///     }
+    /// }
+    /// assert_eq!(strings, ["Elvis"]);
+    /// ```
+    pub fn events(&mut self) -> Events<'_, 'a> {
+        Events {
+            parser: self,
+            done: false,
+        }
+    }
+}
+
+/// Get the next event from a parser that reads from a slice and set `done`
+/// if the JSON text has ended or an error has occurred
+fn next_item(
+    parser: &mut JsonParser<SliceJsonFeeder<'_>>,
+    done: &mut bool,
+) -> Option<Result<JsonEvent, ParserError>> {
+    if *done {
+        return None;
+    }
+    loop {
+        match parser.next_event() {
+            // cannot happen because a slice feeder is always done when it
+            // runs out of input, but skip the event to be safe
+            Ok(Some(JsonEvent::NeedMoreInput)) => continue,
+            Ok(Some(e)) => return Some(Ok(e)),
+            Ok(None) => {
+                *done = true;
+                return None;
+            }
+            Err(e) => {
+                *done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// An [`Iterator`] over the events of a JSON text that owns a parser
+/// reading from a slice. Create it by calling
+/// [`into_iter()`](IntoIterator::into_iter()) on the parser or by using the
+/// parser in a `for` loop. The iterator never yields
+/// [`JsonEvent::NeedMoreInput`] and ends after the first error.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::SliceJsonFeeder;
+///
+/// let mut count = 0;
+/// for event in JsonParser::new(SliceJsonFeeder::new(b"[1, 2, 3]")) {
+///     if event.unwrap() == JsonEvent::ValueInt {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(count, 3);
+/// ```
+pub struct IntoEvents<'a> {
+    parser: JsonParser<SliceJsonFeeder<'a>>,
+    done: bool,
+}
+
+impl<'a> IntoEvents<'a> {
+    /// Get the parser, e.g. to read the value of the last event
+    pub fn parser(&self) -> &JsonParser<SliceJsonFeeder<'a>> {
+        &self.parser
+    }
+
+    /// Consume the iterator and return the parser
+    pub fn into_parser(self) -> JsonParser<SliceJsonFeeder<'a>> {
+        self.parser
+    }
+}
+
+impl<'a> Iterator for IntoEvents<'a> {
+    type Item = Result<JsonEvent, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_item(&mut self.parser, &mut self.done)
+    }
+}
+
+impl<'a> IntoIterator for JsonParser<SliceJsonFeeder<'a>> {
+    type Item = Result<JsonEvent, ParserError>;
+    type IntoIter = IntoEvents<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoEvents {
+            parser: self,
+            done: false,
+        }
+    }
+}
+
+/// An [`Iterator`] over the events of a JSON text that borrows a parser
+/// reading from a slice. Create it with [`JsonParser::events()`]. The
+/// iterator never yields [`JsonEvent::NeedMoreInput`] and ends after the
+/// first error.
+pub struct Events<'p, 'a> {
+    parser: &'p mut JsonParser<SliceJsonFeeder<'a>>,
+    done: bool,
+}
+
+impl<'a> Events<'_, 'a> {
+    /// Get the parser, e.g. to read the value of the last event
+    pub fn parser(&self) -> &JsonParser<SliceJsonFeeder<'a>> {
+        self.parser
+    }
+}
+
+impl Iterator for Events<'_, '_> {
+    type Item = Result<JsonEvent, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_item(self.parser, &mut self.done)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::feeder::JsonFeeder;
+    use crate::feeder::{JsonFeeder, SliceJsonFeeder};
+    use crate::parser::ParserError;
+    use crate::{JsonEvent, JsonParser};
 
     #[test]
     fn empty() {
@@ -57,4 +251,91 @@ mod test {
         assert!(!feeder.has_input());
         assert!(feeder.is_done());
     }
+
+    #[test]
+    fn remaining_input() {
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1, 2}, 3]"));
+        assert_eq!(parser.remaining_input(), b"[1, 2}, 3]");
+        assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+        assert_eq!(parser.remaining_input(), b"1, 2}, 3]");
+        assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+        assert_eq!(parser.remaining_input(), b" 2}, 3]");
+        assert!(parser.next_event().is_err());
+        assert_eq!(parser.remaining_input(), b", 3]");
+
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(b"12"));
+        while parser.next_event().unwrap().is_some() {}
+        assert_eq!(parser.remaining_input(), b"");
+    }
+
+    #[test]
+    fn into_iter() {
+        let events = JsonParser::new(SliceJsonFeeder::new(br#"{"a": [true]}"#))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                JsonEvent::StartObject,
+                JsonEvent::FieldName,
+                JsonEvent::StartArray,
+                JsonEvent::ValueTrue,
+                JsonEvent::EndArray,
+                JsonEvent::EndObject,
+            ]
+        );
+
+        // the iterator ends after the first error
+        let mut events = JsonParser::new(SliceJsonFeeder::new(b"[1, }")).into_iter();
+        assert_eq!(events.next().unwrap().unwrap(), JsonEvent::StartArray);
+        assert_eq!(events.next().unwrap().unwrap(), JsonEvent::ValueInt);
+        assert!(matches!(events.next(), Some(Err(ParserError::SyntaxError))));
+        assert!(events.next().is_none());
+
+        // empty input
+        let mut events = JsonParser::new(SliceJsonFeeder::new(b"")).into_iter();
+        assert!(matches!(events.next(), Some(Err(ParserError::EmptyInput))));
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn events() {
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"["a", 2, "b"]"#));
+        let mut events = parser.events();
+        let mut values = Vec::new();
+        while let Some(event) = events.next() {
+            match event.unwrap() {
+                JsonEvent::ValueString => {
+                    values.push(events.parser().current_str().unwrap().to_string())
+                }
+                JsonEvent::ValueInt => {
+                    values.push(events.parser().current_int::<i64>().unwrap().to_string())
+                }
+                _ => {}
+            }
+        }
+        assert!(events.next().is_none());
+        assert_eq!(values, ["a", "2", "b"]);
+        assert_eq!(parser.event_count(), 5);
+    }
+
+    #[test]
+    fn total_len() {
+        let mut feeder = super::SliceJsonFeeder::new(b"Elvis");
+        assert_eq!(feeder.total_len(), Some(5));
+        feeder.next_input();
+        assert_eq!(feeder.total_len(), Some(5));
+        assert_eq!(super::SliceJsonFeeder::new(b"").total_len(), Some(0));
+    }
+
+    #[test]
+    fn peek() {
+        let mut feeder = super::SliceJsonFeeder::new(b"Elvis");
+        assert_eq!(feeder.peek(), b"Elvis");
+        feeder.next_input();
+        assert_eq!(feeder.peek(), b"lvis");
+        while feeder.next_input().is_some() {}
+        assert_eq!(feeder.peek(), b"");
+    }
 }
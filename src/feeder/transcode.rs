@@ -0,0 +1,269 @@
+use thiserror::Error;
+
+use super::{FeederError, JsonFeeder};
+
+/// The character encoding of the input of a [`TranscodingJsonFeeder`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-16. The byte order is detected from the byte order mark (BOM) at
+    /// the beginning of the input. Big endian is assumed if there is no BOM.
+    Utf16,
+
+    /// UTF-16 little endian
+    Utf16Le,
+
+    /// UTF-16 big endian
+    Utf16Be,
+
+    /// UTF-32. The byte order is detected from the byte order mark (BOM) at
+    /// the beginning of the input. Big endian is assumed if there is no BOM.
+    Utf32,
+
+    /// UTF-32 little endian
+    Utf32Le,
+
+    /// UTF-32 big endian
+    Utf32Be,
+}
+
+impl Encoding {
+    /// The number of bytes of a code unit
+    fn width(&self) -> usize {
+        match self {
+            Encoding::Utf16 | Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf32 | Encoding::Utf32Le | Encoding::Utf32Be => 4,
+        }
+    }
+}
+
+/// An error that can happen when transcoding the input of a
+/// [`TranscodingJsonFeeder`] to UTF-8
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscodeError {
+    /// The UTF-16 input contains a high surrogate that is not followed by a
+    /// low surrogate or a low surrogate that is not preceded by a high one
+    #[error("invalid UTF-16: unpaired surrogate `{0:#06x}'")]
+    UnpairedSurrogate(u16),
+
+    /// The UTF-32 input contains a value that is not a Unicode scalar value
+    #[error("invalid UTF-32: `{0:#010x}' is not a Unicode scalar value")]
+    InvalidCodePoint(u32),
+
+    /// The input ended in the middle of a character
+    #[error("input ended in the middle of a character")]
+    Truncated,
+}
+
+/// A [`JsonFeeder`] that wraps around another feeder providing UTF-16 or
+/// UTF-32 encoded bytes and transcodes them to UTF-8 for the
+/// [`JsonParser`](crate::JsonParser). Transcoding happens on the fly, so the
+/// input can be streamed. A byte order mark (BOM) at the beginning of the
+/// input is skipped.
+///
+/// If the input cannot be transcoded, the feeder stops providing input and
+/// the parser returns [`ParserError::Feeder`](crate::parser::ParserError::Feeder)
+/// with [`FeederError::Transcode`]. The actual [`TranscodeError`] is also
+/// available through [`error()`](Self::error()).
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::{Encoding, SliceJsonFeeder, TranscodingJsonFeeder};
+///
+/// // UTF-16LE with byte order mark
+/// let json = [0xFF, 0xFE, b'"', 0, 0xE4, 0, b'"', 0];
+///
+/// let feeder = TranscodingJsonFeeder::new(SliceJsonFeeder::new(&json), Encoding::Utf16);
+/// let mut parser = JsonParser::new(feeder);
+/// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+/// assert_eq!(parser.current_str().unwrap(), "ä");
+/// assert!(parser.feeder.error().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TranscodingJsonFeeder<F> {
+    /// The wrapped feeder providing the encoded bytes
+    pub inner: F,
+
+    /// The input encoding. If the byte order should be detected, this field
+    /// will be updated as soon as the first code unit has been read.
+    encoding: Encoding,
+
+    /// `true` if the first code unit has been checked for a byte order mark
+    bom_checked: bool,
+
+    /// The bytes of the code unit that is currently being read
+    unit: [u8; 4],
+
+    /// The number of bytes in [`Self::unit`]
+    unit_len: usize,
+
+    /// A UTF-16 high surrogate waiting for its low surrogate
+    high_surrogate: Option<u16>,
+
+    /// The UTF-8 bytes of the last transcoded character
+    out: [u8; 4],
+
+    /// The position of the next byte to return from [`Self::out`]
+    out_pos: usize,
+
+    /// The number of bytes in [`Self::out`]
+    out_len: usize,
+
+    /// The error that has happened during transcoding
+    error: Option<TranscodeError>,
+}
+
+impl<F> TranscodingJsonFeeder<F>
+where
+    F: JsonFeeder,
+{
+    /// Create a new feeder that transcodes the bytes provided by the given
+    /// feeder from the given encoding to UTF-8
+    pub fn new(inner: F, encoding: Encoding) -> Self {
+        TranscodingJsonFeeder {
+            inner,
+            encoding,
+            bom_checked: false,
+            unit: [0; 4],
+            unit_len: 0,
+            high_surrogate: None,
+            out: [0; 4],
+            out_pos: 0,
+            out_len: 0,
+            error: None,
+        }
+    }
+
+    /// Get the error that has happened while transcoding the input or `None`
+    /// if the input has been valid so far
+    pub fn error(&self) -> Option<TranscodeError> {
+        self.error
+    }
+
+    /// Decode the current code unit according to the current encoding
+    fn decode_unit(&self) -> u32 {
+        let u = &self.unit;
+        match self.encoding {
+            Encoding::Utf16 | Encoding::Utf16Be => u16::from_be_bytes([u[0], u[1]]) as u32,
+            Encoding::Utf16Le => u16::from_le_bytes([u[0], u[1]]) as u32,
+            Encoding::Utf32 | Encoding::Utf32Be => u32::from_be_bytes(*u),
+            Encoding::Utf32Le => u32::from_le_bytes(*u),
+        }
+    }
+
+    /// Read the next code unit and decode it to a character. Return `None` if
+    /// there is not enough input or if an error has happened.
+    fn next_char(&mut self) -> Option<char> {
+        let width = self.encoding.width();
+        loop {
+            while self.unit_len < width {
+                if let Some(b) = self.inner.next_input() {
+                    self.unit[self.unit_len] = b;
+                    self.unit_len += 1;
+                } else {
+                    if self.inner.is_done() && (self.unit_len > 0 || self.high_surrogate.is_some())
+                    {
+                        self.error = Some(TranscodeError::Truncated);
+                    }
+                    return None;
+                }
+            }
+            self.unit_len = 0;
+
+            if !self.bom_checked {
+                self.bom_checked = true;
+                self.encoding = match self.encoding {
+                    Encoding::Utf16 if self.unit[..2] == [0xFF, 0xFE] => Encoding::Utf16Le,
+                    Encoding::Utf16 => Encoding::Utf16Be,
+                    Encoding::Utf32 if self.unit == [0xFF, 0xFE, 0, 0] => Encoding::Utf32Le,
+                    Encoding::Utf32 => Encoding::Utf32Be,
+                    e => e,
+                };
+                if self.decode_unit() == 0xFEFF {
+                    // skip byte order mark
+                    continue;
+                }
+            }
+
+            let u = self.decode_unit();
+            if width == 4 {
+                if let Some(c) = char::from_u32(u) {
+                    return Some(c);
+                }
+                self.error = Some(TranscodeError::InvalidCodePoint(u));
+                return None;
+            }
+
+            if let Some(high) = self.high_surrogate.take() {
+                if (0xDC00..=0xDFFF).contains(&u) {
+                    let c = 0x10000 + ((high as u32 - 0xD800) << 10) + (u - 0xDC00);
+                    return char::from_u32(c);
+                }
+                self.error = Some(TranscodeError::UnpairedSurrogate(high));
+                return None;
+            }
+
+            match u {
+                0xD800..=0xDBFF => self.high_surrogate = Some(u as u16),
+                0xDC00..=0xDFFF => {
+                    self.error = Some(TranscodeError::UnpairedSurrogate(u as u16));
+                    return None;
+                }
+                _ => return char::from_u32(u),
+            }
+        }
+    }
+}
+
+impl<F> JsonFeeder for TranscodingJsonFeeder<F>
+where
+    F: JsonFeeder,
+{
+    /// Determine if the feeder has input data that can be parsed. Note that
+    /// this method may return `true` if the wrapped feeder only provides part
+    /// of a character.
+    fn has_input(&self) -> bool {
+        self.error.is_none() && (self.out_pos < self.out_len || self.inner.has_input())
+    }
+
+    fn is_done(&self) -> bool {
+        self.error.is_some() || (self.out_pos == self.out_len && self.inner.is_done())
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        if self.out_pos == self.out_len {
+            if self.error.is_some() {
+                return None;
+            }
+            match self.next_char() {
+                Some(c) => {
+                    self.out_len = c.encode_utf8(&mut self.out).len();
+                    self.out_pos = 0;
+                }
+                None => return None,
+            }
+        }
+        let b = self.out[self.out_pos];
+        self.out_pos += 1;
+        Some(b)
+    }
+
+    fn check_error(&mut self) -> Result<(), FeederError> {
+        self.inner.check_error()?;
+        match self.error {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Return the remaining UTF-8 bytes of the character that has been
+    /// transcoded last. Bytes of the inner feeder that have not been
+    /// transcoded yet are not included.
+    fn peek(&self) -> &[u8] {
+        &self.out[self.out_pos..self.out_len]
+    }
+
+    fn consume(&mut self, n: usize) {
+        debug_assert!(n <= self.out_len - self.out_pos);
+        self.out_pos += n;
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+use super::JsonFeeder;
+
+/// A push-based feeder that reads from a [`VecDeque<u8>`] and drains it from
+/// the front as the parser consumes bytes. This is useful in
+/// producer/consumer setups that already use a `VecDeque<u8>` as a byte
+/// queue: the queue can be handed over with [`From`] or
+/// [`append()`](Self::append()) (which moves the bytes instead of copying
+/// them through a slice) and filled directly through
+/// [`queue_mut()`](Self::queue_mut()).
+///
+/// Pushing bytes is amortized O(1) per byte and consuming a byte is O(1),
+/// because a `VecDeque` is a ring buffer that never has to shift its
+/// remaining contents to the front. This makes the cost of a long stream
+/// linear in its length, no matter how the bytes are split into chunks.
+/// In contrast to [`PushJsonFeeder`](super::PushJsonFeeder), which uses the
+/// same kind of buffer, this feeder has no fixed capacity and grows as
+/// needed. Call [`JsonParser::next_event()`](crate::JsonParser::next_event())
+/// until it returns [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput)
+/// before pushing more bytes to keep memory usage low. Call
+/// [`done()`](Self::done()) to indicate the end of the JSON text.
+///
+/// ```
+/// use std::collections::VecDeque;
+///
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::VecDequeJsonFeeder;
+///
+/// let mut queue = VecDeque::from(b"{\"name\": \"El".to_vec());
+/// let mut parser = JsonParser::new(VecDequeJsonFeeder::new());
+/// parser.feeder.append(&mut queue);
+///
+/// let mut chunks = [&b"vis\"}"[..]].into_iter();
+/// while let Some(event) = parser.next_event().unwrap() {
+///     match event {
+///         JsonEvent::NeedMoreInput => match chunks.next() {
+///             Some(chunk) => parser.feeder.queue_mut().extend(chunk),
+///             None => parser.feeder.done(),
+///         },
+///         JsonEvent::ValueString => assert_eq!(parser.current_str().unwrap(), "Elvis"),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VecDequeJsonFeeder {
+    queue: VecDeque<u8>,
+    done: bool,
+}
+
+impl VecDequeJsonFeeder {
+    /// Create a new feeder with an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Provide more input to the [`JsonParser`](crate::JsonParser). The
+    /// bytes are copied to the end of the queue.
+    pub fn push_bytes(&mut self, buf: &[u8]) {
+        self.queue.extend(buf);
+    }
+
+    /// Move all bytes from the given queue to the end of this feeder's
+    /// queue, leaving `other` empty
+    pub fn append(&mut self, other: &mut VecDeque<u8>) {
+        self.queue.append(other);
+    }
+
+    /// Get the queue of bytes that have not been consumed by the parser yet
+    pub fn queue(&self) -> &VecDeque<u8> {
+        &self.queue
+    }
+
+    /// Get mutable access to the queue, e.g. to push bytes directly. Bytes
+    /// must only be added to the back of the queue. Removing bytes that have
+    /// not been consumed yet changes the input of the parser.
+    pub fn queue_mut(&mut self) -> &mut VecDeque<u8> {
+        &mut self.queue
+    }
+
+    /// Return the number of bytes that have been pushed but that have not
+    /// been consumed by the parser yet
+    pub fn buffered(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Call this method to indicate that the end of the JSON text has been
+    /// reached and that there is no more input to parse.
+    pub fn done(&mut self) {
+        self.done = true;
+    }
+
+    /// Consume the feeder and return the queue with the bytes that have not
+    /// been consumed by the parser yet
+    pub fn into_inner(self) -> VecDeque<u8> {
+        self.queue
+    }
+}
+
+impl From<VecDeque<u8>> for VecDequeJsonFeeder {
+    /// Create a feeder that reads from the given queue. Call
+    /// [`done()`](VecDequeJsonFeeder::done()) if the queue already contains
+    /// the whole JSON text.
+    fn from(queue: VecDeque<u8>) -> Self {
+        VecDequeJsonFeeder { queue, done: false }
+    }
+}
+
+impl JsonFeeder for VecDequeJsonFeeder {
+    fn has_input(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    fn is_done(&self) -> bool {
+        self.done && !self.has_input()
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        self.queue.pop_front()
+    }
+
+    /// Return the buffered bytes up to the point where the queue wraps
+    /// around. Use [`VecDequeJsonFeeder::queue()`] to get all of them.
+    fn peek(&self) -> &[u8] {
+        self.queue.as_slices().0
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.queue.drain(..n);
+    }
+}
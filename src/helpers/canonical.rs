@@ -0,0 +1,198 @@
+//! Compute a hash of the canonical form of a JSON text
+//!
+//! See [`canonical_hash()`]
+use std::hash::Hasher;
+use std::io::{BufReader, Read, Write};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::options::{JsonParserOptionsBuilder, NumberDecision};
+use crate::parser::ParserError;
+use crate::writer::{JsonWriter, WriterError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while computing the hash of a JSON text
+#[derive(Error, Debug)]
+pub enum CanonicalHashError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    Write(#[from] WriterError),
+}
+
+/// An adapter that feeds everything written to it into a [`Hasher`]
+struct HashWriter<'a, H>(&'a mut H);
+
+impl<H> Write for HashWriter<'_, H>
+where
+    H: Hasher,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Read a JSON text from the given reader, convert it to its canonical form,
+/// feed the canonical form into the given hasher, and return the hash. This
+/// is useful for content-addressed storage, deduplication, or caching, where
+/// documents that only differ in their formatting should be treated as
+/// equal.
+///
+/// The canonical form is generated by a [`JsonWriter`] with
+/// [sorted keys](JsonWriter::with_sorted_keys()). It does not contain any
+/// white space, the entries of all objects are sorted by their field names,
+/// and strings are escaped as little as possible. Numbers are converted to
+/// the shortest notation that JavaScript would use for them (e.g. `1.0`,
+/// `10e-1`, and `1` all become `1`, `-0` becomes `0`, and `1e21` becomes
+/// `1e+21`). In contrast to JavaScript, numbers are not rounded, so numbers
+/// that cannot be represented as a 64-bit floating point value keep all
+/// their digits.
+///
+/// Heads up: To be able to sort the entries, each object has to be kept in
+/// memory until it has ended, including all nested values. Memory usage is
+/// therefore bounded by the size of the largest object. A top-level object
+/// is kept in memory completely. Arrays that are not inside an object are
+/// streamed.
+///
+/// ```
+/// use std::hash::DefaultHasher;
+/// use actson::canonical_hash;
+///
+/// let a = canonical_hash(r#"{"b": [1.0, -0], "a": "x"}"#.as_bytes(), DefaultHasher::new());
+/// let b = canonical_hash(r#"{"a":"x","b":[1,0]}"#.as_bytes(), DefaultHasher::new());
+/// assert_eq!(a.unwrap(), b.unwrap());
+/// ```
+pub fn canonical_hash<R, H>(reader: R, mut hasher: H) -> Result<u64, CanonicalHashError>
+where
+    R: Read,
+    H: Hasher,
+{
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    let mut parser = JsonParser::new_with_options(
+        feeder,
+        JsonParserOptionsBuilder::default()
+            .with_number_hook(Arc::new(canonical_number_hook))
+            .build(),
+    );
+
+    let mut writer = JsonWriter::new(HashWriter(&mut hasher)).with_sorted_keys(true);
+    while let Some(event) = parser.next_event()? {
+        match event {
+            JsonEvent::NeedMoreInput => parser.feeder.fill_buf()?,
+            _ => writer.write_event(event, &parser)?,
+        }
+    }
+    drop(writer);
+
+    Ok(hasher.finish())
+}
+
+/// A number hook that rewrites numbers to their canonical form
+fn canonical_number_hook(number: &str) -> NumberDecision {
+    match canonical_number(number) {
+        Some(c) if c != number => NumberDecision::Rewrite(c),
+        _ => NumberDecision::Accept,
+    }
+}
+
+/// Convert a valid JSON number to the notation JavaScript's
+/// `Number.prototype.toString()` would use, but without rounding. Return
+/// `None` if the exponent is too large to be handled.
+fn canonical_number(number: &str) -> Option<String> {
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(n) => (true, n),
+        None => (false, number),
+    };
+
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], number[i + 1..].parse::<i64>().ok()?),
+        None => (number, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    // the number is `digits * 10^exponent`
+    let digits = format!("{int_part}{frac_part}");
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    let exponent = exponent
+        .checked_sub(frac_part.len() as i64)?
+        .checked_add((digits.len() - trimmed.len()) as i64)?;
+    let digits = trimmed;
+    if digits.is_empty() {
+        return Some("0".to_string());
+    }
+
+    // position of the decimal point relative to the first digit
+    let k = digits.len() as i64;
+    let n = k.checked_add(exponent)?;
+
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    if k <= n && n <= 21 {
+        result.push_str(digits);
+        result.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        result.push_str(&digits[..n as usize]);
+        result.push('.');
+        result.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        result.push_str("0.");
+        result.push_str(&"0".repeat(-n as usize));
+        result.push_str(digits);
+    } else {
+        result.push_str(&digits[..1]);
+        if k > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        let e = n - 1;
+        result.push_str(if e > 0 { "e+" } else { "e-" });
+        result.push_str(&e.unsigned_abs().to_string());
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::helpers::canonical::canonical_number;
+
+    /// Test that numbers are converted to their canonical form
+    #[test]
+    fn numbers() {
+        for (number, expected) in [
+            ("0", "0"),
+            ("-0", "0"),
+            ("-0.0e5", "0"),
+            ("1", "1"),
+            ("1.0", "1"),
+            ("10e-1", "1"),
+            ("-12.50", "-12.5"),
+            ("100", "100"),
+            ("1E2", "100"),
+            ("0.001", "0.001"),
+            ("0.000001", "0.000001"),
+            ("0.0000001", "1e-7"),
+            ("123e-20", "1.23e-18"),
+            ("1e21", "1e+21"),
+            ("123456789012345678901", "123456789012345678901"),
+            ("12345678901234567890123", "1.2345678901234567890123e+22"),
+            ("3.14159265358979323846", "3.14159265358979323846"),
+        ] {
+            assert_eq!(canonical_number(number).unwrap(), expected, "{number}");
+        }
+        assert_eq!(canonical_number("1e99999999999999999999"), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Convert a JSON array of objects to CSV (RFC 4180)
+//!
+//! See [`to_csv()`]
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Write};
+
+use thiserror::Error;
+
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::parser::{InvalidStringValueError, ParserError};
+use crate::writer::{JsonWriter, WriterError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen when converting JSON to CSV
+#[derive(Error, Debug)]
+pub enum CsvError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+
+    /// The top-level value is not an array or the array contains an element
+    /// that is not an object
+    #[error("expected an array of objects")]
+    NotAnArrayOfObjects,
+
+    /// The delimiter is not an ASCII character or it is a double quote, a
+    /// carriage return, or a line feed
+    #[error("invalid delimiter: `{}'", char::from(*.0))]
+    InvalidDelimiter(u8),
+}
+
+impl From<WriterError> for CsvError {
+    fn from(err: WriterError) -> Self {
+        match err {
+            WriterError::Io(e) => CsvError::Io(e),
+            WriterError::InvalidStringValue(e) => CsvError::InvalidStringValue(e),
+        }
+    }
+}
+
+/// Options for [`to_csv_with_options()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    delimiter: u8,
+    header: bool,
+}
+
+impl Default for CsvOptions {
+    /// Returns default options: fields are separated by commas and the
+    /// output starts with a header row
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            header: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Returns the character that separates fields
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    /// Returns `true` if the output should start with a header row
+    pub fn header(&self) -> bool {
+        self.header
+    }
+
+    /// Set the character that separates fields (e.g. `b'\t'` for TSV). The
+    /// delimiter must be an ASCII character other than a double quote, a
+    /// carriage return, or a line feed. The default is a comma.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Specify whether the output should start with a header row containing
+    /// the column names. The default is `true`.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+/// Read a JSON text consisting of an array of objects from the given reader
+/// and write one CSV row per object to the given writer. The output starts
+/// with a header row and fields are separated by commas. Use
+/// [`to_csv_with_options()`] to change this.
+///
+/// See [`to_csv_with_options()`] for details.
+///
+/// ```
+/// let json = r#"[
+///     {"name": "Elvis", "born": 1935, "genres": ["rock", "pop"]},
+///     {"born": 1942, "name": "Aretha \"Queen of Soul\" Franklin"},
+///     {"name": "Prince", "albums": 39}
+/// ]"#;
+///
+/// let mut csv = Vec::new();
+/// actson::to_csv(json.as_bytes(), &mut csv, &["name", "born", "genres"]).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(csv).unwrap(),
+///     "name,born,genres\r\n\
+///      Elvis,1935,\"[\"\"rock\"\",\"\"pop\"\"]\"\r\n\
+///      \"Aretha \"\"Queen of Soul\"\" Franklin\",1942,\r\n\
+///      Prince,,\r\n"
+/// );
+/// ```
+pub fn to_csv<R, W>(reader: R, writer: W, columns: &[&str]) -> Result<(), CsvError>
+where
+    R: Read,
+    W: Write,
+{
+    to_csv_with_options(reader, writer, columns, CsvOptions::default())
+}
+
+/// Read a JSON text consisting of an array of objects from the given reader
+/// and write one CSV row per object to the given writer.
+///
+/// Each row contains the values of the given fields (columns) in the given
+/// order. Fields that do not appear in `columns` are skipped without being
+/// buffered. A missing field or a `null` becomes an empty cell. Strings are
+/// written without JSON escaping, numbers exactly as they appeared in the
+/// input, and booleans as `true` or `false`. Nested objects and arrays are
+/// written as compact JSON texts. If an object contains the same field more
+/// than once, the last value wins.
+///
+/// Cells that contain the delimiter, a double quote, a carriage return, or a
+/// line feed are enclosed in double quotes, and double quotes inside them are
+/// doubled (RFC 4180). Rows end with a carriage return and a line feed.
+///
+/// The input is processed incrementally, so only one row is kept in memory at
+/// a time. Each row is written with a single call to [`Write::write_all()`],
+/// so you may want to wrap `writer` in a [`BufWriter`](std::io::BufWriter).
+///
+/// ```
+/// use actson::helpers::{to_csv_with_options, CsvOptions};
+///
+/// let json = r#"[{"a": 1, "b": "x\ty"}, {"b": null, "a": {"c": true}}]"#;
+///
+/// let mut tsv = Vec::new();
+/// to_csv_with_options(
+///     json.as_bytes(),
+///     &mut tsv,
+///     &["a", "b"],
+///     CsvOptions::default().with_delimiter(b'\t').with_header(false),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(tsv).unwrap(),
+///     "1\t\"x\ty\"\r\n\"{\"\"c\"\":true}\"\t\r\n"
+/// );
+/// ```
+pub fn to_csv_with_options<R, W>(
+    reader: R,
+    mut writer: W,
+    columns: &[&str],
+    options: CsvOptions,
+) -> Result<(), CsvError>
+where
+    R: Read,
+    W: Write,
+{
+    let delimiter = options.delimiter;
+    if !delimiter.is_ascii() || matches!(delimiter, b'"' | b'\r' | b'\n') {
+        return Err(CsvError::InvalidDelimiter(delimiter));
+    }
+
+    // map field names to the indices of the columns they belong to
+    let mut indices: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, c) in columns.iter().enumerate() {
+        indices.entry(c).or_default().push(i);
+    }
+
+    let mut line = Vec::new();
+    if options.header {
+        write_row(&mut line, columns.iter().map(|c| c.as_bytes()), delimiter);
+        writer.write_all(&line)?;
+    }
+
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    let mut parser = JsonParser::new(feeder);
+
+    let mut cells = vec![Vec::new(); columns.len()];
+    let mut current: Option<&Vec<usize>> = None;
+    let mut nested: Option<JsonWriter<Vec<u8>>> = None;
+    let mut depth = 0;
+
+    while let Some(event) = parser.next_event()? {
+        if event == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf()?;
+            continue;
+        }
+
+        // serialize nested objects and arrays
+        if let Some(w) = &mut nested {
+            w.write_event(event, &parser)?;
+            match event {
+                JsonEvent::StartObject | JsonEvent::StartArray => depth += 1,
+                JsonEvent::EndObject | JsonEvent::EndArray => depth -= 1,
+                _ => {}
+            }
+            if depth == 2 {
+                let value = nested.take().map(|w| w.into_inner()).unwrap_or_default();
+                set_cells(&mut cells, current.take(), &value);
+            }
+            continue;
+        }
+
+        match (depth, event) {
+            (0, JsonEvent::StartArray) => depth = 1,
+            (1, JsonEvent::StartObject) => {
+                cells.iter_mut().for_each(|c| c.clear());
+                depth = 2;
+            }
+            (1, JsonEvent::EndArray) => depth = 0,
+            (2, JsonEvent::EndObject) => {
+                line.clear();
+                write_row(&mut line, cells.iter().map(|c| c.as_slice()), delimiter);
+                writer.write_all(&line)?;
+                depth = 1;
+            }
+            (2, JsonEvent::FieldName) => {
+                current = indices.get(parser.current_str()?);
+                if current.is_none() {
+                    parser.skip_value();
+                }
+            }
+            (2, JsonEvent::StartObject | JsonEvent::StartArray) => {
+                let mut w = JsonWriter::new(Vec::new());
+                w.write_event(event, &parser)?;
+                nested = Some(w);
+                depth = 3;
+            }
+            (2, JsonEvent::ValueString) => {
+                set_cells(&mut cells, current.take(), parser.current_str()?.as_bytes());
+            }
+            (2, JsonEvent::ValueInt | JsonEvent::ValueFloat) => {
+                let n = parser.current_raw_number()?;
+                set_cells(&mut cells, current.take(), n.as_bytes());
+            }
+            (2, JsonEvent::ValueTrue) => set_cells(&mut cells, current.take(), b"true"),
+            (2, JsonEvent::ValueFalse) => set_cells(&mut cells, current.take(), b"false"),
+            (2, JsonEvent::ValueNull) => set_cells(&mut cells, current.take(), b""),
+            _ => return Err(CsvError::NotAnArrayOfObjects),
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace the contents of the cells with the given indices
+fn set_cells(cells: &mut [Vec<u8>], indices: Option<&Vec<usize>>, value: &[u8]) {
+    for &i in indices.into_iter().flatten() {
+        cells[i].clear();
+        cells[i].extend_from_slice(value);
+    }
+}
+
+/// Append a row consisting of the given cells to the given buffer
+fn write_row<'a, I>(buf: &mut Vec<u8>, cells: I, delimiter: u8)
+where
+    I: Iterator<Item = &'a [u8]>,
+{
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            buf.push(delimiter);
+        }
+        let quote = cell
+            .iter()
+            .any(|&b| b == delimiter || matches!(b, b'"' | b'\r' | b'\n'));
+        if quote {
+            buf.push(b'"');
+            for &b in cell {
+                if b == b'"' {
+                    buf.push(b'"');
+                }
+                buf.push(b);
+            }
+            buf.push(b'"');
+        } else {
+            buf.extend_from_slice(cell);
+        }
+    }
+    buf.extend_from_slice(b"\r\n");
+}
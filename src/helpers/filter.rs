@@ -0,0 +1,102 @@
+//! Forward only the events of a JSON text up to a given nesting depth
+//!
+//! See [`DepthFilter`]
+use crate::feeder::JsonFeeder;
+use crate::parser::ParserError;
+use crate::{JsonEvent, JsonParser};
+
+/// A wrapper around a [`JsonParser`] that only forwards events up to a given
+/// nesting depth. Objects and arrays nested deeper than that are collapsed:
+/// none of their events are forwarded. Instead, a single
+/// [`JsonEvent::TruncatedValue`] is returned in their place, so the forwarded
+/// events remain balanced and every field name is still followed by a value.
+///
+/// The depth of a top-level object or array is 1. Its direct children are on
+/// depth 2, and so on. Scalar values are forwarded if the container they are
+/// in is forwarded. A maximum depth of 0 collapses all objects and arrays.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::SliceJsonFeeder;
+/// use actson::helpers::DepthFilter;
+///
+/// let json = r#"{"name": "Elvis", "albums": [{"title": "Elvis Is Back!"}], "age": 42}"#;
+/// let parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+/// let mut filter = DepthFilter::new(parser, 1);
+///
+/// let mut events = Vec::new();
+/// while let Some(e) = filter.next_event().unwrap() {
+///     events.push(e);
+/// }
+///
+/// assert_eq!(events, vec![
+///     JsonEvent::StartObject,
+///     JsonEvent::FieldName,
+///     JsonEvent::ValueString,
+///     JsonEvent::FieldName,
+///     JsonEvent::TruncatedValue,
+///     JsonEvent::FieldName,
+///     JsonEvent::ValueInt,
+///     JsonEvent::EndObject,
+/// ]);
+/// ```
+pub struct DepthFilter<T> {
+    /// The wrapped parser. Use it to access the feeder and the values of the
+    /// forwarded events.
+    pub parser: JsonParser<T>,
+
+    /// The maximum depth of the forwarded events
+    max_depth: usize,
+}
+
+impl<T> DepthFilter<T>
+where
+    T: JsonFeeder,
+{
+    /// Wrap the given parser and only forward events up to the given maximum
+    /// depth
+    pub fn new(parser: JsonParser<T>, max_depth: usize) -> Self {
+        DepthFilter { parser, max_depth }
+    }
+
+    /// Unwrap the filter and return the wrapped parser
+    pub fn into_inner(self) -> JsonParser<T> {
+        self.parser
+    }
+
+    /// Proceed parsing and return the next event that is not nested deeper
+    /// than the maximum depth. Behaves like [`JsonParser::next_event()`]
+    /// otherwise. [`JsonEvent::NeedMoreInput`] is always forwarded.
+    pub fn next_event(&mut self) -> Result<Option<JsonEvent>, ParserError> {
+        loop {
+            let event = self.parser.next_event()?;
+            let depth = self.parser.context().depth();
+            match event {
+                Some(JsonEvent::StartObject | JsonEvent::StartArray) => {
+                    if depth <= self.max_depth {
+                        return Ok(event);
+                    }
+                }
+
+                Some(JsonEvent::EndObject | JsonEvent::EndArray) => {
+                    // the depth of the container that has just been closed
+                    let depth = depth + 1;
+                    if depth <= self.max_depth {
+                        return Ok(event);
+                    }
+                    if depth == self.max_depth + 1 {
+                        return Ok(Some(JsonEvent::TruncatedValue));
+                    }
+                }
+
+                Some(JsonEvent::NeedMoreInput) | None => return Ok(event),
+
+                Some(_) => {
+                    if depth <= self.max_depth {
+                        return Ok(event);
+                    }
+                }
+            }
+        }
+    }
+}
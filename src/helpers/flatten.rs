@@ -0,0 +1,151 @@
+//! Flatten a JSON text into pairs of paths and scalar values
+//!
+//! See [`flatten()`]
+use std::io::{BufReader, Read};
+
+use thiserror::Error;
+
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::helpers::OwnedScalar;
+use crate::options::JsonParserOptionsBuilder;
+use crate::parser::{InvalidStringValueError, ParserError};
+use crate::path::PathSegment;
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while flattening a JSON text
+#[derive(Error, Debug)]
+pub enum FlattenError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+}
+
+/// An [`Iterator`] over the scalar values of a JSON text and their paths.
+/// Create it with [`flatten()`].
+///
+/// The iterator reads the JSON text incrementally. It ends after the first
+/// error.
+pub struct Flattened<R> {
+    parser: JsonParser<BufReaderJsonFeeder<R>>,
+
+    /// The character that separates the segments of a path
+    separator: char,
+
+    /// `true` if the iterator has ended
+    done: bool,
+}
+
+/// Create an [`Iterator`] that reads a JSON text from the given reader and
+/// yields a pair of a path and a value for each scalar value (string,
+/// number, boolean, or `null`) in the text (see [`Flattened`]). This is
+/// useful to load a JSON document into a flat store such as environment
+/// variables or a key-value database.
+///
+/// The path consists of the field names and array indexes leading to the
+/// value, joined with the given separator (e.g. `.` or `/`). Field names
+/// are not escaped, so a field name that contains the separator leads to an
+/// ambiguous path. Use [`JsonParser::current_pointer()`] directly if you
+/// need an unambiguous JSON Pointer. A top-level scalar has an empty path.
+/// Empty objects and arrays do not produce any pairs.
+///
+/// ```
+/// use actson::flatten;
+/// use actson::helpers::OwnedScalar;
+///
+/// let json = r#"{"db": {"host": "localhost", "ports": [5432, 5433]}, "debug": true}"#;
+///
+/// let pairs = flatten(json.as_bytes(), '.')
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(pairs, vec![
+///     ("db.host".to_string(), OwnedScalar::String("localhost".to_string())),
+///     ("db.ports.0".to_string(), OwnedScalar::Number("5432".to_string())),
+///     ("db.ports.1".to_string(), OwnedScalar::Number("5433".to_string())),
+///     ("debug".to_string(), OwnedScalar::Bool(true)),
+/// ]);
+/// ```
+pub fn flatten<R>(reader: R, separator: char) -> Flattened<R>
+where
+    R: Read,
+{
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    Flattened {
+        parser: JsonParser::new_with_options(
+            feeder,
+            JsonParserOptionsBuilder::default()
+                .with_path_tracking(true)
+                .build(),
+        ),
+        separator,
+        done: false,
+    }
+}
+
+impl<R> Flattened<R>
+where
+    R: Read,
+{
+    /// Read the next scalar value and its path or return `None` if the JSON
+    /// text has ended
+    fn next_pair(&mut self) -> Result<Option<(String, OwnedScalar)>, FlattenError> {
+        while let Some(event) = self.parser.next_event()? {
+            let value = match event {
+                JsonEvent::NeedMoreInput => {
+                    self.parser.feeder.fill_buf()?;
+                    continue;
+                }
+                JsonEvent::ValueString => {
+                    OwnedScalar::String(self.parser.current_str()?.to_string())
+                }
+                JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                    OwnedScalar::Number(self.parser.current_raw_number()?.to_string())
+                }
+                JsonEvent::ValueTrue => OwnedScalar::Bool(true),
+                JsonEvent::ValueFalse => OwnedScalar::Bool(false),
+                JsonEvent::ValueNull => OwnedScalar::Null,
+                _ => continue,
+            };
+            return Ok(Some((self.current_path(), value)));
+        }
+        Ok(None)
+    }
+
+    /// Join the segments of the parser's current path with the separator
+    fn current_path(&self) -> String {
+        let mut path = String::new();
+        for (i, segment) in self.parser.current_path().iter().enumerate() {
+            if i > 0 {
+                path.push(self.separator);
+            }
+            match segment {
+                PathSegment::Key(k) => path.push_str(k),
+                PathSegment::Index(i) => path.push_str(&i.to_string()),
+            }
+        }
+        path
+    }
+}
+
+impl<R> Iterator for Flattened<R>
+where
+    R: Read,
+{
+    type Item = Result<(String, OwnedScalar), FlattenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let r = self.next_pair().transpose();
+        if !matches!(r, Some(Ok(_))) {
+            self.done = true;
+        }
+        r
+    }
+}
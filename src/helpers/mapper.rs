@@ -0,0 +1,250 @@
+//! Map the values of a stream of JSON records to user-defined structs
+//!
+//! See [`RecordMapper`]
+use std::io::{BufReader, Read};
+
+use thiserror::Error;
+
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::options::JsonParserOptionsBuilder;
+use crate::parser::{InvalidScalarValueError, ParserError, Scalar};
+use crate::path::{matches_pointer, parse_pointer};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while mapping JSON records
+#[derive(Error, Debug)]
+pub enum MapperError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidScalarValue(#[from] InvalidScalarValueError),
+
+    /// The given JSON Pointer is invalid
+    #[error("invalid JSON pointer: `{0}'")]
+    InvalidPointer(String),
+}
+
+/// A function that sets a field of a record to a scalar value
+type Setter<T> = Box<dyn FnMut(&mut T, Scalar<'_>)>;
+
+/// Maps the scalar values of a stream of JSON records to instances of a
+/// user-defined type `T` without building a DOM and without a hand-written
+/// event loop. This is useful for ETL jobs that only need a few fields of
+/// each record.
+///
+/// Register a setter for each field with [`Self::with_field()`]. The
+/// setter is identified by a JSON Pointer (RFC 6901) relative to the
+/// record. Then call [`Self::map()`] to get an [`Iterator`] over the
+/// records of a JSON text. For each record, the mapper creates a new
+/// instance of `T` with [`Default::default()`] and calls the setters of all
+/// scalar values (strings, numbers, booleans, and `null`) whose pointers
+/// match. Fields that do not occur in a record keep their default values.
+/// Values without a matching setter as well as pointers that refer to an
+/// object or array are ignored.
+///
+/// If the top-level value of the JSON text is an array, each of its
+/// elements is a record. Otherwise, the top-level value itself is a record.
+/// The JSON text may contain multiple top-level values (e.g.
+/// newline-delimited JSON), each of which is handled the same way.
+///
+/// ```
+/// use actson::helpers::RecordMapper;
+/// use actson::parser::Scalar;
+///
+/// #[derive(Debug, Default, PartialEq)]
+/// struct Person {
+///     name: String,
+///     city: Option<String>,
+///     born: i64,
+/// }
+///
+/// let json = r#"[
+///     {"name": "Elvis", "address": {"city": "Memphis"}, "born": 1935},
+///     {"name": "Max", "born": 1948}
+/// ]"#;
+///
+/// let mut mapper = RecordMapper::new()
+///     .with_field("/name", |p: &mut Person, v| {
+///         if let Scalar::Str(s) = v {
+///             p.name = s.to_string();
+///         }
+///     })
+///     .unwrap()
+///     .with_field("/address/city", |p: &mut Person, v| {
+///         if let Scalar::Str(s) = v {
+///             p.city = Some(s.to_string());
+///         }
+///     })
+///     .unwrap()
+///     .with_field("/born", |p: &mut Person, v| {
+///         if let Scalar::Int(i) = v {
+///             p.born = i;
+///         }
+///     })
+///     .unwrap();
+///
+/// let people = mapper
+///     .map(json.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(people, vec![
+///     Person { name: "Elvis".to_string(), city: Some("Memphis".to_string()), born: 1935 },
+///     Person { name: "Max".to_string(), city: None, born: 1948 },
+/// ]);
+/// ```
+pub struct RecordMapper<T> {
+    /// The unescaped reference tokens of each field's JSON Pointer together
+    /// with the field's setter
+    fields: Vec<(Vec<String>, Setter<T>)>,
+}
+
+impl<T> Default for RecordMapper<T> {
+    fn default() -> Self {
+        RecordMapper { fields: Vec::new() }
+    }
+}
+
+impl<T> RecordMapper<T>
+where
+    T: Default,
+{
+    /// Create a new mapper without any fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a setter that will be called for each scalar value at the
+    /// given JSON Pointer (relative to the record). Multiple setters may be
+    /// registered for the same pointer. They are called in the order in
+    /// which they have been registered. Returns
+    /// [`MapperError::InvalidPointer`] if the pointer is invalid.
+    pub fn with_field<F>(mut self, pointer: &str, setter: F) -> Result<Self, MapperError>
+    where
+        F: FnMut(&mut T, Scalar<'_>) + 'static,
+    {
+        let tokens = parse_pointer(pointer)
+            .ok_or_else(|| MapperError::InvalidPointer(pointer.to_string()))?;
+        self.fields.push((tokens, Box::new(setter)));
+        Ok(self)
+    }
+
+    /// Create an [`Iterator`] that reads JSON records from the given reader
+    /// and yields an instance of `T` for each of them (see [`RecordMapper`]).
+    /// The iterator reads the JSON text incrementally. It ends after the
+    /// first error.
+    pub fn map<R>(&mut self, reader: R) -> Records<'_, R, T>
+    where
+        R: Read,
+    {
+        let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+        Records {
+            mapper: self,
+            parser: JsonParser::new_with_options(
+                feeder,
+                JsonParserOptionsBuilder::default()
+                    .with_streaming(true)
+                    .with_path_tracking(true)
+                    .build(),
+            ),
+            depth: 0,
+            in_array: false,
+            record: None,
+            done: false,
+        }
+    }
+}
+
+/// An [`Iterator`] over the records of a JSON text. Create it with
+/// [`RecordMapper::map()`].
+pub struct Records<'m, R, T> {
+    mapper: &'m mut RecordMapper<T>,
+    parser: JsonParser<BufReaderJsonFeeder<R>>,
+
+    /// The number of objects and arrays that are currently open
+    depth: usize,
+
+    /// `true` if the current top-level value is an array whose elements
+    /// are records
+    in_array: bool,
+
+    /// The record that is currently being mapped
+    record: Option<T>,
+
+    /// `true` if the iterator has ended
+    done: bool,
+}
+
+impl<R, T> Records<'_, R, T>
+where
+    R: Read,
+    T: Default,
+{
+    /// Read the next record or return `None` if the JSON text has ended
+    fn next_record(&mut self) -> Result<Option<T>, MapperError> {
+        while let Some(event) = self.parser.next_event()? {
+            if event == JsonEvent::NeedMoreInput {
+                self.parser.feeder.fill_buf()?;
+                continue;
+            }
+
+            if self.depth == 0 && event == JsonEvent::StartArray {
+                self.depth = 1;
+                self.in_array = true;
+                continue;
+            }
+            if self.in_array && self.depth == 1 && event == JsonEvent::EndArray {
+                self.depth = 0;
+                self.in_array = false;
+                continue;
+            }
+
+            let record_depth = usize::from(self.in_array);
+            let record = self.record.get_or_insert_with(T::default);
+
+            match event {
+                JsonEvent::StartObject | JsonEvent::StartArray => self.depth += 1,
+                JsonEvent::EndObject | JsonEvent::EndArray => self.depth -= 1,
+                JsonEvent::FieldName => {}
+                _ => {
+                    let path = &self.parser.current_path()[record_depth..];
+                    for (tokens, setter) in &mut self.mapper.fields {
+                        if matches_pointer(path, tokens) {
+                            setter(record, self.parser.current_scalar()?);
+                        }
+                    }
+                }
+            }
+
+            if self.depth == record_depth
+                && !matches!(event, JsonEvent::StartObject | JsonEvent::StartArray)
+            {
+                return Ok(self.record.take());
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R, T> Iterator for Records<'_, R, T>
+where
+    R: Read,
+    T: Default,
+{
+    type Item = Result<T, MapperError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let r = self.next_record().transpose();
+        if !matches!(r, Some(Ok(_))) {
+            self.done = true;
+        }
+        r
+    }
+}
@@ -0,0 +1,24 @@
+//! Convenience functions and adapters built on top of [`JsonParser`](crate::JsonParser)
+//!
+//! The most common functions are also re-exported from the crate root.
+mod canonical;
+mod csv;
+mod filter;
+mod flatten;
+mod mapper;
+mod trace;
+mod typecheck;
+mod typed;
+mod unique;
+mod validate;
+
+pub use canonical::{canonical_hash, CanonicalHashError};
+pub use csv::{to_csv, to_csv_with_options, CsvError, CsvOptions};
+pub use filter::DepthFilter;
+pub use flatten::{flatten, FlattenError, Flattened};
+pub use mapper::{MapperError, RecordMapper, Records};
+pub use trace::{EventTrace, RecordError, RecordedEvent};
+pub use typecheck::{check_top_level_types, TypeCheckError, TypeMismatch};
+pub use typed::{parse_vec_i64, parse_vec_string, ParseVecError};
+pub use unique::{unique_scalars, OwnedScalar, UniqueScalars, UniqueScalarsError};
+pub use validate::{validate_slice, validate_str};
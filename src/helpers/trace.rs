@@ -0,0 +1,133 @@
+//! Record the events of a JSON text, e.g. for golden-file tests
+//!
+//! See [`EventTrace`]
+use thiserror::Error;
+
+use crate::feeder::{JsonFeeder, SliceJsonFeeder};
+use crate::parser::{InvalidStringValueError, ParserError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while recording events
+#[derive(Error, Debug)]
+pub enum RecordError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+}
+
+/// An event that has been recorded by an [`EventTrace`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedEvent {
+    /// The event
+    pub event: JsonEvent,
+
+    /// The field name for [`JsonEvent::FieldName`], the string for
+    /// [`JsonEvent::ValueString`], the number exactly as it appeared in the
+    /// JSON text for [`JsonEvent::ValueInt`] and [`JsonEvent::ValueFloat`],
+    /// or `None` for all other events
+    pub value: Option<String>,
+
+    /// The number of objects and arrays that enclose the event. The start
+    /// and the end of an object or array have the same depth as the object
+    /// or array itself (e.g. 0 for the top-level value).
+    pub depth: usize,
+}
+
+/// A sequence of events recorded while parsing a JSON text. If the `serde`
+/// feature is enabled, a trace can be serialized (e.g. to JSON with
+/// `serde_json`) as a list of [`RecordedEvent`]s and deserialized again.
+/// This is useful to compare the parser's output with a golden file or to
+/// share a reproduction of a problem.
+///
+/// ```
+/// use actson::helpers::EventTrace;
+/// use actson::JsonEvent;
+///
+/// let trace = EventTrace::from_slice(br#"{"a": [1.5]}"#).unwrap();
+/// let events = trace.events();
+/// assert_eq!(events.len(), 6);
+/// assert_eq!(events[1].event, JsonEvent::FieldName);
+/// assert_eq!(events[1].value.as_deref(), Some("a"));
+/// assert_eq!(events[3].value.as_deref(), Some("1.5"));
+/// assert_eq!(events[3].depth, 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct EventTrace {
+    events: Vec<RecordedEvent>,
+}
+
+impl EventTrace {
+    /// Create a new empty trace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the given slice and record all of its events
+    pub fn from_slice(json: &[u8]) -> Result<Self, RecordError> {
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+        let mut trace = Self::new();
+        while let Some(event) = parser.next_event()? {
+            trace.record(event, &parser)?;
+        }
+        Ok(trace)
+    }
+
+    /// Record the given event. Call this method for each event returned by
+    /// [`JsonParser::next_event()`] and pass the parser so the trace can get
+    /// the current value. [`JsonEvent::NeedMoreInput`] is ignored.
+    pub fn record<T>(&mut self, event: JsonEvent, parser: &JsonParser<T>) -> Result<(), RecordError>
+    where
+        T: JsonFeeder,
+    {
+        let value = match event {
+            JsonEvent::NeedMoreInput => return Ok(()),
+            JsonEvent::FieldName | JsonEvent::ValueString => {
+                Some(parser.current_str()?.to_string())
+            }
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                Some(parser.current_raw_number()?.to_string())
+            }
+            _ => None,
+        };
+
+        // the depth after the previous event
+        let depth = match self.events.last() {
+            Some(RecordedEvent {
+                event: JsonEvent::StartObject | JsonEvent::StartArray,
+                depth,
+                ..
+            }) => depth + 1,
+            Some(e) => e.depth,
+            None => 0,
+        };
+        let depth = match event {
+            JsonEvent::EndObject | JsonEvent::EndArray => depth.saturating_sub(1),
+            _ => depth,
+        };
+
+        self.events.push(RecordedEvent {
+            event,
+            value,
+            depth,
+        });
+        Ok(())
+    }
+
+    /// Get the recorded events
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Consume the trace and return the recorded events
+    pub fn into_events(self) -> Vec<RecordedEvent> {
+        self.events
+    }
+}
@@ -0,0 +1,142 @@
+//! Check the types of the top-level fields of an object
+//!
+//! See [`check_top_level_types()`]
+use std::collections::HashMap;
+use std::io::{BufReader, Read};
+
+use thiserror::Error;
+
+use crate::event::JsonEventKind;
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::options::JsonParserOptionsBuilder;
+use crate::parser::ParserError;
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while checking the types of the top-level fields
+#[derive(Error, Debug)]
+pub enum TypeCheckError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    /// The top-level value is not an object
+    #[error("expected an object")]
+    NotAnObject,
+}
+
+/// A top-level field whose value does not have the expected kind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeMismatch {
+    /// A JSON Pointer (RFC 6901) to the field (e.g. `/name`)
+    pub pointer: String,
+
+    /// The expected kind
+    pub expected: JsonEventKind,
+
+    /// The kind of the actual value. Integers are reported as
+    /// [`JsonEventKind::Integer`] and floating point numbers as
+    /// [`JsonEventKind::Number`].
+    pub found: JsonEventKind,
+}
+
+/// Read a JSON object from the given reader and check if the values of its
+/// top-level fields have the expected kinds. Fields that do not appear in
+/// `types` are ignored, and so are fields from `types` that are missing in
+/// the object. If a field appears more than once, each occurrence is checked.
+///
+/// The object is processed incrementally, and the values of the fields are
+/// skipped without being kept in memory, so this function can check large
+/// documents cheaply. It does not stop at the first mismatch but returns all
+/// of them in the order they appear in the document. The function returns an
+/// error if the JSON text is invalid or if the top-level value is not an
+/// object.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use actson::{check_top_level_types, JsonEventKind};
+/// use actson::helpers::TypeMismatch;
+///
+/// let json = r#"{"name": "Elvis", "born": "1935", "albums": [], "alive": false}"#;
+/// let types = HashMap::from([
+///     ("name", JsonEventKind::String),
+///     ("born", JsonEventKind::Integer),
+///     ("albums", JsonEventKind::Array),
+///     ("alive", JsonEventKind::Null),
+///     ("genre", JsonEventKind::String),
+/// ]);
+///
+/// let mismatches = check_top_level_types(json.as_bytes(), &types).unwrap();
+/// assert_eq!(mismatches, vec![
+///     TypeMismatch {
+///         pointer: "/born".to_string(),
+///         expected: JsonEventKind::Integer,
+///         found: JsonEventKind::String,
+///     },
+///     TypeMismatch {
+///         pointer: "/alive".to_string(),
+///         expected: JsonEventKind::Null,
+///         found: JsonEventKind::Boolean,
+///     },
+/// ]);
+/// ```
+pub fn check_top_level_types<R>(
+    reader: R,
+    types: &HashMap<&str, JsonEventKind>,
+) -> Result<Vec<TypeMismatch>, TypeCheckError>
+where
+    R: Read,
+{
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    let mut parser = JsonParser::new_with_options(
+        feeder,
+        JsonParserOptionsBuilder::default()
+            .with_path_tracking(true)
+            .build(),
+    );
+
+    let mut mismatches = Vec::new();
+    let mut started = false;
+    let mut expected = None;
+
+    while let Some(event) = parser.next_event()? {
+        match event {
+            JsonEvent::NeedMoreInput => parser.feeder.fill_buf()?,
+
+            _ if !started => {
+                if event != JsonEvent::StartObject {
+                    return Err(TypeCheckError::NotAnObject);
+                }
+                started = true;
+            }
+
+            JsonEvent::FieldName => {
+                let kind = parser.current_str().ok().and_then(|k| types.get(k));
+                match kind {
+                    Some(&kind) => expected = Some((kind, parser.current_pointer())),
+                    None => parser.skip_value(),
+                }
+            }
+
+            JsonEvent::EndObject => {}
+
+            _ => {
+                if let Some((kind, pointer)) = expected.take() {
+                    if let Some(found) = JsonEventKind::of(event) {
+                        if !kind.matches(event) {
+                            mismatches.push(TypeMismatch {
+                                pointer,
+                                expected: kind,
+                                found,
+                            });
+                        }
+                    }
+                }
+                parser.skip_value();
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
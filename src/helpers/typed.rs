@@ -0,0 +1,150 @@
+//! Parse top-level arrays of a single scalar type into a [`Vec`]
+//!
+//! See [`parse_vec_i64()`] and [`parse_vec_string()`]
+use std::io::{BufReader, Read};
+
+use thiserror::Error;
+
+use crate::event::JsonEventKind;
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::parser::{InvalidIntValueError, InvalidStringValueError, ParserError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while parsing an array into a [`Vec`]
+#[derive(Error, Debug)]
+pub enum ParseVecError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+
+    #[error("{0}")]
+    InvalidIntValue(#[from] InvalidIntValueError),
+
+    /// The top-level value is not an array
+    #[error("expected an array")]
+    NotAnArray,
+
+    /// An element of the array does not have the expected kind
+    #[error("expected {expected:?} at `{pointer}' but found {found:?}")]
+    UnexpectedElement {
+        /// A JSON Pointer (RFC 6901) to the element (e.g. `/3`)
+        pointer: String,
+
+        /// The expected kind
+        expected: JsonEventKind,
+
+        /// The kind of the actual element. Integers are reported as
+        /// [`JsonEventKind::Integer`] and floating point numbers as
+        /// [`JsonEventKind::Number`].
+        found: JsonEventKind,
+    },
+}
+
+/// Read a top-level JSON array of integers from the given reader and
+/// collect its elements into a [`Vec`]
+///
+/// The function returns [`ParseVecError::NotAnArray`] if the top-level value
+/// is not an array and [`ParseVecError::UnexpectedElement`] if one of the
+/// elements is not an integer (this includes floating point numbers such as
+/// `1.0`). Integers that do not fit into an `i64` cause an
+/// [`ParseVecError::InvalidIntValue`] error.
+///
+/// ```
+/// use actson::parse_vec_i64;
+/// use actson::helpers::ParseVecError;
+/// use actson::JsonEventKind;
+///
+/// let v = parse_vec_i64("[1, -2, 3]".as_bytes()).unwrap();
+/// assert_eq!(v, vec![1, -2, 3]);
+///
+/// let err = parse_vec_i64("[1, 2.5, 3]".as_bytes()).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     ParseVecError::UnexpectedElement {
+///         pointer,
+///         expected: JsonEventKind::Integer,
+///         found: JsonEventKind::Number,
+///     } if pointer == "/1"
+/// ));
+/// ```
+pub fn parse_vec_i64<R>(reader: R) -> Result<Vec<i64>, ParseVecError>
+where
+    R: Read,
+{
+    parse_vec(reader, JsonEventKind::Integer, |parser| {
+        Ok(parser.current_int()?)
+    })
+}
+
+/// Read a top-level JSON array of strings from the given reader and collect
+/// its elements into a [`Vec`]
+///
+/// The function returns [`ParseVecError::NotAnArray`] if the top-level value
+/// is not an array and [`ParseVecError::UnexpectedElement`] if one of the
+/// elements is not a string.
+///
+/// ```
+/// use actson::parse_vec_string;
+///
+/// let v = parse_vec_string(r#"["Elvis", "Max\n"]"#.as_bytes()).unwrap();
+/// assert_eq!(v, vec!["Elvis", "Max\n"]);
+/// ```
+pub fn parse_vec_string<R>(reader: R) -> Result<Vec<String>, ParseVecError>
+where
+    R: Read,
+{
+    parse_vec(reader, JsonEventKind::String, |parser| {
+        Ok(parser.current_str()?.to_string())
+    })
+}
+
+/// Read a top-level JSON array whose elements have the given kind and
+/// convert each of them with `convert`
+fn parse_vec<R, T, F>(
+    reader: R,
+    expected: JsonEventKind,
+    convert: F,
+) -> Result<Vec<T>, ParseVecError>
+where
+    R: Read,
+    F: Fn(&JsonParser<BufReaderJsonFeeder<R>>) -> Result<T, ParseVecError>,
+{
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    let mut parser = JsonParser::new(feeder);
+
+    let mut result = Vec::new();
+    let mut started = false;
+
+    while let Some(event) = parser.next_event()? {
+        match event {
+            JsonEvent::NeedMoreInput => parser.feeder.fill_buf()?,
+
+            _ if !started => {
+                if event != JsonEvent::StartArray {
+                    return Err(ParseVecError::NotAnArray);
+                }
+                started = true;
+            }
+
+            JsonEvent::EndArray => {}
+
+            _ => {
+                if !expected.matches(event) {
+                    return Err(ParseVecError::UnexpectedElement {
+                        pointer: format!("/{}", result.len()),
+                        expected,
+                        found: JsonEventKind::of(event).unwrap_or(expected),
+                    });
+                }
+                result.push(convert(&parser)?);
+            }
+        }
+    }
+
+    Ok(result)
+}
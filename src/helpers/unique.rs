@@ -0,0 +1,177 @@
+//! Deduplicate the elements of a top-level array of scalars
+//!
+//! See [`unique_scalars()`]
+use std::collections::HashSet;
+use std::io::{BufReader, Read};
+
+use thiserror::Error;
+
+use crate::feeder::{BufReaderJsonFeeder, FeederError};
+use crate::parser::{InvalidStringValueError, ParserError};
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen while deduplicating the elements of an array
+#[derive(Error, Debug)]
+pub enum UniqueScalarsError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+
+    /// The top-level value is not an array or the array contains an object
+    /// or an array
+    #[error("expected an array of scalars")]
+    NotAnArrayOfScalars,
+}
+
+/// A scalar value (i.e. a value that is neither an object nor an array)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OwnedScalar {
+    /// A string
+    String(String),
+
+    /// A number, exactly as it appeared in the JSON text
+    Number(String),
+
+    /// `true` or `false`
+    Bool(bool),
+
+    /// `null`
+    Null,
+}
+
+/// An [`Iterator`] over the distinct elements of a top-level JSON array of
+/// scalars. Create it with [`unique_scalars()`].
+///
+/// The iterator reads the array incrementally and yields each value the
+/// first time it appears. It ends after the first error.
+pub struct UniqueScalars<R> {
+    parser: JsonParser<BufReaderJsonFeeder<R>>,
+
+    /// The values that have already been yielded
+    seen: HashSet<OwnedScalar>,
+
+    /// `true` if the start of the array has been read
+    started: bool,
+
+    /// `true` if the iterator has ended
+    done: bool,
+}
+
+/// Create an [`Iterator`] that reads a top-level JSON array of scalars
+/// (strings, numbers, booleans, and `null`) from the given reader and yields
+/// only the first occurrence of each value (see [`UniqueScalars`]).
+///
+/// The array itself is never kept in memory, but the iterator has to
+/// remember every distinct value it has yielded, so its memory consumption
+/// is proportional to the number (and size) of distinct values. Use this
+/// function for large arrays with a limited number of distinct values.
+///
+/// Numbers are compared by their text, exactly as they appear in the input,
+/// so `1`, `1.0`, and `1e0` are considered different values. Strings are
+/// compared after escape sequences have been decoded. The iterator yields
+/// [`UniqueScalarsError::NotAnArrayOfScalars`] if the top-level value is not
+/// an array or if the array contains an object or another array.
+///
+/// ```
+/// use actson::unique_scalars;
+/// use actson::helpers::OwnedScalar;
+///
+/// let json = r#"["a", 1, "b", "a", 1, null, 1.0, null, true]"#;
+///
+/// let unique = unique_scalars(json.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(unique, vec![
+///     OwnedScalar::String("a".to_string()),
+///     OwnedScalar::Number("1".to_string()),
+///     OwnedScalar::String("b".to_string()),
+///     OwnedScalar::Null,
+///     OwnedScalar::Number("1.0".to_string()),
+///     OwnedScalar::Bool(true),
+/// ]);
+/// ```
+pub fn unique_scalars<R>(reader: R) -> UniqueScalars<R>
+where
+    R: Read,
+{
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    UniqueScalars {
+        parser: JsonParser::new(feeder),
+        seen: HashSet::new(),
+        started: false,
+        done: false,
+    }
+}
+
+impl<R> UniqueScalars<R>
+where
+    R: Read,
+{
+    /// Read the next scalar from the array (regardless of whether it has
+    /// already been seen) or return `None` if the array has ended
+    fn next_scalar(&mut self) -> Result<Option<OwnedScalar>, UniqueScalarsError> {
+        while let Some(event) = self.parser.next_event()? {
+            let scalar = match event {
+                JsonEvent::NeedMoreInput => {
+                    self.parser.feeder.fill_buf()?;
+                    continue;
+                }
+
+                JsonEvent::StartArray if !self.started => {
+                    self.started = true;
+                    continue;
+                }
+
+                _ if !self.started => return Err(UniqueScalarsError::NotAnArrayOfScalars),
+
+                JsonEvent::EndArray => continue,
+
+                JsonEvent::ValueString => {
+                    OwnedScalar::String(self.parser.current_str()?.to_string())
+                }
+                JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                    OwnedScalar::Number(self.parser.current_raw_number()?.to_string())
+                }
+                JsonEvent::ValueTrue => OwnedScalar::Bool(true),
+                JsonEvent::ValueFalse => OwnedScalar::Bool(false),
+                JsonEvent::ValueNull => OwnedScalar::Null,
+
+                _ => return Err(UniqueScalarsError::NotAnArrayOfScalars),
+            };
+            return Ok(Some(scalar));
+        }
+        Ok(None)
+    }
+}
+
+impl<R> Iterator for UniqueScalars<R>
+where
+    R: Read,
+{
+    type Item = Result<OwnedScalar, UniqueScalarsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.next_scalar() {
+                Ok(Some(scalar)) => {
+                    if !self.seen.contains(&scalar) {
+                        self.seen.insert(scalar.clone());
+                        return Some(Ok(scalar));
+                    }
+                }
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
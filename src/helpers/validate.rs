@@ -0,0 +1,32 @@
+use crate::feeder::SliceJsonFeeder;
+use crate::parser::ParserError;
+use crate::JsonParser;
+
+/// Check if a string contains a single, complete, and valid JSON value
+/// without building any representation of it in memory
+///
+/// ```
+/// use actson::validate_str;
+///
+/// assert!(validate_str(r#"{"name": "Elvis"}"#).is_ok());
+/// assert!(validate_str(r#"{"name": }"#).is_err());
+/// ```
+pub fn validate_str(s: &str) -> Result<(), ParserError> {
+    validate_slice(s.as_bytes())
+}
+
+/// Check if a byte slice contains a single, complete, and valid JSON value
+/// without building any representation of it in memory
+///
+/// ```
+/// use actson::validate_slice;
+///
+/// assert!(validate_slice(b"[1, 2, 3]").is_ok());
+/// assert!(validate_slice(b"[1, 2, 3] 4").is_err());
+/// ```
+pub fn validate_slice(v: &[u8]) -> Result<(), ParserError> {
+    let feeder = SliceJsonFeeder::new(v);
+    let mut parser = JsonParser::new(feeder);
+    while parser.next_event()?.is_some() {}
+    Ok(())
+}
@@ -149,6 +149,14 @@
 //! assert_eq!(value["name"], "Elvis");
 //! ```
 //!
+//! Object keys are sorted by default. Enable the `preserve_order` feature and
+//! use `serde_json::ValueOptionsBuilder::with_preserve_order()` if you need
+//! to keep the order in which they appear in the JSON text.
+//!
+//! If you only have a string, `parse_str()` is a shortcut for the same thing.
+//! To just check if a string is valid JSON without building a Value, use
+//! [`validate_str`] instead.
+//!
 //! However, if you find yourself doing this, you probably don't need the
 //! reactive features of Actson and your data seems to completely fit into
 //! memory. In this case, you're most likely better off using Serde JSON
@@ -209,10 +217,47 @@
 //!     JsonEvent::ValueTrue,
 //! ]);
 //! ```
+//!
+//! ### Logging with `tracing`
+//!
+//! If the `tracing` feature is enabled, [`JsonParser::next_event()`] emits
+//! [`tracing`](https://docs.rs/tracing) events with the target `actson`.
+//! This makes it possible to debug a parser embedded deep in a service
+//! with the structured logging infrastructure that is already in place.
+//! If the feature is disabled, no code is generated at all.
+//!
+//! | Level   | Message                            | Emitted when                                |
+//! |---------|------------------------------------|---------------------------------------------|
+//! | `TRACE` | `JSON event`                       | an event is returned                        |
+//! | `TRACE` | `end of JSON text`                 | `None` is returned                          |
+//! | `WARN`  | `recovered from invalid JSON text` | a [lenient](options::JsonParserOptionsBuilder::with_lenient_structure()) parser skips an error |
+//! | `ERROR` | `invalid JSON text`                | an error is returned for the first time     |
+//!
+//! Each event has the following fields:
+//!
+//! * `depth` – the number of objects and arrays that are open after the
+//!   event (or at the position of the error)
+//! * `offset` – the number of bytes parsed so far (see
+//!   [`JsonParser::parsed_bytes()`])
+//! * `event` – the [`JsonEvent`] (`TRACE` level only, `Debug` formatted)
+//! * `error` – the error message (`WARN` and `ERROR` level only)
+//! * `category` – the [`ErrorCategory`](parser::ErrorCategory) of the
+//!   error (`ERROR` level only, `Debug` formatted)
+pub mod base64;
+pub mod batch;
+pub mod cursor;
 pub mod event;
 pub mod feeder;
+pub mod helpers;
 pub mod options;
 pub mod parser;
+pub mod path;
+pub mod pool;
+pub mod validator;
+pub mod writer;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
@@ -220,5 +265,15 @@ pub mod tokio;
 #[cfg(feature = "serde_json")]
 pub mod serde_json;
 
-pub use event::JsonEvent;
+pub use cursor::JsonCursor;
+pub use event::{JsonEvent, JsonEventKind};
+pub use helpers::{
+    canonical_hash, check_top_level_types, flatten, parse_vec_i64, parse_vec_string, to_csv,
+    unique_scalars, validate_slice, validate_str,
+};
 pub use parser::JsonParser;
+
+#[cfg(feature = "serde_json")]
+pub use serde_json::{
+    extract_pointer, from_str as parse_str, ndjson_values, parse_embedded, values_from_slice_iter,
+};
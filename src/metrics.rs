@@ -0,0 +1,38 @@
+//! Counters that help to find out where the parser spends its time
+//!
+//! This module is only available if the `metrics` feature is enabled. See
+//! [`JsonParser::metrics()`](crate::JsonParser::metrics())
+
+/// A snapshot of the counters a [`JsonParser`](crate::JsonParser) keeps
+/// while it parses a JSON text. Get it with
+/// [`JsonParser::metrics()`](crate::JsonParser::metrics()).
+///
+/// The counters help to decide whether a bottleneck is caused by feeding the
+/// parser (many [`need_more_input`](Self::need_more_input) events for only a
+/// few bytes) or by scanning the input (and which kind of input takes up the
+/// most bytes). Use [`JsonParser::event_count()`](crate::JsonParser::event_count())
+/// to get the number of events and
+/// [`JsonParser::parsed_bytes()`](crate::JsonParser::parsed_bytes()) to get
+/// the total number of bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParserMetrics {
+    /// The number of times the parser returned
+    /// [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput)
+    /// (i.e. the number of times the feeder had to be refilled)
+    pub need_more_input: u64,
+
+    /// The number of bytes scanned inside strings and field names,
+    /// including the quotes
+    pub string_bytes: u64,
+
+    /// The number of bytes scanned inside numbers
+    pub number_bytes: u64,
+
+    /// The number of white space bytes scanned between tokens
+    pub whitespace_bytes: u64,
+
+    /// The number of all other bytes (brackets, braces, colons, commas, and
+    /// the literals `true`, `false`, and `null`)
+    pub other_bytes: u64,
+}
@@ -1,13 +1,279 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::parser::RecoveredError;
+
+/// The default maximum stack depth (see
+/// [`JsonParserOptionsBuilder::with_max_depth()`])
+pub const DEFAULT_MAX_DEPTH: usize = 2048;
+
 /// Options for [`JsonParser`](super::JsonParser). Use [`JsonParserOptionsBuilder`]
 /// to create instances of this struct.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct JsonParserOptions {
     /// The maximum stack depth
     pub(super) max_depth: usize,
 
+    /// The maximum number of arrays that can be open at the same time
+    pub(super) max_array_depth: Option<usize>,
+
+    /// The maximum number of objects that can be open at the same time
+    pub(super) max_object_depth: Option<usize>,
+
+    /// The maximum length of strings and field names in bytes
+    pub(super) max_string_length: Option<usize>,
+
+    /// The maximum length of strings and field names in characters
+    pub(super) max_string_chars: Option<usize>,
+
     /// `true` if streaming mode should be enabled, which means that the parser
     /// will be able to handle a stream of multiple JSON values
     pub(super) streaming: bool,
+
+    /// The maximum number of top-level values in streaming mode
+    pub(super) max_values: Option<usize>,
+
+    /// `true` if the parser should handle a JSON text sequence (RFC 7464),
+    /// which means that each JSON value is preceded by an ASCII record
+    /// separator (`0x1E`)
+    pub(super) json_seq: bool,
+
+    /// `true` if objects and arrays that would exceed the maximum stack depth
+    /// should be skipped instead of causing an error
+    pub(super) skip_beyond_max_depth: bool,
+
+    /// `true` if objects and arrays that would exceed a depth limit should
+    /// be flattened into the deepest allowed object or array instead of
+    /// causing an error
+    pub(super) clamp_depth: bool,
+
+    /// `true` if an empty input (or one that only consists of white space)
+    /// should be treated like the end of the input and not as an error
+    pub(super) allow_empty_input: bool,
+
+    /// `true` if an incomplete top-level value at the end of the input
+    /// should be treated like the end of the input and not as an error
+    pub(super) tail: bool,
+
+    /// `true` if a first line starting with `#` should be skipped
+    pub(super) leading_shebang: bool,
+
+    /// `true` if the parser should keep track of the path to the current
+    /// value
+    pub(super) path_tracking: bool,
+
+    /// `true` if field names should be interned
+    pub(super) intern_field_names: bool,
+
+    /// `true` if numbers with an exponent should be rejected
+    pub(super) disallow_exponents: bool,
+
+    /// `true` if numbers with a fraction should be rejected
+    pub(super) disallow_fractions: bool,
+
+    /// `true` if integers that cannot be represented exactly by a 64-bit
+    /// floating point number should be rejected
+    pub(super) safe_integers: bool,
+
+    /// `true` if underscores between the digits of a number should be
+    /// accepted
+    pub(super) underscore_separators: bool,
+
+    /// `true` if a missing comma or colon should be tolerated
+    pub(super) lenient_structure: bool,
+
+    /// `true` if string values can be consumed incrementally
+    pub(super) streaming_strings: bool,
+
+    /// `true` if the parser should keep track of the current line and column
+    pub(super) position_tracking: bool,
+
+    /// `true` if the parser should keep the original text of each scalar
+    /// value
+    pub(super) raw_values: bool,
+
+    /// `true` if empty field names should be rejected
+    pub(super) reject_empty_keys: bool,
+
+    /// A predicate that returns `true` for field names that should be
+    /// rejected
+    pub(super) reject_keys: Option<SharedFn<KeyPredicateFn>>,
+
+    /// A hook that decides whether a number should be accepted, rejected,
+    /// or rewritten
+    pub(super) number_hook: Option<SharedFn<NumberHookFn>>,
+
+    /// A hook that is called for numbers that are not written in canonical
+    /// form
+    pub(super) noncanonical_number_hook: Option<SharedFn<NoncanonicalNumberHookFn>>,
+
+    /// The rules that decide whether a number is written in canonical form
+    pub(super) canonical_number_rules: CanonicalNumberRules,
+
+    /// A hook that is called whenever the parser has recovered from a
+    /// structural error
+    pub(super) recovery_hook: Option<SharedFn<RecoveryHookFn>>,
+
+    /// `true` if scalar values should only be reported as
+    /// [`JsonEvent::Scalar`](crate::JsonEvent::Scalar)
+    pub(super) structure_only: bool,
+
+    /// `true` if the parser should signal the end of the JSON text after it
+    /// has returned an error
+    pub(super) end_after_error: bool,
+
+    /// The point in time after which parsing should be aborted
+    pub(super) deadline: Option<Instant>,
+
+    /// `true` if the JSON text may be wrapped in a JSONP callback
+    pub(super) jsonp: bool,
+
+    /// `true` if decoded strings and field names should be normalized to
+    /// Unicode Normalization Form C (NFC)
+    #[cfg(feature = "unicode-normalization")]
+    pub(super) nfc_strings: bool,
+}
+
+/// A closure that is shared between all parsers created with the same
+/// options. Closures are compared by the address of the shared closure,
+/// which is good enough to compare options.
+pub(super) struct SharedFn<F: ?Sized>(pub(super) Arc<F>);
+
+impl<F: ?Sized> Clone for SharedFn<F> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> PartialEq for SharedFn<F> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<F: ?Sized> Eq for SharedFn<F> {}
+
+impl<F: ?Sized> Debug for SharedFn<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedFn")
+    }
+}
+
+/// The signature of a predicate for field names that should be rejected
+/// (see [`JsonParserOptionsBuilder::with_reject_keys()`])
+pub type KeyPredicateFn = dyn Fn(&str) -> bool + Send + Sync;
+
+/// The signature of a hook that decides what happens with a number (see
+/// [`JsonParserOptionsBuilder::with_number_hook()`])
+pub type NumberHookFn = dyn Fn(&str) -> NumberDecision + Send + Sync;
+
+/// The signature of a hook for numbers that are not written in canonical
+/// form (see [`JsonParserOptionsBuilder::with_noncanonical_number_hook()`])
+pub type NoncanonicalNumberHookFn = dyn Fn(&str, &str) + Send + Sync;
+
+/// The signature of a hook for recovered structural errors (see
+/// [`JsonParserOptionsBuilder::with_recovery_hook()`])
+pub type RecoveryHookFn = dyn Fn(&RecoveredError, &str) + Send + Sync;
+
+/// Decides what happens with a number (see
+/// [`JsonParserOptionsBuilder::with_number_hook()`])
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NumberDecision {
+    /// Accept the number as it is
+    Accept,
+
+    /// Reject the number with
+    /// [`ParserError::NumberRejected`](crate::parser::ParserError::NumberRejected)
+    Reject,
+
+    /// Replace the number with the given text. The text must be a valid
+    /// JSON number, otherwise the number is rejected with
+    /// [`ParserError::NumberRejected`](crate::parser::ParserError::NumberRejected).
+    Rewrite(String),
+}
+
+/// The rules that decide whether a number is written in canonical form
+/// (see [`JsonParserOptionsBuilder::with_noncanonical_number_hook()`]). All
+/// rules are enabled by default. Disable the ones your style guide does not
+/// require.
+///
+/// ```rust
+/// use actson::options::CanonicalNumberRules;
+///
+/// let mut rules = CanonicalNumberRules::default();
+/// assert!(!rules.is_canonical("1.500"));
+/// assert!(!rules.is_canonical("1E5"));
+/// assert!(rules.is_canonical("1.5e5"));
+///
+/// rules.no_trailing_zeros = false;
+/// assert!(rules.is_canonical("1.500"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CanonicalNumberRules {
+    /// The exponent must be introduced by a lowercase `e` (e.g. `1e5`
+    /// instead of `1E5`)
+    pub lowercase_exponent: bool,
+
+    /// The exponent must not have a plus sign (e.g. `1e5` instead of
+    /// `1e+5`)
+    pub no_exponent_plus: bool,
+
+    /// The exponent must not have leading zeros (e.g. `1e5` instead of
+    /// `1e05`)
+    pub no_exponent_leading_zeros: bool,
+
+    /// The fraction must not have trailing zeros (e.g. `1.5` instead of
+    /// `1.500`, and `1` instead of `1.0`)
+    pub no_trailing_zeros: bool,
+}
+
+impl Default for CanonicalNumberRules {
+    /// Returns rules with all checks enabled
+    fn default() -> Self {
+        Self {
+            lowercase_exponent: true,
+            no_exponent_plus: true,
+            no_exponent_leading_zeros: true,
+            no_trailing_zeros: true,
+        }
+    }
+}
+
+impl CanonicalNumberRules {
+    /// Check if the given number (a valid JSON number) complies with the
+    /// enabled rules
+    pub fn is_canonical(&self, number: &str) -> bool {
+        let (mantissa, exponent) = match number.find(['e', 'E']) {
+            Some(i) => (&number[..i], Some(&number[i..])),
+            None => (number, None),
+        };
+
+        if let Some(exponent) = exponent {
+            if self.lowercase_exponent && exponent.starts_with('E') {
+                return false;
+            }
+            let exponent = &exponent[1..];
+            if self.no_exponent_plus && exponent.starts_with('+') {
+                return false;
+            }
+            let digits = exponent.trim_start_matches(['+', '-']);
+            if self.no_exponent_leading_zeros && digits.len() > 1 && digits.starts_with('0') {
+                return false;
+            }
+        }
+
+        if self.no_trailing_zeros {
+            if let Some((_, fraction)) = mantissa.split_once('.') {
+                if fraction.ends_with('0') {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
 }
 
 /// A builder for [`JsonParserOptions`]
@@ -25,7 +291,7 @@ pub struct JsonParserOptions {
 ///         .build(),
 /// );
 /// ```
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct JsonParserOptionsBuilder {
     options: JsonParserOptions,
 }
@@ -34,32 +300,375 @@ impl Default for JsonParserOptions {
     /// Returns default JSON parser options
     fn default() -> Self {
         Self {
-            max_depth: 2048,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_array_depth: None,
+            max_object_depth: None,
+            max_string_length: None,
+            max_string_chars: None,
             streaming: false,
+            max_values: None,
+            json_seq: false,
+            skip_beyond_max_depth: false,
+            clamp_depth: false,
+            allow_empty_input: false,
+            tail: false,
+            leading_shebang: false,
+            path_tracking: false,
+            intern_field_names: false,
+            disallow_exponents: false,
+            disallow_fractions: false,
+            safe_integers: false,
+            underscore_separators: false,
+            lenient_structure: false,
+            streaming_strings: false,
+            position_tracking: false,
+            raw_values: false,
+            reject_empty_keys: false,
+            reject_keys: None,
+            number_hook: None,
+            noncanonical_number_hook: None,
+            canonical_number_rules: CanonicalNumberRules::default(),
+            recovery_hook: None,
+            structure_only: false,
+            end_after_error: false,
+            deadline: None,
+            jsonp: false,
+            #[cfg(feature = "unicode-normalization")]
+            nfc_strings: false,
         }
     }
 }
 
 impl JsonParserOptions {
-    /// Returns the maximum stack depth
+    /// Returns the maximum stack depth. This is [`DEFAULT_MAX_DEPTH`] by
+    /// default and [`usize::MAX`] if the depth is unlimited (see
+    /// [`JsonParserOptionsBuilder::with_unlimited_depth()`]).
     pub fn max_depth(&self) -> usize {
         self.max_depth
     }
 
+    /// Returns the maximum number of arrays that can be open at the same
+    /// time or `None` if only the maximum stack depth applies
+    pub fn max_array_depth(&self) -> Option<usize> {
+        self.max_array_depth
+    }
+
+    /// Returns the maximum number of objects that can be open at the same
+    /// time or `None` if only the maximum stack depth applies
+    pub fn max_object_depth(&self) -> Option<usize> {
+        self.max_object_depth
+    }
+
+    /// Returns the maximum length of strings and field names in bytes or
+    /// `None` if there is no such limit
+    pub fn max_string_length(&self) -> Option<usize> {
+        self.max_string_length
+    }
+
+    /// Returns the maximum length of strings and field names in characters
+    /// (Unicode scalar values) or `None` if there is no such limit
+    pub fn max_string_chars(&self) -> Option<usize> {
+        self.max_string_chars
+    }
+
     /// Returns `true` if streaming mode should be enabled, which means that
     /// the parser will be able to handle a stream of multiple JSON values
     pub fn streaming(&self) -> bool {
         self.streaming
     }
+
+    /// Returns the maximum number of top-level values in streaming mode or
+    /// `None` if there is no such limit
+    pub fn max_values(&self) -> Option<usize> {
+        self.max_values
+    }
+
+    /// Returns `true` if the parser should handle a JSON text sequence
+    /// (RFC 7464)
+    pub fn json_seq(&self) -> bool {
+        self.json_seq
+    }
+
+    /// Returns `true` if objects and arrays that would exceed the maximum
+    /// stack depth should be skipped instead of causing an error
+    pub fn skip_beyond_max_depth(&self) -> bool {
+        self.skip_beyond_max_depth
+    }
+
+    /// Returns `true` if objects and arrays that would exceed a depth limit
+    /// should be flattened into the deepest allowed object or array instead
+    /// of causing an error
+    pub fn clamp_depth(&self) -> bool {
+        self.clamp_depth
+    }
+
+    /// Returns `true` if an empty input (or one that only consists of white
+    /// space) should be treated like the end of the input and not as an error
+    pub fn allow_empty_input(&self) -> bool {
+        self.allow_empty_input
+    }
+
+    /// Returns `true` if an incomplete top-level value at the end of the
+    /// input should be treated like the end of the input and not as an error
+    pub fn tail(&self) -> bool {
+        self.tail
+    }
+
+    /// Returns `true` if a first line starting with `#` should be skipped
+    pub fn leading_shebang(&self) -> bool {
+        self.leading_shebang
+    }
+
+    /// Returns `true` if the parser should keep track of the path to the
+    /// current value
+    pub fn path_tracking(&self) -> bool {
+        self.path_tracking
+    }
+
+    /// Returns `true` if field names should be interned
+    pub fn intern_field_names(&self) -> bool {
+        self.intern_field_names
+    }
+
+    /// Returns `true` if numbers with an exponent should be rejected
+    pub fn disallow_exponents(&self) -> bool {
+        self.disallow_exponents
+    }
+
+    /// Returns `true` if numbers with a fraction should be rejected
+    pub fn disallow_fractions(&self) -> bool {
+        self.disallow_fractions
+    }
+
+    /// Returns `true` if integers that cannot be represented exactly by a
+    /// 64-bit floating point number should be rejected
+    pub fn safe_integers(&self) -> bool {
+        self.safe_integers
+    }
+
+    /// Returns `true` if underscores between the digits of a number should
+    /// be accepted
+    pub fn underscore_separators(&self) -> bool {
+        self.underscore_separators
+    }
+
+    /// Returns `true` if a missing comma or colon should be tolerated
+    pub fn lenient_structure(&self) -> bool {
+        self.lenient_structure
+    }
+
+    /// Returns `true` if string values can be consumed incrementally
+    pub fn streaming_strings(&self) -> bool {
+        self.streaming_strings
+    }
+
+    /// Returns `true` if the parser should keep track of the current line
+    /// and column
+    pub fn position_tracking(&self) -> bool {
+        self.position_tracking
+    }
+
+    /// Returns `true` if the parser should keep the original text of each
+    /// scalar value
+    pub fn raw_values(&self) -> bool {
+        self.raw_values
+    }
+
+    /// Returns `true` if empty field names should be rejected
+    pub fn reject_empty_keys(&self) -> bool {
+        self.reject_empty_keys
+    }
+
+    /// Returns the predicate that decides which field names should be
+    /// rejected (if any)
+    pub fn reject_keys(&self) -> Option<&KeyPredicateFn> {
+        self.reject_keys.as_ref().map(|p| &*p.0)
+    }
+
+    /// Returns the hook that decides whether numbers should be accepted,
+    /// rejected, or rewritten (if any)
+    pub fn number_hook(&self) -> Option<&NumberHookFn> {
+        self.number_hook.as_ref().map(|h| &*h.0)
+    }
+
+    /// Returns the hook that is called for numbers that are not written in
+    /// canonical form (if any)
+    pub fn noncanonical_number_hook(&self) -> Option<&NoncanonicalNumberHookFn> {
+        self.noncanonical_number_hook.as_ref().map(|h| &*h.0)
+    }
+
+    /// Returns the rules that decide whether a number is written in
+    /// canonical form
+    pub fn canonical_number_rules(&self) -> CanonicalNumberRules {
+        self.canonical_number_rules
+    }
+
+    /// Returns the hook that is called whenever the parser has recovered
+    /// from a structural error (if any)
+    pub fn recovery_hook(&self) -> Option<&RecoveryHookFn> {
+        self.recovery_hook.as_ref().map(|h| &*h.0)
+    }
+
+    /// Returns `true` if scalar values should only be reported as
+    /// [`JsonEvent::Scalar`](crate::JsonEvent::Scalar)
+    pub fn structure_only(&self) -> bool {
+        self.structure_only
+    }
+
+    /// Returns `true` if the parser should signal the end of the JSON text
+    /// after it has returned an error
+    pub fn end_after_error(&self) -> bool {
+        self.end_after_error
+    }
+
+    /// Returns the point in time after which parsing should be aborted (if
+    /// any)
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns `true` if the JSON text may be wrapped in a JSONP callback
+    pub fn jsonp(&self) -> bool {
+        self.jsonp
+    }
+
+    /// Returns `true` if decoded strings and field names should be
+    /// normalized to Unicode Normalization Form C (NFC)
+    #[cfg(feature = "unicode-normalization")]
+    pub fn nfc_strings(&self) -> bool {
+        self.nfc_strings
+    }
 }
 
 impl JsonParserOptionsBuilder {
-    /// Set the maximum stack depth
+    /// Set the maximum stack depth. The stack contains one entry for the
+    /// top level and one for each open object or array, so at most
+    /// `max_depth - 1` objects and arrays can be nested. If a JSON text is
+    /// nested more deeply, the parser returns
+    /// [`ParserError::DepthLimitExceeded`](crate::parser::ParserError::DepthLimitExceeded)
+    /// (unless [`Self::with_skip_beyond_max_depth()`] or
+    /// [`Self::with_clamp_depth()`] is enabled).
+    ///
+    /// The default is [`DEFAULT_MAX_DEPTH`] (2048). This protects against
+    /// maliciously deep input while still allowing all documents that occur
+    /// in practice. Use [`Self::with_unlimited_depth()`] to remove the limit.
     pub fn with_max_depth(mut self, max_depth: usize) -> Self {
         self.options.max_depth = max_depth;
         self
     }
 
+    /// Remove the limit of the stack depth (see [`Self::with_max_depth()`]),
+    /// so objects and arrays can be nested arbitrarily deep. This is
+    /// equivalent to setting the maximum stack depth to [`usize::MAX`].
+    /// Memory usage grows with the nesting depth, so only use this if the
+    /// input can be trusted. The limits set with
+    /// [`Self::with_max_array_depth()`] and
+    /// [`Self::with_max_object_depth()`] still apply.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::JsonParser;
+    ///
+    /// let json = format!("{}{}", "[".repeat(5000), "]".repeat(5000));
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json.as_bytes()),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_unlimited_depth()
+    ///         .build(),
+    /// );
+    /// while parser.next_event().unwrap().is_some() {}
+    /// ```
+    pub fn with_unlimited_depth(mut self) -> Self {
+        self.options.max_depth = usize::MAX;
+        self
+    }
+
+    /// Set the maximum number of arrays that can be open at the same time
+    /// (e.g. `1` allows `[1, {"a": 2}]` but not `[[1]]`). Objects are not
+    /// counted. This limit applies in addition to the maximum stack depth
+    /// (see [`Self::with_max_depth()`]). Whichever limit is reached first
+    /// causes [`ParserError::DepthLimitExceeded`](crate::parser::ParserError::DepthLimitExceeded).
+    /// There is no separate limit for arrays by default.
+    pub fn with_max_array_depth(mut self, max_array_depth: usize) -> Self {
+        self.options.max_array_depth = Some(max_array_depth);
+        self
+    }
+
+    /// Set the maximum number of objects that can be open at the same time
+    /// (e.g. `1` allows `{"a": [1]}` but not `{"a": {}}`). Arrays are not
+    /// counted. This limit applies in addition to the maximum stack depth
+    /// (see [`Self::with_max_depth()`]). Whichever limit is reached first
+    /// causes [`ParserError::DepthLimitExceeded`](crate::parser::ParserError::DepthLimitExceeded).
+    /// There is no separate limit for objects by default.
+    pub fn with_max_object_depth(mut self, max_object_depth: usize) -> Self {
+        self.options.max_object_depth = Some(max_object_depth);
+        self
+    }
+
+    /// Set the maximum length of strings and field names in bytes. The
+    /// length is measured after escape sequences have been decoded, i.e. it
+    /// is the length of the UTF-8 encoded string returned by
+    /// [`JsonParser::current_str()`](crate::JsonParser::current_str()). If
+    /// a string gets longer, the parser returns
+    /// [`ParserError::StringTooLong`](crate::parser::ParserError::StringTooLong)
+    /// as soon as the limit has been exceeded, without waiting for the end
+    /// of the string. There is no limit by default.
+    ///
+    /// This limit can be combined with
+    /// [`Self::with_max_string_chars()`]. In this case, the more restrictive
+    /// limit wins, i.e. a string is rejected as soon as it exceeds either of
+    /// them, and the error contains the limit that has been exceeded first.
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.options.max_string_length = Some(max_string_length);
+        self
+    }
+
+    /// Set the maximum length of strings and field names in characters
+    /// (Unicode scalar values, see
+    /// [`JsonParser::current_str_char_len()`](crate::JsonParser::current_str_char_len())).
+    /// In contrast to [`Self::with_max_string_length()`], a multi-byte
+    /// character (e.g. `ä` or `😀`) only counts as one. This is useful if an
+    /// API specifies limits in characters. If a string gets longer, the
+    /// parser returns [`ParserError::StringTooLong`](crate::parser::ParserError::StringTooLong)
+    /// as soon as the limit has been exceeded. There is no limit by default.
+    ///
+    /// If both limits are set, the more restrictive one wins (see
+    /// [`Self::with_max_string_length()`]).
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::{ParserError, StringLimit};
+    /// use actson::JsonParser;
+    ///
+    /// let options = JsonParserOptionsBuilder::default()
+    ///     .with_max_string_chars(4)
+    ///     .build();
+    ///
+    /// // four characters but ten bytes
+    /// let json = r#"["äöü😀"]"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json), options.clone());
+    /// while parser.next_event().unwrap().is_some() {}
+    ///
+    /// let json = r#"["äöü😀!"]"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json), options);
+    /// let err = loop {
+    ///     if let Err(e) = parser.next_event() {
+    ///         break e;
+    ///     }
+    /// };
+    /// assert!(matches!(err, ParserError::StringTooLong(StringLimit::Chars(4))));
+    /// ```
+    pub fn with_max_string_chars(mut self, max_string_chars: usize) -> Self {
+        self.options.max_string_chars = Some(max_string_chars);
+        self
+    }
+
     /// Enable streaming mode, which means that the parser will be able to
     /// handle a stream of multiple JSON values. Values must be clearly
     /// separable. They must either be self-delineating values (i.e. arrays,
@@ -75,6 +684,37 @@ impl JsonParserOptionsBuilder {
     ///
     /// `"a""b"[1, 2, 3] {"key": "value"}`
     ///
+    /// ## End of the stream
+    ///
+    /// In streaming mode, [`JsonParser::next_event()`](crate::JsonParser::next_event())
+    /// returns the events of one value after the other. It never returns
+    /// `None` between two values. After the last complete value, it returns
+    /// [`JsonEvent::NeedMoreInput`](crate::JsonEvent::NeedMoreInput) as long
+    /// as the feeder is not done, since more values might follow. Only after
+    /// the feeder has signalled the end of the input (e.g. after calling
+    /// [`PushJsonFeeder::done()`](crate::feeder::PushJsonFeeder::done())),
+    /// the parser returns `None`, which means that the stream has ended and
+    /// that there are no more values.
+    ///
+    /// Note that a top-level number can only be recognized as complete if it
+    /// is followed by another character or if the feeder is done. The last
+    /// event of a stream ending with a number will therefore only be returned
+    /// after the end of the input has been signalled. If the input ends in
+    /// the middle of a value, the parser returns
+    /// [`ParserError::NoMoreInput`](crate::parser::ParserError::NoMoreInput).
+    ///
+    /// ## Numbers with leading zeros
+    ///
+    /// Two consecutive numbers must be separated by white space, because
+    /// the parser cannot tell where the first one ends otherwise (`12` is a
+    /// single number and not `1` followed by `2`). For the same reason,
+    /// `01` is not parsed as `0` followed by `1`. Although a number cannot
+    /// start with a leading zero and would have to end after the `0`, such
+    /// an input is much more likely to be a malformed number than two
+    /// values. The parser therefore returns
+    /// [`ParserError::MalformedNumber`](crate::parser::ParserError::MalformedNumber),
+    /// just like in non-streaming mode. Use `0 1` to get two values.
+    ///
     /// ## Example:
     ///
     /// ```rust
@@ -119,6 +759,1008 @@ impl JsonParserOptionsBuilder {
         self
     }
 
+    /// Set the maximum number of top-level values the parser accepts in
+    /// streaming mode (see [`Self::with_streaming()`]). This protects
+    /// consumers of a stream from unbounded input. As soon as the parser
+    /// encounters the beginning of another top-level value after `max_values`
+    /// values, it returns
+    /// [`ParserError::TooManyValues`](crate::parser::ParserError::TooManyValues)
+    /// instead of silently ending the stream, so a truncated result can
+    /// always be distinguished from a complete one. White space after the
+    /// last accepted value is still allowed. There is no limit by default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ParserError;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let options = JsonParserOptionsBuilder::default()
+    ///     .with_streaming(true)
+    ///     .with_max_values(2)
+    ///     .build();
+    ///
+    /// let json = br#"1 {"a": 2} "b""#;
+    /// let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json), options);
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::FieldName));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::EndObject));
+    /// assert!(matches!(parser.next_event(), Err(ParserError::TooManyValues(2))));
+    /// ```
+    pub fn with_max_values(mut self, max_values: usize) -> Self {
+        self.options.max_values = Some(max_values);
+        self
+    }
+
+    /// Enable support for JSON text sequences as specified in
+    /// [RFC 7464](https://www.rfc-editor.org/rfc/rfc7464) (media type
+    /// `application/json-seq`). Each JSON value in the sequence is preceded by
+    /// an ASCII record separator (`0x1E`) and typically followed by a line
+    /// feed. This is a variant of streaming mode (see [`Self::with_streaming()`]),
+    /// so enabling it implies streaming.
+    ///
+    /// Top-level numbers, `true`, `false`, and `null` must be followed by white
+    /// space before the next record separator. Otherwise, the record is
+    /// considered truncated and the parser returns an error. If a record is
+    /// invalid, you can call [`JsonParser::skip_to_next_record()`](crate::JsonParser::skip_to_next_record())
+    /// to discard it and to continue with the next one.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = b"\x1E{\"key\":\"value\"}\n\x1E42\n";
+    ///
+    /// let feeder = SliceJsonFeeder::new(json);
+    /// let mut parser = JsonParser::new_with_options(
+    ///     feeder,
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_json_seq(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueString,
+    ///     JsonEvent::EndObject,
+    ///     JsonEvent::ValueInt,
+    /// ]);
+    /// ```
+    pub fn with_json_seq(mut self, json_seq: bool) -> Self {
+        self.options.json_seq = json_seq;
+        self
+    }
+
+    /// Skip objects and arrays that would exceed the maximum stack depth (see
+    /// [`Self::with_max_depth()`]) instead of returning an error. The parser
+    /// consumes the skipped value without generating any events for its
+    /// contents and then returns a single [`JsonEvent::TruncatedValue`](crate::JsonEvent::TruncatedValue)
+    /// in its place. Parsing continues normally afterwards.
+    ///
+    /// The contents of a skipped value are not fully validated. The parser
+    /// only makes sure that they do not contain illegal bytes, that strings
+    /// are terminated, and that every closing bracket matches the opening
+    /// bracket. Other tokens such as numbers or literals are not checked.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = r#"{"a":[1,[2,[3]]],"b":true}"#.as_bytes();
+    ///
+    /// let feeder = SliceJsonFeeder::new(json);
+    /// let mut parser = JsonParser::new_with_options(
+    ///     feeder,
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_max_depth(3)
+    ///         .with_skip_beyond_max_depth(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::TruncatedValue,
+    ///     JsonEvent::EndArray,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueTrue,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn with_skip_beyond_max_depth(mut self, skip_beyond_max_depth: bool) -> Self {
+        self.options.skip_beyond_max_depth = skip_beyond_max_depth;
+        self
+    }
+
+    /// Silently cap the nesting depth instead of returning
+    /// [`ParserError::DepthLimitExceeded`](crate::parser::ParserError::DepthLimitExceeded)
+    /// if an object or array would exceed the maximum stack depth or the
+    /// maximum array or object depth. The contents of such an object or array
+    /// are still parsed and validated, but the events are mapped so that the
+    /// event stream stays well-formed:
+    ///
+    /// * If the object or array is an element of an array, it is flattened.
+    ///   Its [`JsonEvent::StartObject`](crate::JsonEvent::StartObject),
+    ///   [`JsonEvent::StartArray`](crate::JsonEvent::StartArray),
+    ///   [`JsonEvent::EndObject`](crate::JsonEvent::EndObject),
+    ///   [`JsonEvent::EndArray`](crate::JsonEvent::EndArray), and
+    ///   [`JsonEvent::FieldName`](crate::JsonEvent::FieldName) events (as well
+    ///   as those of all objects and arrays nested inside it) are dropped. All
+    ///   other values inside it are returned as usual, in their original
+    ///   order, as if they were elements of the deepest allowed array.
+    /// * Otherwise (i.e. if it is the value of a field or a top-level value),
+    ///   it is replaced by a single [`JsonEvent::TruncatedValue`](crate::JsonEvent::TruncatedValue),
+    ///   just like with [`with_skip_beyond_max_depth()`](Self::with_skip_beyond_max_depth()).
+    ///   Its contents cannot be flattened into the parent object because
+    ///   they would need field names, so they are validated but not reported
+    ///   at all, even if they contain arrays. This is the only case in which
+    ///   values are lost.
+    ///
+    /// Field names that are dropped are not checked against
+    /// [`with_reject_keys()`](Self::with_reject_keys()) or
+    /// [`with_reject_empty_keys()`](Self::with_reject_empty_keys()).
+    /// [`JsonParser::current_path()`](crate::JsonParser::current_path())
+    /// and the depth reported by
+    /// [`JsonParser::drain_into()`](crate::JsonParser::drain_into()) never
+    /// go beyond the limit.
+    ///
+    /// This option takes precedence over
+    /// [`with_skip_beyond_max_depth()`](Self::with_skip_beyond_max_depth()).
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = r#"{"a":[1,[2,{"b":3}]],"c":{"d":{"e":4}}}"#.as_bytes();
+    ///
+    /// let feeder = SliceJsonFeeder::new(json);
+    /// let mut parser = JsonParser::new_with_options(
+    ///     feeder,
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_max_depth(3)
+    ///         .with_clamp_depth(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::ValueInt,        // 2 (from the clamped array)
+    ///     JsonEvent::ValueInt,        // 3 (from the clamped object)
+    ///     JsonEvent::EndArray,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::TruncatedValue,  // {"e":4} (the clamped object)
+    ///     JsonEvent::EndObject,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn with_clamp_depth(mut self, clamp_depth: bool) -> Self {
+        self.options.clamp_depth = clamp_depth;
+        self
+    }
+
+    /// Allow the JSON text to be empty or to only consist of white space. By
+    /// default, the parser returns [`ParserError::EmptyInput`](crate::parser::ParserError::EmptyInput)
+    /// in this case. If this option is enabled,
+    /// [`JsonParser::next_event()`](crate::JsonParser::next_event()) returns
+    /// `None` (i.e. the end of the input) without generating any events.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::JsonParser;
+    ///
+    /// let feeder = SliceJsonFeeder::new(b"  \n");
+    /// let mut parser = JsonParser::new_with_options(
+    ///     feeder,
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_allow_empty_input(true)
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), None);
+    /// ```
+    pub fn with_allow_empty_input(mut self, allow_empty_input: bool) -> Self {
+        self.options.allow_empty_input = allow_empty_input;
+        self
+    }
+
+    /// Enable tail mode, which is useful to follow a file that is still
+    /// being written (e.g. a log file with one JSON value per line). If the
+    /// input ends in the middle of a top-level value, the parser does not
+    /// return [`ParserError::NoMoreInput`](crate::parser::ParserError::NoMoreInput)
+    /// but `None` (i.e. the end of the input), just as if the incomplete
+    /// value was not there. Call
+    /// [`JsonParser::last_value_end()`](crate::JsonParser::last_value_end())
+    /// to get the offset directly after the last complete top-level value.
+    /// The next time the file has grown, create a new parser and start
+    /// reading at this offset.
+    ///
+    /// A top-level number at the very end of the input is considered
+    /// incomplete because more digits might follow. The same applies to
+    /// `true`, `false`, and `null` if they are not complete yet. Events that
+    /// have already been generated for the incomplete value (e.g.
+    /// [`JsonEvent::StartObject`](crate::JsonEvent::StartObject)) are not
+    /// taken back, so you have to discard everything after the last complete
+    /// value yourself. An empty input (e.g. a file that has just been
+    /// created) is not an error in tail mode either.
+    ///
+    /// Tail mode is a variant of streaming mode (see [`Self::with_streaming()`]),
+    /// so enabling it implies streaming and the input may contain multiple
+    /// top-level values. Without streaming, an incomplete value would be the
+    /// only value in the input and there would be nothing to resume from. In
+    /// contrast to tail mode, streaming mode alone expects the last value to
+    /// be complete when the input ends. Tail mode only affects the end of the
+    /// input (i.e. when the feeder is done) and is disabled by default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = b"{\"a\": 1}\n[true]\n{\"b\": [2";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_tail(true)
+    ///         .build(),
+    /// );
+    ///
+    /// while parser.next_event().unwrap().is_some() {}
+    ///
+    /// // resume at the incomplete value later
+    /// assert_eq!(parser.last_value_end(), 15);
+    /// assert_eq!(&json[parser.last_value_end()..], b"\n{\"b\": [2");
+    /// ```
+    pub fn with_tail(mut self, tail: bool) -> Self {
+        self.options.tail = tail;
+        self
+    }
+
+    /// Skip the first line of the input if it starts with `#` (e.g. a
+    /// shebang such as `#!/usr/bin/env tool` or a comment). This is useful
+    /// for configuration files that can be executed directly. The line is
+    /// skipped up to and including the first line feed. Only a `#` at the
+    /// very first byte of the input is recognized. It must not be preceded
+    /// by white space or a byte order mark. Everything after the first line
+    /// is parsed as usual, so the parser does not support comments anywhere
+    /// else. Offsets (e.g. [`JsonParser::parsed_bytes()`](crate::JsonParser::parsed_bytes()))
+    /// still include the skipped line. This option is disabled by default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = b"#!/usr/bin/env tool\n{\"a\": 1}";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_leading_shebang(true)
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    /// ```
+    pub fn with_leading_shebang(mut self, leading_shebang: bool) -> Self {
+        self.options.leading_shebang = leading_shebang;
+        self
+    }
+
+    /// Keep track of the path from the root of the JSON text to the current
+    /// value, so it can be retrieved with [`JsonParser::current_path()`](crate::JsonParser::current_path())
+    /// or [`JsonParser::current_pointer()`](crate::JsonParser::current_pointer()).
+    /// This is disabled by default because it requires the parser to copy
+    /// each field name.
+    pub fn with_path_tracking(mut self, path_tracking: bool) -> Self {
+        self.options.path_tracking = path_tracking;
+        self
+    }
+
+    /// Intern field names, so they can be retrieved with
+    /// [`JsonParser::current_field_interned()`](crate::JsonParser::current_field_interned()).
+    /// The parser keeps one shared copy of each distinct field name it
+    /// encounters. This reduces allocations if many objects share the same
+    /// field names (e.g. in an array of records) and allows field names to
+    /// be compared by pointer.
+    ///
+    /// Heads up: The parser keeps all interned field names in memory for its
+    /// whole lifetime (or until you call
+    /// [`JsonParser::clear_interned_field_names()`](crate::JsonParser::clear_interned_field_names())).
+    /// Only enable this option if the number of distinct field names is
+    /// bounded.
+    pub fn with_intern_field_names(mut self, intern_field_names: bool) -> Self {
+        self.options.intern_field_names = intern_field_names;
+        self
+    }
+
+    /// Reject numbers in exponent notation (e.g. `1.5e3` or `2E-1`) with
+    /// [`ParserError::ExponentNotAllowed`](crate::parser::ParserError::ExponentNotAllowed).
+    /// Numbers with a fraction (e.g. `1.5`) are still accepted unless
+    /// [`Self::with_disallow_fractions()`] is also enabled. This is useful if
+    /// the parsed values are passed on to systems that do not support
+    /// exponents. Exponents are allowed by default.
+    pub fn with_disallow_exponents(mut self, disallow_exponents: bool) -> Self {
+        self.options.disallow_exponents = disallow_exponents;
+        self
+    }
+
+    /// Reject numbers with a fraction (e.g. `1.5`) with
+    /// [`ParserError::FractionNotAllowed`](crate::parser::ParserError::FractionNotAllowed).
+    /// Numbers in exponent notation without a fraction (e.g. `1e3`) are
+    /// still accepted unless [`Self::with_disallow_exponents()`] is also
+    /// enabled. Fractions are allowed by default.
+    pub fn with_disallow_fractions(mut self, disallow_fractions: bool) -> Self {
+        self.options.disallow_fractions = disallow_fractions;
+        self
+    }
+
+    /// Reject integers outside the range `-(2^53 - 1)..=2^53 - 1` with
+    /// [`ParserError::UnsafeInteger`](crate::parser::ParserError::UnsafeInteger).
+    /// JavaScript represents all numbers as 64-bit floating point values and
+    /// cannot represent larger integers exactly, so this is useful to make
+    /// sure that data will not lose precision when it is passed on to a
+    /// JavaScript client. Only [`JsonEvent::ValueInt`](crate::JsonEvent::ValueInt)
+    /// is checked. Numbers with a fraction or an exponent (e.g. `1e20`) are
+    /// not. If a number hook is set (see [`Self::with_number_hook()`]), the
+    /// check applies to the rewritten number. Integers are not checked by
+    /// default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ParserError;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = b"[9007199254740991, 9007199254740992]";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_safe_integers(true)
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert!(matches!(
+    ///     parser.next_event(),
+    ///     Err(ParserError::UnsafeInteger(n)) if n.as_str() == "9007199254740992"
+    /// ));
+    /// ```
+    pub fn with_safe_integers(mut self, safe_integers: bool) -> Self {
+        self.options.safe_integers = safe_integers;
+        self
+    }
+
+    /// Accept single underscores between the digits of a number as digit
+    /// separators (e.g. `1_000_000` or `0.000_001`). This is not standard
+    /// JSON but some relaxed dialects allow it for readability. The
+    /// underscores are removed, so
+    /// [`JsonParser::current_int()`](crate::JsonParser::current_int()) and
+    /// [`JsonParser::current_raw_number()`](crate::JsonParser::current_raw_number())
+    /// only see the digits. [`JsonParser::current_raw()`](crate::JsonParser::current_raw())
+    /// and a number hook (see [`Self::with_number_hook()`]) receive the
+    /// original text.
+    ///
+    /// An underscore must be surrounded by digits of the integer part, the
+    /// fraction, or the exponent. Leading zeros are not allowed either, so
+    /// `0_1` is invalid. Misplaced underscores (e.g. in `1__0`, `1_`, `1_.5`,
+    /// `1._5`, `1e_5`, or `_1`) cause
+    /// [`ParserError::MalformedNumber`](crate::parser::ParserError::MalformedNumber).
+    /// Underscores outside of numbers are still a syntax error. Underscores
+    /// are rejected by default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = b"[1_000_000, 3.141_592]";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_underscore_separators(true)
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert_eq!(parser.current_int::<i64>().unwrap(), 1000000);
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueFloat));
+    /// assert_eq!(parser.current_raw_number().unwrap(), "3.141592");
+    /// ```
+    pub fn with_underscore_separators(mut self, underscore_separators: bool) -> Self {
+        self.options.underscore_separators = underscore_separators;
+        self
+    }
+
+    /// Tolerate certain structural errors in slightly broken (typically
+    /// machine-generated) JSON texts instead of returning
+    /// [`ParserError::SyntaxError`](crate::parser::ParserError::SyntaxError).
+    /// The following errors can be recovered:
+    ///
+    /// * A missing comma between two array elements (e.g. `[1 2]` or
+    ///   `[{} "a"]`). The parser behaves as if there was a comma between
+    ///   them.
+    /// * A missing comma between two object entries (e.g.
+    ///   `{"a": 1 "b": 2}`). The parser behaves as if there was a comma
+    ///   before the next field name.
+    /// * A missing colon between a field name and its value (e.g.
+    ///   `{"a" 1}`). The parser behaves as if there was a colon after the
+    ///   field name.
+    ///
+    /// All other errors are still reported. In particular, the parser only
+    /// inserts a comma where the next value can be clearly told apart from
+    /// the previous one (so `[01]` and `[1-2]` are still invalid) and never
+    /// inserts missing brackets, braces, or quotes. Set a hook with
+    /// [`Self::with_recovery_hook()`] to get notified about each recovered
+    /// error. Strict parsing is the default.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = br#"{"a" [1 2] "b": true}"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_lenient_structure(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::EndArray,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueTrue,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn with_lenient_structure(mut self, lenient_structure: bool) -> Self {
+        self.options.lenient_structure = lenient_structure;
+        self
+    }
+
+    /// Set a hook that is called whenever the parser has recovered from a
+    /// structural error (see [`Self::with_lenient_structure()`]), e.g. to
+    /// log a warning. The hook receives the recovered error and the JSON
+    /// Pointer (RFC 6901) of the object or array in which the error
+    /// occurred. The pointer is only available if path tracking has been
+    /// enabled with [`Self::with_path_tracking()`]. Otherwise, it is always
+    /// empty (i.e. it refers to the top-level value).
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::JsonParser;
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let w = Arc::clone(&warnings);
+    ///
+    /// let json = br#"{"a": [1 2]}"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_lenient_structure(true)
+    ///         .with_recovery_hook(Arc::new(move |err, pointer| {
+    ///             w.lock().unwrap().push(format!("{err} in `{pointer}'"));
+    ///         }))
+    ///         .with_path_tracking(true)
+    ///         .build(),
+    /// );
+    /// while parser.next_event().unwrap().is_some() {}
+    ///
+    /// assert_eq!(*warnings.lock().unwrap(), ["missing comma at offset 9 in `/a'"]);
+    /// ```
+    pub fn with_recovery_hook(mut self, recovery_hook: Arc<RecoveryHookFn>) -> Self {
+        self.options.recovery_hook = Some(SharedFn(recovery_hook));
+        self
+    }
+
+    /// Only report the structure of the JSON text. If enabled, the parser
+    /// generates the usual events for objects, arrays, and field names, but
+    /// reports every string, number, boolean, and `null` as a single
+    /// [`JsonEvent::Scalar`](crate::JsonEvent::Scalar) event. This is useful
+    /// to quickly find out the shape of a document (e.g. to generate a
+    /// skeleton or a schema) without caring about its values.
+    ///
+    /// The input is still fully validated. The characters of string values
+    /// are not kept in memory, so even very long strings do not increase the
+    /// parser's memory usage. If the feeder
+    /// supports [`JsonFeeder::peek()`](crate::feeder::JsonFeeder::peek()),
+    /// string values, runs of digits, and white space between tokens are
+    /// skipped in bulk instead of being passed through the parser's state
+    /// machine byte by byte. In our benchmark (`actson_structure_only_large`
+    /// compared to `actson_novalues_large`), this makes parsing about 20%
+    /// faster. Documents with long string values benefit most. Options that
+    /// need to look at every byte or at the value of a number (e.g.
+    /// [`Self::with_position_tracking()`], [`Self::with_raw_values()`],
+    /// [`Self::with_max_string_chars()`], or [`Self::with_number_hook()`])
+    /// disable bulk skipping. Methods that return the current value
+    /// (such as [`JsonParser::current_str()`](crate::JsonParser::current_str()))
+    /// must not be called after a `Scalar` event. Field names are still
+    /// available. The default value is `false`.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = br#"{"name": "Elvis", "albums": [1956, true, null]}"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_structure_only(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     if e == JsonEvent::FieldName {
+    ///         assert!(!parser.current_str().unwrap().is_empty());
+    ///     }
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::Scalar,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::Scalar,
+    ///     JsonEvent::Scalar,
+    ///     JsonEvent::Scalar,
+    ///     JsonEvent::EndArray,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn with_structure_only(mut self, structure_only: bool) -> Self {
+        self.options.structure_only = structure_only;
+        self
+    }
+
+    /// Signal the end of the JSON text after an error. By default, the
+    /// parser stays in a terminal state after an error and
+    /// [`JsonParser::next_event()`](crate::JsonParser::next_event()) returns
+    /// the same error again on every subsequent call. If this option is
+    /// enabled, the error is still returned once, but all subsequent calls
+    /// return `None`, just like at the end of a valid JSON text. This
+    /// allows event loops that handle errors without leaving the loop (e.g.
+    /// because they only log them) to terminate naturally. The error
+    /// remains available through
+    /// [`JsonParser::last_error()`](crate::JsonParser::last_error()), and no
+    /// more input is consumed. The default value is `false`.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::JsonParser;
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(b"[1, }"),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_end_after_error(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = 0;
+    /// let mut errors = 0;
+    /// loop {
+    ///     match parser.next_event() {
+    ///         Ok(Some(_)) => events += 1,
+    ///         Ok(None) => break,
+    ///         Err(_) => errors += 1,
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(events, 2);
+    /// assert_eq!(errors, 1);
+    /// assert!(parser.last_error().is_some());
+    /// ```
+    pub fn with_end_after_error(mut self, end_after_error: bool) -> Self {
+        self.options.end_after_error = end_after_error;
+        self
+    }
+
+    /// Abort parsing with [`ParserError::Timeout`](crate::parser::ParserError::Timeout)
+    /// if it is still going on after the given point in time. This protects
+    /// request handlers with a time budget against inputs that are huge or
+    /// deliberately slow to parse (e.g. megabytes of white space).
+    ///
+    /// The deadline is checked on a best-effort basis. To keep the check
+    /// cheap, the parser only compares the current time with the deadline
+    /// when it reads the first byte and then every 4096 bytes. Parsing is not
+    /// preempted. Time spent outside the parser (e.g. waiting for a feeder
+    /// to be filled) is only noticed when the parser reads the next byte to
+    /// be checked. By default, there is no deadline.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ParserError;
+    /// use actson::JsonParser;
+    ///
+    /// let json = " ".repeat(1_000_000) + "1";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json.as_bytes()),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_deadline(Instant::now() - Duration::from_secs(1))
+    ///         .build(),
+    /// );
+    ///
+    /// assert!(matches!(parser.next_event(), Err(ParserError::Timeout)));
+    /// ```
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.options.deadline = Some(deadline);
+        self
+    }
+
+    /// Accept a JSON text that is wrapped in a JSONP callback (e.g.
+    /// `callback({"a": 1});`) as returned by some legacy web APIs. If
+    /// enabled, the parser skips a leading callback name and the opening
+    /// parenthesis, parses the JSON value inside, and accepts the closing
+    /// parenthesis followed by an optional semicolon. White space is allowed
+    /// around all of these tokens. The callback name can be retrieved with
+    /// [`JsonParser::jsonp_callback()`](crate::JsonParser::jsonp_callback()).
+    ///
+    /// The callback name must be a JavaScript identifier consisting of
+    /// ASCII letters, digits, `_`, and `$`, optionally separated by dots
+    /// (e.g. `jQuery123_456` or `api.handle`). The wrapper is optional: a
+    /// JSON text without a callback is parsed as usual. Only a single
+    /// top-level value may be wrapped, so this option should not be
+    /// combined with streaming mode. If the option is disabled (the
+    /// default), a JSONP wrapper causes a syntax error.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let json = br#"handleResponse({"name": "Elvis"});"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_jsonp(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     events.push(e);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueString,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// assert_eq!(parser.jsonp_callback(), Some("handleResponse"));
+    /// ```
+    pub fn with_jsonp(mut self, jsonp: bool) -> Self {
+        self.options.jsonp = jsonp;
+        self
+    }
+
+    /// Allow string values to be consumed incrementally with
+    /// [`JsonParser::string_chars()`](crate::JsonParser::string_chars())
+    /// while they are being parsed. This is useful for very large strings
+    /// (e.g. embedded documents) that should not be kept in memory as a
+    /// whole. Field names are not affected.
+    ///
+    /// Heads up: Characters returned by `string_chars()` are removed from
+    /// the parser's buffer. When the string has been parsed completely and
+    /// the parser returns [`JsonEvent::ValueString`](crate::JsonEvent::ValueString),
+    /// [`JsonParser::current_str()`](crate::JsonParser::current_str()) only
+    /// returns the characters that have not been consumed yet.
+    pub fn with_streaming_strings(mut self, streaming_strings: bool) -> Self {
+        self.options.streaming_strings = streaming_strings;
+        self
+    }
+
+    /// Keep track of the line and column of the input consumed so far, so
+    /// they can be retrieved with [`JsonParser::current_line()`](crate::JsonParser::current_line()),
+    /// [`JsonParser::current_column()`](crate::JsonParser::current_column()),
+    /// and [`JsonParser::current_column_utf16()`](crate::JsonParser::current_column_utf16()).
+    /// This is disabled by default because it slightly slows down parsing.
+    pub fn with_position_tracking(mut self, position_tracking: bool) -> Self {
+        self.options.position_tracking = position_tracking;
+        self
+    }
+
+    /// Keep the original text of each string, number, `true`, `false`, and
+    /// `null` (as well as each field name), so it can be retrieved with
+    /// [`JsonParser::current_raw()`](crate::JsonParser::current_raw()). This
+    /// is useful for transformations that should preserve the source text
+    /// exactly. The parser copies the bytes of each token into a separate
+    /// buffer while it consumes them, so this works with any feeder (even if
+    /// the input is read in chunks and a token straddles two chunks). This
+    /// is disabled by default because it slightly slows down parsing.
+    pub fn with_raw_values(mut self, raw_values: bool) -> Self {
+        self.options.raw_values = raw_values;
+        self
+    }
+
+    /// Reject empty field names (e.g. `{"": 1}`) with
+    /// [`ParserError::EmptyKey`](crate::parser::ParserError::EmptyKey).
+    /// Empty field names are valid JSON and are therefore accepted by
+    /// default, but some applications consider them a mistake.
+    pub fn with_reject_empty_keys(mut self, reject_empty_keys: bool) -> Self {
+        self.options.reject_empty_keys = reject_empty_keys;
+        self
+    }
+
+    /// Reject all field names for which the given predicate returns `true`
+    /// with [`ParserError::KeyRejected`](crate::parser::ParserError::KeyRejected).
+    /// The predicate is called with the decoded field name. Field names that
+    /// are not valid UTF-8 are passed with replacement characters. The
+    /// predicate is shared between all parsers created with these options
+    /// and can capture state, such as a deny-list that has been loaded at
+    /// runtime.
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    /// use std::sync::Arc;
+    ///
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ParserError;
+    /// use actson::JsonParser;
+    ///
+    /// let denied: HashSet<String> = ["$type", "__proto__"].map(String::from).into();
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(br#"{"name": "Elvis", "$type": "person"}"#),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_reject_keys(Arc::new(move |k| denied.contains(k)))
+    ///         .build(),
+    /// );
+    ///
+    /// let err = loop {
+    ///     if let Err(e) = parser.next_event() {
+    ///         break e;
+    ///     }
+    /// };
+    /// assert!(matches!(err, ParserError::KeyRejected(k) if k.as_str() == "$type"));
+    /// ```
+    pub fn with_reject_keys(mut self, reject_keys: Arc<KeyPredicateFn>) -> Self {
+        self.options.reject_keys = Some(SharedFn(reject_keys));
+        self
+    }
+
+    /// Call the given hook for each number token before the parser checks it
+    /// against the JSON grammar. The hook receives the token's original text
+    /// and decides whether the number should be accepted, rejected with
+    /// [`ParserError::NumberRejected`](crate::parser::ParserError::NumberRejected),
+    /// or rewritten. This is an extension point for numeric dialects (e.g.
+    /// to accept hexadecimal numbers or numbers with a leading dot, to
+    /// reject negative zero or numbers with too many digits, or to normalize
+    /// numbers to a canonical form).
+    ///
+    /// A number token starts with a digit, `-`, `+`, or `.` where a value is
+    /// expected. As long as the token is a valid prefix of a JSON number, it
+    /// ends where the number would end without a hook. This means a hook
+    /// that accepts every number does not change how the input is parsed
+    /// (e.g. `1true` is still a number followed by `true` in streaming
+    /// mode). Once a character makes the token invalid (e.g. `x` in `0x1F`),
+    /// the token extends up to the next character that is not an ASCII
+    /// letter, a digit, `-`, `+`, `.`, or `_`, so the hook receives the
+    /// whole number written in another format.
+    ///
+    /// An accepted token is parsed as usual, so it must be a valid JSON
+    /// number (or conform to other enabled relaxations such as
+    /// [`Self::with_underscore_separators()`]). The text of a rewritten
+    /// number must be a valid JSON number, otherwise the parser returns
+    /// [`ParserError::NumberRejected`](crate::parser::ParserError::NumberRejected)
+    /// with the original token. The rewritten text is returned by
+    /// [`JsonParser::current_raw_number()`](crate::JsonParser::current_raw_number())
+    /// and all accessors based on it, and the event is
+    /// [`JsonEvent::ValueFloat`](crate::JsonEvent::ValueFloat) if the new text
+    /// has a fraction or an exponent and [`JsonEvent::ValueInt`](crate::JsonEvent::ValueInt)
+    /// otherwise. [`JsonParser::current_raw()`](crate::JsonParser::current_raw())
+    /// still returns the original text.
+    ///
+    /// The hook is shared between all parsers created with these options,
+    /// so it can capture configuration or collect state (e.g. in a
+    /// [`Mutex`](std::sync::Mutex)).
+    ///
+    /// Heads up: Each number token is buffered before it is parsed and the
+    /// hook is called through a trait object for every single number, which
+    /// prevents inlining. Rewriting a number also allocates a new string.
+    /// This slows down parsing of documents with many numbers. Without a
+    /// hook, there is no overhead.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    ///
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::{JsonParserOptionsBuilder, NumberDecision};
+    /// use actson::parser::ParserError;
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// let forbidden = vec!["-0".to_string()];
+    /// let hook = move |n: &str| {
+    ///     if forbidden.iter().any(|f| f == n) {
+    ///         NumberDecision::Reject
+    ///     } else if let Some(hex) = n.strip_prefix("0x") {
+    ///         match u64::from_str_radix(hex, 16) {
+    ///             Ok(v) => NumberDecision::Rewrite(v.to_string()),
+    ///             Err(_) => NumberDecision::Reject,
+    ///         }
+    ///     } else if let Some(n) = n.strip_suffix(".0") {
+    ///         NumberDecision::Rewrite(n.to_string())
+    ///     } else {
+    ///         NumberDecision::Accept
+    ///     }
+    /// };
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(b"[1.0, 0x1F, 2.5, -0]"),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_number_hook(Arc::new(hook))
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert_eq!(parser.current_int::<i32>().unwrap(), 1);
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    /// assert_eq!(parser.current_int::<i32>().unwrap(), 31);
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueFloat));
+    /// assert!(matches!(
+    ///     parser.next_event(),
+    ///     Err(ParserError::NumberRejected(n)) if n.as_str() == "-0"
+    /// ));
+    /// ```
+    pub fn with_number_hook(mut self, number_hook: Arc<NumberHookFn>) -> Self {
+        self.options.number_hook = Some(SharedFn(number_hook));
+        self
+    }
+
+    /// Set a hook that is called for every number that is accepted by the
+    /// parser but that is not written in canonical form, e.g. `1.500` or
+    /// `1E5`. This is useful for linters and other style-checking tools.
+    /// The numbers are not changed or rejected. Use [`Self::with_number_hook()`]
+    /// for this.
+    ///
+    /// The hook receives the number as it appears in the JSON text and a
+    /// JSON Pointer (RFC 6901) to its location. The pointer is only
+    /// available if path tracking has been enabled with
+    /// [`Self::with_path_tracking()`]. Otherwise, it is an empty string.
+    /// Which numbers count as canonical can be configured with
+    /// [`Self::with_canonical_number_rules()`]. Note that numbers JSON does
+    /// not allow at all (e.g. `+2` or `01`) are rejected with an error
+    /// anyhow. If no hook is set (the default), numbers are not checked at
+    /// all.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::JsonParser;
+    ///
+    /// let found = Arc::new(Mutex::new(Vec::new()));
+    /// let f = Arc::clone(&found);
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(br#"{"a": [1.5, 1.500], "b": 2E10}"#),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_noncanonical_number_hook(Arc::new(move |number, pointer| {
+    ///             f.lock().unwrap().push(format!("{pointer}: {number}"));
+    ///         }))
+    ///         .with_path_tracking(true)
+    ///         .build(),
+    /// );
+    /// while parser.next_event().unwrap().is_some() {}
+    ///
+    /// assert_eq!(*found.lock().unwrap(), ["/a/1: 1.500", "/b: 2E10"]);
+    /// ```
+    pub fn with_noncanonical_number_hook(
+        mut self,
+        noncanonical_number_hook: Arc<NoncanonicalNumberHookFn>,
+    ) -> Self {
+        self.options.noncanonical_number_hook = Some(SharedFn(noncanonical_number_hook));
+        self
+    }
+
+    /// Set the rules that decide whether a number is written in canonical
+    /// form (see [`Self::with_noncanonical_number_hook()`]). By default,
+    /// all rules are enabled.
+    pub fn with_canonical_number_rules(mut self, rules: CanonicalNumberRules) -> Self {
+        self.options.canonical_number_rules = rules;
+        self
+    }
+
+    /// Normalize all decoded strings and field names to Unicode
+    /// Normalization Form C (NFC), so canonically equivalent strings (e.g.
+    /// `"\u00e9"` and `"e\u0301"`) can be compared byte by byte. This affects
+    /// [`JsonParser::current_str()`](crate::JsonParser::current_str()) and
+    /// all accessors based on it, as well as field names in paths and
+    /// interned field names. It does not affect numbers and
+    /// [`JsonParser::current_raw_number()`](crate::JsonParser::current_raw_number()).
+    ///
+    /// This option is disabled by default because normalization is
+    /// expensive and changes the bytes of the parsed strings. It is only
+    /// available if the `unicode-normalization` feature is enabled.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn with_nfc_strings(mut self, nfc_strings: bool) -> Self {
+        self.options.nfc_strings = nfc_strings;
+        self
+    }
+
     /// Create a new [`JsonParserOptions`] object
     pub fn build(self) -> JsonParserOptions {
         self.options
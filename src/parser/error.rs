@@ -0,0 +1,445 @@
+use std::{
+    fmt::{Display, Formatter},
+    num::ParseFloatError,
+    str::Utf8Error,
+    sync::Arc,
+};
+
+use btoi::ParseIntegerError;
+use thiserror::Error;
+
+use crate::{
+    feeder::{Encoding, FeederError},
+    JsonEvent, JsonEventKind,
+};
+
+/// An error that can happen when reading the current value as a string
+#[derive(Error, Debug)]
+#[error("invalid string: {0}")]
+pub struct InvalidStringValueError(#[from] Utf8Error);
+
+/// An error that can happen when trying to parse the current value to an integer
+#[derive(Error, Debug)]
+#[error("invalid integer: {0}")]
+pub struct InvalidIntValueError(#[from] ParseIntegerError);
+
+/// An error that can happen when trying to parse the current value to a float
+#[derive(Error, Debug)]
+pub enum InvalidFloatValueError {
+    #[error("unable to convert current value to string: {0}")]
+    String(#[from] InvalidStringValueError),
+
+    #[error("unable to parse current value to float: {0}")]
+    Float(#[from] ParseFloatError),
+
+    /// The value is too large to be represented by the requested float type
+    #[error("number out of range")]
+    NumberOutOfRange,
+}
+
+/// An error that can happen when reading the current value as a boolean.
+/// The error contains the current event.
+#[derive(Error, Debug)]
+#[error("current value is not a boolean: {0:?}")]
+pub struct InvalidBoolValueError(pub JsonEvent);
+
+/// An error that can happen when reading the current value as a [`Scalar`](crate::parser::Scalar)
+#[derive(Error, Debug)]
+pub enum InvalidScalarValueError {
+    #[error("{0}")]
+    String(#[from] InvalidStringValueError),
+
+    #[error("{0}")]
+    Float(#[from] InvalidFloatValueError),
+
+    /// The current event is not a scalar value. The error contains the
+    /// current event.
+    #[error("current value is not a scalar: {0:?}")]
+    NotAScalar(JsonEvent),
+}
+
+/// An error that can happen while folding over the numbers of a JSON text
+/// that is read from a reader (see
+/// [`JsonParser::fold_all_numbers()`](crate::JsonParser::fold_all_numbers()))
+#[derive(Error, Debug)]
+pub enum FoldError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+}
+
+/// An error that can happen while reading the next event of a JSON text
+/// that is read from a reader (see
+/// [`JsonParser::next_event_blocking()`](crate::JsonParser::next_event_blocking()))
+#[derive(Error, Debug)]
+pub enum NextEventError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+}
+
+/// An error that can happen while collecting the field names of an object
+/// (see [`JsonParser::collect_keys_on()`](crate::JsonParser::collect_keys_on()))
+#[derive(Error, Debug)]
+pub enum CollectKeysError {
+    #[error("{0}")]
+    Parse(#[from] ParserError),
+
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+}
+
+/// A coarse classification of a [`ParserError`] (see [`ParserError::category()`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// The input is not valid JSON or it ended prematurely
+    Syntax,
+
+    /// The input exceeds a configured limit
+    Limit,
+
+    /// The input could not be read
+    Io,
+}
+
+/// An error that can happen during parsing
+///
+/// New variants may be added in future versions, so `match` expressions
+/// have to include a wildcard arm. Use [`Self::category()`] if you only want
+/// to distinguish between broad classes of errors.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum ParserError {
+    /// The JSON text contains an illegal byte (e.g. a non-whitespace control
+    /// character inside or outside a string). The error contains the byte
+    /// and its offset, which helps to diagnose input that has been
+    /// contaminated with binary data.
+    ///
+    /// Example inputs: `[1,\x00 2]`, a string containing an unescaped
+    /// control character such as `0x01`
+    #[error("JSON text contains an illegal byte `{byte:#04x}' at byte {offset}")]
+    IllegalCharacter { byte: u8, offset: usize },
+
+    /// The JSON text does not seem to be encoded in UTF-8 but in UTF-16 or
+    /// UTF-32. The error contains the detected encoding. Use a
+    /// [`TranscodingJsonFeeder`](crate::feeder::TranscodingJsonFeeder) to
+    /// parse such input.
+    ///
+    /// The parser only checks for this if one of the first two bytes is
+    /// illegal. It then looks for a byte order mark or the NUL bytes that
+    /// UTF-16 and UTF-32 add to ASCII characters. Otherwise, it returns
+    /// [`ParserError::IllegalCharacter`] or [`ParserError::SyntaxError`].
+    ///
+    /// Example inputs: `[\x00]\x00` (UTF-16LE), `\xFE\xFF\x00[\x00]`
+    /// (UTF-16BE with byte order mark)
+    #[error("the JSON text seems to be encoded in {0:?} instead of UTF-8; use a TranscodingJsonFeeder to parse it")]
+    WrongEncoding(Encoding),
+
+    /// The parsed text is not valid JSON
+    ///
+    /// Example inputs: `[1 2]`, `{"a"}`, `[1}`
+    #[error("syntax error: the parsed text is not valid JSON")]
+    SyntaxError,
+
+    /// There is nothing more to parse. The feeder is done and does not provide
+    /// more input. Either the JSON text ended prematurely or
+    /// [`JsonParser::next_event()`](crate::JsonParser::next_event()) was called
+    /// too many times (i.e. after the end of a valid JSON text was reached).
+    ///
+    /// Example input: `{"a": [1, 2`
+    #[error("nothing more to parse")]
+    NoMoreInput,
+
+    /// The JSON text was empty or only contained white space. This error is
+    /// not returned if empty input has been allowed with
+    /// [`JsonParserOptionsBuilder::with_allow_empty_input()`](crate::options::JsonParserOptionsBuilder::with_allow_empty_input()).
+    ///
+    /// Example inputs: an empty string, `"  \n"`
+    #[error("the JSON text is empty")]
+    EmptyInput,
+
+    /// The JSON text contains more data after the end of the top-level
+    /// value. The error contains the offset of the first non-whitespace byte
+    /// after the value. This error is not returned in streaming mode (see
+    /// [`JsonParserOptionsBuilder::with_streaming()`](crate::options::JsonParserOptionsBuilder::with_streaming())).
+    ///
+    /// Example inputs: `{"a":1}   X`, `[1]]`, `1 2`
+    #[error("unexpected data after the end of the JSON text at byte {0}")]
+    TrailingData(usize),
+
+    /// The JSON text contains a number with a leading zero that is directly
+    /// followed by another digit. This error is also returned in streaming
+    /// mode (see [`JsonParserOptionsBuilder::with_streaming()`](crate::options::JsonParserOptionsBuilder::with_streaming())),
+    /// where the input is not split into two numbers. The error contains the
+    /// offset of the digit following the leading zero.
+    ///
+    /// If [`JsonParserOptionsBuilder::with_underscore_separators()`](crate::options::JsonParserOptionsBuilder::with_underscore_separators())
+    /// is enabled, this error is also returned for numbers with an underscore
+    /// that is not surrounded by digits. In this case, the error contains the
+    /// offset of the underscore.
+    ///
+    /// Example inputs: `01`, `[-00.5]`, `1__0`, `[_1]`
+    #[error("malformed number at byte {0}")]
+    MalformedNumber(usize),
+
+    /// The JSON text contains a number in exponent notation but exponents
+    /// have been disallowed with
+    /// [`JsonParserOptionsBuilder::with_disallow_exponents()`](crate::options::JsonParserOptionsBuilder::with_disallow_exponents()).
+    /// The error contains the offset of the `e` or `E`.
+    ///
+    /// Example inputs: `1e3`, `[1.5E-2]`
+    #[error("number in exponent notation not allowed at byte {0}")]
+    ExponentNotAllowed(usize),
+
+    /// The JSON text contains a number with a fraction but fractions have
+    /// been disallowed with
+    /// [`JsonParserOptionsBuilder::with_disallow_fractions()`](crate::options::JsonParserOptionsBuilder::with_disallow_fractions()).
+    /// The error contains the offset of the decimal point.
+    ///
+    /// Example inputs: `1.5`, `[0.0]`
+    #[error("number with a fraction not allowed at byte {0}")]
+    FractionNotAllowed(usize),
+
+    /// [`JsonParser::expect()`](crate::JsonParser::expect()) or a method
+    /// that requires a value of a certain kind (e.g.
+    /// [`JsonParser::collect_keys()`](crate::JsonParser::collect_keys()))
+    /// found an event that does not start a value of the expected kind.
+    /// `found` is `None` if the end of the JSON text has been reached.
+    #[error("expected {expected:?} value but found {found:?}")]
+    UnexpectedEvent {
+        expected: JsonEventKind,
+        found: Option<JsonEvent>,
+    },
+
+    /// The JSON text contains an empty field name but empty field names have
+    /// been rejected with
+    /// [`JsonParserOptionsBuilder::with_reject_empty_keys()`](crate::options::JsonParserOptionsBuilder::with_reject_empty_keys()).
+    /// The error contains the offset of the field name's opening quote. If
+    /// path tracking is enabled, [`JsonParser::current_pointer()`](crate::JsonParser::current_pointer())
+    /// points to the field.
+    ///
+    /// Example input: `{"": 1}`
+    #[error("empty field name at byte {0}")]
+    EmptyKey(usize),
+
+    /// The JSON text contains a field name that has been rejected by the
+    /// predicate given to
+    /// [`JsonParserOptionsBuilder::with_reject_keys()`](crate::options::JsonParserOptionsBuilder::with_reject_keys()).
+    /// The error contains the field name. If path tracking is enabled,
+    /// [`JsonParser::current_pointer()`](crate::JsonParser::current_pointer())
+    /// points to the field.
+    #[error("field name not allowed: `{0}'")]
+    KeyRejected(Box<String>),
+
+    /// The JSON text contains a number that has been rejected by the hook
+    /// given to
+    /// [`JsonParserOptionsBuilder::with_number_hook()`](crate::options::JsonParserOptionsBuilder::with_number_hook()).
+    /// The error contains the number.
+    #[error("number not allowed: `{0}'")]
+    NumberRejected(Box<String>),
+
+    /// The JSON text contains an integer that cannot be represented exactly
+    /// by a 64-bit floating point number (e.g. in JavaScript). This error is
+    /// only returned if
+    /// [`JsonParserOptionsBuilder::with_safe_integers()`](crate::options::JsonParserOptionsBuilder::with_safe_integers())
+    /// is enabled. The error contains the integer.
+    ///
+    /// Example input: `9007199254740992`
+    #[error("integer is outside the safe range: `{0}'")]
+    UnsafeInteger(Box<String>),
+
+    /// An object or array exceeds one of the configured depth limits. The
+    /// error contains the limit that has been reached. This error is not
+    /// returned if skipping has been enabled with
+    /// [`JsonParserOptionsBuilder::with_skip_beyond_max_depth()`](crate::options::JsonParserOptionsBuilder::with_skip_beyond_max_depth()).
+    ///
+    /// Example input: `[[[1]]]` with a maximum array depth of 2
+    #[error("{0} exceeded")]
+    DepthLimitExceeded(DepthLimit),
+
+    /// A string or field name exceeds one of the configured length limits.
+    /// The error contains the limit that has been exceeded.
+    ///
+    /// Example input: `["abc"]` with a maximum string length of 2
+    #[error("{0} exceeded")]
+    StringTooLong(StringLimit),
+
+    /// The input contains more top-level values than allowed (see
+    /// [`JsonParserOptionsBuilder::with_max_values()`](crate::options::JsonParserOptionsBuilder::with_max_values())).
+    /// The error contains the limit.
+    ///
+    /// Example input: `1 2 3` in streaming mode with a maximum of 2 values
+    #[error("more than {0} top-level values")]
+    TooManyValues(usize),
+
+    /// Parsing has not finished before the configured deadline (see
+    /// [`JsonParserOptionsBuilder::with_deadline()`](crate::options::JsonParserOptionsBuilder::with_deadline()))
+    #[error("parsing took longer than the configured deadline")]
+    Timeout,
+
+    /// The feeder was unable to provide more input (see
+    /// [`JsonFeeder::check_error()`](crate::feeder::JsonFeeder::check_error())). This error does not mean that the
+    /// input is invalid JSON. The error is shared so [`ParserError`] can
+    /// still be cloned.
+    ///
+    /// Feeders that have to be filled by the caller (e.g.
+    /// [`BufReaderJsonFeeder`](crate::feeder::BufReaderJsonFeeder) or
+    /// `AsyncBufReaderJsonFeeder`) do not report errors this way. Their
+    /// `fill_buf()` method returns I/O errors directly.
+    #[error("unable to read input: {0}")]
+    Feeder(#[source] Arc<FeederError>),
+}
+
+impl ParserError {
+    /// Get the category of this error
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::parser::ErrorCategory;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1 2]"));
+    /// let err = loop {
+    ///     if let Err(e) = parser.next_event() {
+    ///         break e;
+    ///     }
+    /// };
+    /// assert_eq!(err.category(), ErrorCategory::Syntax);
+    /// ```
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ParserError::IllegalCharacter { .. }
+            | ParserError::WrongEncoding(_)
+            | ParserError::SyntaxError
+            | ParserError::NoMoreInput
+            | ParserError::EmptyInput
+            | ParserError::TrailingData(_)
+            | ParserError::MalformedNumber(_)
+            | ParserError::ExponentNotAllowed(_)
+            | ParserError::FractionNotAllowed(_)
+            | ParserError::EmptyKey(_)
+            | ParserError::KeyRejected(_)
+            | ParserError::NumberRejected(_)
+            | ParserError::UnexpectedEvent { .. } => ErrorCategory::Syntax,
+            ParserError::UnsafeInteger(_)
+            | ParserError::DepthLimitExceeded(_)
+            | ParserError::StringTooLong(_)
+            | ParserError::TooManyValues(_)
+            | ParserError::Timeout => ErrorCategory::Limit,
+            ParserError::Feeder(_) => ErrorCategory::Io,
+        }
+    }
+}
+
+/// An error that can happen when restoring a [`ParserState`](crate::parser::ParserState)
+/// that is inconsistent (e.g. because it has been modified after it was
+/// serialized). See [`JsonParser::restore_state()`](crate::JsonParser::restore_state()).
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidStateError {
+    /// The state contains an unknown state of the parser's state machine.
+    /// The error contains this state.
+    #[error("unknown parser state: {0}")]
+    UnknownState(i8),
+
+    /// The stack of open objects and arrays is empty or contains an unknown
+    /// entry
+    #[error("invalid stack of open objects and arrays")]
+    InvalidStack,
+
+    /// The depth counters do not match the stack of open objects and arrays
+    #[error("depth counters do not match the stack of open objects and arrays")]
+    DepthMismatch,
+
+    /// The objects and arrays for which events have been returned do not
+    /// match the stack of open objects and arrays
+    #[error("reported objects and arrays do not match the stack of open objects and arrays")]
+    ContainerMismatch,
+
+    /// The pending events or the current event do not fit the objects and
+    /// arrays that are open
+    #[error("events do not match the open objects and arrays")]
+    EventMismatch,
+
+    /// The current path does not match the open objects and arrays
+    #[error("path does not match the open objects and arrays")]
+    PathMismatch,
+
+    /// The depth at which skipping a value ends is larger than the number
+    /// of open objects and arrays
+    #[error("invalid depth at which skipping ends")]
+    InvalidSkip,
+}
+
+/// A structural error the parser has recovered from (see
+/// [`JsonParserOptionsBuilder::with_lenient_structure()`](crate::options::JsonParserOptionsBuilder::with_lenient_structure())). Each variant
+/// contains the offset of the byte before which the missing character has
+/// been assumed.
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecoveredError {
+    /// A comma between two array elements or object entries was missing
+    #[error("missing comma at offset {0}")]
+    MissingComma(usize),
+
+    /// A colon between a field name and its value was missing
+    #[error("missing colon at offset {0}")]
+    MissingColon(usize),
+}
+
+/// A depth limit that has been exceeded (see [`ParserError::DepthLimitExceeded`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DepthLimit {
+    /// The maximum stack depth (see [`JsonParserOptionsBuilder::with_max_depth()`](crate::options::JsonParserOptionsBuilder::with_max_depth()))
+    Total(usize),
+
+    /// The maximum number of open arrays (see
+    /// [`JsonParserOptionsBuilder::with_max_array_depth()`](crate::options::JsonParserOptionsBuilder::with_max_array_depth()))
+    Array(usize),
+
+    /// The maximum number of open objects (see
+    /// [`JsonParserOptionsBuilder::with_max_object_depth()`](crate::options::JsonParserOptionsBuilder::with_max_object_depth()))
+    Object(usize),
+}
+
+impl Display for DepthLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DepthLimit::Total(n) => write!(f, "maximum depth of {}", n),
+            DepthLimit::Array(n) => write!(f, "maximum array depth of {}", n),
+            DepthLimit::Object(n) => write!(f, "maximum object depth of {}", n),
+        }
+    }
+}
+
+/// A string length limit that has been exceeded (see
+/// [`ParserError::StringTooLong`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StringLimit {
+    /// The maximum length in bytes (see
+    /// [`JsonParserOptionsBuilder::with_max_string_length()`](crate::options::JsonParserOptionsBuilder::with_max_string_length()))
+    Bytes(usize),
+
+    /// The maximum length in characters (see
+    /// [`JsonParserOptionsBuilder::with_max_string_chars()`](crate::options::JsonParserOptionsBuilder::with_max_string_chars()))
+    Chars(usize),
+}
+
+impl Display for StringLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringLimit::Bytes(n) => write!(f, "maximum string length of {} bytes", n),
+            StringLimit::Chars(n) => write!(f, "maximum string length of {} characters", n),
+        }
+    }
+}
@@ -0,0 +1,3597 @@
+mod error;
+mod state;
+mod values;
+
+pub use error::{
+    CollectKeysError, DepthLimit, ErrorCategory, FoldError, InvalidBoolValueError,
+    InvalidFloatValueError, InvalidIntValueError, InvalidScalarValueError, InvalidStateError,
+    InvalidStringValueError, NextEventError, ParserError, RecoveredError, StringLimit,
+};
+pub use state::ParserState;
+pub use values::Scalar;
+
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Formatter},
+    str::from_utf8,
+    sync::Arc,
+    time::Instant,
+};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::ParserMetrics;
+use crate::{
+    base64::{self, Base64Alphabet, Base64Error},
+    batch::{BatchedEvent, EventBatch},
+    feeder::{Encoding, JsonFeeder},
+    options::{JsonParserOptions, JsonParserOptionsBuilder, NumberDecision},
+    path::{to_pointer, PathSegment},
+    pool::{BufferPool, PooledBuffer},
+    JsonEvent, JsonEventKind,
+};
+
+const __: i8 = -1; // the universal error code
+
+// Characters are mapped into these 31 character classes. This allows for
+// a significant reduction in the size of the state transition table.
+const C_SPACE: i8 = 0; // space
+const C_WHITE: i8 = 1; // other whitespace
+const C_LCURB: i8 = 2; // {
+const C_RCURB: i8 = 3; // }
+const C_LSQRB: i8 = 4; // [
+const C_RSQRB: i8 = 5; // ]
+const C_COLON: i8 = 6; // :
+const C_COMMA: i8 = 7; // ,
+const C_QUOTE: i8 = 8; // "
+const C_BACKS: i8 = 9; // \
+const C_SLASH: i8 = 10; // /
+const C_PLUS: i8 = 11; // +
+const C_MINUS: i8 = 12; // -
+const C_POINT: i8 = 13; // .
+const C_ZERO: i8 = 14; // 0
+const C_DIGIT: i8 = 15; // 123456789
+const C_LOW_A: i8 = 16; // a
+const C_LOW_B: i8 = 17; // b
+const C_LOW_C: i8 = 18; // c
+const C_LOW_D: i8 = 19; // d
+const C_LOW_E: i8 = 20; // e
+const C_LOW_F: i8 = 21; // f
+const C_LOW_L: i8 = 22; // l
+const C_LOW_N: i8 = 23; // n
+const C_LOW_R: i8 = 24; // r
+const C_LOW_S: i8 = 25; // s
+const C_LOW_T: i8 = 26; // t
+const C_LOW_U: i8 = 27; // u
+const C_ABCDF: i8 = 28; // ABCDF
+const C_E: i8 = 29; // E
+const C_ETC: i8 = 30; // everything else
+
+/// This array maps the 128 ASCII characters into character classes.
+/// The remaining Unicode characters should be mapped to C_ETC.
+/// Non-whitespace control characters are errors.
+#[rustfmt::skip]
+const ASCII_CLASS: [i8; 128] = [
+    __,      __,      __,      __,      __,      __,      __,      __,
+    __,      C_WHITE, C_WHITE, __,      __,      C_WHITE, __,      __,
+    __,      __,      __,      __,      __,      __,      __,      __,
+    __,      __,      __,      __,      __,      __,      __,      __,
+
+    C_SPACE, C_ETC,   C_QUOTE, C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_PLUS,  C_COMMA, C_MINUS, C_POINT, C_SLASH,
+    C_ZERO,  C_DIGIT, C_DIGIT, C_DIGIT, C_DIGIT, C_DIGIT, C_DIGIT, C_DIGIT,
+    C_DIGIT, C_DIGIT, C_COLON, C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,
+
+    C_ETC,   C_ABCDF, C_ABCDF, C_ABCDF, C_ABCDF, C_E,     C_ABCDF, C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_LSQRB, C_BACKS, C_RSQRB, C_ETC,   C_ETC,
+
+    C_ETC,   C_LOW_A, C_LOW_B, C_LOW_C, C_LOW_D, C_LOW_E, C_LOW_F, C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_ETC,   C_LOW_L, C_ETC,   C_LOW_N, C_ETC,
+    C_ETC,   C_ETC,   C_LOW_R, C_LOW_S, C_LOW_T, C_LOW_U, C_ETC,   C_ETC,
+    C_ETC,   C_ETC,   C_ETC,   C_LCURB, C_ETC,   C_RCURB, C_ETC,   C_ETC
+];
+
+/// The state codes.
+const GO: i8 = 0; // start
+const OK: i8 = 1; // ok
+const OB: i8 = 2; // object
+const KE: i8 = 3; // key
+const CO: i8 = 4; // colon
+const VA: i8 = 5; // value
+const AR: i8 = 6; // array
+const ST: i8 = 7; // string
+const ES: i8 = 8; // escape
+const U1: i8 = 9; // u1
+const U2: i8 = 10; // u2
+const U3: i8 = 11; // u3
+const U4: i8 = 12; // u4
+const MI: i8 = 13; // minus
+const ZE: i8 = 14; // zero
+const IN: i8 = 15; // integer
+const F0: i8 = 16; // frac0
+const FR: i8 = 17; // fraction
+const E1: i8 = 18; // e
+const E2: i8 = 19; // ex
+const E3: i8 = 20; // exp
+const T1: i8 = 21; // tr
+const T2: i8 = 22; // tru
+const T3: i8 = 23; // true
+const F1: i8 = 24; // fa
+const F2: i8 = 25; // fal
+const F3: i8 = 26; // fals
+const F4: i8 = 27; // false
+const N1: i8 = 28; // nu
+const N2: i8 = 29; // nul
+const N3: i8 = 30; // null
+const RC: i8 = 99; // recover if in streaming mode, error otherwise
+
+/// The state transition table takes the current state and the current symbol,
+/// and returns either a new state or an action. An action is represented as a
+/// negative number. A JSON text is accepted if at the end of the text the
+/// state is OK and if the mode is MODE_DONE.
+#[rustfmt::skip]
+const STATE_TRANSITION_TABLE: [i8; 992] = [
+/*               white                                      1-9                                   ABCDF  etc
+             space |  {  }  [  ]  :  ,  "  \  /  +  -  .  0  |  a  b  c  d  e  f  l  n  r  s  t  u  |  E  | pad */
+/*start  GO*/  GO,GO,-6,__,-5,__,__,__,ST,__,__,__,MI,__,ZE,IN,__,__,__,__,__,F1,__,N1,__,__,T1,__,__,__,__,__,
+/*ok     OK*/  OK,OK,RC,-8,RC,-7,__,-3,RC,__,__,__,RC,__,RC,RC,__,__,__,__,__,RC,__,RC,__,__,RC,__,__,__,__,__,
+/*object OB*/  OB,OB,__,-9,__,__,__,__,ST,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*key    KE*/  KE,KE,__,__,__,__,__,__,ST,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*colon  CO*/  CO,CO,__,__,__,__,-2,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*value  VA*/  VA,VA,-6,__,-5,__,__,__,ST,__,__,__,MI,__,ZE,IN,__,__,__,__,__,F1,__,N1,__,__,T1,__,__,__,__,__,
+/*array  AR*/  AR,AR,-6,__,-5,-7,__,__,ST,__,__,__,MI,__,ZE,IN,__,__,__,__,__,F1,__,N1,__,__,T1,__,__,__,__,__,
+/*string ST*/  ST,__,ST,ST,ST,ST,ST,ST,-4,ES,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,ST,__,
+/*escape ES*/  __,__,__,__,__,__,__,__,ST,ST,ST,__,__,__,__,__,__,ST,__,__,__,ST,__,ST,ST,__,ST,U1,__,__,__,__,
+/*u1     U1*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,U2,U2,U2,U2,U2,U2,U2,U2,__,__,__,__,__,__,U2,U2,__,__,
+/*u2     U2*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,U3,U3,U3,U3,U3,U3,U3,U3,__,__,__,__,__,__,U3,U3,__,__,
+/*u3     U3*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,U4,U4,U4,U4,U4,U4,U4,U4,__,__,__,__,__,__,U4,U4,__,__,
+/*u4     U4*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,ST,ST,ST,ST,ST,ST,ST,ST,__,__,__,__,__,__,ST,ST,__,__,
+/*minus  MI*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,ZE,IN,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*zero   ZE*/  OK,OK,RC,-8,RC,-7,__,-3,RC,__,__,__,__,F0,__,__,__,__,__,__,E1,RC,__,RC,__,__,RC,__,__,E1,__,__,
+/*int    IN*/  OK,OK,RC,-8,RC,-7,__,-3,RC,__,__,__,__,F0,IN,IN,__,__,__,__,E1,RC,__,RC,__,__,RC,__,__,E1,__,__,
+/*frac0  F0*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,FR,FR,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*frac   FR*/  OK,OK,RC,-8,RC,-7,__,-3,RC,__,__,__,__,__,FR,FR,__,__,__,__,E1,RC,__,RC,__,__,RC,__,__,E1,__,__,
+/*e      E1*/  __,__,__,__,__,__,__,__,__,__,__,E2,E2,__,E3,E3,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*ex     E2*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,E3,E3,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*exp    E3*/  OK,OK,RC,-8,RC,-7,__,-3,RC,__,__,__,__,__,E3,E3,__,__,__,__,__,RC,__,RC,__,__,RC,__,__,__,__,__,
+/*tr     T1*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,T2,__,__,__,__,__,__,__,
+/*tru    T2*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,T3,__,__,__,__,
+/*true   T3*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,OK,__,__,__,__,__,__,__,__,__,__,__,
+/*fa     F1*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,F2,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,
+/*fal    F2*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,F3,__,__,__,__,__,__,__,__,__,
+/*fals   F3*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,F4,__,__,__,__,__,__,
+/*false  F4*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,OK,__,__,__,__,__,__,__,__,__,__,__,
+/*nu     N1*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,N2,__,__,__,__,
+/*nul    N2*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,N3,__,__,__,__,__,__,__,__,__,
+/*null   N3*/  __,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,__,OK,__,__,__,__,__,__,__,__,__,
+];
+
+/// These modes can be pushed on the stack.
+const MODE_ARRAY: i8 = 0;
+const MODE_DONE: i8 = 1;
+const MODE_KEY: i8 = 2;
+const MODE_OBJECT: i8 = 3;
+
+/// The ASCII record separator that precedes each JSON text in a JSON text
+/// sequence (RFC 7464)
+const RECORD_SEPARATOR: u8 = 0x1E;
+
+/// The number of bytes after which the parser checks if the deadline has
+/// passed (see [`JsonParserOptionsBuilder::with_deadline()`])
+const DEADLINE_CHECK_INTERVAL: usize = 4096;
+
+/// Check if the given byte is not the first byte of a UTF-8 encoded character
+fn is_utf8_continuation_byte(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+/// Check if the given byte can belong to a number token that is passed to
+/// the number hook (see [`JsonParserOptionsBuilder::with_number_hook()`]).
+/// This includes characters of other number formats (e.g. `0x1F` or
+/// `1_000`).
+fn is_number_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+' | b'.' | b'_')
+}
+
+/// Check if the given text is a number according to the JSON grammar
+fn is_json_number(s: &str) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    let s = s.strip_prefix('-').unwrap_or(s).as_bytes();
+    let mut i = match digits(s) {
+        0 => return false,
+        n if n > 1 && s[0] == b'0' => return false,
+        n => n,
+    };
+    if s.get(i) == Some(&b'.') {
+        match digits(&s[i + 1..]) {
+            0 => return false,
+            n => i += n + 1,
+        }
+    }
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(s.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(&s[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == s.len()
+}
+
+/// An object or array that is currently open (see
+/// [`JsonParser::container_stack()`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerFrame<'a> {
+    /// An object
+    Object {
+        /// The number of completed entries in the object
+        count: usize,
+
+        /// The name of the field whose value is currently being parsed or
+        /// has just been parsed. This is only available if path tracking
+        /// has been enabled with
+        /// [`JsonParserOptionsBuilder::with_path_tracking()`] and if a field
+        /// name has already been read.
+        field: Option<&'a str>,
+    },
+
+    /// An array
+    Array {
+        /// The number of completed elements in the array
+        count: usize,
+    },
+}
+
+/// A lightweight view of where the parser currently is in the JSON text.
+/// Get it with [`JsonParser::context()`]. It bundles the information that
+/// is otherwise spread across several methods of [`JsonParser`] and is
+/// cheap to create because it only borrows the parser's internal state.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseContext<'a> {
+    event: JsonEvent,
+    containers: &'a [Container],
+    path: Option<&'a [PathSegment]>,
+    buffer: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ParseContext<'a> {
+    /// The event that has been returned last or
+    /// [`JsonEvent::NeedMoreInput`] if there is none
+    pub fn event(&self) -> JsonEvent {
+        self.event
+    }
+
+    /// The number of objects and arrays that are currently open
+    pub fn depth(&self) -> usize {
+        self.containers.len()
+    }
+
+    /// The innermost object or array that is currently open (see
+    /// [`JsonParser::container_stack()`]) or `None` if the parser is on the
+    /// top level
+    pub fn container(&self) -> Option<ContainerFrame<'a>> {
+        self.depth().checked_sub(1).map(|i| self.frame(i))
+    }
+
+    /// All objects and arrays that are currently open, from the outermost
+    /// to the innermost one (see [`JsonParser::container_stack()`])
+    pub fn container_stack(&self) -> impl Iterator<Item = ContainerFrame<'a>> + 'a {
+        let context = *self;
+        (0..self.depth()).map(move |i| context.frame(i))
+    }
+
+    /// The name of the field that has just been parsed (after
+    /// [`JsonEvent::FieldName`]) or whose value is currently being parsed.
+    /// Right after [`JsonEvent::FieldName`], the name is always available.
+    /// Later on, it is only available if path tracking has been enabled
+    /// with [`JsonParserOptionsBuilder::with_path_tracking()`]. Returns
+    /// `None` if the innermost container is not an object or if the name is
+    /// not valid UTF-8.
+    pub fn key(&self) -> Option<&'a str> {
+        if self.event == JsonEvent::FieldName {
+            return from_utf8(self.buffer).ok();
+        }
+        match self.container()? {
+            ContainerFrame::Object { field, .. } => field,
+            ContainerFrame::Array { .. } => None,
+        }
+    }
+
+    /// The path to the value the last event refers to (see
+    /// [`JsonParser::current_path()`]) or `None` if path tracking has not
+    /// been enabled
+    pub fn path(&self) -> Option<&'a [PathSegment]> {
+        self.path
+    }
+
+    /// The path returned by [`Self::path()`] as a JSON Pointer (RFC 6901)
+    pub fn pointer(&self) -> Option<String> {
+        self.path.map(to_pointer)
+    }
+
+    /// The decoded bytes of the string, field name, or number that has just
+    /// been parsed (i.e. what [`JsonParser::current_str()`] returns, but
+    /// without validating UTF-8). The slice is empty for other events.
+    pub fn bytes(&self) -> &'a [u8] {
+        match self.event {
+            JsonEvent::FieldName
+            | JsonEvent::ValueString
+            | JsonEvent::ValueInt
+            | JsonEvent::ValueFloat => self.buffer,
+            _ => &[],
+        }
+    }
+
+    /// The number of bytes parsed so far (see
+    /// [`JsonParser::parsed_bytes()`])
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Convert the container at the given index to a [`ContainerFrame`]
+    fn frame(&self, i: usize) -> ContainerFrame<'a> {
+        let c = &self.containers[i];
+        if c.is_object {
+            let field = match self.path.and_then(|p| p.get(i)) {
+                Some(PathSegment::Key(k)) => Some(k.as_str()),
+                _ => None,
+            };
+            ContainerFrame::Object {
+                count: c.count,
+                field,
+            }
+        } else {
+            ContainerFrame::Array { count: c.count }
+        }
+    }
+}
+
+/// The result of [`JsonParser::fold_numbers()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fold<A> {
+    /// The parser needs more input. Provide more input to the feeder and
+    /// call [`JsonParser::fold_numbers()`] again with the contained
+    /// accumulator to continue.
+    NeedMoreInput(A),
+
+    /// The end of the JSON text has been reached. The result contains the
+    /// final accumulator.
+    Done(A),
+}
+
+/// An object or array that has been opened but not closed yet
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Container {
+    /// `true` if the container is an object, `false` if it is an array
+    is_object: bool,
+
+    /// The number of completed values in this container
+    count: usize,
+}
+
+/// Where the parser is in a JSONP callback wrapper (see
+/// [`JsonParserOptionsBuilder::with_jsonp()`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum JsonpState {
+    /// JSONP is disabled or the JSON text is not wrapped
+    Off,
+
+    /// Before or inside the callback name
+    Callback,
+
+    /// In white space between the callback name and the opening parenthesis
+    BeforeParen,
+
+    /// Inside the parentheses
+    Inner,
+
+    /// After the closing parenthesis
+    AfterParen,
+
+    /// After the semicolon
+    Done,
+}
+
+/// The line and column of the input consumed so far
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Position {
+    /// The zero-based line
+    line: usize,
+
+    /// The zero-based column in Unicode scalar values
+    column: usize,
+
+    /// The zero-based column in UTF-16 code units
+    column_utf16: usize,
+
+    /// `true` if the last byte was a carriage return, so a directly following
+    /// line feed does not start another line
+    after_cr: bool,
+}
+
+impl Position {
+    /// Advance the position by the given byte of a UTF-8 encoded input
+    fn advance(&mut self, b: u8) {
+        match b {
+            b'\n' if self.after_cr => {}
+            b'\n' | b'\r' => {
+                self.line += 1;
+                self.column = 0;
+                self.column_utf16 = 0;
+            }
+            // continuation bytes do not start a new character
+            0x80..=0xBF => {}
+            // characters encoded with 4 bytes are outside the BMP and
+            // require a UTF-16 surrogate pair
+            0xF0.. => {
+                self.column += 1;
+                self.column_utf16 += 2;
+            }
+            _ => {
+                self.column += 1;
+                self.column_utf16 += 1;
+            }
+        }
+        self.after_cr = b == b'\r';
+    }
+}
+
+/// A non-blocking, event-based JSON parser.
+///
+/// If the feeder implements [`Clone`], the parser can be cloned too. This
+/// allows you to take a snapshot of the parser's state (e.g. before a
+/// speculative decision), continue parsing, and restore the snapshot later
+/// to backtrack. Cloning copies the parser's internal stack and buffers as
+/// well as the feeder, so it takes time proportional to the size of the
+/// current state. The clone and the original are completely independent.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::SliceJsonFeeder;
+///
+/// let json = r#"[1, 2]"#.as_bytes();
+/// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+/// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+///
+/// let checkpoint = parser.clone();
+/// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+/// assert_eq!(parser.current_int::<i32>().unwrap(), 1);
+///
+/// // backtrack
+/// let mut parser = checkpoint;
+/// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+/// assert_eq!(parser.current_int::<i32>().unwrap(), 1);
+/// ```
+#[derive(Clone)]
+pub struct JsonParser<T> {
+    pub feeder: T,
+
+    /// The options the parser has been created with
+    options: JsonParserOptions,
+
+    /// The stack containing the current modes
+    stack: Vec<i8>,
+
+    /// The maximum number of modes on the stack
+    depth: usize,
+
+    /// The number of arrays on the stack
+    array_depth: usize,
+
+    /// The number of objects on the stack
+    object_depth: usize,
+
+    /// `true` if streaming mode is enabled, which means that the parser can
+    /// handle a stream of multiple JSON values
+    streaming: bool,
+
+    /// `true` if the parser should handle a JSON text sequence (RFC 7464)
+    json_seq: bool,
+
+    /// `true` if the parser should discard all input up to the next record
+    /// separator (see [`Self::skip_to_next_record()`])
+    skipping_record: bool,
+
+    /// `true` if objects and arrays that would exceed the maximum stack depth
+    /// should be skipped instead of causing an error
+    skip_beyond_max_depth: bool,
+
+    /// `true` if an empty input (or one that only consists of white space)
+    /// should be treated like the end of the input and not as an error
+    allow_empty_input: bool,
+
+    /// The modes ([`MODE_ARRAY`] or [`MODE_KEY`]) of the brackets that are
+    /// currently open in a value that is being skipped because it exceeds the
+    /// maximum stack depth. Empty if no value is being skipped.
+    skipped_brackets: Vec<i8>,
+
+    /// `true` if the parser is inside a string of a skipped value
+    skipped_in_string: bool,
+
+    /// The number of objects and arrays that are currently open beyond a
+    /// depth limit and whose events are suppressed because depth clamping
+    /// is enabled (see [`JsonParserOptionsBuilder::with_clamp_depth()`])
+    clamped_depth: usize,
+
+    /// `true` if the values inside the clamped objects and arrays are
+    /// reported as elements of the enclosing array, `false` if the
+    /// outermost clamped object or array is reported as a single
+    /// [`JsonEvent::TruncatedValue`] (see
+    /// [`JsonParserOptionsBuilder::with_clamp_depth()`])
+    clamp_flatten: bool,
+
+    /// `true` if the previous character in a string of a skipped value was a
+    /// backslash
+    skipped_escape: bool,
+
+    /// The current state
+    state: i8,
+
+    /// Collects all characters if the current state is ST (String),
+    /// IN (Integer), FR (Fraction) or the like
+    current_buffer: PooledBuffer,
+
+    /// The number of Unicode scalar values in the string that is currently
+    /// being parsed, including the ones that have already been consumed from
+    /// [`Self::current_buffer`] in streaming string mode. Characters are only
+    /// counted while parsing if [`Self::count_chars`] is `true`. Otherwise,
+    /// this is just the number of consumed characters.
+    char_count: usize,
+
+    /// `true` if a limit for the number of characters in a string has been
+    /// configured, so [`Self::char_count`] has to be maintained while parsing
+    count_chars: bool,
+
+    /// `true` if the characters of a string need more processing than just
+    /// being added to [`Self::current_buffer`] (e.g. because string limits,
+    /// raw values, or structure-only mode are enabled). Computed once when
+    /// the parser is created so the shortcut for plain string characters
+    /// only has to check a single flag.
+    string_extras: bool,
+
+    /// The number of bytes that have already been consumed from
+    /// [`Self::current_buffer`] in streaming string mode
+    consumed_string_bytes: usize,
+
+    /// `true` if string values, numbers, and white space are skipped in bulk
+    /// instead of being passed through the state machine byte by byte (see
+    /// [`JsonParserOptionsBuilder::with_structure_only()`])
+    fast_skip: bool,
+
+    /// `false` if the characters of the string that is currently being
+    /// parsed are not kept in [`Self::current_buffer`] (see
+    /// [`JsonParserOptionsBuilder::with_structure_only()`])
+    buffer_string: bool,
+
+    /// Collects the original text of the current scalar value or field name
+    /// if raw values are enabled
+    raw_buffer: Vec<u8>,
+
+    /// The first event returned by [`Self::parse()`]
+    event1: JsonEvent,
+
+    /// The second event returned by [`Self::parse()`]
+    event2: JsonEvent,
+
+    /// Tracks the number of bytes that have been processed
+    parsed_bytes: usize,
+
+    /// The offset directly after the last complete top-level value
+    last_value_end: usize,
+
+    /// The number of top-level values that have been completed so far
+    value_count: usize,
+
+    /// `true` if any of [`Self::byte_extras`], [`Self::char_extras`],
+    /// [`Self::string_extras`], or [`Self::fast_skip`] is `true`, i.e. if
+    /// options have to be checked for each byte
+    extras: bool,
+
+    /// `true` if events have to be checked, converted, or normalized before
+    /// they are returned (e.g. because safe integers, a maximum number of
+    /// top-level values, or structure-only mode are enabled). Computed once
+    /// when the parser is created, like [`Self::byte_extras`].
+    event_extras: bool,
+
+    /// `true` if characters have to be checked before or after they are
+    /// passed through the state machine (e.g. because a number hook, raw
+    /// values, underscore separators, or a restricted number format are
+    /// enabled). Computed once when the parser is created, like
+    /// [`Self::byte_extras`].
+    char_extras: bool,
+
+    /// `true` if every input byte has to be checked before it is parsed
+    /// (e.g. because a deadline, position tracking, a leading shebang line,
+    /// or JSONP is enabled). Computed once when the parser is created so
+    /// the default configuration only has to check a single flag per byte.
+    byte_extras: bool,
+
+    /// `true` while the parser skips a leading line starting with `#` (see
+    /// [`JsonParserOptionsBuilder::with_leading_shebang()`])
+    in_shebang: bool,
+
+    /// Where the parser is in a JSONP callback wrapper (see
+    /// [`JsonParserOptionsBuilder::with_jsonp()`])
+    jsonp: JsonpState,
+
+    /// The name of the JSONP callback read so far
+    jsonp_callback: String,
+
+    /// `true` if the last byte was an underscore inside a number (see
+    /// [`JsonParserOptionsBuilder::with_underscore_separators()`])
+    pending_underscore: bool,
+
+    /// The original text of the number that is currently being read if a
+    /// number hook is set (see
+    /// [`JsonParserOptionsBuilder::with_number_hook()`]). The text is passed
+    /// to the hook as a whole before it is parsed.
+    number_token: Vec<u8>,
+
+    /// The value of [`Self::parsed_bytes`] after the first byte of
+    /// [`Self::number_token`] has been read
+    number_token_start: usize,
+
+    /// The state of the JSON number grammar after the bytes in
+    /// [`Self::number_token`] or `__` if the grammar has already failed (see
+    /// [`Self::number_token_state()`])
+    number_token_state: i8,
+
+    /// A character that has been put back to be parsed at the next call
+    /// of [`Self::next_event()`]
+    putback_character: Option<u8>,
+
+    /// Tracks if a UTF-16 high surrogate has been encountered
+    high_surrogate_pair: bool,
+
+    /// The last event returned by [`Self::next_event()`] (other than
+    /// [`JsonEvent::NeedMoreInput`])
+    current_event: JsonEvent,
+
+    /// The containers that have been opened but not closed yet. In contrast
+    /// to [`Self::stack`], this stack is only updated when an event is
+    /// returned from [`Self::next_event()`].
+    containers: Vec<Container>,
+
+    /// `true` if [`Self::path`] should be maintained
+    path_tracking: bool,
+
+    /// The path to the value the last event refers to (only maintained if
+    /// path tracking is enabled)
+    path: Vec<PathSegment>,
+
+    /// `true` if the last segment of [`Self::path`] belongs to a value that
+    /// has been completed and should be removed with the next event
+    pop_path: bool,
+
+    /// If a value is being skipped (see [`Self::skip_value()`]), the number
+    /// of open containers at which skipping ends
+    skip_until: Option<usize>,
+
+    /// All interned field names (only used if interning is enabled)
+    interned_field_names: Option<HashSet<Arc<str>>>,
+
+    /// The interned name of the last field
+    current_field: Option<Arc<str>>,
+
+    /// The position of the input consumed so far (only maintained if
+    /// position tracking is enabled)
+    position: Option<Position>,
+
+    /// The error returned by [`Self::next_event()`] (if any). Once an error
+    /// has occurred, it is returned again on every subsequent call.
+    last_error: Option<ParserError>,
+
+    /// The number of events returned by [`Self::next_event()`] (see
+    /// [`Self::event_count()`])
+    event_count: u64,
+
+    /// Counters for profiling (see [`Self::metrics()`])
+    #[cfg(feature = "metrics")]
+    metrics: ParserMetrics,
+}
+
+impl<T> JsonParser<T>
+where
+    T: JsonFeeder,
+{
+    /// Create a new JSON parser using the given [`JsonFeeder`]
+    pub fn new(feeder: T) -> Self {
+        Self::new_with_options(feeder, JsonParserOptions::default())
+    }
+
+    /// Create a new JSON parser using the given [`JsonFeeder`] with
+    /// streaming mode enabled, so the parser can handle a stream of multiple
+    /// top-level JSON values. This is a shortcut for
+    /// [`Self::new_with_options()`] with
+    /// [`JsonParserOptionsBuilder::with_streaming()`]. Use the latter if you
+    /// need to combine streaming mode with other options.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let mut parser = JsonParser::new_streaming(SliceJsonFeeder::new(b"1 2 3"));
+    /// let mut sum = 0;
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::ValueInt {
+    ///         sum += parser.current_int::<i32>().unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn new_streaming(feeder: T) -> Self {
+        Self::new_with_options(
+            feeder,
+            JsonParserOptionsBuilder::default()
+                .with_streaming(true)
+                .build(),
+        )
+    }
+
+    /// Create a new JSON parser using the given [`JsonFeeder`] and with a
+    /// defined maximum stack depth
+    #[deprecated(since = "1.1.0", note = "use `new_with_options` instead")]
+    pub fn new_with_max_depth(feeder: T, max_depth: usize) -> Self {
+        Self::new_with_options(
+            feeder,
+            JsonParserOptionsBuilder::default()
+                .with_max_depth(max_depth)
+                .build(),
+        )
+    }
+
+    /// Create a new JSON parser using the given [`JsonFeeder`] and
+    /// [`JsonParserOptions`]
+    pub fn new_with_options(feeder: T, options: JsonParserOptions) -> Self {
+        #[cfg(feature = "unicode-normalization")]
+        let nfc_strings = options.nfc_strings;
+        #[cfg(not(feature = "unicode-normalization"))]
+        let nfc_strings = false;
+
+        let mut parser = JsonParser {
+            feeder,
+            stack: Vec::from([MODE_DONE]),
+            depth: options.max_depth,
+            array_depth: 0,
+            object_depth: 0,
+            streaming: options.streaming || options.json_seq || options.tail,
+            json_seq: options.json_seq,
+            skipping_record: false,
+            skip_beyond_max_depth: options.skip_beyond_max_depth,
+            allow_empty_input: options.allow_empty_input,
+            skipped_brackets: Vec::new(),
+            skipped_in_string: false,
+            skipped_escape: false,
+            clamped_depth: 0,
+            clamp_flatten: false,
+            state: GO,
+            current_buffer: PooledBuffer::default(),
+            char_count: 0,
+            count_chars: options.max_string_chars.is_some(),
+            string_extras: options.max_string_length.is_some()
+                || options.max_string_chars.is_some()
+                || options.raw_values
+                || options.structure_only,
+            consumed_string_bytes: 0,
+            fast_skip: options.structure_only
+                && !options.raw_values
+                && options.max_string_chars.is_none()
+                && !options.underscore_separators
+                && !options.safe_integers
+                && options.number_hook.is_none()
+                && options.noncanonical_number_hook.is_none()
+                && options.deadline.is_none()
+                && !options.position_tracking
+                && !options.leading_shebang
+                && !options.jsonp,
+            buffer_string: true,
+            raw_buffer: vec![],
+            event1: JsonEvent::NeedMoreInput,
+            event2: JsonEvent::NeedMoreInput,
+            parsed_bytes: 0,
+            last_value_end: 0,
+            value_count: 0,
+            extras: false,
+            event_extras: options.safe_integers
+                || options.max_values.is_some()
+                || options.structure_only
+                || options.noncanonical_number_hook.is_some()
+                || options.reject_empty_keys
+                || options.reject_keys.is_some()
+                || nfc_strings
+                || options.intern_field_names,
+            char_extras: options.number_hook.is_some()
+                || options.json_seq
+                || options.skip_beyond_max_depth
+                || options.underscore_separators
+                || options.raw_values
+                || options.disallow_exponents
+                || options.disallow_fractions,
+            byte_extras: options.deadline.is_some()
+                || options.position_tracking
+                || options.leading_shebang
+                || options.jsonp,
+            in_shebang: false,
+            jsonp: if options.jsonp {
+                JsonpState::Callback
+            } else {
+                JsonpState::Off
+            },
+            jsonp_callback: String::new(),
+            pending_underscore: false,
+            number_token: vec![],
+            number_token_start: 0,
+            number_token_state: GO,
+            putback_character: None,
+            high_surrogate_pair: false,
+            current_event: JsonEvent::NeedMoreInput,
+            containers: vec![],
+            path_tracking: options.path_tracking,
+            path: vec![],
+            pop_path: false,
+            skip_until: None,
+            interned_field_names: options.intern_field_names.then(HashSet::new),
+            current_field: None,
+            position: options.position_tracking.then(Position::default),
+            last_error: None,
+            event_count: 0,
+            #[cfg(feature = "metrics")]
+            metrics: ParserMetrics::default(),
+            options,
+        };
+        parser.extras =
+            parser.byte_extras || parser.char_extras || parser.string_extras || parser.fast_skip;
+        parser
+    }
+
+    /// Take the buffer that collects strings, field names, and numbers from
+    /// the given pool and give it back when the parser is dropped. By
+    /// default, the parser allocates the buffer itself. Clones of the parser
+    /// take their own buffer from the same pool.
+    ///
+    /// Call this method right after creating the parser. Characters that
+    /// have already been collected are copied to the new buffer.
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::pool::BufferPool;
+    ///
+    /// /// A pool that recycles buffers
+    /// #[derive(Default)]
+    /// struct RecyclingPool(Mutex<Vec<Vec<u8>>>);
+    ///
+    /// impl BufferPool for RecyclingPool {
+    ///     fn alloc_buf(&self) -> Vec<u8> {
+    ///         self.0.lock().unwrap().pop().unwrap_or_default()
+    ///     }
+    ///
+    ///     fn return_buf(&self, buf: Vec<u8>) {
+    ///         self.0.lock().unwrap().push(buf);
+    ///     }
+    /// }
+    ///
+    /// let pool = Arc::new(RecyclingPool::default());
+    /// for json in [r#"{"name": "Elvis"}"#, r#"{"name": "Prince"}"#] {
+    ///     let feeder = SliceJsonFeeder::new(json.as_bytes());
+    ///     let mut parser = JsonParser::new(feeder).with_buffer_pool(pool.clone());
+    ///     while parser.next_event().unwrap().is_some() {}
+    /// }
+    ///
+    /// // the buffer has been returned to the pool
+    /// assert_eq!(pool.0.lock().unwrap().len(), 1);
+    /// ```
+    pub fn with_buffer_pool(mut self, pool: Arc<dyn BufferPool>) -> Self {
+        let mut buf = PooledBuffer::with_pool(pool);
+        buf.extend_from_slice(&self.current_buffer);
+        self.current_buffer = buf;
+        self
+    }
+
+    /// Push to the stack. Return `false` if the maximum stack depth has been
+    /// exceeded. Objects and arrays beyond the limit are allowed if depth
+    /// clamping is in effect.
+    fn push(&mut self, mode: i8) -> bool {
+        if self.stack.len() >= self.depth && self.clamped_depth == 0 {
+            return false;
+        }
+        self.stack.push(mode);
+        true
+    }
+
+    /// Push a new object (`MODE_KEY`) or array (`MODE_ARRAY`) to the stack.
+    /// Return `false` if the object or array exceeds a depth limit and will
+    /// be skipped or clamped (i.e. if no start event should be generated),
+    /// or an error if it can be neither skipped nor clamped.
+    fn open_container(&mut self, mode: i8) -> Result<bool, ParserError> {
+        if self.clamped_depth > 0 {
+            self.stack.push(mode);
+            self.clamped_depth += 1;
+            return Ok(false);
+        }
+
+        let (depth, max_depth, limit): (_, _, fn(usize) -> DepthLimit) = if mode == MODE_ARRAY {
+            (
+                self.array_depth,
+                self.options.max_array_depth,
+                DepthLimit::Array,
+            )
+        } else {
+            (
+                self.object_depth,
+                self.options.max_object_depth,
+                DepthLimit::Object,
+            )
+        };
+
+        let exceeded = match max_depth {
+            Some(max) if depth >= max => Some(limit(max)),
+            _ if !self.push(mode) => Some(DepthLimit::Total(self.depth)),
+            _ => None,
+        };
+
+        if let Some(limit) = exceeded {
+            if self.options.clamp_depth {
+                // values can only be flattened into an array without making
+                // the event stream ill-formed
+                self.clamp_flatten = self.stack.last() == Some(&MODE_ARRAY);
+                self.stack.push(mode);
+                self.clamped_depth = 1;
+                return Ok(false);
+            }
+            if self.start_skipping(mode) {
+                return Ok(false);
+            }
+            return Err(ParserError::DepthLimitExceeded(limit));
+        }
+
+        if mode == MODE_ARRAY {
+            self.array_depth += 1;
+        } else {
+            self.object_depth += 1;
+        }
+        Ok(true)
+    }
+
+    /// Update the depth counters after an object or array has been popped from
+    /// the stack. Return the event that should be generated (or
+    /// [`JsonEvent::NeedMoreInput`] if the object or array has been clamped
+    /// and is not reported as a [`JsonEvent::TruncatedValue`]).
+    fn close_container(&mut self, mode: i8) -> JsonEvent {
+        if self.clamped_depth > 0 {
+            self.clamped_depth -= 1;
+            if self.clamped_depth == 0 && !self.clamp_flatten {
+                return JsonEvent::TruncatedValue;
+            }
+            return JsonEvent::NeedMoreInput;
+        }
+        if mode == MODE_ARRAY {
+            self.array_depth -= 1;
+            JsonEvent::EndArray
+        } else {
+            self.object_depth -= 1;
+            JsonEvent::EndObject
+        }
+    }
+
+    /// Pop the stack, assuring that the current mode matches the expectation.
+    /// Return `false` if there is underflow or if the modes mismatch.
+    fn pop(&mut self, mode: i8) -> bool {
+        if self.stack.is_empty() || *self.stack.last().unwrap() != mode {
+            return false;
+        }
+        self.stack.pop();
+        true
+    }
+
+    /// Get the next input character either from [`Self::putback_character`] or
+    /// from [`Self::feeder`]
+    fn get_next_input(&mut self) -> Option<u8> {
+        self.putback_character
+            .take()
+            .or_else(|| self.feeder.next_input())
+    }
+
+    /// Put back the given character to be parsed at the next call of
+    /// [`Self::next_event()`]
+    fn put_back(&mut self, c: u8) {
+        assert!(
+            self.putback_character.is_none(),
+            "Only one character can be put back"
+        );
+        self.putback_character = Some(c);
+        self.parsed_bytes -= 1;
+        if let Some(p) = &mut self.position {
+            // characters that are put back are never line breaks or part of
+            // multi-byte sequences
+            p.column -= 1;
+            p.column_utf16 -= 1;
+        }
+    }
+
+    /// Call this method to proceed parsing the JSON text and to get the next
+    /// event. The method returns [`Some(JsonEvent::NeedMoreInput)`](JsonEvent::NeedMoreInput)
+    /// if it needs more input data from the feeder or `None` if the end of the
+    /// JSON text has been reached.
+    ///
+    /// If the method returns an error, the parser is in a terminal state.
+    /// Every subsequent call returns the same error again without consuming
+    /// any more input (see also [`Self::last_error()`]). The only exception
+    /// is a JSON text sequence, which can be recovered from with
+    /// [`Self::skip_to_next_record()`]. Enable
+    /// [`JsonParserOptionsBuilder::with_end_after_error()`] to get `None`
+    /// instead of the same error.
+    pub fn next_event(&mut self) -> Result<Option<JsonEvent>, ParserError> {
+        if let Some(e) = &self.last_error {
+            if self.options.end_after_error {
+                return Ok(None);
+            }
+            return Err(e.clone());
+        }
+        let r = self.next_event_skipping();
+        match &r {
+            Ok(Some(JsonEvent::NeedMoreInput)) => {
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.need_more_input += 1;
+                }
+            }
+            Ok(None) => {}
+            Ok(Some(_)) => self.event_count += 1,
+            Err(e) => self.last_error = Some(e.clone()),
+        }
+        #[cfg(feature = "tracing")]
+        self.trace_result(&r);
+        r
+    }
+
+    /// Emit a `tracing` event for the result of [`Self::next_event()`]
+    /// (see the crate documentation for a list of fields)
+    #[cfg(feature = "tracing")]
+    fn trace_result(&self, r: &Result<Option<JsonEvent>, ParserError>) {
+        let depth = self.containers.len();
+        let offset = self.parsed_bytes;
+        match r {
+            Ok(Some(event)) => {
+                tracing::trace!(target: "actson", ?event, depth, offset, "JSON event")
+            }
+            Ok(None) => tracing::trace!(target: "actson", depth, offset, "end of JSON text"),
+            Err(error) => tracing::error!(
+                target: "actson",
+                %error,
+                category = ?error.category(),
+                depth,
+                offset,
+                "invalid JSON text"
+            ),
+        }
+    }
+
+    /// Proceed parsing and return the next event that is not
+    /// [`JsonEvent::NeedMoreInput`]. Whenever the parser needs more input,
+    /// this method calls `refill` with the feeder, so the caller does not
+    /// have to handle [`JsonEvent::NeedMoreInput`] in its own event loop.
+    /// Behaves like [`Self::next_event()`] otherwise.
+    ///
+    /// `refill` must either provide more input to the feeder or signal that
+    /// there is no more input (e.g. with [`PushJsonFeeder::done()`](crate::feeder::PushJsonFeeder::done())).
+    /// Otherwise, this method calls it again and again. Errors returned by
+    /// `refill` are passed through. For feeders that read from a reader, use
+    /// [`Self::next_event_blocking()`] instead.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::{JsonFeeder, PushJsonFeeder};
+    /// use actson::parser::ParserError;
+    ///
+    /// let json = br#"{"name": "Elvis"}"#;
+    /// let mut chunks = json.chunks(4);
+    ///
+    /// let mut parser = JsonParser::new(PushJsonFeeder::new());
+    /// let mut events = Vec::new();
+    /// while let Some(event) = parser
+    ///     .next_event_blocking_on(|feeder| {
+    ///         match chunks.next() {
+    ///             Some(chunk) => assert_eq!(feeder.push_bytes(chunk), chunk.len()),
+    ///             None => feeder.done(),
+    ///         }
+    ///         Ok::<_, ParserError>(())
+    ///     })
+    ///     .unwrap()
+    /// {
+    ///     events.push(event);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueString,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn next_event_blocking_on<F, E>(&mut self, mut refill: F) -> Result<Option<JsonEvent>, E>
+    where
+        F: FnMut(&mut T) -> Result<(), E>,
+        E: From<ParserError>,
+    {
+        loop {
+            match self.next_event()? {
+                Some(JsonEvent::NeedMoreInput) => refill(&mut self.feeder)?,
+                event => return Ok(event),
+            }
+        }
+    }
+
+    /// Collect the field names of the object that has just started and skip
+    /// their values. Call this method after [`Self::next_event()`] has
+    /// returned [`JsonEvent::StartObject`]. The method reads up to and
+    /// including the [`JsonEvent::EndObject`] that closes the object, so
+    /// the next call of [`Self::next_event()`] returns the event after the
+    /// object. Nested objects and arrays are skipped entirely (see
+    /// [`Self::skip_value()`]) but still validated. This is useful to
+    /// discover the structure of a JSON text without reading all values.
+    ///
+    /// The method returns [`ParserError::UnexpectedEvent`] if the current
+    /// event is not [`JsonEvent::StartObject`]. Whenever the parser needs
+    /// more input, it calls `refill` with the feeder (see
+    /// [`Self::next_event_blocking_on()`]). For feeders that read from a
+    /// reader, use [`Self::collect_keys()`] instead.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::{JsonFeeder, PushJsonFeeder};
+    /// use actson::parser::CollectKeysError;
+    ///
+    /// let json = br#"{"name": "Elvis", "albums": [{"title": "Elvis Is Back!"}], "born": 1935}"#;
+    /// let mut parser = JsonParser::new(PushJsonFeeder::new());
+    /// parser.feeder.push_bytes(json);
+    /// parser.feeder.done();
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    /// let keys = parser
+    ///     .collect_keys_on(|_| Ok::<_, CollectKeysError>(()))
+    ///     .unwrap();
+    /// assert_eq!(keys, ["name", "albums", "born"]);
+    /// assert_eq!(parser.next_event().unwrap(), None);
+    /// ```
+    pub fn collect_keys_on<F, E>(&mut self, mut refill: F) -> Result<Vec<String>, E>
+    where
+        F: FnMut(&mut T) -> Result<(), E>,
+        E: From<ParserError> + From<InvalidStringValueError>,
+    {
+        if self.current_event != JsonEvent::StartObject {
+            return Err(ParserError::UnexpectedEvent {
+                expected: JsonEventKind::Object,
+                found: Some(self.current_event),
+            }
+            .into());
+        }
+
+        let mut keys = Vec::new();
+        loop {
+            match self.next_event_blocking_on(&mut refill)? {
+                Some(JsonEvent::FieldName) => {
+                    keys.push(self.current_str()?.to_string());
+                    self.skip_value();
+                }
+                Some(JsonEvent::EndObject) => return Ok(keys),
+                Some(_) => {}
+                None => return Err(ParserError::NoMoreInput.into()),
+            }
+        }
+    }
+
+    /// Return the error that [`Self::next_event()`] has returned, or `None`
+    /// if no error has occurred so far. This allows you to inspect the error
+    /// after the fact (e.g. if the error has been passed on and the parser is
+    /// checked later).
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::parser::ParserError;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1, }"));
+    /// while let Ok(Some(_)) = parser.next_event() {}
+    ///
+    /// assert!(matches!(parser.last_error(), Some(ParserError::SyntaxError)));
+    /// ```
+    pub fn last_error(&self) -> Option<&ParserError> {
+        self.last_error.as_ref()
+    }
+
+    /// Get the next event taking [`Self::skip_until`] into account
+    fn next_event_skipping(&mut self) -> Result<Option<JsonEvent>, ParserError> {
+        loop {
+            let event = self.next_event_internal()?;
+            let Some(until) = self.skip_until else {
+                return Ok(event);
+            };
+            match event {
+                Some(JsonEvent::NeedMoreInput) | None => return Ok(event),
+                Some(JsonEvent::StartObject | JsonEvent::StartArray | JsonEvent::FieldName) => {}
+                Some(_) => {
+                    if self.containers.len() == until {
+                        self.skip_until = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check if the given error has been caused by input that is encoded in
+    /// UTF-16 or UTF-32 instead of UTF-8 and, if so, replace it with
+    /// [`ParserError::WrongEncoding`]. `b` is the byte that caused the error.
+    /// The heuristic only applies to the first two bytes of the input and
+    /// looks at up to three further bytes the feeder has already buffered
+    /// (see [`JsonFeeder::peek()`]) without consuming them, so the offset of
+    /// the error and the remaining input do not change. JSON text sequences
+    /// are not checked because the parser can skip to the next record after
+    /// an error.
+    fn detect_wrong_encoding(&self, err: ParserError, b: u8) -> ParserError {
+        if self.json_seq
+            || self.parsed_bytes > 2
+            || !matches!(
+                err,
+                ParserError::IllegalCharacter { .. } | ParserError::SyntaxError
+            )
+        {
+            return err;
+        }
+
+        let offset = self.parsed_bytes - 1;
+        let peek = self.feeder.peek();
+        let next = [
+            peek.first().copied(),
+            peek.get(1).copied(),
+            peek.get(2).copied(),
+        ];
+
+        let encoding = match (offset, b, next) {
+            // byte order marks
+            (0, 0xFE, [Some(0xFF), ..]) => Encoding::Utf16Be,
+            (0, 0xFF, [Some(0xFE), Some(0), Some(0)]) => Encoding::Utf32Le,
+            (0, 0xFF, [Some(0xFE), ..]) => Encoding::Utf16Le,
+            (0, 0, [Some(0), Some(0xFE), Some(0xFF)]) => Encoding::Utf32Be,
+
+            // ASCII characters padded with NUL bytes
+            (0, 0, [Some(0), Some(0), Some(c)]) if c != 0 => Encoding::Utf32Be,
+            (0, 0, [Some(c), Some(0), _]) if c != 0 => Encoding::Utf16Be,
+            (1, 0, [Some(0), Some(0), _]) => Encoding::Utf32Le,
+            (1, 0, [Some(c), Some(0), _]) if c != 0 => Encoding::Utf16Le,
+
+            _ => return err,
+        };
+
+        ParserError::WrongEncoding(encoding)
+    }
+
+    /// Update the counters returned by [`Self::metrics()`] after the byte `b`
+    /// has been parsed. `state_before` is the state before `b` was parsed.
+    #[cfg(feature = "metrics")]
+    fn record_metrics(&mut self, state_before: i8, b: u8) {
+        let is_string_state = |s: i8| (ST..=U4).contains(&s);
+        if is_string_state(state_before) || is_string_state(self.state) {
+            self.metrics.string_bytes += 1;
+        } else if (MI..=E3).contains(&self.state) {
+            self.metrics.number_bytes += 1;
+        } else if matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+            self.metrics.whitespace_bytes += 1;
+        } else {
+            self.metrics.other_bytes += 1;
+        }
+    }
+
+    /// Get the next event without taking [`Self::skip_until`] into account
+    fn next_event_internal(&mut self) -> Result<Option<JsonEvent>, ParserError> {
+        if self.extras {
+            self.parse_next_event::<true>()
+        } else {
+            self.parse_next_event::<false>()
+        }
+    }
+
+    /// Parse the input until the next event is available. If `EXTRAS` is
+    /// `false`, all options that are checked for each byte are ignored, so
+    /// the default configuration does not have to pay for them (see
+    /// [`Self::extras`]).
+    fn parse_next_event<const EXTRAS: bool>(&mut self) -> Result<Option<JsonEvent>, ParserError> {
+        loop {
+            while self.event1 == JsonEvent::NeedMoreInput {
+                if let Some(b) = self.get_next_input() {
+                    self.parsed_bytes += 1;
+                    if EXTRAS && self.byte_extras && self.preprocess_byte(b)? {
+                        continue;
+                    }
+                    if self.state == ST && (32..=127).contains(&b) && b != b'\\' && b != b'"' {
+                        // shortcut
+                        #[cfg(feature = "metrics")]
+                        {
+                            self.metrics.string_bytes += 1;
+                        }
+                        if EXTRAS && self.string_extras {
+                            self.push_string_char(b)?;
+                        } else {
+                            self.current_buffer.push(b);
+                        }
+                    } else {
+                        #[cfg(feature = "metrics")]
+                        let state_before = self.state;
+                        self.parse::<EXTRAS>(b)
+                            .map_err(|e| self.detect_wrong_encoding(e, b))?;
+                        #[cfg(feature = "metrics")]
+                        self.record_metrics(state_before, b);
+                        if EXTRAS && self.fast_skip && self.event1 == JsonEvent::NeedMoreInput {
+                            self.skip_insignificant_chars();
+                        }
+                    }
+                } else {
+                    if let Err(e) = self.feeder.check_error() {
+                        return Err(ParserError::Feeder(Arc::new(e)));
+                    }
+                    if self.feeder.is_done() {
+                        match self.jsonp {
+                            JsonpState::Callback | JsonpState::BeforeParen => {
+                                // the input was not wrapped (e.g. `true`)
+                                self.end_jsonp_wrapper()?;
+                                continue;
+                            }
+                            JsonpState::Inner => {
+                                // the closing parenthesis is missing
+                                return Err(ParserError::NoMoreInput);
+                            }
+                            _ => {}
+                        }
+                        if !self.number_token.is_empty() {
+                            // the input ended with a number
+                            self.finish_number_token()?;
+                        }
+                        if self.options.tail && !self.is_at_value_boundary() {
+                            // ignore the incomplete value (see `with_tail()`)
+                            return Ok(None);
+                        }
+                        if self.pending_underscore {
+                            // the input ended directly after an underscore
+                            return Err(ParserError::MalformedNumber(self.parsed_bytes - 1));
+                        }
+                        if self.state != OK {
+                            let r = self.state_to_event();
+                            if r != JsonEvent::NeedMoreInput {
+                                self.state = OK;
+                                return self.finish_event(r).map(Some);
+                            }
+                        }
+                        if self.state == GO
+                            && self.skipped_brackets.is_empty()
+                            && self.stack.len() == 1
+                            && self.stack[0] == MODE_DONE
+                        {
+                            // the input was empty or only contained white space
+                            if !self.allow_empty_input && !self.options.tail {
+                                return Err(ParserError::EmptyInput);
+                            }
+                            self.stack.pop();
+                            return Ok(None);
+                        }
+                        return if self.state == OK && self.pop(MODE_DONE) {
+                            Ok(None)
+                        } else {
+                            Err(ParserError::NoMoreInput)
+                        };
+                    }
+                    return Ok(Some(JsonEvent::NeedMoreInput));
+                }
+            }
+
+            let r = self.event1;
+            self.event1 = self.event2;
+            self.event2 = JsonEvent::NeedMoreInput;
+
+            if self.is_clamped_event(r) {
+                continue;
+            }
+
+            return self.finish_event(r).map(Some);
+        }
+    }
+
+    /// Check if the given event belongs to an object or array that exceeds a
+    /// depth limit and must not be reported because depth clamping is
+    /// enabled (see [`Self::clamp_flatten`])
+    fn is_clamped_event(&self, event: JsonEvent) -> bool {
+        self.clamped_depth > 0 && (!self.clamp_flatten || event == JsonEvent::FieldName)
+    }
+
+    /// Check the deadline, update the position, and skip a leading shebang
+    /// line or a JSONP callback wrapper if any of these are enabled (see
+    /// [`Self::byte_extras`]). Return `true` if the given byte has been
+    /// consumed and should not be parsed.
+    #[inline(never)]
+    fn preprocess_byte(&mut self, b: u8) -> Result<bool, ParserError> {
+        if self.parsed_bytes % DEADLINE_CHECK_INTERVAL == 1 {
+            self.check_deadline()?;
+        }
+        if let Some(p) = &mut self.position {
+            p.advance(b);
+        }
+        if self.in_shebang {
+            self.in_shebang = b != b'\n';
+            return Ok(true);
+        }
+        if self.parsed_bytes == 1 && b == b'#' && self.options.leading_shebang {
+            self.in_shebang = true;
+            return Ok(true);
+        }
+        if self.jsonp != JsonpState::Off {
+            return self.skip_jsonp_wrapper(b);
+        }
+        Ok(false)
+    }
+
+    /// Add a plain ASCII character to the string that is currently being
+    /// parsed if string limits, raw values, or structure-only mode are
+    /// enabled (see [`Self::string_extras`])
+    #[inline(never)]
+    fn push_string_char(&mut self, b: u8) -> Result<(), ParserError> {
+        if self.buffer_string {
+            self.current_buffer.push(b);
+        } else {
+            // the character is not kept in memory but still counts towards
+            // the maximum string length
+            self.consumed_string_bytes += 1;
+            if self.fast_skip {
+                self.check_string_length()?;
+                return self.skip_string_chars();
+            }
+        }
+        if self.count_chars {
+            self.char_count += 1;
+        }
+        self.check_string_length()?;
+        if self.options.raw_values {
+            self.raw_buffer.push(b);
+        }
+        Ok(())
+    }
+
+    /// Consume the remaining characters of a string value that is not kept
+    /// in memory up to the next character that needs to be parsed (see
+    /// [`JsonParserOptionsBuilder::with_structure_only()`])
+    fn skip_string_chars(&mut self) -> Result<(), ParserError> {
+        // do not skip beyond the maximum string length, so the character
+        // exceeding it is reported at the same offset as usual
+        let max = match self.options.max_string_length {
+            Some(max) => max.saturating_sub(self.consumed_string_bytes + self.current_buffer.len()),
+            None => usize::MAX,
+        };
+        let n = self.consume_while(max, |b| b >= 32 && b != b'"' && b != b'\\');
+        self.consumed_string_bytes += n;
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.string_bytes += n as u64;
+        }
+        Ok(())
+    }
+
+    /// Consume characters that do not change the parser's state after a
+    /// character has been parsed in structure-only mode (see
+    /// [`JsonParserOptionsBuilder::with_structure_only()`]). These are white
+    /// space between tokens and the digits of a number, which is not kept
+    /// in memory.
+    fn skip_insignificant_chars(&mut self) {
+        if self.putback_character.is_some() {
+            // the next byte must be parsed first
+            return;
+        }
+        if self.state < ST {
+            let _n = self.consume_while(usize::MAX, |b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'));
+            #[cfg(feature = "metrics")]
+            {
+                self.metrics.whitespace_bytes += _n as u64;
+            }
+        } else if matches!(self.state, IN | FR | E3) {
+            let _n = self.consume_while(usize::MAX, |b| b.is_ascii_digit());
+            #[cfg(feature = "metrics")]
+            {
+                self.metrics.number_bytes += _n as u64;
+            }
+        }
+    }
+
+    /// Consume bytes from the feeder's buffer as long as they match the
+    /// given predicate without passing them through the state machine, but
+    /// not more than `max` bytes. Return the number of consumed bytes.
+    #[inline(always)]
+    fn consume_while(&mut self, max: usize, f: impl Fn(u8) -> bool) -> usize {
+        let mut total = 0;
+        loop {
+            let buf = self.feeder.peek();
+            let buf = &buf[..buf.len().min(max - total)];
+            let len = buf.len();
+            let n = buf.iter().position(|&b| !f(b)).unwrap_or(len);
+            self.feeder.consume(n);
+            total += n;
+            if n < len || len == 0 {
+                // a byte that needs to be parsed or the end of the feeder's
+                // buffer has been reached
+                self.parsed_bytes += total;
+                return total;
+            }
+        }
+    }
+
+    /// Process a byte of a JSONP callback wrapper (see
+    /// [`JsonParserOptionsBuilder::with_jsonp()`]). Return `true` if the
+    /// byte belongs to the wrapper and has been consumed, or `false` if it
+    /// should be parsed as usual.
+    fn skip_jsonp_wrapper(&mut self, b: u8) -> Result<bool, ParserError> {
+        let is_space = matches!(b, b' ' | b'\t' | b'\r' | b'\n');
+        match self.jsonp {
+            JsonpState::Callback => {
+                let is_start = b.is_ascii_alphabetic() || b == b'_' || b == b'$';
+                let after_dot =
+                    self.jsonp_callback.is_empty() || self.jsonp_callback.ends_with('.');
+                if is_start || (!after_dot && (b.is_ascii_digit() || b == b'.')) {
+                    self.jsonp_callback.push(b as char);
+                    Ok(true)
+                } else if is_space && self.jsonp_callback.is_empty() {
+                    Ok(true)
+                } else if is_space && !after_dot {
+                    self.jsonp = JsonpState::BeforeParen;
+                    Ok(true)
+                } else if b == b'(' && !after_dot {
+                    self.jsonp = JsonpState::Inner;
+                    Ok(true)
+                } else {
+                    self.leave_jsonp_wrapper(b)
+                }
+            }
+
+            JsonpState::BeforeParen => {
+                if is_space {
+                    Ok(true)
+                } else if b == b'(' {
+                    self.jsonp = JsonpState::Inner;
+                    Ok(true)
+                } else {
+                    self.leave_jsonp_wrapper(b)
+                }
+            }
+
+            JsonpState::Inner => {
+                if b != b')'
+                    || !self.skipped_brackets.is_empty()
+                    || self.stack.len() != 1
+                    || self.stack[0] != MODE_DONE
+                {
+                    return Ok(false);
+                }
+                match self.state {
+                    OK => {}
+                    ZE | IN | FR | E3 | T3 | F4 | N3 => {
+                        // the parenthesis ends the number or literal
+                        self.event1 = self.state_to_event();
+                        self.state = OK;
+                    }
+                    _ => return Ok(false),
+                }
+                self.jsonp = JsonpState::AfterParen;
+                Ok(true)
+            }
+
+            JsonpState::AfterParen | JsonpState::Done => {
+                if is_space {
+                    Ok(true)
+                } else if b == b';' && self.jsonp == JsonpState::AfterParen {
+                    self.jsonp = JsonpState::Done;
+                    Ok(true)
+                } else {
+                    Err(ParserError::TrailingData(self.parsed_bytes - 1))
+                }
+            }
+
+            JsonpState::Off => Ok(false),
+        }
+    }
+
+    /// Called if it turns out that the JSON text is not wrapped in a JSONP
+    /// callback. The only values that can have been taken for a callback
+    /// name are the literals `true`, `false`, and `null`. Parse them as
+    /// usual.
+    fn end_jsonp_wrapper(&mut self) -> Result<(), ParserError> {
+        self.jsonp = JsonpState::Off;
+        let callback = std::mem::take(&mut self.jsonp_callback);
+        match callback.as_str() {
+            "" => Ok(()),
+            "true" | "false" | "null" => callback.bytes().try_for_each(|b| self.parse::<true>(b)),
+            _ => Err(ParserError::SyntaxError),
+        }
+    }
+
+    /// Like [`Self::end_jsonp_wrapper()`] but called with the byte `b` that
+    /// has revealed that the JSON text is not wrapped. Return `true` if `b`
+    /// has been put back because the literal has already produced an event,
+    /// or `false` if `b` should be parsed as usual.
+    fn leave_jsonp_wrapper(&mut self, b: u8) -> Result<bool, ParserError> {
+        self.end_jsonp_wrapper()?;
+        if self.event1 != JsonEvent::NeedMoreInput {
+            self.put_back(b);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Check if the deadline set with
+    /// [`JsonParserOptionsBuilder::with_deadline()`] has passed
+    fn check_deadline(&self) -> Result<(), ParserError> {
+        match self.options.deadline {
+            Some(deadline) if Instant::now() > deadline => Err(ParserError::Timeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// If only the structure should be reported (see
+    /// [`JsonParserOptionsBuilder::with_structure_only()`]), replace the
+    /// event of a scalar value with [`JsonEvent::Scalar`] and discard what
+    /// has been buffered of the value
+    fn structure_event(&mut self, event: JsonEvent) -> JsonEvent {
+        if !self.options.structure_only {
+            return event;
+        }
+        match event {
+            JsonEvent::ValueString
+            | JsonEvent::ValueInt
+            | JsonEvent::ValueFloat
+            | JsonEvent::ValueTrue
+            | JsonEvent::ValueFalse
+            | JsonEvent::ValueNull => {
+                self.current_buffer.clear();
+                JsonEvent::Scalar
+            }
+            _ => event,
+        }
+    }
+
+    /// Call the hook for non-canonical numbers (if any) if the number that
+    /// has just been parsed is not written in canonical form (see
+    /// [`JsonParserOptionsBuilder::with_noncanonical_number_hook()`])
+    fn check_canonical_number(&self, event: JsonEvent) {
+        let Some(hook) = &self.options.noncanonical_number_hook else {
+            return;
+        };
+        if !matches!(event, JsonEvent::ValueInt | JsonEvent::ValueFloat) {
+            return;
+        }
+        // numbers only consist of ASCII characters
+        let number = from_utf8(&self.current_buffer).unwrap_or_default();
+        if !self.options.canonical_number_rules.is_canonical(number) {
+            hook.0(number, &to_pointer(&self.path));
+        }
+    }
+
+    /// If safe integers are enabled, check if the integer that has just been
+    /// parsed can be represented exactly by a 64-bit floating point number
+    fn check_safe_integer(&self, event: JsonEvent) -> Result<(), ParserError> {
+        const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+        if !self.options.safe_integers || event != JsonEvent::ValueInt {
+            return Ok(());
+        }
+        match btoi::btoi::<i64>(&self.current_buffer) {
+            Ok(i) if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&i) => Ok(()),
+            _ => Err(ParserError::UnsafeInteger(Box::new(
+                String::from_utf8_lossy(&self.current_buffer).into_owned(),
+            ))),
+        }
+    }
+
+    /// If the number of top-level values is limited, check if the given
+    /// event would start another top-level value
+    fn check_max_values(&self, event: JsonEvent) -> Result<(), ParserError> {
+        let Some(max) = self.options.max_values else {
+            return Ok(());
+        };
+        if self.containers.is_empty()
+            && self.value_count >= max
+            && !matches!(
+                event,
+                JsonEvent::EndObject
+                    | JsonEvent::EndArray
+                    | JsonEvent::FieldName
+                    | JsonEvent::NeedMoreInput
+            )
+        {
+            return Err(ParserError::TooManyValues(max));
+        }
+        Ok(())
+    }
+
+    /// Check if the field name that has just been parsed should be rejected
+    fn check_field_name(&self) -> Result<(), ParserError> {
+        if self.options.reject_empty_keys && self.current_buffer.is_empty() {
+            // the field name is always reported directly after its closing
+            // quote, so the opening quote is two bytes before
+            return Err(ParserError::EmptyKey(self.parsed_bytes - 2));
+        }
+        if let Some(reject) = &self.options.reject_keys {
+            let key = String::from_utf8_lossy(&self.current_buffer);
+            if reject.0(&key) {
+                return Err(ParserError::KeyRejected(Box::new(key.into_owned())));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check and convert an event that is about to be returned from
+    /// [`Self::next_event()`] and update the parser's bookkeeping
+    fn finish_event(&mut self, event: JsonEvent) -> Result<JsonEvent, ParserError> {
+        if self.event_extras {
+            return self.finish_event_with_extras(event);
+        }
+        Ok(self.on_event(event))
+    }
+
+    /// Like [`Self::finish_event()`] but also apply the options that check,
+    /// convert, or normalize events (see [`Self::event_extras`])
+    #[inline(never)]
+    fn finish_event_with_extras(&mut self, event: JsonEvent) -> Result<JsonEvent, ParserError> {
+        self.check_safe_integer(event)?;
+        self.check_max_values(event)?;
+        let event = self.structure_event(event);
+        if matches!(event, JsonEvent::FieldName | JsonEvent::ValueString) {
+            self.normalize_current_string();
+        }
+        if event == JsonEvent::FieldName {
+            self.intern_field_name();
+        }
+        let event = self.on_event(event);
+        self.check_canonical_number(event);
+        if event == JsonEvent::FieldName {
+            self.check_field_name()?;
+        }
+        Ok(event)
+    }
+
+    /// Update the parser's bookkeeping for an event that is about to be
+    /// returned from [`Self::next_event()`] and return the event unchanged
+    fn on_event(&mut self, event: JsonEvent) -> JsonEvent {
+        self.current_event = event;
+
+        if self.pop_path {
+            self.path.pop();
+            self.pop_path = false;
+        }
+
+        match event {
+            JsonEvent::StartObject | JsonEvent::StartArray => {
+                self.push_index();
+                self.containers.push(Container {
+                    is_object: event == JsonEvent::StartObject,
+                    count: 0,
+                });
+            }
+
+            JsonEvent::EndObject | JsonEvent::EndArray => {
+                self.containers.pop();
+                self.complete_value();
+            }
+
+            JsonEvent::FieldName => {
+                if self.path_tracking {
+                    let key = String::from_utf8_lossy(&self.current_buffer).into_owned();
+                    self.path.push(PathSegment::Key(key));
+                }
+            }
+
+            JsonEvent::ValueString
+            | JsonEvent::ValueInt
+            | JsonEvent::ValueFloat
+            | JsonEvent::ValueTrue
+            | JsonEvent::ValueFalse
+            | JsonEvent::ValueNull
+            | JsonEvent::TruncatedValue
+            | JsonEvent::Scalar => {
+                self.push_index();
+                self.complete_value();
+            }
+
+            JsonEvent::NeedMoreInput => {}
+        }
+
+        if self.containers.is_empty()
+            && !matches!(
+                event,
+                JsonEvent::StartObject
+                    | JsonEvent::StartArray
+                    | JsonEvent::FieldName
+                    | JsonEvent::NeedMoreInput
+            )
+        {
+            self.last_value_end = self.parsed_bytes;
+            self.value_count += 1;
+        }
+
+        event
+    }
+
+    /// If NFC normalization is enabled, normalize the string or field name
+    /// that has just been parsed
+    #[cfg(feature = "unicode-normalization")]
+    fn normalize_current_string(&mut self) {
+        use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+        if !self.options.nfc_strings {
+            return;
+        }
+        let Ok(s) = from_utf8(&self.current_buffer) else {
+            return;
+        };
+        if is_nfc_quick(s.chars()) == IsNormalized::Yes {
+            return;
+        }
+        let normalized = s.nfc().collect::<String>();
+        if self.count_chars {
+            self.char_count = normalized.chars().count();
+        }
+        self.current_buffer.clear();
+        self.current_buffer.extend_from_slice(normalized.as_bytes());
+    }
+
+    #[cfg(not(feature = "unicode-normalization"))]
+    fn normalize_current_string(&mut self) {}
+
+    /// If interning is enabled, look up the current field name in the set of
+    /// interned field names or add it
+    fn intern_field_name(&mut self) {
+        let Some(names) = &mut self.interned_field_names else {
+            return;
+        };
+        self.current_field = from_utf8(&self.current_buffer).ok().map(|name| {
+            if let Some(n) = names.get(name) {
+                Arc::clone(n)
+            } else {
+                let n: Arc<str> = Arc::from(name);
+                names.insert(Arc::clone(&n));
+                n
+            }
+        });
+    }
+
+    /// If path tracking is enabled and a value is about to start inside an
+    /// array, add the value's index to the path
+    fn push_index(&mut self) {
+        if !self.path_tracking {
+            return;
+        }
+        if let Some(c) = self.containers.last() {
+            if !c.is_object {
+                self.path.push(PathSegment::Index(c.count));
+            }
+        }
+    }
+
+    /// Increase the number of completed values in the current container and
+    /// make sure the completed value's path segment will be removed with the
+    /// next event
+    fn complete_value(&mut self) {
+        if let Some(c) = self.containers.last_mut() {
+            c.count += 1;
+            self.pop_path = self.path_tracking;
+        }
+    }
+
+    /// This function is called for each character (or partial character) in the
+    /// JSON text. It will set [`self::event1`] and [`self::event2`] accordingly.
+    /// As a precondition, these fields should have a value of [`JsonEvent::NeedMoreInput`].
+    fn parse<const EXTRAS: bool>(&mut self, next_char: u8) -> Result<(), ParserError> {
+        if EXTRAS && self.char_extras && self.preprocess_char(next_char)? {
+            return Ok(());
+        }
+        self.parse_char::<EXTRAS>(next_char)
+    }
+
+    /// Collect number tokens for the number hook, handle record separators
+    /// in JSON text sequences, and skip values that exceed the maximum depth
+    /// if any of these are enabled (see [`Self::char_extras`]). Return `true`
+    /// if the given character has been consumed and should not be parsed.
+    #[inline(never)]
+    fn preprocess_char(&mut self, next_char: u8) -> Result<bool, ParserError> {
+        if !self.number_token.is_empty() {
+            if is_number_token_char(next_char) {
+                if let Some(s) = self.number_token_state(self.number_token_state, next_char) {
+                    self.number_token_state = s;
+                    self.number_token.push(next_char);
+                    return Ok(true);
+                }
+            }
+            self.finish_number_token()?;
+        }
+
+        if self.json_seq {
+            if self.skipping_record {
+                if next_char == RECORD_SEPARATOR {
+                    self.skipping_record = false;
+                }
+                return Ok(true);
+            }
+            if next_char == RECORD_SEPARATOR {
+                self.parse_record_separator()?;
+                return Ok(true);
+            }
+        }
+
+        if !self.skipped_brackets.is_empty() {
+            self.skip(next_char)?;
+            return Ok(true);
+        }
+
+        if self.options.number_hook.is_some()
+            && self.state < ST
+            && matches!(next_char, b'0'..=b'9' | b'-' | b'+' | b'.')
+        {
+            // collect the whole number token, so it can be passed to the hook
+            self.number_token.push(next_char);
+            self.number_token_start = self.parsed_bytes;
+            self.number_token_state = self.number_token_state(GO, next_char).unwrap_or(__);
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Get the state of the JSON number grammar after the given byte of a
+    /// number token has been read in the given state. Return `None` if the
+    /// byte does not belong to the token because the grammar ends the
+    /// number before it without failing (e.g. `t` in `1true`, which starts
+    /// another value in streaming mode). Once the grammar has failed (state
+    /// `__`), all following number token characters belong to the token, so
+    /// other number formats (e.g. `0x1F`) are passed to the hook as a whole
+    /// while valid input is split into the same tokens as without a hook.
+    fn number_token_state(&self, state: i8, b: u8) -> Option<i8> {
+        if state == __ || (b == b'_' && self.options.underscore_separators) {
+            // underscores are checked when the token is parsed
+            return Some(state);
+        }
+        let next_state =
+            STATE_TRANSITION_TABLE[((state as usize) << 5) + ASCII_CLASS[b as usize] as usize];
+        if next_state == __ || (MI..=E3).contains(&next_state) {
+            Some(next_state)
+        } else {
+            None
+        }
+    }
+
+    /// Call the number hook for the token in [`Self::number_token`] and
+    /// parse the accepted or rewritten text
+    fn finish_number_token(&mut self) -> Result<(), ParserError> {
+        let token = std::mem::take(&mut self.number_token);
+        let Some(hook) = &self.options.number_hook else {
+            return Ok(());
+        };
+
+        // a token that is not ASCII cannot be a number and will be rejected
+        // by the grammar below
+        let number = from_utf8(&token).unwrap_or_default();
+        let decision = hook.0(number);
+        let (text, rewritten) = match decision {
+            NumberDecision::Accept => (token.as_slice(), false),
+            NumberDecision::Reject => {
+                return Err(ParserError::NumberRejected(Box::new(number.to_string())));
+            }
+            NumberDecision::Rewrite(ref n) => {
+                if !is_json_number(n) {
+                    return Err(ParserError::NumberRejected(Box::new(number.to_string())));
+                }
+                (n.as_bytes(), true)
+            }
+        };
+
+        // Parse the text as if it came from the input. Errors in an accepted
+        // token refer to the offset of the offending byte. The text must be
+        // a single number (e.g. `1true` is not a number followed by `true`
+        // in streaming mode).
+        let parsed_bytes = self.parsed_bytes;
+        for (i, &b) in text.iter().enumerate() {
+            self.parsed_bytes = if rewritten {
+                self.number_token_start
+            } else {
+                self.number_token_start + i
+            };
+            self.parse_char::<true>(b)?;
+            if !(MI..=E3).contains(&self.state) || self.putback_character.is_some() {
+                return Err(ParserError::SyntaxError);
+            }
+        }
+        self.parsed_bytes = parsed_bytes;
+
+        if rewritten && self.options.raw_values {
+            // keep the original text
+            self.raw_buffer.clear();
+            self.raw_buffer.extend_from_slice(&token);
+        }
+
+        // reuse the allocation
+        self.number_token = token;
+        self.number_token.clear();
+
+        Ok(())
+    }
+
+    /// Process a single character that is not part of a number token that is
+    /// passed to the number hook
+    #[inline(always)]
+    fn parse_char<const EXTRAS: bool>(&mut self, next_char: u8) -> Result<(), ParserError> {
+        if EXTRAS
+            && self.char_extras
+            && self.options.underscore_separators
+            && self.parse_underscore(next_char)?
+        {
+            return Ok(());
+        }
+
+        // determine the character's class.
+        let next_class;
+        if next_char >= 128 {
+            next_class = C_ETC;
+        } else {
+            next_class = ASCII_CLASS[next_char as usize];
+            if next_class <= __ {
+                return Err(ParserError::IllegalCharacter {
+                    byte: next_char,
+                    offset: self.parsed_bytes - 1,
+                });
+            }
+        }
+
+        // Get the next state from the state transition table.
+        let mut next_state =
+            STATE_TRANSITION_TABLE[((self.state as usize) << 5) + next_class as usize];
+
+        // Characters that do not simply continue the current token need
+        // further checks
+        if next_state < 0 || next_state == RC {
+            next_state = self.resolve_transition(next_char, next_class, next_state)?;
+        }
+
+        if EXTRAS && self.char_extras {
+            self.check_char(next_char, next_state)?;
+        }
+
+        if next_state >= 0 {
+            if (ST..=E3).contains(&next_state) {
+                // According to 'STATE_TRANSITION_TABLE', we don't need to check
+                // for "state <= E3". There is no way we can get here without
+                // 'state' being less than or equal to E3.
+                // if state >= ST && state <= E3 {
+                if self.state >= ST {
+                    if self.state == ES {
+                        if let Some(d) = Self::decode_escape_character(next_char) {
+                            self.current_buffer.pop();
+                            self.current_buffer.push(d);
+                            next_state = ST;
+                        } else {
+                            self.current_buffer.push(next_char);
+                            if self.count_chars {
+                                self.char_count += 1;
+                            }
+                        }
+                    } else if self.state == U4 {
+                        self.current_buffer.push(next_char);
+                        if self.count_chars {
+                            self.char_count += 1;
+                        }
+
+                        // the last 6 bytes in the buffer will now be an
+                        // escaped unicode character in the form \uXXXX
+                        self.decode_utf_escape()?;
+                    } else {
+                        self.current_buffer.push(next_char);
+                        if self.count_chars && !is_utf8_continuation_byte(next_char) {
+                            self.char_count += 1;
+                        }
+                    }
+                } else {
+                    self.current_buffer.clear();
+                    self.char_count = 0;
+                    self.consumed_string_bytes = 0;
+                    if next_state != ST {
+                        self.current_buffer.push(next_char);
+                    } else {
+                        self.buffer_string =
+                            !self.options.structure_only || self.stack.last() == Some(&MODE_KEY);
+                    }
+                }
+            } else if next_state == OK {
+                // end of token identified, convert state to result
+                self.event1 = self.state_to_event();
+            }
+
+            // Change the state.
+            self.state = next_state;
+
+            if EXTRAS && next_state == ST && self.string_extras {
+                self.check_string_length()?;
+            }
+        } else {
+            // Or perform one of the actions.
+            self.perform_action(next_state)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a transition to an action, an error, or the recovery state `RC`
+    /// (i.e. to `next_state`) for unexpected data after the top-level value
+    /// and malformed numbers, and try to recover from missing commas or
+    /// colons and at the end of a value in streaming mode. Return the state
+    /// the parser should switch to instead.
+    fn resolve_transition(
+        &mut self,
+        next_char: u8,
+        next_class: i8,
+        mut next_state: i8,
+    ) -> Result<i8, ParserError> {
+        // Check if there is unexpected data after the top-level value. After
+        // a complete value (state OK), every non-whitespace character is
+        // trailing data. After a number, only characters that could start
+        // another token are. Others (e.g. `x` in `1x`) are handled below and
+        // cause a syntax error (or a malformed number if a leading zero is
+        // followed by a digit).
+        if (self.state == OK || next_state != __)
+            && next_class > C_WHITE
+            && self.is_top_level_value_complete()
+        {
+            return Err(ParserError::TrailingData(self.parsed_bytes - 1));
+        }
+
+        // Try to recover from a missing comma or colon if enabled.
+        if (next_state == RC || next_state == __) && self.options.lenient_structure {
+            if let Some(s) = self.recover_structure(next_char, next_class, next_state)? {
+                next_state = s;
+            }
+        }
+
+        // Try to recover if in streaming mode.
+        if next_state == RC {
+            if self.streaming && self.stack.len() == 1 && *self.stack.last().unwrap() == MODE_DONE {
+                // Streaming is enabled and we're in a state where we can handle
+                // another JSON value.
+                if self.state == OK {
+                    // The previous value has been converted to an event. Try
+                    // again to get the next state but start from the GO state.
+                    next_state = STATE_TRANSITION_TABLE[((GO as usize) << 5) + next_class as usize];
+                } else {
+                    // Switch to the OK state to convert the current value into
+                    // an event. Put back the character so it will be parsed again.
+                    next_state = OK;
+                    self.put_back(next_char);
+                }
+            } else {
+                // Streaming is not enabled or we're not on the top level. This
+                // is a syntax error.
+                next_state = __;
+            }
+        }
+
+        // A leading zero must not be followed by another digit. This is
+        // not treated as two numbers in streaming mode either.
+        if self.state == ZE && (next_class == C_ZERO || next_class == C_DIGIT) {
+            return Err(ParserError::MalformedNumber(self.parsed_bytes - 1));
+        }
+
+        Ok(next_state)
+    }
+
+    /// Check if the number format is restricted and collect the original
+    /// text of the current value if raw values are enabled (see
+    /// [`Self::char_extras`]). `next_state` is the state after `next_char`.
+    #[inline(never)]
+    fn check_char(&mut self, next_char: u8, next_state: i8) -> Result<(), ParserError> {
+        if next_state == E1 && self.options.disallow_exponents {
+            return Err(ParserError::ExponentNotAllowed(self.parsed_bytes - 1));
+        }
+        if next_state == F0 && self.options.disallow_fractions {
+            return Err(ParserError::FractionNotAllowed(self.parsed_bytes - 1));
+        }
+        if self.options.raw_values {
+            self.collect_raw(next_char, next_state);
+        }
+        Ok(())
+    }
+
+    /// Try to recover from a missing comma between two values or a missing
+    /// colon between a field name and its value (see
+    /// [`JsonParserOptionsBuilder::with_lenient_structure()`]). Return the
+    /// state that should be used for the given character instead of
+    /// `next_state` or `None` if the error cannot be recovered.
+    fn recover_structure(
+        &mut self,
+        next_char: u8,
+        next_class: i8,
+        next_state: i8,
+    ) -> Result<Option<i8>, ParserError> {
+        let mode = *self.stack.last().unwrap();
+        let err = if self.state == CO {
+            // the character must start the field's value
+            if STATE_TRANSITION_TABLE[((VA as usize) << 5) + next_class as usize] == __ {
+                return Ok(None);
+            }
+            self.perform_action(-2)?;
+            RecoveredError::MissingColon(self.parsed_bytes - 1)
+        } else if next_state == RC && (mode == MODE_ARRAY || mode == MODE_OBJECT) {
+            // the character starts a new value (or a field name)
+            if mode == MODE_OBJECT && next_class != C_QUOTE {
+                return Ok(None);
+            }
+            if self.state != OK {
+                // Convert the current number into an event first. Put back
+                // the character so it will be parsed again.
+                self.put_back(next_char);
+                return Ok(Some(OK));
+            }
+            self.perform_action(-3)?;
+            RecoveredError::MissingComma(self.parsed_bytes - 1)
+        } else {
+            return Ok(None);
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            target: "actson",
+            error = %err,
+            depth = self.containers.len(),
+            offset = self.parsed_bytes,
+            "recovered from invalid JSON text"
+        );
+
+        if let Some(hook) = &self.options.recovery_hook {
+            // every open object or array except the outermost one has a
+            // segment in the path
+            let len = self.path.len().min(self.containers.len().saturating_sub(1));
+            hook.0(&err, &to_pointer(&self.path[..len]));
+        }
+
+        Ok(Some(
+            STATE_TRANSITION_TABLE[((self.state as usize) << 5) + next_class as usize],
+        ))
+    }
+
+    /// Check if the string that is currently being parsed exceeds one of the
+    /// configured length limits. Strings are only checked between characters
+    /// and not in the middle of an escape sequence or a surrogate pair.
+    fn check_string_length(&self) -> Result<(), ParserError> {
+        if self.high_surrogate_pair {
+            return Ok(());
+        }
+        if let Some(max) = self.options.max_string_length {
+            if self.consumed_string_bytes + self.current_buffer.len() > max {
+                return Err(ParserError::StringTooLong(StringLimit::Bytes(max)));
+            }
+        }
+        if let Some(max) = self.options.max_string_chars {
+            if self.char_count > max {
+                return Err(ParserError::StringTooLong(StringLimit::Chars(max)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle underscores between the digits of a number (see
+    /// [`JsonParserOptionsBuilder::with_underscore_separators()`]). Returns
+    /// `true` if the given character was an underscore and has been consumed.
+    fn parse_underscore(&mut self, next_char: u8) -> Result<bool, ParserError> {
+        if next_char == b'_' && matches!(self.state, GO | VA | AR) {
+            // a number must not start with an underscore
+            return Err(ParserError::MalformedNumber(self.parsed_bytes - 1));
+        }
+
+        if next_char == b'_' && (MI..=E3).contains(&self.state) {
+            // an underscore must follow a digit (and not a leading zero)
+            if self.pending_underscore || !matches!(self.state, IN | FR | E3) {
+                return Err(ParserError::MalformedNumber(self.parsed_bytes - 1));
+            }
+            self.pending_underscore = true;
+            if self.options.raw_values {
+                self.raw_buffer.push(next_char);
+            }
+            return Ok(true);
+        }
+
+        if self.pending_underscore {
+            // an underscore must be followed by a digit
+            self.pending_underscore = false;
+            if !next_char.is_ascii_digit() {
+                return Err(ParserError::MalformedNumber(self.parsed_bytes - 2));
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Add a character to the original text of the current scalar value or
+    /// field name if it belongs to it. Clear the text if a new value starts.
+    fn collect_raw(&mut self, next_char: u8, next_state: i8) {
+        let in_token = |state: i8| (ST..=N3).contains(&state);
+        if in_token(next_state) {
+            if !in_token(self.state) {
+                self.raw_buffer.clear();
+            }
+            self.raw_buffer.push(next_char);
+        } else if (self.state == ST && next_state == -4)
+            || (matches!(self.state, T3 | F4 | N3) && next_state == OK)
+        {
+            // closing quote or last character of a literal name
+            self.raw_buffer.push(next_char);
+        }
+    }
+
+    /// Check if the parser is not in streaming mode and has completely parsed
+    /// a top-level value (or would have done so if the current number ended
+    /// here)
+    fn is_top_level_value_complete(&self) -> bool {
+        !self.streaming
+            && self.stack.len() == 1
+            && self.stack[0] == MODE_DONE
+            && matches!(self.state, OK | ZE | IN | FR | E3)
+    }
+
+    /// Check if the parser is between two top-level values (or before the
+    /// first one), i.e. if no top-level value has been started but not
+    /// completed yet
+    fn is_at_value_boundary(&self) -> bool {
+        self.skipped_brackets.is_empty()
+            && self.stack.len() == 1
+            && self.stack[0] == MODE_DONE
+            && matches!(self.state, GO | OK)
+    }
+
+    /// Consume a character of a value that is skipped because it exceeds the
+    /// maximum stack depth. Generate a [`JsonEvent::TruncatedValue`] as soon
+    /// as the skipped value has ended.
+    fn skip(&mut self, next_char: u8) -> Result<(), ParserError> {
+        if next_char < 128 && ASCII_CLASS[next_char as usize] <= __ {
+            return Err(ParserError::IllegalCharacter {
+                byte: next_char,
+                offset: self.parsed_bytes - 1,
+            });
+        }
+
+        if self.skipped_in_string {
+            if self.skipped_escape {
+                self.skipped_escape = false;
+            } else if next_char == b'\\' {
+                self.skipped_escape = true;
+            } else if next_char == b'"' {
+                self.skipped_in_string = false;
+            }
+            return Ok(());
+        }
+
+        match next_char {
+            b'"' => self.skipped_in_string = true,
+            b'{' => self.skipped_brackets.push(MODE_KEY),
+            b'[' => self.skipped_brackets.push(MODE_ARRAY),
+            b'}' | b']' => {
+                let mode = if next_char == b'}' {
+                    MODE_KEY
+                } else {
+                    MODE_ARRAY
+                };
+                if self.skipped_brackets.pop() != Some(mode) {
+                    return Err(ParserError::SyntaxError);
+                }
+                if self.skipped_brackets.is_empty() {
+                    self.state = OK;
+                    self.event1 = JsonEvent::TruncatedValue;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Start skipping an object or array that would exceed the maximum stack
+    /// depth if this is enabled. Return `false` otherwise.
+    fn start_skipping(&mut self, mode: i8) -> bool {
+        if !self.skip_beyond_max_depth {
+            return false;
+        }
+        self.skipped_brackets.clear();
+        self.skipped_brackets.push(mode);
+        self.skipped_in_string = false;
+        self.skipped_escape = false;
+        true
+    }
+
+    /// Handle a record separator in a JSON text sequence. Record separators
+    /// are only allowed between top-level values. Top-level numbers and
+    /// keywords must be followed by white space (typically a line feed)
+    /// before the next record separator. Otherwise, the record is considered
+    /// truncated.
+    fn parse_record_separator(&mut self) -> Result<(), ParserError> {
+        if self.stack.len() == 1
+            && *self.stack.last().unwrap() == MODE_DONE
+            && (self.state == GO || self.state == OK)
+        {
+            return Ok(());
+        }
+
+        // put back the record separator so it will be found again by
+        // `skip_to_next_record()`
+        self.put_back(RECORD_SEPARATOR);
+        Err(ParserError::SyntaxError)
+    }
+
+    /// Discard the current (invalid) record of a JSON text sequence and
+    /// continue parsing at the next record separator. Call this method after
+    /// [`Self::next_event()`] has returned an error to recover from it. The
+    /// method does not consume any input itself. Input up to the next record
+    /// separator will be discarded by subsequent calls of
+    /// [`Self::next_event()`].
+    ///
+    /// This method only has an effect if JSON text sequences have been enabled
+    /// with [`JsonParserOptionsBuilder::with_json_seq()`](crate::options::JsonParserOptionsBuilder::with_json_seq()).
+    pub fn skip_to_next_record(&mut self) {
+        if !self.json_seq {
+            return;
+        }
+        self.stack.clear();
+        self.stack.push(MODE_DONE);
+        self.array_depth = 0;
+        self.object_depth = 0;
+        self.containers.clear();
+        self.path.clear();
+        self.pop_path = false;
+        self.skip_until = None;
+        self.skipped_brackets.clear();
+        self.clamped_depth = 0;
+        self.state = OK;
+        self.current_buffer.clear();
+        self.char_count = 0;
+        self.consumed_string_bytes = 0;
+        self.raw_buffer.clear();
+        self.event1 = JsonEvent::NeedMoreInput;
+        self.event2 = JsonEvent::NeedMoreInput;
+        self.high_surrogate_pair = false;
+        self.pending_underscore = false;
+        self.number_token.clear();
+        self.last_error = None;
+        self.skipping_record = true;
+    }
+
+    /// Decodes an escape character
+    fn decode_escape_character(next_char: u8) -> Option<u8> {
+        match next_char {
+            b'\\' => Some(0x5C),
+            b'n' => Some(0x0A),
+            b'r' => Some(0x0D),
+            b't' => Some(0x09),
+            b'b' => Some(0x08),
+            b'f' => Some(0x0C),
+            b'/' => Some(0x2F),
+            b'"' => Some(0x22),
+            _ => None,
+        }
+    }
+
+    /// Decodes a UTF escape sequence (e.g. `\uXXXX`, or a surrogate pair
+    /// `\uXXXX\uXXXX`) to a character. Directly modifies the buffer.
+    fn decode_utf_escape(&mut self) -> Result<(), ParserError> {
+        // there have to be at least 6 bytes in the buffer
+        if self.current_buffer.len() < 6 {
+            return Err(ParserError::SyntaxError);
+        }
+
+        let unicode_in_utf8 = from_utf8(&self.current_buffer[self.current_buffer.len() - 4..])
+            .map_err(|_| ParserError::SyntaxError)?;
+
+        // convert the UTF-8 encoded unicode code point to a u32
+        let unicode =
+            u32::from_str_radix(unicode_in_utf8, 16).map_err(|_| ParserError::SyntaxError)?;
+
+        if (0xD800..=0xDBFF).contains(&unicode) {
+            // UTF-16 high pair
+            if self.high_surrogate_pair {
+                return Err(ParserError::SyntaxError);
+            }
+            self.high_surrogate_pair = true;
+        } else if (0xDC00..=0xDFFF).contains(&unicode) {
+            // UTF-16 low pair
+            if !self.high_surrogate_pair {
+                return Err(ParserError::SyntaxError);
+            }
+            self.high_surrogate_pair = false;
+
+            // UTF-16 surrogate pair detected; combine the high and low
+            // surrogates to get the unicode character. This will be the last
+            // 12 characters in the buffer
+            //
+            // \uXXXX\uXXXX
+            //   |  |  |  |
+            //   high  low
+
+            if self.current_buffer.len() < 12 {
+                return Err(ParserError::SyntaxError);
+            }
+
+            // create the high code point
+            let high_code_point = u16::from_str_radix(
+                from_utf8(
+                    &self.current_buffer
+                        [self.current_buffer.len() - 10..self.current_buffer.len() - 6],
+                )
+                .map_err(|_| ParserError::SyntaxError)?,
+                16,
+            )
+            .map_err(|_| ParserError::SyntaxError)?;
+
+            // create the low code point
+            let low_code_point = u16::from_str_radix(
+                from_utf8(&self.current_buffer[self.current_buffer.len() - 4..])
+                    .map_err(|_| ParserError::SyntaxError)?,
+                16,
+            )
+            .map_err(|_| ParserError::SyntaxError)?;
+
+            let char = char::decode_utf16([high_code_point, low_code_point].iter().cloned())
+                .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect::<String>();
+
+            // remove last 12 bytes and insert new
+            let len = self.current_buffer.len();
+            self.current_buffer.truncate(len - 12);
+            self.current_buffer.extend_from_slice(char.as_bytes());
+            if self.count_chars {
+                self.char_count -= 11;
+            }
+        } else {
+            // convert the u32 to a char
+            let unicode_char = char::from_u32(unicode).ok_or(ParserError::SyntaxError)?;
+
+            // regular case
+            // convert the char to a String and get the u8 bytes
+            let unicode_as_string = unicode_char.to_string();
+
+            // remove the last 6 bytes from the buffer
+            let len = self.current_buffer.len();
+            self.current_buffer.truncate(len - 6);
+            if self.count_chars {
+                self.char_count -= 5;
+            }
+
+            // add the UTF-8 encoded unicode code point to the buffer
+            self.current_buffer
+                .extend_from_slice(unicode_as_string.as_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Perform an action that changes the parser state. The method is
+    /// called for every structural character, so it is always inlined.
+    #[inline(always)]
+    fn perform_action(&mut self, action: i8) -> Result<(), ParserError> {
+        match action {
+            // empty }
+            -9 => {
+                if !self.pop(MODE_KEY) {
+                    return Err(ParserError::SyntaxError);
+                }
+                self.state = OK;
+                self.event1 = self.close_container(MODE_KEY);
+            }
+
+            // }
+            -8 => {
+                if !self.pop(MODE_OBJECT) {
+                    return Err(ParserError::SyntaxError);
+                }
+                let clamped = self.clamped_depth > 0;
+                let end = self.close_container(MODE_OBJECT);
+                match self.state_to_event() {
+                    JsonEvent::NeedMoreInput => self.event1 = end,
+                    _ if clamped && !self.clamp_flatten => self.event1 = end,
+                    e => {
+                        self.event1 = e;
+                        self.event2 = end;
+                    }
+                }
+                self.state = OK;
+            }
+
+            // ]
+            -7 => {
+                if !self.pop(MODE_ARRAY) {
+                    return Err(ParserError::SyntaxError);
+                }
+                let clamped = self.clamped_depth > 0;
+                let end = self.close_container(MODE_ARRAY);
+                match self.state_to_event() {
+                    JsonEvent::NeedMoreInput => self.event1 = end,
+                    _ if clamped && !self.clamp_flatten => self.event1 = end,
+                    e => {
+                        self.event1 = e;
+                        self.event2 = end;
+                    }
+                }
+                self.state = OK;
+            }
+
+            // {
+            -6 => {
+                let start = self.open_container(MODE_KEY)?;
+                if !self.skipped_brackets.is_empty() {
+                    return Ok(());
+                }
+                self.state = OB;
+                if start {
+                    self.event1 = JsonEvent::StartObject;
+                }
+            }
+
+            // [
+            -5 => {
+                let start = self.open_container(MODE_ARRAY)?;
+                if !self.skipped_brackets.is_empty() {
+                    return Ok(());
+                }
+                self.state = AR;
+                if start {
+                    self.event1 = JsonEvent::StartArray;
+                }
+            }
+
+            // "
+            -4 => {
+                if *self.stack.last().unwrap() == MODE_KEY {
+                    self.state = CO;
+                    self.event1 = JsonEvent::FieldName;
+                } else {
+                    self.state = OK;
+                    self.event1 = JsonEvent::ValueString;
+                }
+            }
+
+            // ,
+            -3 => {
+                match *self.stack.last().unwrap() {
+                    MODE_OBJECT => {
+                        // A comma causes a flip from object mode to key mode.
+                        if !self.pop(MODE_OBJECT) || !self.push(MODE_KEY) {
+                            return Err(ParserError::SyntaxError);
+                        }
+                        self.event1 = self.state_to_event();
+                        self.state = KE;
+                    }
+
+                    MODE_ARRAY => {
+                        self.event1 = self.state_to_event();
+                        self.state = VA;
+                    }
+
+                    _ => {
+                        return Err(ParserError::SyntaxError);
+                    }
+                }
+            }
+
+            // :
+            -2 => {
+                // A colon causes a flip from key mode to object mode.
+                if !self.pop(MODE_KEY) || !self.push(MODE_OBJECT) {
+                    return Err(ParserError::SyntaxError);
+                }
+                self.state = VA;
+            }
+
+            // Bad action.
+            _ => {
+                return Err(ParserError::SyntaxError);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts the current parser state to a JSON event. Returns the JSON
+    /// event or [`JsonEvent::NeedMoreInput`] if the current state does
+    /// not produce a JSON event
+    fn state_to_event(&self) -> JsonEvent {
+        match self.state {
+            IN | ZE => JsonEvent::ValueInt,
+            FR..=E3 => JsonEvent::ValueFloat,
+            T3 => JsonEvent::ValueTrue,
+            F4 => JsonEvent::ValueFalse,
+            N3 => JsonEvent::ValueNull,
+            _ => JsonEvent::NeedMoreInput,
+        }
+    }
+
+    /// Get the number of values that have been completed so far in the
+    /// innermost object or array that is currently open. For objects, this is
+    /// the number of entries (a field name and its value count as one entry).
+    ///
+    /// The count is incremented whenever [`Self::next_event()`] returns an
+    /// event that completes a value inside the container, i.e. a scalar value
+    /// or the end of a nested object or array. It starts at 0 for each new
+    /// container (so it is 0 right after [`JsonEvent::StartObject`] or
+    /// [`JsonEvent::StartArray`]). After [`JsonEvent::EndObject`] or
+    /// [`JsonEvent::EndArray`], it refers to the parent container again,
+    /// whose count now includes the container that has just been closed.
+    ///
+    /// Returns 0 if the parser is not inside an object or array.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = r#"[1, [2, 3], 4]"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    ///
+    /// let mut counts = Vec::new();
+    /// while let Some(_) = parser.next_event().unwrap() {
+    ///     counts.push(parser.current_container_count());
+    /// }
+    ///
+    /// assert_eq!(counts, vec![0, 1, 0, 1, 2, 2, 3, 0]);
+    /// ```
+    pub fn current_container_count(&self) -> usize {
+        self.containers.last().map(|c| c.count).unwrap_or(0)
+    }
+
+    /// Get an iterator over all objects and arrays that are currently open,
+    /// from the outermost to the innermost one. This is useful for debugging
+    /// or to render a breadcrumb if an error occurs deep inside a document.
+    /// The number of frames is the current nesting depth. Objects and arrays
+    /// that have been skipped (see
+    /// [`JsonParserOptionsBuilder::with_skip_beyond_max_depth()`]) are not
+    /// included.
+    ///
+    /// In contrast to [`Self::current_path()`], the iterator also returns
+    /// the number of completed values in each object or array. Field names
+    /// are only available if path tracking has been enabled.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ContainerFrame;
+    ///
+    /// let json = r#"{"users": [{}, {}, {"name": "Elvis", "age": }]}"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_path_tracking(true)
+    ///         .build(),
+    /// );
+    ///
+    /// while parser.next_event().is_ok() {}
+    ///
+    /// let frames = parser.container_stack().collect::<Vec<_>>();
+    /// assert_eq!(frames, vec![
+    ///     ContainerFrame::Object { count: 0, field: Some("users") },
+    ///     ContainerFrame::Array { count: 2 },
+    ///     ContainerFrame::Object { count: 1, field: Some("age") },
+    /// ]);
+    /// ```
+    pub fn container_stack(&self) -> impl Iterator<Item = ContainerFrame<'_>> + '_ {
+        self.context().container_stack()
+    }
+
+    /// Get a [`ParseContext`] that answers all "where am I" questions in
+    /// one place: the last event, the nesting depth, the innermost
+    /// container, the current field name, the current path (if path
+    /// tracking is enabled), the bytes of the last value, and the offset in
+    /// the input.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    /// use actson::parser::ContainerFrame;
+    ///
+    /// let json = r#"{"name": "Elvis", "albums": [1956, 1957]}"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_path_tracking(true)
+    ///         .build(),
+    /// );
+    ///
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::ValueInt {
+    ///         let context = parser.context();
+    ///         assert_eq!(context.depth(), 2);
+    ///         assert!(matches!(context.container(), Some(ContainerFrame::Array { .. })));
+    ///         assert_eq!(context.key(), None);
+    ///         assert!(context.pointer().unwrap().starts_with("/albums/"));
+    ///         assert_eq!(context.bytes().len(), 4);
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub fn context(&self) -> ParseContext<'_> {
+        ParseContext {
+            event: self.current_event,
+            containers: &self.containers,
+            path: self.path_tracking.then_some(&self.path[..]),
+            buffer: &self.current_buffer,
+            offset: self.parsed_bytes,
+        }
+    }
+
+    /// Get the interned name of the field that has just been parsed. Call this
+    /// function after you've received [`JsonEvent::FieldName`](JsonEvent#variant.FieldName).
+    /// All field names with the same value share the same allocation, so
+    /// they can be compared with [`Arc::ptr_eq()`]. Cloning the returned
+    /// [`Arc`] is cheap.
+    ///
+    /// Returns `None` if interning has not been enabled with
+    /// [`JsonParserOptionsBuilder::with_intern_field_names()`], if no field
+    /// name has been parsed yet, or if the field name is not valid UTF-8.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = r#"[{"name": "Elvis"}, {"name": "Max"}]"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_intern_field_names(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut names = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::FieldName {
+    ///         names.push(parser.current_field_interned().unwrap());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(&*names[0], "name");
+    /// assert!(Arc::ptr_eq(&names[0], &names[1]));
+    /// ```
+    pub fn current_field_interned(&self) -> Option<Arc<str>> {
+        self.current_field.clone()
+    }
+
+    /// Remove all interned field names to free memory. Field names returned
+    /// by [`Self::current_field_interned()`] stay valid, but field names
+    /// interned after calling this method will not share their allocations.
+    pub fn clear_interned_field_names(&mut self) {
+        if let Some(names) = &mut self.interned_field_names {
+            names.clear();
+        }
+    }
+
+    /// Get the path from the root of the JSON text to the value the last
+    /// event refers to. After [`JsonEvent::FieldName`], the path points to
+    /// the field's value. After [`JsonEvent::StartObject`] or
+    /// [`JsonEvent::StartArray`] as well as after [`JsonEvent::EndObject`] or
+    /// [`JsonEvent::EndArray`], it points to the object or array itself. The
+    /// path is empty for top-level values.
+    ///
+    /// The path is only maintained if path tracking has been enabled with
+    /// [`JsonParserOptionsBuilder::with_path_tracking()`]. Otherwise, this
+    /// method always returns an empty slice.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = r#"{"albums": [{"title": "Elvis Is Back!"}]}"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_path_tracking(true)
+    ///         .build(),
+    /// );
+    ///
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::ValueString {
+    ///         assert_eq!(parser.current_pointer(), "/albums/0/title");
+    ///     }
+    /// }
+    /// ```
+    pub fn current_path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Get the path returned by [`Self::current_path()`] as a JSON Pointer
+    /// (RFC 6901)
+    pub fn current_pointer(&self) -> String {
+        to_pointer(&self.path)
+    }
+
+    /// Get the name of the JSONP callback the JSON text is wrapped in (see
+    /// [`JsonParserOptionsBuilder::with_jsonp()`]). Returns `None` if JSONP
+    /// is disabled, if the JSON text is not wrapped, or if the opening
+    /// parenthesis has not been parsed yet.
+    pub fn jsonp_callback(&self) -> Option<&str> {
+        match self.jsonp {
+            JsonpState::Inner | JsonpState::AfterParen | JsonpState::Done => {
+                Some(&self.jsonp_callback)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip the current value. Call this method after
+    /// [`Self::next_event()`] has returned [`JsonEvent::StartObject`] or
+    /// [`JsonEvent::StartArray`] to skip all events up to and including the
+    /// matching [`JsonEvent::EndObject`] or [`JsonEvent::EndArray`]. Call it
+    /// after [`JsonEvent::FieldName`] to skip the field's value. The method
+    /// has no effect after any other event.
+    ///
+    /// The method does not consume any input itself. The skipped events will
+    /// be consumed by subsequent calls of [`Self::next_event()`], which will
+    /// only return [`JsonEvent::NeedMoreInput`] in the meantime. The skipped
+    /// value is still validated.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = r#"{"a": {"b": [1, 2]}, "c": 3}"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::FieldName && parser.current_str().unwrap() == "a" {
+    ///         parser.skip_value();
+    ///     }
+    ///     events.push(event);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// ```
+    pub fn skip_value(&mut self) {
+        match self.current_event {
+            JsonEvent::StartObject | JsonEvent::StartArray => {
+                self.skip_until = Some(self.containers.len() - 1);
+            }
+            JsonEvent::FieldName => self.skip_until = Some(self.containers.len()),
+            _ => {}
+        }
+    }
+
+    /// Skip the rest of the current top-level value, regardless of how deep
+    /// inside the value the parser currently is. All events up to and
+    /// including the one that closes the top-level object or array are
+    /// skipped. The next call of [`Self::next_event()`] that does not return
+    /// [`JsonEvent::NeedMoreInput`] will return the first event of the next
+    /// value (in streaming mode) or `None`. The method has no effect if the
+    /// parser is not inside an object or array.
+    ///
+    /// In contrast to [`Self::skip_value()`], which has to be called at the
+    /// beginning of a value, this method can be called at any time (e.g. if
+    /// you decide in the middle of a value that you do not need the rest of
+    /// it). Just like with [`Self::skip_value()`], the skipped input is still
+    /// consumed and validated.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = r#"{"a": [1, {"b": 2}], "c": 3} [4]"#.as_bytes();
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_streaming(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::ValueInt && parser.current_int::<i32>().unwrap() == 1 {
+    ///         parser.skip_to_value_end();
+    ///     }
+    ///     events.push(event);
+    /// }
+    ///
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::StartObject,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::StartArray,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::EndArray,
+    /// ]);
+    /// ```
+    pub fn skip_to_value_end(&mut self) {
+        if !self.containers.is_empty() {
+            self.skip_until = Some(0);
+        }
+    }
+
+    /// Consume the characters of the string value that is currently being
+    /// parsed and that have been decoded so far. This method only returns
+    /// characters if streaming strings have been enabled with
+    /// [`JsonParserOptionsBuilder::with_streaming_strings()`] and if the
+    /// parser is in the middle of a string value (i.e. typically after
+    /// [`Self::next_event()`] has returned [`JsonEvent::NeedMoreInput`]).
+    /// Otherwise, the returned iterator is empty.
+    ///
+    /// Consumed characters are removed from the parser's buffer, so the
+    /// buffer never has to hold the whole string. As a consequence, after
+    /// the string has been parsed completely (i.e. after
+    /// [`JsonEvent::ValueString`]), [`Self::current_str()`] only returns the
+    /// characters that have not been consumed with this method yet.
+    /// Incomplete escape sequences and UTF-8 byte sequences are kept in the
+    /// buffer until they are complete.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::PushJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     PushJsonFeeder::new(),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_streaming_strings(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut s = String::new();
+    /// for chunk in [&br#"["Hello "#[..], &br#"W\u00f6rld"#[..]] {
+    ///     parser.feeder.push_bytes(chunk);
+    ///     while parser.next_event().unwrap() != Some(JsonEvent::NeedMoreInput) {}
+    ///     s.extend(parser.string_chars());
+    /// }
+    /// parser.feeder.push_bytes(br#"!"]"#);
+    /// parser.feeder.done();
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+    /// s.push_str(parser.current_str().unwrap());
+    /// assert_eq!(s, "Hello Wörld!");
+    /// ```
+    pub fn string_chars(&mut self) -> impl Iterator<Item = char> {
+        let mut part = String::new();
+        if self.options.streaming_strings
+            && self.state == ST
+            && !self.high_surrogate_pair
+            && self.stack.last() != Some(&MODE_KEY)
+        {
+            let valid = match from_utf8(&self.current_buffer) {
+                Ok(s) => s.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            let consumed = self.current_buffer.drain(..valid).collect();
+            self.consumed_string_bytes += valid;
+            part = String::from_utf8(consumed).unwrap_or_default();
+            if !self.count_chars {
+                self.char_count += part.chars().count();
+            }
+        }
+
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            let c = part[pos..].chars().next()?;
+            pos += c.len_utf8();
+            Some(c)
+        })
+    }
+
+    /// Decode the Base64-encoded string value that is currently being parsed
+    /// and write the decoded bytes to the given writer. Return the number of
+    /// bytes written.
+    ///
+    /// Call this method after [`Self::next_event()`] has returned
+    /// [`JsonEvent::ValueString`] to decode the whole value. If streaming
+    /// strings have been enabled with
+    /// [`JsonParserOptionsBuilder::with_streaming_strings()`], you can also
+    /// call it in the middle of a string value (i.e. typically after
+    /// [`Self::next_event()`] has returned [`JsonEvent::NeedMoreInput`]) to
+    /// decode the characters parsed so far. This way, neither the whole
+    /// Base64 text nor the whole decoded data has to be kept in memory.
+    /// Characters that cannot be decoded yet (an incomplete group of four
+    /// characters or padding) are kept in the parser's buffer until the next
+    /// call. Decoded characters are removed from the buffer (see
+    /// [`Self::string_chars()`]). If streaming strings are disabled, nothing
+    /// is decoded before the value has been parsed completely.
+    ///
+    /// Padding at the end of the value is optional. The method returns
+    /// [`Base64Error::InvalidCharacter`] if the value contains a character
+    /// that does not belong to the given alphabet (including white space and
+    /// line breaks). If the parser is neither inside a string value nor has
+    /// just returned [`JsonEvent::ValueString`], nothing is decoded.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::base64::Base64Alphabet;
+    /// use actson::feeder::PushJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let mut parser = JsonParser::new_with_options(
+    ///     PushJsonFeeder::new(),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_streaming_strings(true)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut decoded = Vec::new();
+    /// for chunk in [&br#"{"data": "SGVsbG8"#[..], &br#"gV29ybG"#[..]] {
+    ///     parser.feeder.push_bytes(chunk);
+    ///     while parser.next_event().unwrap() != Some(JsonEvent::NeedMoreInput) {}
+    ///     parser.decode_base64_into(Base64Alphabet::Standard, &mut decoded).unwrap();
+    /// }
+    /// parser.feeder.push_bytes(br#"Q="}"#);
+    /// parser.feeder.done();
+    ///
+    /// assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+    /// parser.decode_base64_into(Base64Alphabet::Standard, &mut decoded).unwrap();
+    /// assert_eq!(decoded, b"Hello World");
+    /// ```
+    pub fn decode_base64_into<W>(
+        &mut self,
+        alphabet: Base64Alphabet,
+        writer: &mut W,
+    ) -> Result<usize, Base64Error>
+    where
+        W: std::io::Write + ?Sized,
+    {
+        let last = if self.state == ST {
+            if !self.options.streaming_strings
+                || self.high_surrogate_pair
+                || self.stack.last() == Some(&MODE_KEY)
+            {
+                return Ok(0);
+            }
+            false
+        } else if self.current_event == JsonEvent::ValueString {
+            true
+        } else {
+            return Ok(0);
+        };
+
+        let (consumed, written) = base64::decode(&self.current_buffer, alphabet, last, writer)?;
+        self.current_buffer.drain(..consumed);
+        self.consumed_string_bytes += consumed;
+        if !self.count_chars {
+            // Base64 only consists of ASCII characters
+            self.char_count += consumed;
+        }
+        Ok(written)
+    }
+
+    /// Parse up to `max` events and collect them together with copies of
+    /// their values in the given [`EventBatch`]. This allows you to separate
+    /// parsing from processing and to handle multiple events at once (e.g.
+    /// to hand them over to another thread or to process them in a tight
+    /// loop).
+    ///
+    /// Heads up: Since the values of field names, strings, and numbers have
+    /// to be copied, this method is not faster than calling
+    /// [`Self::next_event()`] for every single event and accessing the values
+    /// in place (see the `actson_batch` benchmarks). Use it if you need the
+    /// values to outlive the next call of the parser anyway.
+    ///
+    /// The method stops if `max` events have been collected, if the batch is
+    /// full, if the parser needs more input, or if the end of the JSON text
+    /// has been reached. Just like [`Self::next_event()`], it returns
+    /// [`Some(JsonEvent::NeedMoreInput)`](JsonEvent::NeedMoreInput) if the
+    /// parser needs more input data from the feeder and `None` if the end of
+    /// the JSON text has been reached. Otherwise, it returns the last event
+    /// added to the batch (or the last event the parser has returned if the
+    /// batch was already full or `max` is 0). [`JsonEvent::NeedMoreInput`]
+    /// is never added to the batch. Events collected before an error
+    /// occurred remain in the batch.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::batch::EventBatch;
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = br#"{"a": [1, "x"], "b": true}"#;
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    /// let mut batch = EventBatch::new(4);
+    ///
+    /// let mut values = Vec::new();
+    /// while parser.drain_into(&mut batch, usize::MAX).unwrap().is_some() {
+    ///     for e in batch.drain() {
+    ///         if let Some(v) = e.value {
+    ///             values.push((v, e.depth));
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(values, vec![
+    ///     ("a".into(), 1),
+    ///     ("1".into(), 2),
+    ///     ("x".into(), 2),
+    ///     ("b".into(), 1),
+    /// ]);
+    /// ```
+    pub fn drain_into(
+        &mut self,
+        buf: &mut EventBatch,
+        max: usize,
+    ) -> Result<Option<JsonEvent>, ParserError> {
+        let mut last = Some(self.current_event);
+        for _ in 0..max {
+            if buf.is_full() {
+                break;
+            }
+
+            let event = match self.next_event()? {
+                Some(JsonEvent::NeedMoreInput) => return Ok(Some(JsonEvent::NeedMoreInput)),
+                Some(event) => event,
+                None => return Ok(None),
+            };
+
+            let value = match event {
+                JsonEvent::FieldName | JsonEvent::ValueString => Some(
+                    String::from_utf8_lossy(&self.current_buffer)
+                        .into_owned()
+                        .into_boxed_str(),
+                ),
+                JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                    Some(from_utf8(&self.current_buffer).unwrap_or_default().into())
+                }
+                _ => None,
+            };
+
+            let depth = match event {
+                JsonEvent::EndObject | JsonEvent::EndArray => self.containers.len() + 1,
+                _ => self.containers.len(),
+            };
+
+            buf.push(BatchedEvent {
+                event,
+                value,
+                depth,
+            });
+            last = Some(event);
+        }
+        Ok(last)
+    }
+
+    /// Get the next event and check that it starts a value of the expected
+    /// kind (see [`JsonEventKind::matches()`]). Return
+    /// [`ParserError::UnexpectedEvent`] if it doesn't. This is useful for
+    /// hand-written parsers for JSON texts with a known structure. All events
+    /// a value of the given kind can start with are accepted (e.g.
+    /// [`JsonEvent::ValueInt`] and [`JsonEvent::ValueFloat`] for
+    /// [`JsonEventKind::Number`]).
+    ///
+    /// Just like [`Self::next_event()`], the method returns
+    /// [`Some(JsonEvent::NeedMoreInput)`](JsonEvent::NeedMoreInput) if the
+    /// parser needs more input. In this case, provide more input and call the
+    /// method again. Otherwise, it returns the event that has been found.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonEventKind, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::parser::ParserError;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"[1.5, "a"]"#));
+    /// assert_eq!(
+    ///     parser.expect(JsonEventKind::Array).unwrap(),
+    ///     Some(JsonEvent::StartArray),
+    /// );
+    /// assert_eq!(
+    ///     parser.expect(JsonEventKind::Number).unwrap(),
+    ///     Some(JsonEvent::ValueFloat),
+    /// );
+    /// assert!(matches!(
+    ///     parser.expect(JsonEventKind::Number),
+    ///     Err(ParserError::UnexpectedEvent {
+    ///         expected: JsonEventKind::Number,
+    ///         found: Some(JsonEvent::ValueString),
+    ///     }),
+    /// ));
+    /// ```
+    pub fn expect(&mut self, expected: JsonEventKind) -> Result<Option<JsonEvent>, ParserError> {
+        match self.next_event()? {
+            Some(JsonEvent::NeedMoreInput) => Ok(Some(JsonEvent::NeedMoreInput)),
+            Some(e) if expected.matches(e) => Ok(Some(e)),
+            found => Err(ParserError::UnexpectedEvent { expected, found }),
+        }
+    }
+
+    /// Call the given closure with each number in the JSON text (i.e. after
+    /// each [`JsonEvent::ValueInt`] and [`JsonEvent::ValueFloat`]) and
+    /// accumulate the results, similar to [`Iterator::fold()`]. This is
+    /// useful to reduce a large JSON text to a single value (e.g. a sum or a
+    /// checksum) without keeping the numbers in memory. All other events are
+    /// skipped.
+    ///
+    /// The closure receives each number as a [`Scalar`] like
+    /// [`Self::current_scalar()`] returns it, so integers keep their
+    /// precision: [`Scalar::Int`] for integers that fit into an `i64`,
+    /// [`Scalar::BigInt`] with the original text for larger ones, and
+    /// [`Scalar::Float`] for numbers with a fraction or an exponent.
+    ///
+    /// The method returns [`Fold::NeedMoreInput`] if the feeder needs more
+    /// input. In this case, provide more input and call the method again
+    /// with the returned accumulator. Use [`Self::fold_all_numbers()`] for
+    /// feeders that read from a [`Read`](std::io::Read)er, which handle this
+    /// automatically.
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::PushJsonFeeder;
+    /// use actson::parser::{Fold, Scalar};
+    ///
+    /// // sum up integers exactly and count the other numbers
+    /// let add = |(sum, others): (i64, usize), n: Scalar| match n {
+    ///     Scalar::Int(i) => (sum + i, others),
+    ///     _ => (sum, others + 1),
+    /// };
+    ///
+    /// let mut parser = JsonParser::new(PushJsonFeeder::new());
+    /// let mut acc = (0, 0);
+    /// for chunk in [&b"[9007199254740993, 2.5, {\"a\": 3"[..], &b"}, 4]"[..]] {
+    ///     parser.feeder.push_bytes(chunk);
+    ///     acc = match parser.fold_numbers(acc, add).unwrap() {
+    ///         Fold::NeedMoreInput(acc) => acc,
+    ///         Fold::Done(_) => unreachable!(),
+    ///     };
+    /// }
+    /// parser.feeder.done();
+    /// let acc = match parser.fold_numbers(acc, add).unwrap() {
+    ///     Fold::Done(acc) => acc,
+    ///     Fold::NeedMoreInput(_) => unreachable!(),
+    /// };
+    /// assert_eq!(acc, (9007199254741000, 1));
+    /// ```
+    pub fn fold_numbers<A, F>(&mut self, init: A, mut f: F) -> Result<Fold<A>, ParserError>
+    where
+        F: FnMut(A, Scalar<'_>) -> A,
+    {
+        let mut acc = init;
+        loop {
+            match self.next_event()? {
+                Some(JsonEvent::NeedMoreInput) => return Ok(Fold::NeedMoreInput(acc)),
+                Some(JsonEvent::ValueInt | JsonEvent::ValueFloat) => {
+                    acc = f(acc, self.current_number());
+                }
+                Some(_) => {}
+                None => return Ok(Fold::Done(acc)),
+            }
+        }
+    }
+
+    /// Get the options the parser has been created with
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::PushJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let parser = JsonParser::new_with_options(
+    ///     PushJsonFeeder::new(),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_streaming(true)
+    ///         .build(),
+    /// );
+    /// assert!(parser.options().streaming());
+    /// assert_eq!(parser.options().max_depth(), 2048);
+    /// ```
+    pub fn options(&self) -> &JsonParserOptions {
+        &self.options
+    }
+
+    /// Return the number of events [`Self::next_event()`] has returned so
+    /// far. [`JsonEvent::NeedMoreInput`] is not counted, and neither is the
+    /// end of the JSON text (when `next_event()` returns `None`) or an
+    /// error. Events that are never returned because they have been skipped
+    /// (e.g. with [`Self::skip_value()`]) are not counted either. The counter
+    /// is restored by [`Self::restore_state()`].
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"{"a": [1, 2]}"#));
+    /// while parser.next_event().unwrap().is_some() {}
+    /// assert_eq!(parser.event_count(), 7);
+    /// ```
+    pub fn event_count(&self) -> u64 {
+        self.event_count
+    }
+
+    /// Return a snapshot of the counters the parser keeps for profiling (see
+    /// [`ParserMetrics`]). The counters are not affected by
+    /// [`Self::restore_state()`]. This method is only available if the
+    /// `metrics` feature is enabled. Otherwise, the parser does not keep any
+    /// counters at all.
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"{"a": 12}"#));
+    /// while parser.next_event().unwrap().is_some() {}
+    ///
+    /// let metrics = parser.metrics();
+    /// assert_eq!(metrics.string_bytes, 3);
+    /// assert_eq!(metrics.number_bytes, 2);
+    /// assert_eq!(metrics.whitespace_bytes, 1);
+    /// assert_eq!(metrics.other_bytes, 3);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> ParserMetrics {
+        self.metrics
+    }
+
+    /// Return the number of bytes parsed so far
+    pub fn parsed_bytes(&self) -> usize {
+        self.parsed_bytes
+    }
+
+    /// Return the fraction of the input that has been parsed so far as a
+    /// value between 0.0 and 1.0, or `None` if the total length of the input
+    /// is unknown (see [`JsonFeeder::total_len()`]). This is useful to
+    /// display a progress bar. An empty input is considered to be fully
+    /// parsed.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::{PushJsonFeeder, SliceJsonFeeder};
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1, 2]"));
+    /// assert_eq!(parser.progress(), Some(0.0));
+    /// while parser.next_event().unwrap() != Some(JsonEvent::ValueInt) {}
+    /// assert_eq!(parser.progress(), Some(0.5));
+    /// while parser.next_event().unwrap().is_some() {}
+    /// assert_eq!(parser.progress(), Some(1.0));
+    ///
+    /// // the length of a push feeder's input is unknown
+    /// let parser = JsonParser::new(PushJsonFeeder::new());
+    /// assert_eq!(parser.progress(), None);
+    /// ```
+    pub fn progress(&self) -> Option<f64> {
+        let total = self.feeder.total_len()?;
+        if total == 0 {
+            return Some(1.0);
+        }
+        Some((self.parsed_bytes as f64 / total as f64).min(1.0))
+    }
+
+    /// Return the offset directly after the last complete top-level value,
+    /// or 0 if no top-level value has been completed yet. In streaming mode,
+    /// this is the offset at which parsing can be resumed with a new parser
+    /// if the input ends in the middle of the next value (see
+    /// [`JsonParserOptionsBuilder::with_tail()`](crate::options::JsonParserOptionsBuilder::with_tail())).
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::PushJsonFeeder;
+    ///
+    /// let mut parser = JsonParser::new_streaming(PushJsonFeeder::new());
+    /// parser.feeder.push_bytes(br#"{"a": 1} {"b""#);
+    /// while parser.next_event().unwrap() != Some(JsonEvent::NeedMoreInput) {}
+    /// assert_eq!(parser.last_value_end(), 8);
+    /// ```
+    pub fn last_value_end(&self) -> usize {
+        self.last_value_end
+    }
+
+    /// Get the zero-based line of the input consumed so far (i.e. the line
+    /// of the next byte to parse). Line feeds, carriage returns, and
+    /// carriage returns followed by line feeds are treated as line breaks.
+    ///
+    /// The line is only maintained if position tracking has been enabled
+    /// with [`JsonParserOptionsBuilder::with_position_tracking()`].
+    /// Otherwise, this method always returns 0.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = "{\n  \"a\": \"😀\", \"b\": true\n}";
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json.as_bytes()),
+    ///     JsonParserOptionsBuilder::default()
+    ///         .with_position_tracking(true)
+    ///         .build(),
+    /// );
+    ///
+    /// while let Some(e) = parser.next_event().unwrap() {
+    ///     if e == JsonEvent::ValueTrue {
+    ///         assert_eq!(parser.current_line(), 1);
+    ///         assert_eq!(parser.current_column(), 21);
+    ///         assert_eq!(parser.current_column_utf16(), 22);
+    ///     }
+    /// }
+    /// ```
+    pub fn current_line(&self) -> usize {
+        self.position.as_ref().map_or(0, |p| p.line)
+    }
+
+    /// Get the zero-based column of the input consumed so far (i.e. the
+    /// column of the next byte to parse) in Unicode scalar values. See
+    /// [`Self::current_line()`] for more information.
+    pub fn current_column(&self) -> usize {
+        self.position.as_ref().map_or(0, |p| p.column)
+    }
+
+    /// Get the zero-based column of the input consumed so far in UTF-16 code
+    /// units, just like editors and the Language Server Protocol count
+    /// columns. Characters outside the Basic Multilingual Plane count as two
+    /// code units. See [`Self::current_line()`] for more information.
+    pub fn current_column_utf16(&self) -> usize {
+        self.position.as_ref().map_or(0, |p| p.column_utf16)
+    }
+}
+
+impl<T> Debug for JsonParser<T>
+where
+    T: Debug,
+{
+    /// Show the parser's current state in a concise form. The current token
+    /// is shortened to at most 32 bytes.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const MAX_TOKEN_LEN: usize = 32;
+        let token = String::from_utf8_lossy(
+            &self.current_buffer[..self.current_buffer.len().min(MAX_TOKEN_LEN)],
+        );
+        let token = if self.current_buffer.len() > MAX_TOKEN_LEN {
+            format!("{}...", token)
+        } else {
+            token.into_owned()
+        };
+
+        f.debug_struct("JsonParser")
+            .field("current_event", &self.current_event)
+            .field("state", &state_name(self.state))
+            .field("depth", &self.containers.len())
+            .field("parsed_bytes", &self.parsed_bytes)
+            .field("streaming", &self.streaming)
+            .field("current_token", &token)
+            .field("feeder", &self.feeder)
+            .finish()
+    }
+}
+
+/// Get a human-readable name of the given parser state
+fn state_name(state: i8) -> &'static str {
+    match state {
+        GO => "start",
+        OK => "ok",
+        OB => "object",
+        KE => "key",
+        CO => "colon",
+        VA => "value",
+        AR => "array",
+        ST => "string",
+        ES => "escape",
+        U1..=U4 => "unicode escape",
+        MI => "minus",
+        ZE => "zero",
+        IN => "integer",
+        F0 | FR => "fraction",
+        E1..=E3 => "exponent",
+        T1..=T3 => "true",
+        F1..=F4 => "false",
+        N1..=N3 => "null",
+        _ => "unknown",
+    }
+}
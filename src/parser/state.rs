@@ -0,0 +1,305 @@
+use crate::{feeder::JsonFeeder, path::PathSegment, JsonEvent};
+
+use super::{
+    Container, InvalidStateError, JsonParser, JsonpState, Position, __, GO, MODE_ARRAY, MODE_DONE,
+    MODE_KEY, MODE_OBJECT, STATE_TRANSITION_TABLE,
+};
+
+/// A snapshot of the logical state of a [`JsonParser`] created with
+/// [`JsonParser::save_state()`]. It contains everything the parser needs to
+/// continue parsing (e.g. the stack of open objects and arrays, the current
+/// path, and the number of bytes parsed so far) but not the input buffered
+/// in the feeder.
+///
+/// If the `serde` feature is enabled, the state can be serialized, so it can
+/// be stored as a checkpoint and restored later, possibly in another process
+/// (see [`JsonParser::restore_state()`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParserState {
+    stack: Vec<i8>,
+    array_depth: usize,
+    object_depth: usize,
+    streaming: bool,
+    skipping_record: bool,
+    skipped_brackets: Vec<i8>,
+    skipped_in_string: bool,
+    skipped_escape: bool,
+    clamped_depth: usize,
+    clamp_flatten: bool,
+    state: i8,
+    pending_events: Vec<JsonEvent>,
+    parsed_bytes: usize,
+    last_value_end: usize,
+    value_count: usize,
+    in_shebang: bool,
+    jsonp: JsonpState,
+    jsonp_callback: String,
+    pending_underscore: bool,
+    number_token: Vec<u8>,
+    number_token_start: usize,
+    current_event: JsonEvent,
+    containers: Vec<Container>,
+    path: Vec<PathSegment>,
+    pop_path: bool,
+    skip_until: Option<usize>,
+    position: Option<Position>,
+    event_count: u64,
+}
+
+impl ParserState {
+    /// Return the number of bytes the parser had consumed when the state was
+    /// saved. This is the offset in the input at which parsing continues
+    /// after the state has been restored.
+    pub fn parsed_bytes(&self) -> usize {
+        self.parsed_bytes
+    }
+
+    /// Check that the state is consistent, so the parser does not panic or
+    /// produce a malformed event stream after it has been restored.
+    /// `path_tracking` specifies if the path of the restoring parser is
+    /// maintained.
+    fn validate(&self, path_tracking: bool) -> Result<(), InvalidStateError> {
+        // the transition table has 32 columns per state
+        if self.state < 0 || self.state as usize >= STATE_TRANSITION_TABLE.len() / 32 {
+            return Err(InvalidStateError::UnknownState(self.state));
+        }
+
+        let Some((&MODE_DONE, open)) = self.stack.split_first() else {
+            return Err(InvalidStateError::InvalidStack);
+        };
+        if !open
+            .iter()
+            .all(|m| matches!(*m, MODE_ARRAY | MODE_KEY | MODE_OBJECT))
+            || !self
+                .skipped_brackets
+                .iter()
+                .all(|m| matches!(*m, MODE_ARRAY | MODE_KEY))
+        {
+            return Err(InvalidStateError::InvalidStack);
+        }
+
+        // clamped objects and arrays are on the stack but not counted
+        let Some(counted) = open.len().checked_sub(self.clamped_depth) else {
+            return Err(InvalidStateError::DepthMismatch);
+        };
+        let arrays = open[..counted].iter().filter(|m| **m == MODE_ARRAY).count();
+        if arrays != self.array_depth || counted - arrays != self.object_depth {
+            return Err(InvalidStateError::DepthMismatch);
+        }
+
+        // objects and arrays are added to `containers` when their start
+        // event is returned and removed when their end event is returned,
+        // so applying the pending events must yield the counted part of the
+        // stack
+        let is_object = |m: &i8| *m != MODE_ARRAY;
+        let expected = open[..counted].iter().map(is_object).collect::<Vec<_>>();
+        let mut reported = self
+            .containers
+            .iter()
+            .map(|c| c.is_object)
+            .collect::<Vec<_>>();
+        if self.pending_events.len() > 2 {
+            return Err(InvalidStateError::EventMismatch);
+        }
+        for e in &self.pending_events {
+            let fits = match e {
+                JsonEvent::StartObject | JsonEvent::StartArray => {
+                    reported.push(*e == JsonEvent::StartObject);
+                    true
+                }
+                JsonEvent::EndObject | JsonEvent::EndArray => {
+                    reported.pop() == Some(*e == JsonEvent::EndObject)
+                }
+                JsonEvent::FieldName => reported.last() == Some(&true),
+                JsonEvent::NeedMoreInput => false,
+                _ => true,
+            };
+            if !fits {
+                return Err(InvalidStateError::EventMismatch);
+            }
+        }
+        if reported != expected {
+            return Err(InvalidStateError::ContainerMismatch);
+        }
+
+        // the current event has already been applied to `containers`
+        let innermost = self.containers.last().map(|c| c.is_object);
+        let fits = match self.current_event {
+            JsonEvent::StartObject | JsonEvent::FieldName => innermost == Some(true),
+            JsonEvent::StartArray => innermost == Some(false),
+            _ => true,
+        };
+        if !fits {
+            return Err(InvalidStateError::EventMismatch);
+        }
+
+        // each open object or array has a segment for the value inside it,
+        // except for the innermost one if no field name or value has
+        // started there yet
+        if path_tracking {
+            let n = self.containers.len();
+            if self.path.len() > n
+                || self.path.len() + 1 < n
+                || ((self.pop_path || self.current_event == JsonEvent::FieldName)
+                    && self.path.len() != n)
+                || self
+                    .path
+                    .iter()
+                    .zip(&self.containers)
+                    .any(|(s, c)| matches!(s, PathSegment::Key(_)) != c.is_object)
+            {
+                return Err(InvalidStateError::PathMismatch);
+            }
+        }
+
+        if self.skip_until.is_some_and(|s| s > self.containers.len()) {
+            return Err(InvalidStateError::InvalidSkip);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> JsonParser<T>
+where
+    T: JsonFeeder,
+{
+    /// Save the logical state of the parser (see [`ParserState`]), so
+    /// parsing can be resumed later with [`Self::restore_state()`] (e.g. to
+    /// implement checkpoints for long-running jobs that process very large
+    /// files).
+    ///
+    /// Call this method only between two events, i.e. after
+    /// [`Self::next_event()`] has returned an event. The state does not
+    /// contain the value of the current event (e.g. the string returned by
+    /// [`Self::current_str()`]) or any input buffered in the feeder.
+    pub fn save_state(&self) -> ParserState {
+        ParserState {
+            stack: self.stack.clone(),
+            array_depth: self.array_depth,
+            object_depth: self.object_depth,
+            streaming: self.streaming,
+            skipping_record: self.skipping_record,
+            skipped_brackets: self.skipped_brackets.clone(),
+            skipped_in_string: self.skipped_in_string,
+            skipped_escape: self.skipped_escape,
+            clamped_depth: self.clamped_depth,
+            clamp_flatten: self.clamp_flatten,
+            state: self.state,
+            pending_events: [self.event1, self.event2]
+                .into_iter()
+                .filter(|e| *e != JsonEvent::NeedMoreInput)
+                .collect(),
+            parsed_bytes: self.parsed_bytes,
+            last_value_end: self.last_value_end,
+            value_count: self.value_count,
+            in_shebang: self.in_shebang,
+            jsonp: self.jsonp,
+            jsonp_callback: self.jsonp_callback.clone(),
+            pending_underscore: self.pending_underscore,
+            number_token: self.number_token.clone(),
+            number_token_start: self.number_token_start,
+            current_event: self.current_event,
+            containers: self.containers.clone(),
+            path: self.path.clone(),
+            pop_path: self.pop_path,
+            skip_until: self.skip_until,
+            position: self.position.clone(),
+            event_count: self.event_count,
+        }
+    }
+
+    /// Restore a state previously saved with [`Self::save_state()`]. The
+    /// parser should have been created with the same options as the one the
+    /// state was saved from.
+    ///
+    /// The method does not touch the feeder. It is the caller's
+    /// responsibility to reposition the input, so that the next byte the
+    /// feeder provides is the one at offset [`ParserState::parsed_bytes()`]
+    /// (e.g. by seeking a file and creating a new feeder for it). Any error
+    /// that has occurred before is cleared.
+    ///
+    /// The state is checked for consistency before it is restored, since it
+    /// might have been modified after it was serialized. The stack of open
+    /// objects and arrays must match the depth counters, the pending and
+    /// current events, and (if path tracking is enabled) the path. If it is
+    /// inconsistent, the method returns an [`InvalidStateError`] and the
+    /// parser remains unchanged.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = br#"{"a": [1, 2], "b": 3}"#;
+    ///
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    /// while parser.next_event().unwrap() != Some(JsonEvent::StartArray) {}
+    /// let state = parser.save_state();
+    ///
+    /// // resume in another parser with the rest of the input
+    /// let rest = &json[state.parsed_bytes()..];
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(rest));
+    /// parser.restore_state(state).unwrap();
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     events.push(event);
+    /// }
+    /// assert_eq!(events, vec![
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::EndArray,
+    ///     JsonEvent::FieldName,
+    ///     JsonEvent::ValueInt,
+    ///     JsonEvent::EndObject,
+    /// ]);
+    /// assert_eq!(parser.current_pointer(), "");
+    /// ```
+    pub fn restore_state(&mut self, state: ParserState) -> Result<(), InvalidStateError> {
+        state.validate(self.path_tracking)?;
+        let mut pending = state.pending_events.into_iter();
+        self.stack = state.stack;
+        self.array_depth = state.array_depth;
+        self.object_depth = state.object_depth;
+        self.streaming = state.streaming;
+        self.skipping_record = state.skipping_record;
+        self.skipped_brackets = state.skipped_brackets;
+        self.skipped_in_string = state.skipped_in_string;
+        self.skipped_escape = state.skipped_escape;
+        self.clamped_depth = state.clamped_depth;
+        self.clamp_flatten = state.clamp_flatten;
+        self.state = state.state;
+        self.current_buffer.clear();
+        self.char_count = 0;
+        self.consumed_string_bytes = 0;
+        self.raw_buffer.clear();
+        self.event1 = pending.next().unwrap_or(JsonEvent::NeedMoreInput);
+        self.event2 = pending.next().unwrap_or(JsonEvent::NeedMoreInput);
+        self.parsed_bytes = state.parsed_bytes;
+        self.last_value_end = state.last_value_end;
+        self.value_count = state.value_count;
+        self.in_shebang = state.in_shebang;
+        self.jsonp = state.jsonp;
+        self.jsonp_callback = state.jsonp_callback;
+        self.pending_underscore = state.pending_underscore;
+        self.number_token = state.number_token;
+        self.number_token_start = state.number_token_start;
+        self.number_token_state = self
+            .number_token
+            .iter()
+            .fold(GO, |s, &b| self.number_token_state(s, b).unwrap_or(__));
+        self.putback_character = None;
+        self.high_surrogate_pair = false;
+        self.current_event = state.current_event;
+        self.containers = state.containers;
+        self.path = state.path;
+        self.pop_path = state.pop_path;
+        self.skip_until = state.skip_until;
+        self.current_field = None;
+        self.position = state.position;
+        self.last_error = None;
+        self.event_count = state.event_count;
+        Ok(())
+    }
+}
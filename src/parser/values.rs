@@ -0,0 +1,368 @@
+use std::str::from_utf8;
+
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Zero};
+
+use crate::{feeder::JsonFeeder, JsonEvent};
+
+use super::{
+    is_utf8_continuation_byte, InvalidBoolValueError, InvalidFloatValueError, InvalidIntValueError,
+    InvalidScalarValueError, InvalidStringValueError, JsonParser,
+};
+
+/// The value of the scalar that has just been parsed (see
+/// [`JsonParser::current_scalar()`]). Strings and large integers are
+/// borrowed from the parser's internal buffer, so they are only valid until
+/// the next call of [`JsonParser::next_event()`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scalar<'a> {
+    /// `null`
+    Null,
+
+    /// `true` or `false`
+    Bool(bool),
+
+    /// An integer that fits into an `i64`
+    Int(i64),
+
+    /// An integer that does not fit into an `i64`, exactly as it appeared in
+    /// the JSON text
+    BigInt(&'a str),
+
+    /// A number with a fraction or an exponent
+    Float(f64),
+
+    /// A string
+    Str(&'a str),
+}
+
+impl<T> JsonParser<T>
+where
+    T: JsonFeeder,
+{
+    /// Get the value of the string that has just been parsed. Call this
+    /// function after you've received [`JsonEvent::FieldName`](JsonEvent#variant.FieldName)
+    /// or [`JsonEvent::ValueString`](JsonEvent#variant.ValueString).
+    pub fn current_str(&self) -> Result<&str, InvalidStringValueError> {
+        Ok(from_utf8(&self.current_buffer)?)
+    }
+
+    /// Check if the string returned by [`Self::current_str()`] is a field
+    /// name (i.e. if the current event is
+    /// [`JsonEvent::FieldName`](JsonEvent#variant.FieldName)). This is
+    /// useful in loops that handle field names and string values in the
+    /// same way but still need to tell them apart.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = r#"{"name": "Elvis"}"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    ///
+    /// let mut strings = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if matches!(event, JsonEvent::FieldName | JsonEvent::ValueString) {
+    ///         let s = parser.current_str().unwrap();
+    ///         if parser.current_is_key() {
+    ///             strings.push(format!("key {s}"));
+    ///         } else {
+    ///             strings.push(format!("value {s}"));
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(strings, ["key name", "value Elvis"]);
+    /// ```
+    pub fn current_is_key(&self) -> bool {
+        self.current_event == JsonEvent::FieldName
+    }
+
+    /// Compare the field name that has just been parsed with the given
+    /// candidates and return the index of the first one that matches.
+    /// Returns `None` if no candidate matches or if the current event is not
+    /// [`JsonEvent::FieldName`](JsonEvent#variant.FieldName). This is useful
+    /// for schema-driven parsing where only a small set of fields is of
+    /// interest. The field name is compared byte by byte. It is neither
+    /// validated nor copied.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// const FIELDS: [&str; 2] = ["name", "city"];
+    ///
+    /// let json = r#"{"name": "Elvis", "born": 1935, "city": "Memphis"}"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    ///
+    /// let mut values: [Option<String>; 2] = Default::default();
+    /// let mut current = None;
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     match event {
+    ///         JsonEvent::FieldName => current = parser.match_field(&FIELDS),
+    ///         JsonEvent::ValueString => {
+    ///             if let Some(i) = current {
+    ///                 values[i] = Some(parser.current_str().unwrap().to_string());
+    ///             }
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(values, [Some("Elvis".to_string()), Some("Memphis".to_string())]);
+    /// ```
+    pub fn match_field(&self, candidates: &[&str]) -> Option<usize> {
+        if self.current_event != JsonEvent::FieldName {
+            return None;
+        }
+        let name: &[u8] = &self.current_buffer;
+        candidates.iter().position(|c| c.as_bytes() == name)
+    }
+
+    /// Get the number of Unicode scalar values (i.e. [`char`]s) in the string
+    /// or field name that has just been parsed. This is the same as
+    /// `parser.current_str().unwrap().chars().count()` but does not need to
+    /// decode the string. If [`JsonParserOptionsBuilder::with_max_string_chars()`](crate::options::JsonParserOptionsBuilder::with_max_string_chars())
+    /// has been set, the number is computed while the string is parsed, so the
+    /// string does not have to be scanned again. Escape sequences count as
+    /// the character they
+    /// represent, and a surrogate pair (e.g. `\ud83d\ude00`) counts as one
+    /// character. Characters that have already been consumed with
+    /// [`Self::string_chars()`] or [`Self::decode_base64_into()`] are
+    /// included.
+    ///
+    /// Returns `None` if the current event is neither
+    /// [`JsonEvent::ValueString`](JsonEvent#variant.ValueString) nor
+    /// [`JsonEvent::FieldName`](JsonEvent#variant.FieldName). If the string is
+    /// not valid UTF-8, the result is unspecified.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    ///
+    /// let json = r#"{"emoji": "a\u00e4\ud83d\ude00ä😀"}"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     match event {
+    ///         JsonEvent::FieldName => assert_eq!(parser.current_str_char_len(), Some(5)),
+    ///         JsonEvent::ValueString => {
+    ///             assert_eq!(parser.current_str().unwrap(), "aä😀ä😀");
+    ///             assert_eq!(parser.current_str().unwrap().len(), 13);
+    ///             assert_eq!(parser.current_str_char_len(), Some(5));
+    ///         }
+    ///         _ => assert_eq!(parser.current_str_char_len(), None),
+    ///     }
+    /// }
+    /// ```
+    pub fn current_str_char_len(&self) -> Option<usize> {
+        match self.current_event {
+            JsonEvent::FieldName | JsonEvent::ValueString if self.count_chars => {
+                Some(self.char_count)
+            }
+            JsonEvent::FieldName | JsonEvent::ValueString => Some(
+                self.char_count
+                    + self
+                        .current_buffer
+                        .iter()
+                        .filter(|&&b| !is_utf8_continuation_byte(b))
+                        .count(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Get the value of the integer that has just been parsed. Call this
+    /// function after you've received [`JsonEvent::ValueInt`](JsonEvent#variant.ValueInt).
+    pub fn current_int<I>(&self) -> Result<I, InvalidIntValueError>
+    where
+        I: FromPrimitive + Zero + CheckedAdd + CheckedSub + CheckedMul,
+    {
+        Ok(btoi::btoi(&self.current_buffer)?)
+    }
+
+    /// Get the value of the float that has just been parsed. Call this
+    /// function after you've received [`JsonEvent::ValueFloat`](JsonEvent#variant.ValueFloat).
+    pub fn current_float(&self) -> Result<f64, InvalidFloatValueError> {
+        Ok(self.current_str()?.parse()?)
+    }
+
+    /// Get the value of the float that has just been parsed as an `f32`. Call
+    /// this function after you've received [`JsonEvent::ValueFloat`](JsonEvent#variant.ValueFloat)
+    /// (or [`JsonEvent::ValueInt`](JsonEvent#variant.ValueInt)). The number
+    /// is parsed directly to an `f32`, so it's only rounded once. If its
+    /// magnitude is too large for an `f32`, the function returns
+    /// [`InvalidFloatValueError::NumberOutOfRange`] instead of infinity.
+    /// Use [`Self::current_float()`] if you need more precision.
+    pub fn current_f32(&self) -> Result<f32, InvalidFloatValueError> {
+        let f: f32 = self.current_str()?.parse()?;
+        if f.is_infinite() {
+            return Err(InvalidFloatValueError::NumberOutOfRange);
+        }
+        Ok(f)
+    }
+
+    /// Get the original text of the number that has just been parsed, exactly
+    /// as it appeared in the JSON text. Call this function after you've
+    /// received [`JsonEvent::ValueInt`](JsonEvent#variant.ValueInt) or
+    /// [`JsonEvent::ValueFloat`](JsonEvent#variant.ValueFloat). This is useful
+    /// if the number is too large for [`Self::current_int()`] or
+    /// [`Self::current_float()`], or if you need to preserve its precision.
+    pub fn current_raw_number(&self) -> Result<&str, InvalidStringValueError> {
+        self.current_str()
+    }
+
+    /// Get the original text of the scalar value or field name that has just
+    /// been parsed, exactly as it appeared in the JSON text. This includes
+    /// the quotes and escape sequences of strings, as well as `true`,
+    /// `false`, and `null`. Raw values have to be enabled with
+    /// [`JsonParserOptionsBuilder::with_raw_values()`](crate::options::JsonParserOptionsBuilder::with_raw_values()).
+    /// Otherwise, this function returns an empty string.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::options::JsonParserOptionsBuilder;
+    ///
+    /// let json = r#"{"n\u0061me": "\u0045lvis", "born": 1.935E3, "dead": null}"#;
+    /// let mut parser = JsonParser::new_with_options(
+    ///     SliceJsonFeeder::new(json.as_bytes()),
+    ///     JsonParserOptionsBuilder::default().with_raw_values(true).build(),
+    /// );
+    ///
+    /// let mut raw = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event != JsonEvent::StartObject && event != JsonEvent::EndObject {
+    ///         raw.push(parser.current_raw().unwrap().to_string());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(raw, [
+    ///     r#""n\u0061me""#, r#""\u0045lvis""#, r#""born""#, "1.935E3", r#""dead""#, "null",
+    /// ]);
+    /// ```
+    pub fn current_raw(&self) -> Result<&str, InvalidStringValueError> {
+        Ok(from_utf8(&self.raw_buffer)?)
+    }
+
+    /// Get the value of the scalar (i.e. a string, number, boolean, or
+    /// `null`) that has just been parsed. This is useful if the type of the
+    /// value is not known in advance and you want to handle all types in a
+    /// single `match` expression. Call this function after you've received
+    /// [`JsonEvent::ValueString`], [`JsonEvent::ValueInt`],
+    /// [`JsonEvent::ValueFloat`], [`JsonEvent::ValueTrue`],
+    /// [`JsonEvent::ValueFalse`], or [`JsonEvent::ValueNull`]. For all other
+    /// events, the function returns [`InvalidScalarValueError::NotAScalar`].
+    ///
+    /// Integers that do not fit into an `i64` are returned as
+    /// [`Scalar::BigInt`] with their original text. The returned value
+    /// borrows from the parser, so it has to be converted (e.g. with
+    /// [`ToString::to_string()`]) before the next event can be read if you want
+    /// to keep it.
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::parser::Scalar;
+    ///
+    /// let json = r#"["a", 1, 2.5, true, null, 18446744073709551616]"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    ///
+    /// let mut values = Vec::new();
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     if event == JsonEvent::StartArray || event == JsonEvent::EndArray {
+    ///         continue;
+    ///     }
+    ///     match parser.current_scalar().unwrap() {
+    ///         Scalar::Str(s) => values.push(format!("string {s}")),
+    ///         Scalar::Int(i) => values.push(format!("int {i}")),
+    ///         Scalar::BigInt(i) => values.push(format!("big int {i}")),
+    ///         Scalar::Float(f) => values.push(format!("float {f}")),
+    ///         Scalar::Bool(b) => values.push(format!("bool {b}")),
+    ///         Scalar::Null => values.push("null".to_string()),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(values, [
+    ///     "string a", "int 1", "float 2.5", "bool true", "null",
+    ///     "big int 18446744073709551616",
+    /// ]);
+    /// ```
+    pub fn current_scalar(&self) -> Result<Scalar<'_>, InvalidScalarValueError> {
+        match self.current_event {
+            JsonEvent::ValueString => Ok(Scalar::Str(self.current_str()?)),
+            JsonEvent::ValueInt => match self.current_int::<i64>() {
+                Ok(i) => Ok(Scalar::Int(i)),
+                Err(_) => Ok(Scalar::BigInt(self.current_raw_number()?)),
+            },
+            JsonEvent::ValueFloat => Ok(Scalar::Float(self.current_float()?)),
+            JsonEvent::ValueTrue => Ok(Scalar::Bool(true)),
+            JsonEvent::ValueFalse => Ok(Scalar::Bool(false)),
+            JsonEvent::ValueNull => Ok(Scalar::Null),
+            e => Err(InvalidScalarValueError::NotAScalar(e)),
+        }
+    }
+
+    /// Get the value of the number that has just been parsed as a
+    /// [`Scalar::Int`], [`Scalar::BigInt`], or [`Scalar::Float`]. Numbers
+    /// only consist of ASCII characters and have been validated by the
+    /// grammar, so they can always be converted. Floats whose magnitude is
+    /// too large become infinite.
+    pub(super) fn current_number(&self) -> Scalar<'_> {
+        let number = from_utf8(&self.current_buffer).unwrap_or_default();
+        if self.current_event == JsonEvent::ValueInt {
+            match number.parse() {
+                Ok(i) => Scalar::Int(i),
+                Err(_) => Scalar::BigInt(number),
+            }
+        } else {
+            Scalar::Float(number.parse().unwrap_or(f64::NAN))
+        }
+    }
+
+    /// Get the value of the boolean that has just been parsed. Call this
+    /// function after you've received [`JsonEvent::ValueTrue`](JsonEvent#variant.ValueTrue)
+    /// or [`JsonEvent::ValueFalse`](JsonEvent#variant.ValueFalse). For all
+    /// other events, the function returns an [`InvalidBoolValueError`].
+    pub fn current_bool(&self) -> Result<bool, InvalidBoolValueError> {
+        match self.current_event {
+            JsonEvent::ValueTrue => Ok(true),
+            JsonEvent::ValueFalse => Ok(false),
+            e => Err(InvalidBoolValueError(e)),
+        }
+    }
+
+    /// Get the value of the string that has just been parsed or `None` if the
+    /// value was `null`. Call this function after you've received
+    /// [`JsonEvent::ValueString`](JsonEvent#variant.ValueString) or
+    /// [`JsonEvent::ValueNull`](JsonEvent#variant.ValueNull).
+    pub fn current_opt_str(&self) -> Result<Option<&str>, InvalidStringValueError> {
+        if self.current_event == JsonEvent::ValueNull {
+            return Ok(None);
+        }
+        Ok(Some(self.current_str()?))
+    }
+
+    /// Get the value of the integer that has just been parsed or `None` if
+    /// the value was `null`. Call this function after you've received
+    /// [`JsonEvent::ValueInt`](JsonEvent#variant.ValueInt) or
+    /// [`JsonEvent::ValueNull`](JsonEvent#variant.ValueNull).
+    pub fn current_opt_int<I>(&self) -> Result<Option<I>, InvalidIntValueError>
+    where
+        I: FromPrimitive + Zero + CheckedAdd + CheckedSub + CheckedMul,
+    {
+        if self.current_event == JsonEvent::ValueNull {
+            return Ok(None);
+        }
+        Ok(Some(self.current_int()?))
+    }
+
+    /// Get the value of the float that has just been parsed or `None` if the
+    /// value was `null`. Call this function after you've received
+    /// [`JsonEvent::ValueFloat`](JsonEvent#variant.ValueFloat) or
+    /// [`JsonEvent::ValueNull`](JsonEvent#variant.ValueNull).
+    pub fn current_opt_float(&self) -> Result<Option<f64>, InvalidFloatValueError> {
+        if self.current_event == JsonEvent::ValueNull {
+            return Ok(None);
+        }
+        Ok(Some(self.current_float()?))
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+/// A segment of the path from the root of a JSON text to a value (see
+/// [`JsonParser::current_path()`](crate::JsonParser::current_path()))
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathSegment {
+    /// The name of a field in an object
+    Key(String),
+
+    /// The index of an element in an array
+    Index(usize),
+}
+
+impl PathSegment {
+    /// Check if this segment matches the given reference token of a JSON
+    /// Pointer (RFC 6901). The token must already be unescaped.
+    pub fn matches_token(&self, token: &str) -> bool {
+        match self {
+            PathSegment::Key(k) => k == token,
+            PathSegment::Index(i) => {
+                token.bytes().all(|b| b.is_ascii_digit())
+                    && (token == "0" || !token.starts_with('0'))
+                    && token.parse() == Ok(*i)
+            }
+        }
+    }
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Key(k) => write!(f, "{}", k.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+/// Convert the given path to a JSON Pointer (RFC 6901)
+///
+/// ```
+/// use actson::path::{to_pointer, PathSegment};
+///
+/// let path = [PathSegment::Key("a/b".to_string()), PathSegment::Index(3)];
+/// assert_eq!(to_pointer(&path), "/a~1b/3");
+/// ```
+pub fn to_pointer(path: &[PathSegment]) -> String {
+    let mut r = String::new();
+    for s in path {
+        r.push('/');
+        r.push_str(&s.to_string());
+    }
+    r
+}
+
+/// Split a JSON Pointer (RFC 6901) into its unescaped reference tokens.
+/// Returns `None` if the pointer is invalid (i.e. if it is not empty and does
+/// not start with a slash).
+///
+/// ```
+/// use actson::path::parse_pointer;
+///
+/// assert_eq!(parse_pointer("/a~1b/3").unwrap(), vec!["a/b", "3"]);
+/// assert_eq!(parse_pointer("").unwrap(), Vec::<String>::new());
+/// assert!(parse_pointer("a").is_none());
+/// ```
+pub fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(vec![]);
+    }
+    let rest = pointer.strip_prefix('/')?;
+    Some(
+        rest.split('/')
+            .map(|t| t.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Check if the given path matches the given (unescaped) reference tokens of
+/// a JSON Pointer
+pub fn matches_pointer(path: &[PathSegment], tokens: &[String]) -> bool {
+    path.len() == tokens.len() && path.iter().zip(tokens).all(|(s, t)| s.matches_token(t))
+}
@@ -0,0 +1,81 @@
+//! Supply the buffers the parser uses for strings and numbers
+//!
+//! See [`JsonParser::with_buffer_pool()`](crate::JsonParser::with_buffer_pool())
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// A pool the parser takes its internal buffer for strings, field names, and
+/// numbers from. Implement this trait to recycle buffers between parsers or
+/// to manage them in an arena (e.g. to reduce fragmentation or to release
+/// them all at once).
+///
+/// The pool is shared between a parser and its clones, so the methods take
+/// `&self`. Use interior mutability (e.g. a [`Mutex`](std::sync::Mutex)) if
+/// the pool needs to keep track of buffers.
+pub trait BufferPool: Send + Sync {
+    /// Get a buffer. The parser clears the buffer before it uses it, so the
+    /// buffer may have any length and capacity. The buffer grows as needed.
+    fn alloc_buf(&self) -> Vec<u8>;
+
+    /// Give back a buffer the parser does not need anymore (e.g. because the
+    /// parser has been dropped)
+    fn return_buf(&self, buf: Vec<u8>);
+}
+
+/// A buffer that is taken from a [`BufferPool`] and returned to it when it
+/// is dropped, or a plain [`Vec`] if there is no pool
+#[derive(Default)]
+pub(crate) struct PooledBuffer {
+    buf: Vec<u8>,
+    pool: Option<Arc<dyn BufferPool>>,
+}
+
+impl PooledBuffer {
+    /// Take a new buffer from the given pool
+    pub(crate) fn with_pool(pool: Arc<dyn BufferPool>) -> Self {
+        let mut buf = pool.alloc_buf();
+        buf.clear();
+        PooledBuffer {
+            buf,
+            pool: Some(pool),
+        }
+    }
+}
+
+impl Clone for PooledBuffer {
+    fn clone(&self) -> Self {
+        match &self.pool {
+            Some(pool) => {
+                let mut r = Self::with_pool(Arc::clone(pool));
+                r.buf.extend_from_slice(&self.buf);
+                r
+            }
+            None => PooledBuffer {
+                buf: self.buf.clone(),
+                pool: None,
+            },
+        }
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            pool.return_buf(std::mem::take(&mut self.buf));
+        }
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf
+    }
+}
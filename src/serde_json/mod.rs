@@ -1,10 +1,16 @@
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use std::str::FromStr;
+
 use serde_json::{Map, Number, Value};
 use thiserror::Error;
 
-use crate::feeder::{JsonFeeder, SliceJsonFeeder};
+use crate::feeder::{BufReaderJsonFeeder, FeederError, JsonFeeder, SliceJsonFeeder};
+use crate::options::{JsonParserOptionsBuilder, DEFAULT_MAX_DEPTH};
 use crate::parser::{
     InvalidFloatValueError, InvalidIntValueError, InvalidStringValueError, ParserError,
 };
+use crate::path::{matches_pointer, parse_pointer};
 use crate::{JsonEvent, JsonParser};
 
 /// An error that can happen when parsing JSON to a Serde [`Value`]
@@ -22,25 +28,289 @@ pub enum IntoSerdeValueError {
     #[error("{0}")]
     InvalidFloatValue(#[from] InvalidFloatValueError),
 
-    #[error("not a JSON number: {0}")]
-    IllegalJsonNumber(f64),
+    #[error("{0}")]
+    Fill(#[from] FeederError),
+
+    /// The given JSON Pointer is invalid
+    #[error("invalid JSON pointer: `{0}'")]
+    InvalidPointer(String),
+
+    /// A number is too large to be represented by a Serde JSON [`Number`]
+    /// (see [`NumberOverflow`])
+    #[error("number out of range: {0}")]
+    NumberOutOfRange(String),
+
+    /// A value could not be deserialized into the requested type
+    #[error("{0}")]
+    Deserialize(#[from] serde_json::Error),
+
+    /// Preserving the order of object keys was requested (see
+    /// [`ValueOptionsBuilder::with_preserve_order()`]) but Serde JSON's
+    /// `preserve_order` feature is not enabled
+    #[error("preserving the order of object keys requires the `preserve_order` feature")]
+    PreserveOrderUnavailable,
+
+    /// The input does not contain an object or array after the given offset
+    /// (see [`parse_embedded()`])
+    #[error("no JSON object or array found")]
+    NoEmbeddedValue,
 }
 
-fn to_value<T>(event: &JsonEvent, parser: &JsonParser<T>) -> Result<Value, IntoSerdeValueError>
+/// Specifies what happens if a number cannot be represented by a Serde
+/// JSON [`Number`] without losing its magnitude (i.e. an integer that does
+/// not fit into an `i64` or `u64`, or a floating point number that does not
+/// fit into an `f64`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberOverflow {
+    /// Return [`IntoSerdeValueError::NumberOutOfRange`]. This is the default.
+    #[default]
+    Error,
+
+    /// Clamp the number to the closest value that can be represented (i.e.
+    /// `i64::MIN`, `u64::MAX`, `-f64::MAX`, or `f64::MAX`)
+    Clamp,
+
+    /// Convert the number's original text (see
+    /// [`JsonParser::current_raw_number()`]) to a [`Number`]. If Serde JSON's
+    /// `arbitrary_precision` feature is enabled in your project, the number
+    /// is preserved without any loss of precision. Otherwise, integers that
+    /// do not fit into an `i64` or `u64` are converted to an `f64`, and
+    /// floating point numbers that do not fit into an `f64` cause an
+    /// [`IntoSerdeValueError::NumberOutOfRange`] error.
+    Preserve,
+}
+
+/// Options that control how JSON is converted to a Serde [`Value`]. Use
+/// [`ValueOptionsBuilder`] to create instances of this struct.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValueOptions {
+    /// What happens with numbers that are too large
+    number_overflow: NumberOverflow,
+
+    /// `true` if the order of object keys must be preserved
+    preserve_order: bool,
+
+    /// `true` if `-0` should be converted to a negative zero
+    preserve_negative_zero: bool,
+
+    /// The maximum stack depth or `None` if the parser's default should be
+    /// used
+    max_depth: Option<usize>,
+}
+
+impl ValueOptions {
+    /// Returns what happens with numbers that are too large
+    pub fn number_overflow(&self) -> NumberOverflow {
+        self.number_overflow
+    }
+
+    /// Returns `true` if the order of object keys must be preserved
+    pub fn preserve_order(&self) -> bool {
+        self.preserve_order
+    }
+
+    /// Returns `true` if `-0` should be converted to a negative zero
+    pub fn preserve_negative_zero(&self) -> bool {
+        self.preserve_negative_zero
+    }
+
+    /// Returns the maximum stack depth. This is [`DEFAULT_MAX_DEPTH`] by
+    /// default.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH)
+    }
+}
+
+/// A builder for [`ValueOptions`]
+///
+/// ```rust
+/// use actson::serde_json::{from_slice_with_options, NumberOverflow, ValueOptionsBuilder};
+///
+/// let options = ValueOptionsBuilder::default()
+///     .with_number_overflow(NumberOverflow::Clamp)
+///     .build();
+///
+/// let value = from_slice_with_options(b"[1e400, 100000000000000000000]", options).unwrap();
+/// assert_eq!(value[0], f64::MAX);
+/// assert_eq!(value[1], u64::MAX);
+/// ```
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValueOptionsBuilder {
+    options: ValueOptions,
+}
+
+impl ValueOptionsBuilder {
+    /// Specify what happens if a number is too large to be represented by a
+    /// Serde JSON [`Number`]
+    pub fn with_number_overflow(mut self, number_overflow: NumberOverflow) -> Self {
+        self.options.number_overflow = number_overflow;
+        self
+    }
+
+    /// Specify whether the keys of objects must keep the order in which they
+    /// appear in the JSON text. This is useful if the value will be
+    /// serialized again and the output should be easy to compare with the
+    /// input (or should be read by humans).
+    ///
+    /// Whether a Serde JSON [`Map`] keeps the insertion order of its keys is
+    /// determined at compile time by Serde JSON's `preserve_order` feature.
+    /// By default, this feature is disabled and keys are sorted. Enable
+    /// Actson's `preserve_order` feature (which enables the Serde JSON
+    /// feature) to preserve the order. If this option is `true` but the
+    /// feature is not enabled, converting an object fails with
+    /// [`IntoSerdeValueError::PreserveOrderUnavailable`] instead of silently
+    /// reordering its keys. The default value is `false`.
+    ///
+    /// ```rust
+    /// use actson::serde_json::{from_slice_with_options, ValueOptionsBuilder};
+    ///
+    /// let options = ValueOptionsBuilder::default()
+    ///     .with_preserve_order(true)
+    ///     .build();
+    ///
+    /// let value = from_slice_with_options(br#"{"b": 1, "a": 2}"#, options);
+    /// # #[cfg(feature = "preserve_order")]
+    /// assert_eq!(serde_json::to_string(&value.unwrap()).unwrap(), r#"{"b":1,"a":2}"#);
+    /// ```
+    pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
+        self.options.preserve_order = preserve_order;
+        self
+    }
+
+    /// Specify whether the integer `-0` should be converted to a negative
+    /// zero (i.e. the floating point number `-0.0`). By default, `-0` equals
+    /// `0` and is converted to the integer `0`, which loses its sign. Enable
+    /// this option if your application distinguishes between the two (e.g.
+    /// because it follows IEEE 754 semantics). Floating point numbers such as
+    /// `-0.0` always keep their sign. The default value is `false`.
+    ///
+    /// Note that the sign is always available through
+    /// [`JsonParser::current_float()`] and [`JsonParser::current_raw_number()`]
+    /// if you use the parser directly.
+    ///
+    /// ```rust
+    /// use actson::serde_json::{from_slice, from_slice_with_options, ValueOptionsBuilder};
+    ///
+    /// let options = ValueOptionsBuilder::default()
+    ///     .with_preserve_negative_zero(true)
+    ///     .build();
+    ///
+    /// let value = from_slice_with_options(b"-0", options).unwrap();
+    /// assert!(value.as_f64().unwrap().is_sign_negative());
+    ///
+    /// let value = from_slice(b"-0").unwrap();
+    /// assert_eq!(value.as_i64(), Some(0));
+    /// ```
+    pub fn with_preserve_negative_zero(mut self, preserve_negative_zero: bool) -> Self {
+        self.options.preserve_negative_zero = preserve_negative_zero;
+        self
+    }
+
+    /// Set the maximum stack depth of the parser that reads the JSON text
+    /// (see [`JsonParserOptionsBuilder::with_max_depth()`]). The default
+    /// value is [`DEFAULT_MAX_DEPTH`]. Values nested deeper than this cause
+    /// an [`IntoSerdeValueError::Parse`] error with
+    /// [`ParserError::DepthLimitExceeded`].
+    ///
+    /// The [`Value`] is built without recursion, so a high limit does not
+    /// cause a stack overflow while the value is being constructed. Note,
+    /// however, that Serde JSON drops, compares, and serializes values
+    /// recursively. Handling extremely deeply nested values may still
+    /// overflow the stack after they have been returned.
+    ///
+    /// ```rust
+    /// use actson::parser::ParserError;
+    /// use actson::serde_json::{from_slice_with_options, IntoSerdeValueError, ValueOptionsBuilder};
+    ///
+    /// let options = ValueOptionsBuilder::default().with_max_depth(3).build();
+    ///
+    /// assert!(from_slice_with_options(b"[[1]]", options).is_ok());
+    /// assert!(matches!(
+    ///     from_slice_with_options(b"[[[1]]]", options),
+    ///     Err(IntoSerdeValueError::Parse(ParserError::DepthLimitExceeded(_)))
+    /// ));
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.options.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Create a new [`ValueOptions`] object
+    pub fn build(self) -> ValueOptions {
+        self.options
+    }
+}
+
+fn to_number<T>(
+    event: &JsonEvent,
+    parser: &JsonParser<T>,
+    options: &ValueOptions,
+) -> Result<Number, IntoSerdeValueError>
+where
+    T: JsonFeeder,
+{
+    let raw = parser.current_raw_number()?;
+
+    if options.preserve_negative_zero && *event == JsonEvent::ValueInt && raw == "-0" {
+        return Number::from_f64(-0.0)
+            .ok_or_else(|| IntoSerdeValueError::NumberOutOfRange(raw.to_string()));
+    }
+
+    if options.number_overflow == NumberOverflow::Preserve {
+        return Number::from_str(raw)
+            .map_err(|_| IntoSerdeValueError::NumberOutOfRange(raw.to_string()));
+    }
+
+    if *event == JsonEvent::ValueInt {
+        if let Ok(i) = parser.current_int::<i64>() {
+            return Ok(Number::from(i));
+        }
+        if let Ok(u) = parser.current_int::<u64>() {
+            return Ok(Number::from(u));
+        }
+        return match options.number_overflow {
+            NumberOverflow::Clamp if raw.starts_with('-') => Ok(Number::from(i64::MIN)),
+            NumberOverflow::Clamp => Ok(Number::from(u64::MAX)),
+            _ => Err(IntoSerdeValueError::NumberOutOfRange(raw.to_string())),
+        };
+    }
+
+    let f = parser.current_float()?;
+    let f = if f.is_infinite() && options.number_overflow == NumberOverflow::Clamp {
+        f.signum() * f64::MAX
+    } else {
+        f
+    };
+    Number::from_f64(f).ok_or_else(|| IntoSerdeValueError::NumberOutOfRange(raw.to_string()))
+}
+
+/// Check if a Serde JSON [`Map`] keeps the insertion order of its keys. This
+/// depends on whether Serde JSON's `preserve_order` feature is enabled, which
+/// might have happened in another crate of the dependency graph, so we check
+/// the actual behavior instead of Actson's own feature.
+fn map_preserves_order() -> bool {
+    let mut m = Map::new();
+    m.insert("b".to_string(), Value::Null);
+    m.insert("a".to_string(), Value::Null);
+    m.keys().next().is_some_and(|k| k == "b")
+}
+
+fn to_value<T>(
+    event: &JsonEvent,
+    parser: &JsonParser<T>,
+    options: &ValueOptions,
+) -> Result<Value, IntoSerdeValueError>
 where
     T: JsonFeeder,
 {
     Ok(match event {
         JsonEvent::ValueString => Value::String(parser.current_str()?.to_string()),
-        JsonEvent::ValueInt => Value::Number(Number::from(parser.current_int::<i64>()?)),
-        JsonEvent::ValueFloat => {
-            let f = parser.current_float()?;
-            let n = Number::from_f64(f).ok_or(IntoSerdeValueError::IllegalJsonNumber(f))?;
-            Value::Number(n)
+        JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+            Value::Number(to_number(event, parser, options)?)
         }
         JsonEvent::ValueTrue => Value::Bool(true),
         JsonEvent::ValueFalse => Value::Bool(false),
-        JsonEvent::ValueNull => Value::Null,
+        JsonEvent::ValueNull | JsonEvent::TruncatedValue | JsonEvent::Scalar => Value::Null,
         _ => unreachable!("this function will only be called for valid events"),
     })
 }
@@ -58,75 +328,482 @@ where
 /// let actual = from_slice(&json).unwrap();
 /// assert_eq!(expected, actual);
 /// ```
+///
+/// Integers that do not fit into an `i64` or `u64` and floating point numbers
+/// that do not fit into an `f64` cause an [`IntoSerdeValueError::NumberOutOfRange`]
+/// error. Use [`from_slice_with_options()`] and [`NumberOverflow`] to change
+/// this.
 pub fn from_slice(v: &[u8]) -> Result<Value, IntoSerdeValueError> {
+    from_slice_with_options(v, ValueOptions::default())
+}
+
+/// Parse a byte slice into a Serde JSON [Value] using the given
+/// [`ValueOptions`]
+pub fn from_slice_with_options(
+    v: &[u8],
+    options: ValueOptions,
+) -> Result<Value, IntoSerdeValueError> {
     let feeder = SliceJsonFeeder::new(v);
-    let mut parser = JsonParser::new(feeder);
+    let mut parser = JsonParser::new_with_options(
+        feeder,
+        JsonParserOptionsBuilder::default()
+            .with_max_depth(options.max_depth())
+            .build(),
+    );
 
-    let mut stack = vec![];
+    let mut builder = ValueBuilder::new(options);
     let mut result = None;
-    let mut current_key = None;
 
     while let Some(event) = parser.next_event()? {
+        if let Some(v) = builder.on_event(event, &parser)? {
+            if result.is_some() {
+                return Err(IntoSerdeValueError::Parse(ParserError::SyntaxError));
+            }
+            result = Some(v);
+        }
+    }
+
+    result.ok_or(IntoSerdeValueError::Parse(ParserError::NoMoreInput))
+}
+
+/// Builds a Serde JSON [`Value`] from the events generated by a [`JsonParser`]
+pub(crate) struct ValueBuilder {
+    options: ValueOptions,
+
+    /// The objects and arrays that are currently being built together with
+    /// the field names under which they will be inserted into their parents
+    stack: Vec<(Option<String>, Value)>,
+
+    /// The name of the field whose value is expected next
+    current_key: Option<String>,
+}
+
+impl ValueBuilder {
+    pub(crate) fn new(options: ValueOptions) -> Self {
+        ValueBuilder {
+            options,
+            stack: vec![],
+            current_key: None,
+        }
+    }
+
+    /// Process the given event. Return the value as soon as it is complete.
+    pub(crate) fn on_event<T>(
+        &mut self,
+        event: JsonEvent,
+        parser: &JsonParser<T>,
+    ) -> Result<Option<Value>, IntoSerdeValueError>
+    where
+        T: JsonFeeder,
+    {
         match event {
-            JsonEvent::NeedMoreInput => {}
+            JsonEvent::NeedMoreInput => Ok(None),
 
             JsonEvent::StartObject | JsonEvent::StartArray => {
+                if event == JsonEvent::StartObject
+                    && self.options.preserve_order
+                    && !map_preserves_order()
+                {
+                    return Err(IntoSerdeValueError::PreserveOrderUnavailable);
+                }
                 let v = if event == JsonEvent::StartObject {
                     Value::Object(Map::new())
                 } else {
                     Value::Array(vec![])
                 };
-                stack.push((current_key, v));
-                current_key = None;
+                self.stack.push((self.current_key.take(), v));
+                Ok(None)
             }
 
             JsonEvent::EndObject | JsonEvent::EndArray => {
-                let v = stack.pop().unwrap();
-                if let Some((_, top)) = stack.last_mut() {
-                    if let Some(m) = top.as_object_mut() {
-                        m.insert(v.0.unwrap(), v.1);
-                    } else if let Some(a) = top.as_array_mut() {
-                        a.push(v.1);
-                    }
-                } else {
-                    result = Some(v.1);
-                }
+                let (key, v) = self.stack.pop().unwrap();
+                self.current_key = key;
+                Ok(self.insert(v))
             }
 
-            JsonEvent::FieldName => current_key = Some(parser.current_str()?.to_string()),
+            JsonEvent::FieldName => {
+                self.current_key = Some(parser.current_str()?.to_string());
+                Ok(None)
+            }
 
             JsonEvent::ValueString
             | JsonEvent::ValueInt
             | JsonEvent::ValueFloat
             | JsonEvent::ValueTrue
             | JsonEvent::ValueFalse
-            | JsonEvent::ValueNull => {
-                if let Some((_, top)) = stack.last_mut() {
-                    let v = to_value(&event, &parser)?;
-                    if let Some(m) = top.as_object_mut() {
-                        m.insert(current_key.unwrap(), v);
-                        current_key = None
-                    } else if let Some(a) = top.as_array_mut() {
-                        a.push(v);
+            | JsonEvent::ValueNull
+            | JsonEvent::TruncatedValue
+            | JsonEvent::Scalar => {
+                let v = to_value(&event, parser, &self.options)?;
+                Ok(self.insert(v))
+            }
+        }
+    }
+
+    /// Insert a completed value into the current object or array. Return the
+    /// value if there is no current object or array.
+    fn insert(&mut self, v: Value) -> Option<Value> {
+        match self.stack.last_mut() {
+            Some((_, Value::Object(m))) => {
+                m.insert(self.current_key.take().unwrap(), v);
+                None
+            }
+            Some((_, Value::Array(a))) => {
+                a.push(v);
+                None
+            }
+            _ => Some(v),
+        }
+    }
+}
+
+/// Read a JSON text from the given reader and extract the value at the
+/// given JSON Pointer (RFC 6901). The JSON text is streamed. Only the
+/// extracted value is kept in memory. Everything before it is skipped and
+/// parsing stops as soon as the value has been found, so the rest of the
+/// input is not read (and not validated). Returns `None` if the JSON text
+/// does not contain a value at the given pointer.
+///
+/// This function is also available as [`actson::extract_pointer`](crate::extract_pointer).
+///
+/// ```
+/// use serde_json::json;
+/// use actson::serde_json::extract_pointer;
+///
+/// let json = r#"{"data": {"items": [{"id": 1}, {"id": 2, "tags": ["a", "b"]}]}}"#;
+///
+/// let value = extract_pointer(json.as_bytes(), "/data/items/1").unwrap();
+/// assert_eq!(value, Some(json!({"id": 2, "tags": ["a", "b"]})));
+///
+/// let value = extract_pointer(json.as_bytes(), "/data/items/2").unwrap();
+/// assert_eq!(value, None);
+/// ```
+pub fn extract_pointer<R>(reader: R, pointer: &str) -> Result<Option<Value>, IntoSerdeValueError>
+where
+    R: Read,
+{
+    let tokens = parse_pointer(pointer)
+        .ok_or_else(|| IntoSerdeValueError::InvalidPointer(pointer.to_string()))?;
+
+    let feeder = BufReaderJsonFeeder::new(BufReader::new(reader));
+    let mut parser = JsonParser::new_with_options(
+        feeder,
+        JsonParserOptionsBuilder::default()
+            .with_path_tracking(true)
+            .build(),
+    );
+
+    let mut builder: Option<ValueBuilder> = None;
+    while let Some(event) = parser.next_event()? {
+        if event == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf()?;
+            continue;
+        }
+
+        if let Some(b) = &mut builder {
+            if let Some(v) = b.on_event(event, &parser)? {
+                return Ok(Some(v));
+            }
+            continue;
+        }
+
+        let path = parser.current_path();
+        match event {
+            JsonEvent::FieldName => {
+                let prefix = path.len() <= tokens.len()
+                    && path.iter().zip(&tokens).all(|(s, t)| s.matches_token(t));
+                if !prefix {
+                    parser.skip_value();
+                }
+            }
+
+            JsonEvent::EndObject | JsonEvent::EndArray => {
+                if path.len() < tokens.len() {
+                    // the container that could have contained the value has ended
+                    if matches_pointer(path, &tokens[..path.len()]) {
+                        return Ok(None);
+                    }
+                }
+            }
+
+            _ => {
+                if matches_pointer(path, &tokens) {
+                    let mut b = ValueBuilder::new(ValueOptions::default());
+                    if let Some(v) = b.on_event(event, &parser)? {
+                        return Ok(Some(v));
+                    }
+                    builder = Some(b);
+                } else if matches!(event, JsonEvent::StartObject | JsonEvent::StartArray) {
+                    let prefix = path.len() < tokens.len()
+                        && path.iter().zip(&tokens).all(|(s, t)| s.matches_token(t));
+                    if !prefix {
+                        parser.skip_value();
                     }
-                } else if result.is_none() {
-                    let v = to_value(&event, &parser)?;
-                    result = Some(v);
-                } else {
-                    return Err(IntoSerdeValueError::Parse(ParserError::SyntaxError));
                 }
             }
         }
     }
 
-    result.ok_or(IntoSerdeValueError::Parse(ParserError::NoMoreInput))
+    Ok(None)
+}
+
+/// An [`Iterator`] over the values of a newline-delimited JSON text
+/// (NDJSON, also known as JSON Lines). Create it with [`ndjson_values()`].
+pub struct NdjsonValues<R> {
+    reader: BufReader<R>,
+
+    /// The one-based number of the next line
+    line: usize,
+
+    /// The current line
+    buf: Vec<u8>,
+
+    /// `true` if the end of the input has been reached or reading has
+    /// failed
+    done: bool,
+}
+
+/// Read a newline-delimited JSON text (NDJSON, also known as JSON Lines)
+/// from the given reader and convert each line to a Serde JSON [`Value`].
+///
+/// The iterator yields one result per line. If a line does not contain
+/// exactly one complete JSON value, the iterator yields an error together
+/// with the one-based number of the line (i.e. the line number an editor
+/// would show, unlike the zero-based [`JsonParser::current_line()`]) and
+/// continues with the next line. This isolates malformed lines (e.g. in log
+/// files) from the rest of the input. A value spanning multiple lines is an
+/// error for each of these lines.
+/// Empty lines (or lines that only consist of white space) are skipped. If
+/// the reader fails, the iterator yields an [`IntoSerdeValueError::Fill`]
+/// error and ends.
+///
+/// Only one line is kept in memory at a time.
+///
+/// This function is also available as [`actson::ndjson_values`](crate::ndjson_values).
+///
+/// ```
+/// use serde_json::json;
+/// use actson::serde_json::ndjson_values;
+///
+/// let ndjson = "{\"id\": 1}\n{\"id\": \n\n[true, null]\n";
+/// let results = ndjson_values(ndjson.as_bytes()).collect::<Vec<_>>();
+///
+/// assert_eq!(results.len(), 3);
+/// assert_eq!(results[0].as_ref().unwrap(), &json!({"id": 1}));
+/// assert!(matches!(results[1], Err((2, _))));
+/// assert_eq!(results[2].as_ref().unwrap(), &json!([true, null]));
+/// ```
+pub fn ndjson_values<R>(reader: R) -> NdjsonValues<R>
+where
+    R: Read,
+{
+    NdjsonValues {
+        reader: BufReader::new(reader),
+        line: 1,
+        buf: Vec::new(),
+        done: false,
+    }
+}
+
+impl<R> Iterator for NdjsonValues<R>
+where
+    R: Read,
+{
+    type Item = Result<Value, (usize, IntoSerdeValueError)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let line = self.line;
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+                    if self
+                        .buf
+                        .iter()
+                        .all(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+                    {
+                        continue;
+                    }
+                    return Some(from_slice(&self.buf).map_err(|e| (line, e)));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err((line, FeederError::from(e).into())));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An [`Iterator`] over the top-level values of a byte slice. Create it
+/// with [`values_from_slice_iter()`].
+pub struct Values<'a> {
+    parser: JsonParser<SliceJsonFeeder<'a>>,
+    builder: ValueBuilder,
+
+    /// `true` if the end of the input has been reached or parsing has failed
+    done: bool,
+}
+
+/// Lazily parse a byte slice containing a stream of top-level JSON values
+/// (see [`JsonParserOptionsBuilder::with_streaming()`]) into Serde JSON
+/// [`Value`]s. Each call of [`Iterator::next()`] only parses as much of the
+/// input as is necessary to build the next value, so iteration can be
+/// stopped early without parsing (or validating) the rest of the input.
+/// Only the current value is kept in memory.
+///
+/// The iterator ends after the first error. Like [`JsonParser`], it yields
+/// [`ParserError::EmptyInput`] if the slice does not contain any value. Use [`Values::parser()`] or
+/// [`Values::into_parser()`] to access the underlying parser, e.g. to find
+/// out where the last value ended or to continue parsing events after the
+/// last value.
+///
+/// This function is also available as [`actson::values_from_slice_iter`](crate::values_from_slice_iter).
+///
+/// ```
+/// use serde_json::json;
+/// use actson::serde_json::values_from_slice_iter;
+///
+/// let json = br#"{"id": 1} [true, null] "a" {"id": "#;
+/// let mut values = values_from_slice_iter(json);
+///
+/// assert_eq!(values.next().unwrap().unwrap(), json!({"id": 1}));
+/// assert_eq!(values.next().unwrap().unwrap(), json!([true, null]));
+/// assert_eq!(values.next().unwrap().unwrap(), json!("a"));
+/// assert!(values.next().unwrap().is_err());
+/// assert!(values.next().is_none());
+/// ```
+pub fn values_from_slice_iter(bytes: &[u8]) -> Values<'_> {
+    Values {
+        parser: JsonParser::new_with_options(
+            SliceJsonFeeder::new(bytes),
+            JsonParserOptionsBuilder::default()
+                .with_streaming(true)
+                .build(),
+        ),
+        builder: ValueBuilder::new(ValueOptions::default()),
+        done: false,
+    }
+}
+
+impl<'a> Values<'a> {
+    /// Get the underlying parser
+    pub fn parser(&self) -> &JsonParser<SliceJsonFeeder<'a>> {
+        &self.parser
+    }
+
+    /// Consume the iterator and return the underlying parser
+    pub fn into_parser(self) -> JsonParser<SliceJsonFeeder<'a>> {
+        self.parser
+    }
+
+    /// Build the next value or return `None` if the input has ended
+    fn next_value(&mut self) -> Result<Option<Value>, IntoSerdeValueError> {
+        while let Some(event) = self.parser.next_event()? {
+            if let Some(v) = self.builder.on_event(event, &self.parser)? {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Iterator for Values<'_> {
+    type Item = Result<Value, IntoSerdeValueError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let r = self.next_value().transpose();
+        if !matches!(r, Some(Ok(_))) {
+            self.done = true;
+        }
+        r
+    }
+}
+
+/// Find the first JSON object or array in a larger text and parse it into a
+/// Serde JSON [`Value`]. This is useful to extract JSON payloads embedded in
+/// other text (e.g. log lines that start with a timestamp or a log level).
+///
+/// The function looks for the first `{` or `[` at or after the byte offset
+/// `start` and parses exactly one value from there. It returns the value
+/// together with the byte range it occupies in `bytes`. Everything after
+/// the value is ignored, so the range can be used to continue searching for
+/// more values. Returns [`IntoSerdeValueError::NoEmbeddedValue`] if there
+/// is no `{` or `[` at or after `start`.
+///
+/// Heads up: The surrounding text is not validated at all. The function
+/// simply takes the first `{` or `[` it finds, even if it is part of the
+/// surrounding text (e.g. `[INFO]` in a log line). In this case, parsing
+/// most likely fails. Pass a `start` offset behind such text to avoid this.
+/// Top-level strings, numbers, booleans, and `null` cannot be found.
+///
+/// This function is also available as [`actson::parse_embedded`](crate::parse_embedded).
+///
+/// ```
+/// use serde_json::json;
+/// use actson::serde_json::parse_embedded;
+///
+/// let log = br#"2024-05-01 12:00:00 INFO request {"path": "/", "status": 200} took 3ms"#;
+/// let (value, range) = parse_embedded(log, 0).unwrap();
+/// assert_eq!(value, json!({"path": "/", "status": 200}));
+/// assert_eq!(range, 33..61);
+/// assert_eq!(&log[range.end..], b" took 3ms");
+/// ```
+pub fn parse_embedded(
+    bytes: &[u8],
+    start: usize,
+) -> Result<(Value, Range<usize>), IntoSerdeValueError> {
+    let begin = bytes
+        .get(start..)
+        .and_then(|rest| rest.iter().position(|b| matches!(b, b'{' | b'[')))
+        .map(|i| start + i)
+        .ok_or(IntoSerdeValueError::NoEmbeddedValue)?;
+
+    let feeder = SliceJsonFeeder::new(&bytes[begin..]);
+    let mut parser = JsonParser::new(feeder);
+
+    let mut builder = ValueBuilder::new(ValueOptions::default());
+    while let Some(event) = parser.next_event()? {
+        if let Some(v) = builder.on_event(event, &parser)? {
+            return Ok((v, begin..begin + parser.last_value_end()));
+        }
+    }
+
+    // a slice feeder never runs out of input before the value is complete
+    // without returning an error
+    Err(IntoSerdeValueError::Parse(ParserError::NoMoreInput))
+}
+
+/// Parse a string into a Serde JSON [Value]
+///
+/// This function is also available as [`actson::parse_str`](crate::parse_str).
+///
+/// ```
+/// use serde_json::json;
+/// use actson::serde_json::from_str;
+///
+/// let actual = from_str(r#"{"name": "Elvis"}"#).unwrap();
+/// assert_eq!(json!({"name": "Elvis"}), actual);
+/// ```
+pub fn from_str(s: &str) -> Result<Value, IntoSerdeValueError> {
+    from_slice(s.as_bytes())
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         parser::ParserError,
-        serde_json::{from_slice, IntoSerdeValueError},
+        serde_json::{
+            extract_pointer, from_slice, from_slice_with_options, from_str, ndjson_values,
+            parse_embedded, values_from_slice_iter, IntoSerdeValueError, NumberOverflow,
+            ValueOptionsBuilder,
+        },
     };
     use serde_json::{from_slice as serde_from_slice, Value};
 
@@ -254,4 +931,345 @@ mod test {
             Err(IntoSerdeValueError::Parse(ParserError::SyntaxError))
         ));
     }
+
+    /// Test that trailing data after the value is rejected
+    #[test]
+    fn trailing_data() {
+        assert!(matches!(
+            from_str(r#"{"name": "Elvis"} 5"#),
+            Err(IntoSerdeValueError::Parse(ParserError::TrailingData(18)))
+        ));
+    }
+
+    /// Test that numbers that are too large cause an error by default
+    #[test]
+    fn number_overflow_error() {
+        assert_eq!(
+            from_slice(b"18446744073709551615").unwrap(),
+            Value::from(u64::MAX)
+        );
+        assert!(matches!(
+            from_slice(b"18446744073709551616"),
+            Err(IntoSerdeValueError::NumberOutOfRange(n)) if n == "18446744073709551616"
+        ));
+        assert!(matches!(
+            from_slice(b"-9223372036854775809"),
+            Err(IntoSerdeValueError::NumberOutOfRange(_))
+        ));
+        assert!(matches!(
+            from_slice(b"[-1e400]"),
+            Err(IntoSerdeValueError::NumberOutOfRange(n)) if n == "-1e400"
+        ));
+    }
+
+    /// Test that numbers that are too large can be clamped
+    #[test]
+    fn number_overflow_clamp() {
+        let options = ValueOptionsBuilder::default()
+            .with_number_overflow(NumberOverflow::Clamp)
+            .build();
+        assert_eq!(
+            from_slice_with_options(
+                b"[18446744073709551616, -9223372036854775809, 1e400, -1e400, 1.5]",
+                options
+            )
+            .unwrap(),
+            serde_json::json!([u64::MAX, i64::MIN, f64::MAX, -f64::MAX, 1.5])
+        );
+    }
+
+    /// Test that numbers can be converted from their original text
+    #[test]
+    fn number_overflow_preserve() {
+        let options = ValueOptionsBuilder::default()
+            .with_number_overflow(NumberOverflow::Preserve)
+            .build();
+        let json = b"[1, -2, 18446744073709551616, 1.5e3]";
+        assert_eq!(
+            from_slice_with_options(json, options).unwrap(),
+            serde_from_slice::<Value>(json).unwrap()
+        );
+    }
+
+    /// Test that the sign of zero can be preserved
+    #[test]
+    fn preserve_negative_zero() {
+        let json = b"[0, -0, 0.0, -0.0, -0e1]";
+
+        let value = from_slice(json).unwrap();
+        assert_eq!(value[1].as_i64(), Some(0));
+        assert!(value[3].as_f64().unwrap().is_sign_negative());
+
+        let options = ValueOptionsBuilder::default()
+            .with_preserve_negative_zero(true)
+            .build();
+        let value = from_slice_with_options(json, options).unwrap();
+        let signs = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_f64().unwrap().is_sign_negative())
+            .collect::<Vec<_>>();
+        assert_eq!(signs, vec![false, true, false, true, true]);
+        assert_eq!(value[0].as_u64(), Some(0));
+        assert_eq!(serde_json::to_string(&value[1]).unwrap(), "-0.0");
+    }
+
+    /// Test that the order of object keys can be preserved
+    #[test]
+    fn preserve_order() {
+        let json = br#"{"z": 1, "a": {"y": [{"c": 2, "b": 3}], "x": null}}"#;
+        let options = ValueOptionsBuilder::default()
+            .with_preserve_order(true)
+            .build();
+
+        let value = from_slice_with_options(json, options);
+        if cfg!(feature = "preserve_order") {
+            assert_eq!(
+                serde_json::to_vec(&value.unwrap()).unwrap(),
+                json.iter()
+                    .filter(|b| **b != b' ')
+                    .copied()
+                    .collect::<Vec<_>>()
+            );
+        } else {
+            assert!(matches!(
+                value,
+                Err(IntoSerdeValueError::PreserveOrderUnavailable)
+            ));
+        }
+
+        // arrays and scalars do not need the feature
+        assert_eq!(
+            from_slice_with_options(b"[1, [2]]", options).unwrap(),
+            serde_json::json!([1, [2]])
+        );
+    }
+
+    /// Test that values can be extracted by JSON Pointer
+    #[test]
+    fn extract() {
+        let json = r#"{
+            "a": {"b": [1, {"c": true}], "d": "e"},
+            "f/g": {"h~i": null},
+            "": [[], 2, {"x": [3]}],
+            "j": 4
+        }"#;
+        let extract = |p| extract_pointer(json.as_bytes(), p).unwrap();
+        let full = serde_from_slice::<Value>(json.as_bytes()).unwrap();
+
+        for p in [
+            "",
+            "/a",
+            "/a/b",
+            "/a/b/0",
+            "/a/b/1",
+            "/a/b/1/c",
+            "/a/d",
+            "/f~1g",
+            "/f~1g/h~0i",
+            "/",
+            "//0",
+            "//1",
+            "//2/x/0",
+            "/j",
+        ] {
+            assert_eq!(extract(p).as_ref(), full.pointer(p), "{p}");
+        }
+
+        for p in [
+            "/x", "/a/b/2", "/a/b/01", "/a/b/-", "/a/b/+1", "/a/d/0", "/j/k", "//2/y",
+        ] {
+            assert_eq!(extract(p), None, "{p}");
+        }
+
+        assert!(matches!(
+            extract_pointer(json.as_bytes(), "a"),
+            Err(IntoSerdeValueError::InvalidPointer(_))
+        ));
+        assert!(matches!(
+            extract_pointer(r#"{"a": [1, }"#.as_bytes(), "/a/1"),
+            Err(IntoSerdeValueError::Parse(ParserError::SyntaxError))
+        ));
+    }
+
+    /// Test that each line of an NDJSON text is converted separately and
+    /// that malformed lines do not affect the others
+    #[test]
+    fn ndjson() {
+        let ndjson = "{\"a\": 1}\r\n\
+            [1, \n\
+            2]\n\
+            \n\
+            \"x\" 5\n\
+            \t \n\
+            {\"b\": [null, -1.5]}\n\
+            \"unterminated\n\
+            7";
+        let results = ndjson_values(ndjson.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 7);
+        assert_eq!(results[0].as_ref().unwrap(), &serde_json::json!({"a": 1}));
+        assert!(matches!(
+            results[1],
+            Err((2, IntoSerdeValueError::Parse(ParserError::NoMoreInput)))
+        ));
+        assert!(matches!(
+            results[2],
+            Err((3, IntoSerdeValueError::Parse(ParserError::TrailingData(1))))
+        ));
+        assert!(matches!(
+            results[3],
+            Err((5, IntoSerdeValueError::Parse(ParserError::TrailingData(4))))
+        ));
+        assert_eq!(
+            results[4].as_ref().unwrap(),
+            &serde_json::json!({"b": [null, -1.5]})
+        );
+        assert!(matches!(
+            results[5],
+            Err((8, IntoSerdeValueError::Parse(_)))
+        ));
+        assert_eq!(results[6].as_ref().unwrap(), &serde_json::json!(7));
+
+        assert_eq!(ndjson_values(&b""[..]).count(), 0);
+        assert_eq!(ndjson_values(&b"\n\n"[..]).count(), 0);
+    }
+
+    /// Test that the iterator ends after a read error
+    #[test]
+    fn ndjson_read_error() {
+        struct FailingReader(bool);
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0 {
+                    return Err(std::io::Error::other("failed"));
+                }
+                self.0 = true;
+                buf[..3].copy_from_slice(b"1\n2");
+                Ok(3)
+            }
+        }
+
+        let results = ndjson_values(FailingReader(false)).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &serde_json::json!(1));
+        assert!(matches!(results[1], Err((2, IntoSerdeValueError::Fill(_)))));
+    }
+
+    /// Test that a JSON value embedded in other text can be found and parsed
+    #[test]
+    fn embedded() {
+        let text = br#"[INFO] got {"a": [1, 2]} and [true] and {"b": "#;
+
+        // the first bracket belongs to the surrounding text
+        assert!(matches!(
+            parse_embedded(text, 0),
+            Err(IntoSerdeValueError::Parse(ParserError::SyntaxError))
+        ));
+
+        let (v, r) = parse_embedded(text, 6).unwrap();
+        assert_eq!(v, serde_json::json!({"a": [1, 2]}));
+        assert_eq!(&text[r.clone()], br#"{"a": [1, 2]}"#);
+
+        let (v, r) = parse_embedded(text, r.end).unwrap();
+        assert_eq!(v, serde_json::json!([true]));
+        assert_eq!(&text[r.clone()], b"[true]");
+
+        // incomplete value
+        assert!(matches!(
+            parse_embedded(text, r.end),
+            Err(IntoSerdeValueError::Parse(ParserError::NoMoreInput))
+        ));
+
+        assert!(matches!(
+            parse_embedded(b"no json here", 0),
+            Err(IntoSerdeValueError::NoEmbeddedValue)
+        ));
+        assert!(matches!(
+            parse_embedded(b"[1]", 4),
+            Err(IntoSerdeValueError::NoEmbeddedValue)
+        ));
+    }
+
+    #[test]
+    fn values_iter() {
+        let json = br#"1 "a" {"b": [true]} [] null"#;
+        let values = values_from_slice_iter(json)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            values,
+            [
+                serde_json::json!(1),
+                serde_json::json!("a"),
+                serde_json::json!({"b": [true]}),
+                serde_json::json!([]),
+                Value::Null,
+            ]
+        );
+        for json in [&b""[..], b"  "] {
+            assert!(matches!(
+                values_from_slice_iter(json).next(),
+                Some(Err(IntoSerdeValueError::Parse(ParserError::EmptyInput)))
+            ));
+        }
+
+        // stop early without parsing the rest
+        let json = br#"{"a": 1} {"b": 2} ]]]"#;
+        let mut values = values_from_slice_iter(json);
+        assert_eq!(values.next().unwrap().unwrap(), serde_json::json!({"a": 1}));
+        assert_eq!(values.parser().last_value_end(), 8);
+        assert_eq!(values.next().unwrap().unwrap(), serde_json::json!({"b": 2}));
+        let mut parser = values.into_parser();
+        assert_eq!(parser.last_value_end(), 17);
+        assert!(parser.next_event().is_err());
+
+        // the iterator ends after the first error
+        let mut values = values_from_slice_iter(b"[1] [2,] [3]");
+        assert_eq!(values.next().unwrap().unwrap(), serde_json::json!([1]));
+        assert!(matches!(
+            values.next(),
+            Some(Err(IntoSerdeValueError::Parse(ParserError::SyntaxError)))
+        ));
+        assert!(values.next().is_none());
+    }
+
+    #[test]
+    fn deeply_nested() {
+        const LEVELS: usize = 50_000;
+        let mut json = "[".repeat(LEVELS);
+        json.push_str(&"]".repeat(LEVELS));
+
+        // the default limit applies
+        assert!(matches!(
+            from_str(&json),
+            Err(IntoSerdeValueError::Parse(ParserError::DepthLimitExceeded(
+                _
+            )))
+        ));
+
+        let options = ValueOptionsBuilder::default()
+            .with_max_depth(LEVELS + 1)
+            .build();
+        let mut v = from_slice_with_options(json.as_bytes(), options).unwrap();
+        let mut depth = 1;
+        // unwrap the value level by level because Serde JSON drops nested
+        // values recursively, which would overflow the stack
+        while let Some(inner) = v.as_array_mut().and_then(|a| a.pop()) {
+            v = inner;
+            depth += 1;
+        }
+        assert_eq!(depth, LEVELS);
+
+        let options = ValueOptionsBuilder::default()
+            .with_max_depth(LEVELS)
+            .build();
+        assert!(matches!(
+            from_slice_with_options(json.as_bytes(), options),
+            Err(IntoSerdeValueError::Parse(ParserError::DepthLimitExceeded(
+                _
+            )))
+        ));
+    }
 }
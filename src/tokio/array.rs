@@ -0,0 +1,206 @@
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncRead, BufReader};
+
+use crate::parser::ParserError;
+use crate::serde_json::{IntoSerdeValueError, ValueBuilder, ValueOptions};
+use crate::tokio::AsyncBufReaderJsonFeeder;
+use crate::{JsonEvent, JsonEventKind, JsonParser};
+
+/// A [`Stream`] of the elements of a top-level JSON array that is read
+/// asynchronously. Create it with [`array_elements()`].
+///
+/// Each element is deserialized into a value of type `T` as soon as it has
+/// been read. Only one element is kept in memory at a time. The stream only
+/// reads from the underlying reader if the consumer polls for the next
+/// element, so a slow consumer does not cause the whole input to be read.
+///
+/// If an element cannot be converted to `T` (e.g. because it has the wrong
+/// type), the stream yields an error. By default, the stream ends after the
+/// first error. Use [`Self::with_skip_invalid()`] to continue with the next
+/// element instead. Syntax errors and I/O errors always end the stream.
+pub struct ArrayElements<T, R> {
+    parser: JsonParser<AsyncBufReaderJsonFeeder<R>>,
+    options: ValueOptions,
+    builder: ValueBuilder,
+
+    /// `true` if elements that cannot be converted should be skipped
+    skip_invalid: bool,
+
+    /// `true` if the start of the array has been read
+    started: bool,
+
+    /// The number of objects and arrays that are currently open inside the
+    /// current element
+    depth: usize,
+
+    /// `true` if the rest of the current element should be skipped because
+    /// it could not be converted
+    skipping: bool,
+
+    /// `true` if the stream has ended
+    done: bool,
+
+    element_type: PhantomData<fn() -> T>,
+}
+
+/// Create a [`Stream`] that asynchronously reads a top-level JSON array from
+/// the given reader and yields its elements one by one, deserialized into
+/// values of type `T` (see [`ArrayElements`])
+///
+/// ```
+/// use actson::tokio::array_elements;
+/// use futures_core::Stream;
+/// use std::pin::pin;
+/// use std::future::poll_fn;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let json = r#"[{"id": 1}, {"id": 2}, {"id": 3}]"#.as_bytes();
+///
+/// let mut elements = pin!(array_elements::<serde_json::Value, _>(json));
+/// let mut sum = 0;
+/// while let Some(e) = poll_fn(|cx| elements.as_mut().poll_next(cx)).await {
+///     sum += e.unwrap()["id"].as_i64().unwrap();
+/// }
+/// assert_eq!(sum, 6);
+/// # }
+/// ```
+///
+/// In practice, you will typically use the combinators of a crate such as
+/// [`futures`](https://docs.rs/futures) or [`tokio-stream`](https://docs.rs/tokio-stream)
+/// to consume the stream.
+pub fn array_elements<T, R>(reader: R) -> ArrayElements<T, R>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    let feeder = AsyncBufReaderJsonFeeder::new(BufReader::new(reader));
+    ArrayElements::new(JsonParser::new(feeder), ValueOptions::default())
+}
+
+impl<T, R> ArrayElements<T, R>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    /// Create a stream that yields the elements of the top-level JSON array
+    /// parsed by the given parser. The elements are converted to Serde JSON
+    /// values with the given options before they are deserialized.
+    pub fn new(parser: JsonParser<AsyncBufReaderJsonFeeder<R>>, options: ValueOptions) -> Self {
+        ArrayElements {
+            parser,
+            options,
+            builder: ValueBuilder::new(options),
+            skip_invalid: false,
+            started: false,
+            depth: 0,
+            skipping: false,
+            done: false,
+            element_type: PhantomData,
+        }
+    }
+
+    /// Specify whether elements that cannot be converted to `T` should be
+    /// skipped. If `true`, the stream yields an error for each invalid
+    /// element and then continues with the next one.
+    pub fn with_skip_invalid(mut self, skip_invalid: bool) -> Self {
+        self.skip_invalid = skip_invalid;
+        self
+    }
+
+    /// Handle an error that happened while converting the current element.
+    /// Return the error to yield.
+    fn element_error(&mut self, e: IntoSerdeValueError) -> Option<Result<T, IntoSerdeValueError>> {
+        self.builder = ValueBuilder::new(self.options);
+        self.skipping = self.depth > 0;
+        self.done = !self.skip_invalid;
+        Some(Err(e))
+    }
+
+    /// Handle a fatal error. Return the error to yield.
+    fn fatal_error(&mut self, e: IntoSerdeValueError) -> Option<Result<T, IntoSerdeValueError>> {
+        self.done = true;
+        Some(Err(e))
+    }
+
+    /// Process an event of an element. Return the element as soon as it is
+    /// complete.
+    fn on_element_event(&mut self, event: JsonEvent) -> Option<Result<T, IntoSerdeValueError>> {
+        match event {
+            JsonEvent::StartObject | JsonEvent::StartArray => self.depth += 1,
+            JsonEvent::EndObject | JsonEvent::EndArray => self.depth -= 1,
+            _ => {}
+        }
+
+        if self.skipping {
+            self.skipping = self.depth > 0;
+            return None;
+        }
+
+        match self.builder.on_event(event, &self.parser) {
+            Ok(Some(v)) => match serde_json::from_value(v) {
+                Ok(e) => Some(Ok(e)),
+                Err(e) => self.element_error(e.into()),
+            },
+            Ok(None) => None,
+            Err(e) => self.element_error(e),
+        }
+    }
+}
+
+impl<T, R> Stream for ArrayElements<T, R>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<T, IntoSerdeValueError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.done {
+            let event = match this.parser.next_event() {
+                Ok(Some(event)) => event,
+                Ok(None) => break,
+                Err(e) => return Poll::Ready(this.fatal_error(e.into())),
+            };
+
+            if event == JsonEvent::NeedMoreInput {
+                match this.parser.feeder.poll_fill_buf(cx) {
+                    Poll::Ready(Ok(())) => continue,
+                    Poll::Ready(Err(e)) => return Poll::Ready(this.fatal_error(e.into())),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            if !this.started {
+                if event != JsonEvent::StartArray {
+                    let e = ParserError::UnexpectedEvent {
+                        expected: JsonEventKind::Array,
+                        found: Some(event),
+                    };
+                    return Poll::Ready(this.fatal_error(e.into()));
+                }
+                this.started = true;
+                continue;
+            }
+
+            if event == JsonEvent::EndArray && this.depth == 0 {
+                // end of the top-level array. Continue parsing to make sure
+                // there is no trailing data.
+                continue;
+            }
+
+            if let Some(r) = this.on_element_event(event) {
+                return Poll::Ready(Some(r));
+            }
+        }
+
+        this.done = true;
+        Poll::Ready(None)
+    }
+}
@@ -1,11 +1,24 @@
-use crate::feeder::{FillError, JsonFeeder};
-use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use crate::feeder::{FeederError, JsonFeeder};
+use crate::parser::{Fold, FoldError, NextEventError, Scalar};
+use crate::{JsonEvent, JsonParser};
+use std::future::poll_fn;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncRead, BufReader};
 
 /// A [`JsonFeeder`] that reads from an asynchronous [`BufReader`].
+#[derive(Debug)]
 pub struct AsyncBufReaderJsonFeeder<T> {
     reader: BufReader<T>,
     filled: bool,
     pos: usize,
+    len: usize,
+    consumed: usize,
+    max_bytes: Option<usize>,
+    total_len: Option<usize>,
 }
 
 impl<T> AsyncBufReaderJsonFeeder<T>
@@ -18,16 +31,84 @@ where
             reader,
             filled: false,
             pos: 0,
+            len: 0,
+            consumed: 0,
+            max_bytes: None,
+            total_len: None,
         }
     }
 
+    /// Create a new feeder that reads from the given asynchronous [`BufReader`] but
+    /// refuses to read more than `max_bytes` bytes in total. If the input is
+    /// larger, [`fill_buf()`](Self::fill_buf()) returns
+    /// [`FeederError::InputTooLarge`].
+    ///
+    /// This limit is enforced on the I/O side: the feeder stops reading from
+    /// the underlying reader as soon as the limit would be exceeded, which is
+    /// useful if you want to stop receiving data (e.g. from a socket)
+    /// altogether. The parser itself is not aware of the limit.
+    pub fn new_with_max_bytes(reader: BufReader<T>, max_bytes: usize) -> Self {
+        AsyncBufReaderJsonFeeder {
+            max_bytes: Some(max_bytes),
+            ..Self::new(reader)
+        }
+    }
+
+    /// Specify the total length of the input in bytes (e.g. the size of the
+    /// file the reader reads from), so the parser can report its
+    /// [`progress()`](JsonParser::progress()). The feeder cannot determine
+    /// the length itself because the reader might be a stream.
+    pub fn with_total_len(mut self, total_len: usize) -> Self {
+        self.total_len = Some(total_len);
+        self
+    }
+
+    /// Consume the feeder and return the underlying reader together with all
+    /// bytes that have already been read into the buffer but that have not
+    /// been consumed by the parser yet. This allows you to continue reading
+    /// from the reader after the JSON text (e.g. if the JSON text is followed
+    /// by other data).
+    ///
+    /// Note that the parser may have to consume one byte after the end of a
+    /// top-level number to detect where the number ends. This byte is not
+    /// part of the returned bytes.
+    pub fn into_remainder(self) -> (T, Vec<u8>) {
+        let rest = self
+            .reader
+            .buffer()
+            .get(self.pos..)
+            .unwrap_or_default()
+            .to_vec();
+        (self.reader.into_inner(), rest)
+    }
+
     /// Fill the feeder's internal buffer
-    pub async fn fill_buf(&mut self) -> Result<(), FillError> {
-        self.reader.consume(self.pos);
-        self.reader.fill_buf().await?;
-        self.filled = true;
+    pub async fn fill_buf(&mut self) -> Result<(), FeederError> {
+        poll_fn(|cx| self.poll_fill_buf(cx)).await
+    }
+
+    /// Attempt to fill the feeder's internal buffer. If no data is
+    /// available yet, the method returns [`Poll::Pending`] and arranges for
+    /// the current task to be woken up. This is useful if you want to drive
+    /// the parser from a manually implemented [`Future`](std::future::Future)
+    /// or stream. Otherwise, use [`Self::fill_buf()`].
+    pub fn poll_fill_buf(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), FeederError>> {
+        let mut reader = Pin::new(&mut self.reader);
+        reader.as_mut().consume(self.pos);
+        self.consumed += self.pos;
         self.pos = 0;
-        Ok(())
+        self.len = 0;
+
+        let buf = ready!(reader.poll_fill_buf(cx))?;
+        if let Some(max_bytes) = self.max_bytes {
+            if self.consumed + buf.len() > max_bytes {
+                return Poll::Ready(Err(FeederError::InputTooLarge(max_bytes)));
+            }
+        }
+
+        self.len = buf.len();
+        self.filled = true;
+        Poll::Ready(Ok(()))
     }
 }
 
@@ -36,7 +117,7 @@ where
     T: AsyncRead + Unpin,
 {
     fn has_input(&self) -> bool {
-        self.pos < self.reader.buffer().len()
+        self.pos < self.len
     }
 
     fn is_done(&self) -> bool {
@@ -44,13 +125,122 @@ where
     }
 
     fn next_input(&mut self) -> Option<u8> {
-        let buf = self.reader.buffer();
-        if self.pos < buf.len() {
-            let r = Some(buf[self.pos]);
+        if self.pos < self.len {
+            let r = Some(self.reader.buffer()[self.pos]);
             self.pos += 1;
             r
         } else {
             None
         }
     }
+
+    fn total_len(&self) -> Option<usize> {
+        self.total_len
+    }
+
+    fn peek(&self) -> &[u8] {
+        self.reader
+            .buffer()
+            .get(self.pos..self.len)
+            .unwrap_or_default()
+    }
+
+    fn consume(&mut self, n: usize) {
+        debug_assert!(n <= self.len - self.pos);
+        self.pos += n;
+    }
+}
+
+impl<T> JsonParser<AsyncBufReaderJsonFeeder<T>>
+where
+    T: AsyncRead + Unpin,
+{
+    /// Proceed parsing and return the next event that is not
+    /// [`JsonEvent::NeedMoreInput`]. This method asynchronously reads from
+    /// the feeder's reader whenever the parser needs more input. This is the
+    /// asynchronous counterpart of [`JsonParser::next_event_blocking()`].
+    ///
+    /// ```
+    /// use actson::{JsonEvent, JsonParser};
+    /// use actson::tokio::AsyncBufReaderJsonFeeder;
+    /// use tokio::io::BufReader;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let json = r#"[1, 2, 3]"#.as_bytes();
+    /// let feeder = AsyncBufReaderJsonFeeder::new(BufReader::with_capacity(2, json));
+    /// let mut parser = JsonParser::new(feeder);
+    ///
+    /// let mut sum = 0;
+    /// while let Some(event) = parser.next_event_async().await.unwrap() {
+    ///     if event == JsonEvent::ValueInt {
+    ///         sum += parser.current_int::<i64>().unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(sum, 6);
+    /// # }
+    /// ```
+    pub async fn next_event_async(&mut self) -> Result<Option<JsonEvent>, NextEventError> {
+        loop {
+            match self.next_event()? {
+                Some(JsonEvent::NeedMoreInput) => self.feeder.fill_buf().await?,
+                event => return Ok(event),
+            }
+        }
+    }
+
+    /// Call the given closure with each number in the JSON text and
+    /// accumulate the results (see [`JsonParser::fold_numbers()`]). In
+    /// contrast to [`JsonParser::fold_numbers()`], this method asynchronously
+    /// reads from the feeder's reader until the end of the JSON text has been
+    /// reached.
+    pub async fn fold_all_numbers<A, F>(&mut self, init: A, mut f: F) -> Result<A, FoldError>
+    where
+        F: FnMut(A, Scalar<'_>) -> A,
+    {
+        let mut acc = init;
+        loop {
+            match self.fold_numbers(acc, &mut f)? {
+                Fold::NeedMoreInput(a) => {
+                    acc = a;
+                    self.feeder.fill_buf().await?;
+                }
+                Fold::Done(a) => return Ok(a),
+            }
+        }
+    }
+}
+
+impl JsonParser<AsyncBufReaderJsonFeeder<File>> {
+    /// Asynchronously open the file at the given path and create a parser
+    /// that reads from it through an asynchronous [`BufReader`] with default
+    /// capacity and default options. This is the asynchronous counterpart of
+    /// [`JsonParser::from_path()`]. If you need to configure the reader, the
+    /// feeder, or the parser, create them manually instead.
+    ///
+    /// The feeder knows the size of the file, so the parser can report its
+    /// [`progress()`](JsonParser::progress()).
+    ///
+    /// ```no_run
+    /// use actson::{JsonEvent, JsonParser};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let mut parser = JsonParser::from_path_async("data.json").await.unwrap();
+    /// loop {
+    ///     match parser.next_event().unwrap() {
+    ///         Some(JsonEvent::NeedMoreInput) => parser.feeder.fill_buf().await.unwrap(),
+    ///         Some(_) => {}
+    ///         None => break,
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn from_path_async<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path).await?;
+        let len = file.metadata().await?.len();
+        Ok(JsonParser::new(
+            AsyncBufReaderJsonFeeder::new(BufReader::new(file)).with_total_len(len as usize),
+        ))
+    }
 }
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, BufReader};
+
+use crate::feeder::{FeederError, JsonFeeder};
+use crate::tokio::AsyncBufReaderJsonFeeder;
+
+/// A [`JsonFeeder`] that asynchronously reads from a sequence of readers, one
+/// after the other, as if they were a single input. This is useful if a JSON
+/// text is split across multiple sources (e.g. a header file and a body
+/// file). The feeder moves on to the next reader as soon as the current one
+/// is exhausted and only reports the end of the input after the last reader
+/// has been exhausted. Tokens may straddle the boundary between two readers.
+///
+/// All readers must have the same type. Use a boxed trait object (e.g.
+/// `Box<dyn AsyncRead + Unpin + Send>`) to chain readers of different types.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::tokio::AsyncChainJsonFeeder;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parts: [&[u8]; 2] = [br#"{"name": "El"#, br#"vis"}"#];
+///
+/// let feeder = AsyncChainJsonFeeder::new(parts);
+/// let mut parser = JsonParser::new(feeder);
+/// while let Some(event) = parser.next_event().unwrap() {
+///     match event {
+///         JsonEvent::NeedMoreInput => parser.feeder.fill_buf().await.unwrap(),
+///         JsonEvent::ValueString => assert_eq!(parser.current_str().unwrap(), "Elvis"),
+///         _ => {}
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncChainJsonFeeder<T> {
+    /// The reader that is currently being read
+    current: Option<AsyncBufReaderJsonFeeder<T>>,
+
+    /// The readers that have not been read yet
+    remaining: VecDeque<T>,
+
+    /// The zero-based index of the current reader
+    index: usize,
+}
+
+impl<T> AsyncChainJsonFeeder<T>
+where
+    T: AsyncRead + Unpin,
+{
+    /// Create a new feeder that reads from the given readers in order
+    pub fn new<I>(readers: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut remaining = readers.into_iter().collect::<VecDeque<_>>();
+        let current = remaining
+            .pop_front()
+            .map(|r| AsyncBufReaderJsonFeeder::new(BufReader::new(r)));
+        AsyncChainJsonFeeder {
+            current,
+            remaining,
+            index: 0,
+        }
+    }
+
+    /// Return the zero-based index of the reader that is currently being
+    /// read
+    pub fn current_index(&self) -> usize {
+        self.index
+    }
+
+    /// Fill the feeder's internal buffer. If the current reader fails,
+    /// the method returns [`FeederError::Reader`] with the reader's index.
+    pub async fn fill_buf(&mut self) -> Result<(), FeederError> {
+        poll_fn(|cx| self.poll_fill_buf(cx)).await
+    }
+
+    /// Attempt to fill the feeder's internal buffer (see
+    /// [`AsyncBufReaderJsonFeeder::poll_fill_buf()`])
+    pub fn poll_fill_buf(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), FeederError>> {
+        loop {
+            let Some(current) = &mut self.current else {
+                return Poll::Ready(Ok(()));
+            };
+
+            match ready!(current.poll_fill_buf(cx)) {
+                Ok(()) => {}
+                Err(FeederError::Io(source)) => {
+                    return Poll::Ready(Err(FeederError::Reader {
+                        index: self.index,
+                        source,
+                    }));
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+
+            if current.has_input() {
+                return Poll::Ready(Ok(()));
+            }
+
+            // the current reader is exhausted
+            let Some(next) = self.remaining.pop_front() else {
+                return Poll::Ready(Ok(()));
+            };
+            self.current = Some(AsyncBufReaderJsonFeeder::new(BufReader::new(next)));
+            self.index += 1;
+        }
+    }
+}
+
+impl<T> JsonFeeder for AsyncChainJsonFeeder<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn has_input(&self) -> bool {
+        self.current.as_ref().is_some_and(|c| c.has_input())
+    }
+
+    fn is_done(&self) -> bool {
+        match &self.current {
+            Some(c) => self.remaining.is_empty() && c.is_done(),
+            None => true,
+        }
+    }
+
+    fn next_input(&mut self) -> Option<u8> {
+        self.current.as_mut()?.next_input()
+    }
+
+    fn peek(&self) -> &[u8] {
+        self.current.as_ref().map(|c| c.peek()).unwrap_or_default()
+    }
+
+    fn consume(&mut self, n: usize) {
+        if let Some(c) = self.current.as_mut() {
+            c.consume(n);
+        }
+    }
+}
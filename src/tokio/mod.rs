@@ -1,3 +1,9 @@
+#[cfg(feature = "serde_json")]
+mod array;
 mod asyncbufreader;
+mod chain;
 
+#[cfg(feature = "serde_json")]
+pub use array::{array_elements, ArrayElements};
 pub use asyncbufreader::AsyncBufReaderJsonFeeder;
+pub use chain::AsyncChainJsonFeeder;
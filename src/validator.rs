@@ -0,0 +1,250 @@
+//! Hooks for validating a JSON text while it is being parsed
+//!
+//! Implement the [`Validator`] trait and call [`Validator::on_event()`] for
+//! each event the parser generates. Since validators typically need to know
+//! where in the JSON text an event occurred, path tracking should be enabled
+//! (see [`JsonParserOptionsBuilder::with_path_tracking()`](crate::options::JsonParserOptionsBuilder::with_path_tracking())).
+//!
+//! [`SimpleValidator`] is a small reference implementation that checks a
+//! handful of constraints (value types, required fields, minimum and maximum
+//! values). It is not a full JSON Schema implementation.
+//!
+//! ```
+//! use actson::feeder::SliceJsonFeeder;
+//! use actson::options::JsonParserOptionsBuilder;
+//! use actson::validator::{SimpleValidator, ValueType, Validator};
+//! use actson::JsonParser;
+//!
+//! let json = r#"{"name": "Elvis", "age": -1}"#.as_bytes();
+//!
+//! let mut validator = SimpleValidator::new()
+//!     .with_type("", ValueType::Object)
+//!     .with_required("", ["name", "age"])
+//!     .with_type("/age", ValueType::Integer)
+//!     .with_minimum("/age", 0.0);
+//!
+//! let feeder = SliceJsonFeeder::new(json);
+//! let mut parser = JsonParser::new_with_options(
+//!     feeder,
+//!     JsonParserOptionsBuilder::default()
+//!         .with_path_tracking(true)
+//!         .build(),
+//! );
+//!
+//! let mut errors = Vec::new();
+//! while let Some(event) = parser.next_event().unwrap() {
+//!     if let Err(e) = validator.on_event(event, &parser) {
+//!         errors.push(e);
+//!     }
+//! }
+//!
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].pointer, "/age");
+//! ```
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::feeder::JsonFeeder;
+use crate::{JsonEvent, JsonParser};
+
+/// An error that occurs if a JSON text does not satisfy a constraint
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid value at `{pointer}': {message}")]
+pub struct ValidationError {
+    /// A JSON Pointer (RFC 6901) to the invalid value
+    pub pointer: String,
+
+    /// A message describing the violated constraint
+    pub message: String,
+}
+
+/// A validator receives the parser's events one by one and checks if the
+/// JSON text satisfies its constraints
+pub trait Validator {
+    /// Process an event the parser has just returned. Return an error if
+    /// the event violates a constraint. Implementations must not assume that
+    /// parsing stops after the first error.
+    fn on_event<T>(
+        &mut self,
+        event: JsonEvent,
+        parser: &JsonParser<T>,
+    ) -> Result<(), ValidationError>
+    where
+        T: JsonFeeder;
+}
+
+/// The type of a JSON value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    Null,
+    Boolean,
+    /// A number without a fraction or exponent
+    Integer,
+    /// Any number (including integers)
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ValueType {
+    /// Check if a value that has produced the given event has this type
+    fn matches(&self, event: JsonEvent) -> bool {
+        matches!(
+            (self, event),
+            (ValueType::Null, JsonEvent::ValueNull)
+                | (
+                    ValueType::Boolean,
+                    JsonEvent::ValueTrue | JsonEvent::ValueFalse
+                )
+                | (ValueType::Integer, JsonEvent::ValueInt)
+                | (
+                    ValueType::Number,
+                    JsonEvent::ValueInt | JsonEvent::ValueFloat
+                )
+                | (ValueType::String, JsonEvent::ValueString)
+                | (ValueType::Array, JsonEvent::StartArray)
+                | (ValueType::Object, JsonEvent::StartObject)
+        )
+    }
+}
+
+/// Constraints for the value at a specific location
+#[derive(Clone, Debug, Default)]
+struct Constraints {
+    value_type: Option<ValueType>,
+    required: Vec<String>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
+/// A simple [`Validator`] that checks constraints for values at given JSON
+/// Pointers (RFC 6901). Pointers must refer to concrete locations. Wildcards
+/// are not supported.
+///
+/// The parser must have path tracking enabled. Otherwise, all constraints
+/// are checked against the top-level value.
+#[derive(Clone, Debug, Default)]
+pub struct SimpleValidator {
+    constraints: HashMap<String, Constraints>,
+
+    /// For each object and array that is currently being parsed, the names
+    /// of required fields that have not been found yet
+    missing: Vec<Vec<String>>,
+}
+
+impl SimpleValidator {
+    /// Create a new validator without any constraints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the value at the given pointer to have the given type
+    pub fn with_type(mut self, pointer: &str, value_type: ValueType) -> Self {
+        self.constraints_mut(pointer).value_type = Some(value_type);
+        self
+    }
+
+    /// Require the object at the given pointer to contain the given fields
+    pub fn with_required<I, S>(mut self, pointer: &str, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.constraints_mut(pointer)
+            .required
+            .extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /// Require the number at the given pointer to be greater than or equal
+    /// to the given value
+    pub fn with_minimum(mut self, pointer: &str, minimum: f64) -> Self {
+        self.constraints_mut(pointer).minimum = Some(minimum);
+        self
+    }
+
+    /// Require the number at the given pointer to be less than or equal to
+    /// the given value
+    pub fn with_maximum(mut self, pointer: &str, maximum: f64) -> Self {
+        self.constraints_mut(pointer).maximum = Some(maximum);
+        self
+    }
+
+    fn constraints_mut(&mut self, pointer: &str) -> &mut Constraints {
+        self.constraints.entry(pointer.to_string()).or_default()
+    }
+}
+
+impl Validator for SimpleValidator {
+    fn on_event<T>(
+        &mut self,
+        event: JsonEvent,
+        parser: &JsonParser<T>,
+    ) -> Result<(), ValidationError>
+    where
+        T: JsonFeeder,
+    {
+        let error = |pointer: String, message: String| Err(ValidationError { pointer, message });
+
+        match event {
+            JsonEvent::NeedMoreInput | JsonEvent::TruncatedValue => Ok(()),
+
+            JsonEvent::FieldName => {
+                if let (Some(missing), Ok(name)) = (self.missing.last_mut(), parser.current_str()) {
+                    missing.retain(|f| f != name);
+                }
+                Ok(())
+            }
+
+            JsonEvent::EndObject | JsonEvent::EndArray => {
+                let missing = self.missing.pop().unwrap_or_default();
+                if missing.is_empty() {
+                    return Ok(());
+                }
+                error(
+                    parser.current_pointer(),
+                    format!("missing required fields: {}", missing.join(", ")),
+                )
+            }
+
+            _ => {
+                let pointer = parser.current_pointer();
+                let constraints = self.constraints.get(&pointer);
+
+                if matches!(event, JsonEvent::StartObject | JsonEvent::StartArray) {
+                    let required = match constraints {
+                        Some(c) if event == JsonEvent::StartObject => c.required.clone(),
+                        _ => vec![],
+                    };
+                    self.missing.push(required);
+                }
+
+                let Some(constraints) = constraints else {
+                    return Ok(());
+                };
+
+                if let Some(t) = constraints.value_type {
+                    if !t.matches(event) {
+                        return error(pointer, format!("expected value of type {:?}", t));
+                    }
+                }
+
+                if matches!(event, JsonEvent::ValueInt | JsonEvent::ValueFloat) {
+                    let Ok(n) = parser.current_float() else {
+                        return error(pointer, "invalid number".to_string());
+                    };
+                    if constraints.minimum.is_some_and(|m| n < m) {
+                        return error(pointer, format!("{} is less than the minimum", n));
+                    }
+                    if constraints.maximum.is_some_and(|m| n > m) {
+                        return error(pointer, format!("{} is greater than the maximum", n));
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::io::Write;
+
+use thiserror::Error;
+
+use crate::feeder::JsonFeeder;
+use crate::parser::InvalidStringValueError;
+use crate::{JsonEvent, JsonParser};
+
+/// An error that can happen when writing JSON events
+#[derive(Error, Debug)]
+pub enum WriterError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    InvalidStringValue(#[from] InvalidStringValueError),
+}
+
+/// A container that is currently open
+enum Frame {
+    /// An array. `true` if no element has been written yet.
+    Array(bool),
+
+    /// An object whose entries are written immediately. `true` if no entry
+    /// has been written yet.
+    Object(bool),
+
+    /// An object whose entries are buffered until the object ends, so they
+    /// can be written in sorted order. Each entry consists of the field name
+    /// and the serialized value.
+    SortedObject(Vec<(String, Vec<u8>)>),
+}
+
+/// Writes the events generated by a [`JsonParser`] back to a JSON text.
+/// The output is compact, i.e. it does not contain any white space. Numbers
+/// are written exactly as they appeared in the input.
+///
+/// ```
+/// use actson::{JsonEvent, JsonParser};
+/// use actson::feeder::SliceJsonFeeder;
+/// use actson::writer::JsonWriter;
+///
+/// let json = r#"{"name": "Elvis", "albums": ["Elvis Is Back!"]}"#.as_bytes();
+/// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+/// let mut writer = JsonWriter::new(Vec::new());
+/// while let Some(event) = parser.next_event().unwrap() {
+///     writer.write_event(event, &parser).unwrap();
+/// }
+///
+/// assert_eq!(
+///     writer.into_inner(),
+///     br#"{"name":"Elvis","albums":["Elvis Is Back!"]}"#
+/// );
+/// ```
+pub struct JsonWriter<W> {
+    out: W,
+    sorted_keys: bool,
+    stack: Vec<Frame>,
+}
+
+impl<W> JsonWriter<W>
+where
+    W: Write,
+{
+    /// Create a new writer that writes to the given output
+    pub fn new(out: W) -> Self {
+        JsonWriter {
+            out,
+            sorted_keys: false,
+            stack: vec![],
+        }
+    }
+
+    /// Write the entries of all objects in ascending order of their field
+    /// names. Field names are compared by their UTF-8 bytes (which is the
+    /// same as comparing them by their Unicode code points). Entries with
+    /// equal field names keep their original order. This is useful to
+    /// generate a canonical representation of a JSON text (e.g. for signing
+    /// or hashing).
+    ///
+    /// Heads up: To be able to sort the entries, the writer has to keep each
+    /// object in memory until it has ended. This includes all nested values.
+    /// A top-level object will therefore be buffered completely. Arrays that
+    /// are not inside an object are streamed without buffering.
+    ///
+    /// ```
+    /// use actson::JsonParser;
+    /// use actson::feeder::SliceJsonFeeder;
+    /// use actson::writer::JsonWriter;
+    ///
+    /// let json = r#"[{"b": 1, "a": {"d": true, "c": null}}]"#.as_bytes();
+    /// let mut parser = JsonParser::new(SliceJsonFeeder::new(json));
+    /// let mut writer = JsonWriter::new(Vec::new()).with_sorted_keys(true);
+    /// while let Some(event) = parser.next_event().unwrap() {
+    ///     writer.write_event(event, &parser).unwrap();
+    /// }
+    ///
+    /// assert_eq!(writer.into_inner(), br#"[{"a":{"c":null,"d":true},"b":1}]"#);
+    /// ```
+    pub fn with_sorted_keys(mut self, sorted_keys: bool) -> Self {
+        self.sorted_keys = sorted_keys;
+        self
+    }
+
+    /// Write the given event. Call this method for each event returned by
+    /// [`JsonParser::next_event()`] and pass the parser so the writer can
+    /// get the current value. [`JsonEvent::NeedMoreInput`] is ignored.
+    /// [`JsonEvent::TruncatedValue`] and [`JsonEvent::Scalar`] are written as `null`.
+    pub fn write_event<T>(
+        &mut self,
+        event: JsonEvent,
+        parser: &JsonParser<T>,
+    ) -> Result<(), WriterError>
+    where
+        T: JsonFeeder,
+    {
+        match event {
+            JsonEvent::NeedMoreInput => {}
+
+            JsonEvent::StartObject => {
+                self.begin_value()?;
+                if self.sorted_keys {
+                    self.stack.push(Frame::SortedObject(vec![]));
+                } else {
+                    self.write(b"{")?;
+                    self.stack.push(Frame::Object(true));
+                }
+            }
+
+            JsonEvent::EndObject => match self.stack.pop() {
+                Some(Frame::SortedObject(mut entries)) => {
+                    entries.sort_by(|a, b| a.0.cmp(&b.0));
+                    let mut buf = vec![b'{'];
+                    for (i, (key, value)) in entries.iter().enumerate() {
+                        if i > 0 {
+                            buf.push(b',');
+                        }
+                        write_string(&mut buf, key);
+                        buf.push(b':');
+                        buf.extend_from_slice(value);
+                    }
+                    buf.push(b'}');
+                    self.write(&buf)?;
+                }
+                _ => self.write(b"}")?,
+            },
+
+            JsonEvent::StartArray => {
+                self.begin_value()?;
+                self.write(b"[")?;
+                self.stack.push(Frame::Array(true));
+            }
+
+            JsonEvent::EndArray => {
+                self.stack.pop();
+                self.write(b"]")?;
+            }
+
+            JsonEvent::FieldName => {
+                let key = parser.current_str()?;
+                match self.stack.last_mut() {
+                    Some(Frame::SortedObject(entries)) => {
+                        entries.push((key.to_string(), vec![]));
+                    }
+                    Some(Frame::Object(first)) => {
+                        let mut buf = vec![];
+                        if !*first {
+                            buf.push(b',');
+                        }
+                        *first = false;
+                        write_string(&mut buf, key);
+                        buf.push(b':');
+                        self.write(&buf)?;
+                    }
+                    _ => {}
+                }
+            }
+
+            JsonEvent::ValueString => {
+                self.begin_value()?;
+                let mut buf = vec![];
+                write_string(&mut buf, parser.current_str()?);
+                self.write(&buf)?;
+            }
+
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                self.begin_value()?;
+                self.write(parser.current_raw_number()?.as_bytes())?;
+            }
+
+            JsonEvent::ValueTrue => {
+                self.begin_value()?;
+                self.write(b"true")?;
+            }
+
+            JsonEvent::ValueFalse => {
+                self.begin_value()?;
+                self.write(b"false")?;
+            }
+
+            JsonEvent::ValueNull | JsonEvent::TruncatedValue | JsonEvent::Scalar => {
+                self.begin_value()?;
+                self.write(b"null")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the output this writer writes to
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Write a comma if a value is about to be written into an array that
+    /// already contains an element
+    fn begin_value(&mut self) -> Result<(), WriterError> {
+        if let Some(Frame::Array(first)) = self.stack.last_mut() {
+            if !*first {
+                self.write(b",")?;
+            } else {
+                *first = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the given bytes either to the value of the current entry of the
+    /// innermost sorted object or, if there is none, to the output
+    fn write(&mut self, buf: &[u8]) -> Result<(), WriterError> {
+        let sorted = self.stack.iter_mut().rev().find_map(|f| match f {
+            Frame::SortedObject(entries) => entries.last_mut(),
+            _ => None,
+        });
+        match sorted {
+            Some((_, value)) => value.extend_from_slice(buf),
+            None => self.out.write_all(buf)?,
+        }
+        Ok(())
+    }
+}
+
+/// Write the given string as a quoted and escaped JSON string
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    buf.push(b'"');
+    for &b in s.as_bytes() {
+        match b {
+            b'"' => buf.extend_from_slice(b"\\\""),
+            b'\\' => buf.extend_from_slice(b"\\\\"),
+            b'\n' => buf.extend_from_slice(b"\\n"),
+            b'\r' => buf.extend_from_slice(b"\\r"),
+            b'\t' => buf.extend_from_slice(b"\\t"),
+            0x08 => buf.extend_from_slice(b"\\b"),
+            0x0C => buf.extend_from_slice(b"\\f"),
+            0..=0x1F => buf.extend_from_slice(format!("\\u{:04x}", b).as_bytes()),
+            _ => buf.push(b),
+        }
+    }
+    buf.push(b'"');
+}
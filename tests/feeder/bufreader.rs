@@ -3,7 +3,8 @@ use std::fs::File;
 use std::io::{BufReader, Read};
 
 use crate::prettyprinter::PrettyPrinter;
-use actson::feeder::{BufReaderJsonFeeder, JsonFeeder};
+use actson::feeder::{BufReaderJsonFeeder, DynReadJsonFeeder, Encoding, FeederError, JsonFeeder};
+use actson::parser::{CollectKeysError, FoldError, NextEventError, ParserError, Scalar};
 use actson::{JsonEvent, JsonParser};
 
 /// Test if [`BufReaderJsonFeeder`] can fully consume a file
@@ -78,3 +79,297 @@ fn parse_from_file() {
     let am: Value = serde_json::from_str(actual).unwrap();
     assert_eq!(em, am);
 }
+
+/// Test that a parser can be created from a file path
+#[test]
+fn from_path() {
+    let mut parser = JsonParser::from_path("tests/fixtures/pass1.txt").unwrap();
+    let mut prettyprinter = PrettyPrinter::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf().unwrap();
+        }
+        prettyprinter.on_event(e, &parser).unwrap();
+    }
+    let expected: Value =
+        serde_json::from_str(&std::fs::read_to_string("tests/fixtures/pass1.txt").unwrap())
+            .unwrap();
+    let actual: Value = serde_json::from_str(prettyprinter.get_result()).unwrap();
+    assert_eq!(expected, actual);
+
+    assert!(JsonParser::from_path("tests/fixtures/does-not-exist.txt").is_err());
+}
+
+/// Test that [`BufReaderJsonFeeder`] refuses to read more than the configured
+/// maximum number of bytes
+#[test]
+fn max_bytes() {
+    let len = std::fs::metadata("tests/fixtures/pass1.txt").unwrap().len() as usize;
+
+    // the whole file fits
+    let file = File::open("tests/fixtures/pass1.txt").unwrap();
+    let reader = BufReader::with_capacity(32, file);
+    let feeder = BufReaderJsonFeeder::new_with_max_bytes(reader, len);
+    let mut parser = JsonParser::new(feeder);
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf().unwrap();
+        }
+    }
+
+    // the file is one byte too large
+    let file = File::open("tests/fixtures/pass1.txt").unwrap();
+    let reader = BufReader::with_capacity(32, file);
+    let feeder = BufReaderJsonFeeder::new_with_max_bytes(reader, len - 1);
+    let mut parser = JsonParser::new(feeder);
+    loop {
+        let e = parser.next_event().unwrap();
+        if e == Some(JsonEvent::NeedMoreInput) {
+            if let Err(err) = parser.feeder.fill_buf() {
+                assert!(matches!(err, FeederError::InputTooLarge(n) if n == len - 1));
+                break;
+            }
+        }
+        assert!(e.is_some(), "input should have been too large");
+    }
+}
+
+/// Test that [`BufReaderJsonFeeder::into_remainder()`] returns the bytes that
+/// have not been consumed by the parser
+#[test]
+fn into_remainder() {
+    let input = br#"{"a": [1, 2]}NEXT FRAME"#;
+    let reader = BufReader::with_capacity(8, &input[..]);
+    let feeder = BufReaderJsonFeeder::new(reader);
+    let mut parser = JsonParser::new(feeder);
+    loop {
+        match parser.next_event().unwrap() {
+            Some(JsonEvent::NeedMoreInput) => parser.feeder.fill_buf().unwrap(),
+            Some(JsonEvent::EndObject) => break,
+            _ => {}
+        }
+    }
+
+    let (mut reader, mut rest) = parser.feeder.into_remainder();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"NEXT FRAME");
+}
+
+/// Test that detecting a wrong encoding does not consume input, so
+/// [`BufReaderJsonFeeder::into_remainder()`] returns everything after the
+/// byte that caused the error
+#[test]
+fn into_remainder_after_wrong_encoding() {
+    let input = b"[\x001\x00]\x00";
+    let reader = BufReader::with_capacity(16, &input[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    parser.feeder.fill_buf().unwrap();
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::WrongEncoding(Encoding::Utf16Le))
+    ));
+    assert_eq!(parser.parsed_bytes(), 2);
+
+    let (mut reader, mut rest) = parser.feeder.into_remainder();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"1\x00]\x00");
+}
+
+/// Test that [`JsonParser::fold_all_numbers()`] reads the whole input
+#[test]
+fn fold_all_numbers() {
+    let json = br#"{"a": [1, 2, {"b": 3.5}], "c": "4", "d": -0.5, "e": 9007199254740993}"#;
+    let reader = BufReader::with_capacity(4, &json[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    let (ints, sum) = parser
+        .fold_all_numbers((0, 0.0), |(ints, sum), n| match n {
+            Scalar::Int(i) => (ints + i, sum),
+            Scalar::Float(f) => (ints, sum + f),
+            _ => (ints, sum),
+        })
+        .unwrap();
+    assert_eq!(ints, 9007199254740996);
+    assert_eq!(sum, 3.0);
+}
+
+/// Test that [`JsonParser::fold_all_numbers()`] reports syntax errors
+#[test]
+fn fold_all_numbers_error() {
+    let json = br#"[1, 2,]"#;
+    let reader = BufReader::with_capacity(4, &json[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    assert!(matches!(
+        parser.fold_all_numbers(0, |acc, _| acc + 1),
+        Err(FoldError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Test that [`JsonParser::next_event_blocking()`] never returns
+/// [`JsonEvent::NeedMoreInput`]
+#[test]
+fn next_event_blocking() {
+    let json = br#"{"a": [1, 2, {"b": 3.5}], "c": "4"}"#;
+    let reader = BufReader::with_capacity(3, &json[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    let mut events = Vec::new();
+    while let Some(event) = parser.next_event_blocking().unwrap() {
+        events.push(event);
+    }
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::ValueFloat,
+            JsonEvent::EndObject,
+            JsonEvent::EndArray,
+            JsonEvent::FieldName,
+            JsonEvent::ValueString,
+            JsonEvent::EndObject,
+        ]
+    );
+
+    let reader = BufReader::with_capacity(3, &b"[1, "[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    assert_eq!(
+        parser.next_event_blocking().unwrap(),
+        Some(JsonEvent::StartArray)
+    );
+    assert_eq!(
+        parser.next_event_blocking().unwrap(),
+        Some(JsonEvent::ValueInt)
+    );
+    assert!(matches!(
+        parser.next_event_blocking(),
+        Err(NextEventError::Parse(ParserError::NoMoreInput))
+    ));
+}
+
+/// Test that the parser reports its progress if the total length of the
+/// input is known
+#[test]
+fn progress() {
+    let mut parser = JsonParser::from_path("tests/fixtures/pass1.txt").unwrap();
+    let mut last = 0.0;
+    assert_eq!(parser.progress(), Some(0.0));
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf().unwrap();
+        }
+        let progress = parser.progress().unwrap();
+        assert!(progress >= last);
+        last = progress;
+    }
+    assert_eq!(parser.progress(), Some(1.0));
+
+    // the length of a reader is unknown unless it is specified
+    let json = b"[1, 2, 3]";
+    let reader = BufReader::new(&json[..]);
+    let parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    assert_eq!(parser.progress(), None);
+
+    let reader = BufReader::with_capacity(2, &json[..]);
+    let feeder = BufReaderJsonFeeder::new(reader).with_total_len(json.len());
+    let mut parser = JsonParser::new(feeder);
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf().unwrap();
+        }
+    }
+    assert_eq!(parser.feeder.total_len(), Some(9));
+    assert_eq!(parser.progress(), Some(1.0));
+}
+
+/// Test that feeders for different reader types can be stored under the
+/// same type and that read errors are reported like with generic feeders
+#[test]
+fn dyn_read() {
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("failed"))
+        }
+    }
+
+    let mut feeders: Vec<DynReadJsonFeeder> = vec![
+        DynReadJsonFeeder::new_dyn(File::open("tests/fixtures/pass1.txt").unwrap()),
+        DynReadJsonFeeder::new_dyn(&b"[1, 2]"[..]),
+        DynReadJsonFeeder::new_dyn(FailingReader),
+    ];
+
+    let mut parser = JsonParser::new(feeders.remove(0));
+    let mut count = 0;
+    while parser.next_event_blocking().unwrap().is_some() {
+        count += 1;
+    }
+    assert!(count > 0);
+
+    let mut parser = JsonParser::new(feeders.remove(0));
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event_blocking().unwrap() {
+        events.push(e);
+    }
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray
+        ]
+    );
+
+    let mut parser = JsonParser::new(feeders.remove(0));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::NeedMoreInput));
+    assert!(matches!(parser.feeder.fill_buf(), Err(FeederError::Io(_))));
+}
+
+/// Test that [`JsonParser::collect_keys()`] reads from the reader until the
+/// end of the object
+#[test]
+fn collect_keys() {
+    let json = br#"{"name": "Elvis", "albums": [{"title": "Elvis Is Back!"}], "born": 1935} 1"#;
+    let reader = BufReader::with_capacity(3, &json[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    assert_eq!(
+        parser.next_event_blocking().unwrap(),
+        Some(JsonEvent::StartObject)
+    );
+    assert_eq!(parser.collect_keys().unwrap(), ["name", "albums", "born"]);
+    assert!(matches!(
+        parser.next_event_blocking(),
+        Err(NextEventError::Parse(ParserError::TrailingData(73)))
+    ));
+
+    let reader = BufReader::with_capacity(3, &br#"{"a": 1, "b"#[..]);
+    let mut parser = JsonParser::new(BufReaderJsonFeeder::new(reader));
+    parser.next_event_blocking().unwrap();
+    assert!(matches!(
+        parser.collect_keys(),
+        Err(CollectKeysError::Parse(ParserError::NoMoreInput))
+    ));
+}
+
+/// Test that [`BufReaderJsonFeeder::peek()`] only returns what is left in
+/// the current buffer
+#[test]
+fn peek() {
+    let reader = BufReader::with_capacity(4, &b"[1, 2]"[..]);
+    let mut feeder = BufReaderJsonFeeder::new(reader);
+    assert_eq!(feeder.peek(), b"");
+    feeder.fill_buf().unwrap();
+    assert_eq!(feeder.peek(), b"[1, ");
+    assert_eq!(feeder.next_input(), Some(b'['));
+    assert_eq!(feeder.peek(), b"1, ");
+    while feeder.next_input().is_some() {}
+    assert_eq!(feeder.peek(), b"");
+    feeder.fill_buf().unwrap();
+    assert_eq!(feeder.peek(), b"2]");
+}
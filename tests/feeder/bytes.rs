@@ -0,0 +1,47 @@
+use ::bytes::{Bytes, BytesMut};
+
+use actson::feeder::{BytesJsonFeeder, JsonFeeder};
+use actson::{JsonEvent, JsonParser};
+
+/// Test that chunks are consumed in order and that tokens can straddle
+/// chunk boundaries
+#[test]
+fn chunks() {
+    let json = r#"{"name": "Elvis", "born": 1935, "alive": false}"#;
+    let mut feeder = BytesJsonFeeder::new();
+    for chunk in json.as_bytes().chunks(3) {
+        feeder.push(Bytes::copy_from_slice(chunk));
+    }
+    feeder.push(Bytes::new());
+    feeder.push(BytesMut::from(&b" "[..]));
+    assert_eq!(feeder.buffered(), json.len() + 1);
+    feeder.done();
+
+    let mut parser = JsonParser::new(feeder);
+    let mut strings = vec![];
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::FieldName | JsonEvent::ValueString => {
+                strings.push(parser.current_str().unwrap().to_string())
+            }
+            JsonEvent::ValueInt => assert_eq!(parser.current_int::<i32>().unwrap(), 1935),
+            _ => {}
+        }
+    }
+    assert_eq!(strings, vec!["name", "Elvis", "born", "alive"]);
+    assert!(parser.feeder.is_done());
+}
+
+/// Test that the feeder is not done before all chunks have been consumed
+#[test]
+fn done() {
+    let mut feeder = BytesJsonFeeder::new();
+    assert!(!feeder.has_input());
+    feeder.push(Bytes::from_static(b"ab"));
+    feeder.done();
+    assert!(!feeder.is_done());
+    assert_eq!(feeder.next_input(), Some(b'a'));
+    assert_eq!(feeder.next_input(), Some(b'b'));
+    assert_eq!(feeder.next_input(), None);
+    assert!(feeder.is_done());
+}
@@ -0,0 +1,96 @@
+use actson::feeder::{FeederError, LengthPrefixedJsonFeeder};
+use actson::parser::ParserError;
+use actson::{JsonEvent, JsonParser};
+
+/// Prefix each JSON text with its length as a 32-bit big-endian integer
+fn frames(jsons: &[&[u8]]) -> Vec<u8> {
+    let mut r = Vec::new();
+    for json in jsons {
+        r.extend((json.len() as u32).to_be_bytes());
+        r.extend(*json);
+    }
+    r
+}
+
+/// Parse the current frame and return the events or the parser error
+fn parse_frame(
+    feeder: &mut LengthPrefixedJsonFeeder<&[u8]>,
+) -> Result<Result<Vec<JsonEvent>, ParserError>, FeederError> {
+    let mut parser = JsonParser::new(feeder);
+    let mut events = vec![];
+    loop {
+        match parser.next_event() {
+            Ok(Some(JsonEvent::NeedMoreInput)) => parser.feeder.fill_buf()?,
+            Ok(Some(e)) => events.push(e),
+            Ok(None) => return Ok(Ok(events)),
+            Err(e) => return Ok(Err(e)),
+        }
+    }
+}
+
+/// Test that multiple frames can be parsed one after the other
+#[test]
+fn multiple_frames() {
+    let input = frames(&[b"[1,2]", b"  42 ", b"{\"a\":\"bcdefghij\"}"]);
+    let mut feeder = LengthPrefixedJsonFeeder::with_capacity(3, input.as_slice());
+
+    assert!(feeder.next_frame().unwrap());
+    assert_eq!(parse_frame(&mut feeder).unwrap().unwrap().len(), 4);
+    assert!(feeder.next_frame().unwrap());
+    assert_eq!(
+        parse_frame(&mut feeder).unwrap().unwrap(),
+        vec![JsonEvent::ValueInt]
+    );
+    assert!(feeder.next_frame().unwrap());
+    assert_eq!(parse_frame(&mut feeder).unwrap().unwrap().len(), 4);
+    assert!(!feeder.next_frame().unwrap());
+}
+
+/// Test that a frame's JSON text cannot continue in the next frame
+#[test]
+fn no_bleeding() {
+    let input = frames(&[b"[1,", b"2]"]);
+    let mut feeder = LengthPrefixedJsonFeeder::new(input.as_slice());
+    assert!(feeder.next_frame().unwrap());
+    assert!(matches!(
+        parse_frame(&mut feeder).unwrap(),
+        Err(ParserError::NoMoreInput)
+    ));
+}
+
+/// Test that unparsed bytes of a frame are skipped
+#[test]
+fn skip_rest_of_frame() {
+    let input = frames(&[b"[1, 2, 3, 4, 5, 6, 7]", b"true"]);
+    let mut feeder = LengthPrefixedJsonFeeder::with_capacity(4, input.as_slice());
+    assert!(feeder.next_frame().unwrap());
+    let mut parser = JsonParser::new(&mut feeder);
+    parser.feeder.fill_buf().unwrap();
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+
+    assert!(feeder.next_frame().unwrap());
+    assert_eq!(
+        parse_frame(&mut feeder).unwrap().unwrap(),
+        vec![JsonEvent::ValueTrue]
+    );
+}
+
+/// Test that truncated frames and length prefixes are reported
+#[test]
+fn truncated() {
+    let mut input = frames(&[b"[1, 2]"]);
+    input.pop();
+    let mut feeder = LengthPrefixedJsonFeeder::new(input.as_slice());
+    assert!(feeder.next_frame().unwrap());
+    assert!(matches!(
+        parse_frame(&mut feeder),
+        Err(FeederError::TruncatedFrame)
+    ));
+
+    let input = [0u8, 0, 1];
+    let mut feeder = LengthPrefixedJsonFeeder::new(input.as_slice());
+    assert!(matches!(
+        feeder.next_frame(),
+        Err(FeederError::TruncatedFrame)
+    ));
+}
@@ -1 +1,6 @@
 mod bufreader;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod lengthprefixed;
+mod transcode;
+mod vecdeque;
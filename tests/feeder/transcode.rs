@@ -0,0 +1,130 @@
+use actson::feeder::{
+    Encoding, PushJsonFeeder, SliceJsonFeeder, TranscodeError, TranscodingJsonFeeder,
+};
+use actson::parser::ParserError;
+use actson::{JsonEvent, JsonParser};
+
+const JSON: &str = r#"{"name": "Elvis 🎸", "city": "Memphis ä"}"#;
+
+/// Encode the given string in UTF-16, optionally with a byte order mark
+fn utf16(s: &str, le: bool, bom: bool) -> Vec<u8> {
+    let mut r = vec![];
+    for u in bom.then_some(0xFEFF).into_iter().chain(s.encode_utf16()) {
+        r.extend(if le { u.to_le_bytes() } else { u.to_be_bytes() });
+    }
+    r
+}
+
+/// Encode the given string in UTF-32, optionally with a byte order mark
+fn utf32(s: &str, le: bool, bom: bool) -> Vec<u8> {
+    let mut r = vec![];
+    for c in bom.then_some('\u{FEFF}').into_iter().chain(s.chars()) {
+        let u = c as u32;
+        r.extend(if le { u.to_le_bytes() } else { u.to_be_bytes() });
+    }
+    r
+}
+
+/// Parse the given bytes byte by byte and return all strings
+fn parse(bytes: &[u8], encoding: Encoding) -> (Result<Vec<String>, ()>, Option<TranscodeError>) {
+    let feeder = TranscodingJsonFeeder::new(PushJsonFeeder::new(), encoding);
+    let mut parser = JsonParser::new(feeder);
+    let mut i = 0;
+    let mut strings = vec![];
+    loop {
+        match parser.next_event() {
+            Ok(Some(JsonEvent::NeedMoreInput)) => {
+                if i < bytes.len() {
+                    parser.feeder.inner.push_bytes(&bytes[i..i + 1]);
+                    i += 1;
+                } else {
+                    parser.feeder.inner.done();
+                }
+            }
+            Ok(Some(JsonEvent::FieldName | JsonEvent::ValueString)) => {
+                strings.push(parser.current_str().unwrap().to_string())
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => return (Ok(strings), parser.feeder.error()),
+            Err(e) => {
+                assert!(matches!(e, ParserError::Feeder(_)));
+                return (Err(()), parser.feeder.error());
+            }
+        }
+    }
+}
+
+/// Test that UTF-16 and UTF-32 input is transcoded with and without byte
+/// order marks
+#[test]
+fn utf16_and_utf32() {
+    let expected = ["name", "Elvis 🎸", "city", "Memphis ä"].map(String::from);
+    for (bytes, encoding) in [
+        (utf16(JSON, true, true), Encoding::Utf16),
+        (utf16(JSON, false, true), Encoding::Utf16),
+        (utf16(JSON, false, false), Encoding::Utf16),
+        (utf16(JSON, true, false), Encoding::Utf16Le),
+        (utf16(JSON, true, true), Encoding::Utf16Le),
+        (utf16(JSON, false, false), Encoding::Utf16Be),
+        (utf32(JSON, true, true), Encoding::Utf32),
+        (utf32(JSON, false, true), Encoding::Utf32),
+        (utf32(JSON, false, false), Encoding::Utf32),
+        (utf32(JSON, true, false), Encoding::Utf32Le),
+        (utf32(JSON, false, true), Encoding::Utf32Be),
+    ] {
+        assert_eq!(parse(&bytes, encoding), (Ok(expected.to_vec()), None));
+    }
+}
+
+/// Test that unpaired UTF-16 surrogates are reported
+#[test]
+fn unpaired_surrogate() {
+    let mut bytes = utf16("\"a", true, false);
+    bytes.extend([0x3D, 0xD8, b'b', 0, b'"', 0]);
+    assert_eq!(
+        parse(&bytes, Encoding::Utf16Le),
+        (Err(()), Some(TranscodeError::UnpairedSurrogate(0xD83D)))
+    );
+
+    let mut bytes = utf16("\"a", false, false);
+    bytes.extend([0xDC, 0x00, 0, b'"']);
+    assert_eq!(
+        parse(&bytes, Encoding::Utf16Be),
+        (Err(()), Some(TranscodeError::UnpairedSurrogate(0xDC00)))
+    );
+}
+
+/// Test that UTF-32 code points outside of the Unicode range are reported
+#[test]
+fn invalid_code_point() {
+    let mut bytes = utf32("\"", false, false);
+    bytes.extend([0, 0x11, 0, 0]);
+    assert_eq!(
+        parse(&bytes, Encoding::Utf32),
+        (Err(()), Some(TranscodeError::InvalidCodePoint(0x110000)))
+    );
+}
+
+/// Test that input that ends in the middle of a code unit is reported
+#[test]
+fn truncated() {
+    let mut bytes = utf16("42", true, false);
+    bytes.push(b' ');
+    assert_eq!(
+        parse(&bytes, Encoding::Utf16Le),
+        (Err(()), Some(TranscodeError::Truncated))
+    );
+}
+
+/// Test that [`TranscodingJsonFeeder`] can wrap a [`SliceJsonFeeder`]
+#[test]
+fn slice() {
+    let bytes = utf16("[1, 2]", false, true);
+    let feeder = TranscodingJsonFeeder::new(SliceJsonFeeder::new(&bytes), Encoding::Utf16);
+    let mut parser = JsonParser::new(feeder);
+    let mut events = vec![];
+    while let Some(e) = parser.next_event().unwrap() {
+        events.push(e);
+    }
+    assert_eq!(events.len(), 4);
+}
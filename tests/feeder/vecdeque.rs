@@ -0,0 +1,52 @@
+use std::collections::VecDeque;
+
+use actson::feeder::{JsonFeeder, VecDequeJsonFeeder};
+use actson::{JsonEvent, JsonParser};
+
+/// Test that bytes are consumed from the front of the queue
+#[test]
+fn drain() {
+    let mut feeder = VecDequeJsonFeeder::from(VecDeque::from(b"ab".to_vec()));
+    assert!(feeder.has_input());
+    feeder.push_bytes(b"c");
+    let mut other = VecDeque::from(b"de".to_vec());
+    feeder.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(feeder.buffered(), 5);
+
+    assert_eq!(feeder.next_input(), Some(b'a'));
+    assert_eq!(*feeder.queue(), b"bcde");
+    feeder.queue_mut().push_back(b'f');
+    assert_eq!(feeder.buffered(), 5);
+    assert!(!feeder.is_done());
+
+    for b in *b"bcdef" {
+        assert_eq!(feeder.next_input(), Some(b));
+    }
+    assert_eq!(feeder.next_input(), None);
+    assert!(!feeder.has_input());
+    assert!(!feeder.is_done());
+    feeder.done();
+    assert!(feeder.is_done());
+    assert!(feeder.into_inner().is_empty());
+}
+
+/// Test that a JSON text can be parsed while the queue is filled
+#[test]
+fn parse() {
+    let json = br#"{"a": [1, 2.5, "xyz"], "b": null}"#;
+    let mut parser = JsonParser::new(VecDequeJsonFeeder::new());
+    let mut chunks = json.chunks(3);
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => match chunks.next() {
+                Some(c) => parser.feeder.push_bytes(c),
+                None => parser.feeder.done(),
+            },
+            e => events.push(e),
+        }
+    }
+    assert_eq!(events.len(), 10);
+    assert_eq!(parser.feeder.buffered(), 0);
+}
@@ -175,7 +175,9 @@ impl PrettyPrinter {
             JsonEvent::ValueFloat => self.on_value_float(parser.current_float()?),
             JsonEvent::ValueTrue => self.on_value_bool(true),
             JsonEvent::ValueFalse => self.on_value_bool(false),
-            JsonEvent::ValueNull => self.on_value_null(),
+            JsonEvent::ValueNull | JsonEvent::TruncatedValue => self.on_value_null(),
+            // other values (e.g. JsonEvent::Scalar) are printed as `null`
+            _ => self.on_value_null(),
         }
         Ok(())
     }
@@ -2,12 +2,36 @@ mod feeder;
 mod prettyprinter;
 mod tokio;
 
+use std::collections::HashMap;
 use std::fs;
+use std::hash::DefaultHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use actson::feeder::PushJsonFeeder;
-use actson::options::JsonParserOptionsBuilder;
-use actson::parser::ParserError;
-use actson::{JsonEvent, JsonParser};
+use actson::base64::{Base64Alphabet, Base64Error};
+use actson::batch::EventBatch;
+use actson::cursor::{CursorError, CursorValue, JsonCursor};
+use actson::feeder::{Encoding, FeederError, JsonFeeder, PushJsonFeeder, SliceJsonFeeder};
+use actson::helpers::{
+    canonical_hash, check_top_level_types, flatten, parse_vec_i64, parse_vec_string, to_csv,
+    to_csv_with_options, unique_scalars, CanonicalHashError, CsvError, CsvOptions, DepthFilter,
+    EventTrace, FlattenError, MapperError, OwnedScalar, ParseVecError, RecordError, RecordMapper,
+    RecordedEvent, TypeCheckError, TypeMismatch, UniqueScalarsError,
+};
+use actson::options::{
+    CanonicalNumberRules, JsonParserOptions, JsonParserOptionsBuilder, NumberDecision,
+    DEFAULT_MAX_DEPTH,
+};
+use actson::parser::{
+    CollectKeysError, ContainerFrame, DepthLimit, ErrorCategory, InvalidBoolValueError,
+    InvalidScalarValueError, ParserError, RecoveredError, Scalar, StringLimit,
+};
+use actson::path::PathSegment;
+use actson::pool::BufferPool;
+use actson::validator::{SimpleValidator, ValidationError, Validator, ValueType};
+use actson::writer::JsonWriter;
+use actson::{JsonEvent, JsonEventKind, JsonParser};
 use prettyprinter::PrettyPrinter;
 use serde_json::Value;
 
@@ -125,21 +149,117 @@ fn test_utf16_in_array() {
 
 #[test]
 fn test_fail() {
-    let feeder = PushJsonFeeder::new();
-    let mut parser = JsonParser::new_with_options(
-        feeder,
-        JsonParserOptionsBuilder::default()
-            .with_max_depth(16)
-            .build(),
-    );
     for i in 2..=34 {
         let json = fs::read_to_string(format!("tests/fixtures/fail{}.txt", i)).unwrap();
 
+        // errors are terminal, so we need a new parser for each file
+        let mut parser = JsonParser::new_with_options(
+            PushJsonFeeder::new(),
+            JsonParserOptionsBuilder::default()
+                .with_max_depth(16)
+                .build(),
+        );
+
         // ignore return value - we accept any error
         parse_fail_with_parser(json.as_bytes(), &mut parser);
     }
 }
 
+/// Test that the parser stays in a terminal state after an error
+#[test]
+fn terminal_error() {
+    let json = b"[1, }, 2]";
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    assert!(parser.last_error().is_none());
+
+    let err = parse_fail_with_parser(json, &mut parser);
+    assert!(matches!(err, ParserError::SyntaxError));
+    assert!(matches!(
+        parser.last_error(),
+        Some(ParserError::SyntaxError)
+    ));
+    let parsed_bytes = parser.parsed_bytes();
+
+    // further calls return the same error and do not consume more input
+    for _ in 0..2 {
+        assert!(matches!(parser.next_event(), Err(ParserError::SyntaxError)));
+    }
+    assert_eq!(parser.parsed_bytes(), parsed_bytes);
+    assert!(matches!(
+        parser.last_error(),
+        Some(ParserError::SyntaxError)
+    ));
+}
+
+/// Test that the parser signals the end of the JSON text after an error if
+/// `with_end_after_error()` is enabled
+#[test]
+fn end_after_error() {
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_end_after_error(true)
+            .build(),
+    );
+    let err = parse_fail_with_parser(b"[1, }, 2]", &mut parser);
+    assert!(matches!(err, ParserError::SyntaxError));
+    let parsed_bytes = parser.parsed_bytes();
+
+    for _ in 0..2 {
+        assert_eq!(parser.next_event().unwrap(), None);
+    }
+    assert_eq!(parser.parsed_bytes(), parsed_bytes);
+    assert!(matches!(
+        parser.last_error(),
+        Some(ParserError::SyntaxError)
+    ));
+    assert_eq!(parser.event_count(), 2);
+}
+
+/// Test that parsing is aborted if the deadline has passed
+#[test]
+fn deadline() {
+    let json = " ".repeat(100_000) + "[1, 2]";
+
+    let options = JsonParserOptionsBuilder::default()
+        .with_deadline(Instant::now() + Duration::from_secs(3600))
+        .build();
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json.as_bytes()), options);
+    while parser.next_event().unwrap().is_some() {}
+
+    // an expired deadline is detected at the first byte
+    let options = JsonParserOptionsBuilder::default()
+        .with_deadline(Instant::now() - Duration::from_secs(1))
+        .build();
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json.as_bytes()), options);
+    let err = parser.next_event().unwrap_err();
+    assert!(matches!(err, ParserError::Timeout));
+    assert_eq!(err.category(), ErrorCategory::Limit);
+    assert_eq!(parser.parsed_bytes(), 1);
+
+    // a deadline that passes while parsing is detected after at most 4096
+    // more bytes
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let options = JsonParserOptionsBuilder::default()
+        .with_deadline(deadline)
+        .build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+    parser.feeder.push_bytes(b"[");
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    std::thread::sleep(
+        deadline.saturating_duration_since(Instant::now()) + Duration::from_millis(10),
+    );
+    let r = loop {
+        parser.feeder.push_bytes(b"1, ");
+        match parser.next_event() {
+            Ok(Some(_)) => {}
+            r => break r,
+        }
+    };
+    assert!(matches!(r, Err(ParserError::Timeout)));
+    assert!(parser.parsed_bytes() <= 4097);
+}
+
 /// Test that an empty object is parsed correctly
 #[test]
 fn empty_object() {
@@ -196,7 +316,102 @@ fn illegal_character() {
     let json = "{\"key\":\x02}";
     assert!(matches!(
         parse_fail(json.as_bytes()),
-        ParserError::IllegalInput(0x02)
+        ParserError::IllegalCharacter {
+            byte: 0x02,
+            offset: 7
+        }
+    ));
+
+    // between tokens
+    let err = parse_fail(b"[1,\x00 2]");
+    assert!(matches!(
+        err,
+        ParserError::IllegalCharacter { byte: 0, offset: 3 }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "JSON text contains an illegal byte `0x00' at byte 3"
+    );
+
+    // inside a string
+    assert!(matches!(
+        parse_fail(b"[\"ab\x1f\"]"),
+        ParserError::IllegalCharacter {
+            byte: 0x1f,
+            offset: 4
+        }
+    ));
+}
+
+/// Test that UTF-16 and UTF-32 input is detected at the beginning of the text
+#[test]
+fn wrong_encoding() {
+    let utf16le = |s: &str| {
+        s.encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>()
+    };
+    let utf16be = |s: &str| {
+        s.encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>()
+    };
+    let utf32le = |s: &str| {
+        s.chars()
+            .flat_map(|c| (c as u32).to_le_bytes())
+            .collect::<Vec<_>>()
+    };
+    let utf32be = |s: &str| {
+        s.chars()
+            .flat_map(|c| (c as u32).to_be_bytes())
+            .collect::<Vec<_>>()
+    };
+
+    for (json, expected) in [
+        (utf16le(r#"{"a": 1}"#), Encoding::Utf16Le),
+        (utf16le("\u{feff}[1]"), Encoding::Utf16Le),
+        (utf16be(r#"{"a": 1}"#), Encoding::Utf16Be),
+        (utf16be("\u{feff}[1]"), Encoding::Utf16Be),
+        (utf16be(" 1"), Encoding::Utf16Be),
+        (utf32le("[true]"), Encoding::Utf32Le),
+        (utf32le("\u{feff}[1]"), Encoding::Utf32Le),
+        (utf32be("[true]"), Encoding::Utf32Be),
+        (utf32be("\u{feff}[1]"), Encoding::Utf32Be),
+    ] {
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(&json));
+        let err = loop {
+            if let Err(e) = parser.next_event() {
+                break e;
+            }
+        };
+        assert!(
+            matches!(err, ParserError::WrongEncoding(e) if e == expected),
+            "{json:?}: {err}"
+        );
+    }
+
+    // the detection does not consume any input
+    let json = utf16le(r#"{"a": 1}"#);
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(&json));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::WrongEncoding(Encoding::Utf16Le))
+    ));
+    assert_eq!(parser.parsed_bytes(), 2);
+    assert_eq!(parser.remaining_input(), &json[2..]);
+    assert_eq!(parser.feeder.peek(), &json[2..]);
+
+    // illegal bytes later in the text are not affected
+    assert!(matches!(
+        parse_fail(b"[1, \x00]\x00"),
+        ParserError::IllegalCharacter { byte: 0, offset: 4 }
+    ));
+
+    // a single NUL byte is not enough
+    assert!(matches!(
+        parse_fail(b"1\x00"),
+        ParserError::IllegalCharacter { byte: 0, offset: 1 }
     ));
 }
 
@@ -411,6 +626,62 @@ fn streaming_numbers() {
     assert_eq!("12345", r);
 }
 
+/// Test that a leading zero followed by a digit is a malformed number and
+/// that it is not split into two numbers in streaming mode
+#[test]
+fn leading_zeros() {
+    for (json, offset) in [
+        ("01", 1),
+        ("00", 1),
+        ("-01", 2),
+        ("[1, 012]", 5),
+        (r#"{"a": 00.5}"#, 7),
+        ("[0e1, 01]", 7),
+    ] {
+        assert!(matches!(
+            parse_fail(json.as_bytes()),
+            ParserError::MalformedNumber(o) if o == offset
+        ));
+
+        let options = JsonParserOptionsBuilder::default()
+            .with_streaming(true)
+            .build();
+        assert!(matches!(
+            parse_events_with_options(json.as_bytes(), options),
+            Err(ParserError::MalformedNumber(o)) if o == offset
+        ));
+    }
+
+    // other invalid characters after a zero are still syntax errors
+    assert!(matches!(parse_fail(b"0x1"), ParserError::SyntaxError));
+    assert!(matches!(parse_fail(b"[0x1]"), ParserError::SyntaxError));
+
+    // numbers with a zero are still valid
+    assert!(actson::validate_str("[0, -0, 0.01, 10, 100, 0e0, -0.0]").is_ok());
+
+    // separated by white space, a zero and another number are two values in
+    // streaming mode
+    let options = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .build();
+    assert!(matches!(
+        parse_events_with_options(b"0 1 00", options.clone()),
+        Err(ParserError::MalformedNumber(5))
+    ));
+    assert_eq!(
+        parse_events_with_options(b"0 1 0[0]", options).unwrap(),
+        vec![
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+    assert!(matches!(parse_fail(b"0 1"), ParserError::TrailingData(2)));
+}
+
 /// Test if multiple top-level strings can be parsed in streaming mode
 #[test]
 fn streaming_strings() {
@@ -466,6 +737,18 @@ fn streaming_objects() {
     );
 }
 
+/// Test that [`JsonParser::new_streaming()`] enables streaming mode
+#[test]
+fn streaming_constructor() {
+    let json = r#"{"a":1} [2] "3" 4"#;
+
+    let mut parser = JsonParser::new_streaming(PushJsonFeeder::new());
+    assert!(parser.options().streaming());
+    let r = parse_with_parser(json, &mut parser);
+
+    assert_eq!("{\n  \"a\": 1\n}[\n  2\n]\"3\"4", r);
+}
+
 /// Test if multiple top-level numbers can be parsed in streaming mode
 #[test]
 fn streaming_complex() {
@@ -491,3 +774,4295 @@ fn streaming_complex() {
         r
     );
 }
+
+/// Test that the `current_opt_*` accessors return `None` for `null` values
+/// and `Some` for actual values
+#[test]
+fn optional_values() {
+    let json = r#"{"a":null,"b":"Elvis","c":null,"d":42,"e":null,"f":1.5}"#;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(json.as_bytes());
+    parser.feeder.done();
+
+    let mut strs = Vec::new();
+    let mut ints = Vec::new();
+    let mut floats = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::ValueString => {
+                strs.push(parser.current_opt_str().unwrap().map(String::from))
+            }
+            JsonEvent::ValueInt => ints.push(parser.current_opt_int::<i64>().unwrap()),
+            JsonEvent::ValueFloat => floats.push(parser.current_opt_float().unwrap()),
+            JsonEvent::ValueNull => {
+                assert_eq!(parser.current_opt_str().unwrap(), None);
+                assert_eq!(parser.current_opt_int::<i64>().unwrap(), None);
+                assert_eq!(parser.current_opt_float().unwrap(), None);
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(strs, vec![Some("Elvis".to_string())]);
+    assert_eq!(ints, vec![Some(42)]);
+    assert_eq!(floats, vec![Some(1.5)]);
+}
+
+/// Test that [`JsonParser::current_scalar()`] returns the value of each
+/// scalar and an error for other events
+#[test]
+fn current_scalar() {
+    let json = r#"{"s":"a\"b","i":-42,"f":1e2,"t":true,"n":null,"x":false,
+        "big":-9223372036854775809,"max":9223372036854775807}"#;
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+
+    let mut scalars = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::StartObject | JsonEvent::FieldName | JsonEvent::EndObject => {
+                assert!(matches!(
+                    parser.current_scalar(),
+                    Err(InvalidScalarValueError::NotAScalar(n)) if n == e
+                ));
+            }
+            _ => scalars.push(format!("{:?}", parser.current_scalar().unwrap())),
+        }
+    }
+
+    assert_eq!(
+        scalars,
+        vec![
+            r#"Str("a\"b")"#,
+            "Int(-42)",
+            "Float(100.0)",
+            "Bool(true)",
+            "Null",
+            "Bool(false)",
+            r#"BigInt("-9223372036854775809")"#,
+            "Int(9223372036854775807)",
+        ]
+    );
+}
+
+/// Test that [`JsonParser::current_is_key()`] is only `true` for field names
+#[test]
+fn current_is_key() {
+    let json = r#"{"a": "b", "c": ["d", {"e": 1}]}"#;
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+    assert!(!parser.current_is_key());
+
+    let mut keys = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        assert_eq!(parser.current_is_key(), e == JsonEvent::FieldName);
+        if parser.current_is_key() {
+            keys.push(parser.current_str().unwrap().to_string());
+        }
+    }
+    assert_eq!(keys, vec!["a", "c", "e"]);
+}
+
+/// Test that values and white space that are skipped in bulk in
+/// structure-only mode are still validated and counted correctly
+#[test]
+fn structure_only_bulk_skip() {
+    fn events(
+        json: &[u8],
+        structure_only: bool,
+        chunk: usize,
+    ) -> Vec<Result<(JsonEvent, usize), String>> {
+        let mut parser = JsonParser::new_with_options(
+            PushJsonFeeder::new(),
+            JsonParserOptionsBuilder::default()
+                .with_structure_only(structure_only)
+                .build(),
+        );
+        let mut i = 0;
+        let mut result = Vec::new();
+        loop {
+            match parser.next_event() {
+                Ok(Some(JsonEvent::NeedMoreInput)) => {
+                    if i < json.len() {
+                        i += parser
+                            .feeder
+                            .push_bytes(&json[i..json.len().min(i + chunk)]);
+                    } else {
+                        parser.feeder.done();
+                    }
+                }
+                Ok(Some(e)) => {
+                    let e = match e {
+                        JsonEvent::ValueString
+                        | JsonEvent::ValueInt
+                        | JsonEvent::ValueFloat
+                        | JsonEvent::ValueTrue
+                        | JsonEvent::ValueFalse
+                        | JsonEvent::ValueNull => JsonEvent::Scalar,
+                        e => e,
+                    };
+                    result.push(Ok((e, parser.parsed_bytes())));
+                }
+                Ok(None) => return result,
+                Err(e) => {
+                    result.push(Err(format!("{e:?}")));
+                    return result;
+                }
+            }
+        }
+    }
+
+    let docs: [&[u8]; 7] = [
+        b"{\"a\":   \"long string value\",\n  \"b\": [12345, -6.25e+10, true]}",
+        b"[\"x\\\"y\\u00e4z\",\t\"\"]",
+        b"[\"a\tb\"]",
+        b"[1234 5678]",
+        b"[1.5e]",
+        b"[123, 0123]",
+        b"[\"abc",
+    ];
+    for json in docs {
+        for chunk in [1, 3, 1024] {
+            assert_eq!(events(json, true, chunk), events(json, false, chunk));
+        }
+    }
+}
+
+/// Test that only structural events and field names are reported if
+/// `with_structure_only()` is enabled
+#[test]
+fn structure_only() {
+    let json = r#"{"a": "x\"y\u00e4", "b\n": [1, -2.5e3, true, false, null, "\ud83c\udfb8"],
+        "c": {"d": "long string value"}} 7 "z""#;
+    let options = JsonParserOptionsBuilder::default()
+        .with_structure_only(true)
+        .with_streaming(true)
+        .with_path_tracking(true)
+        .build();
+    let mut parser =
+        JsonParser::new_with_options(SliceJsonFeeder::new(json.as_bytes()), options.clone());
+
+    let mut events = Vec::new();
+    let mut keys = Vec::new();
+    let mut pointers = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::FieldName => keys.push(parser.current_str().unwrap().to_string()),
+            JsonEvent::Scalar => pointers.push(parser.current_pointer()),
+            _ => {}
+        }
+        events.push(e);
+    }
+
+    // the same events as without the option, only with scalars replaced
+    let mut parser = JsonParser::new_with_options(
+        SliceJsonFeeder::new(json.as_bytes()),
+        JsonParserOptionsBuilder::default()
+            .with_streaming(true)
+            .build(),
+    );
+    let mut expected = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        expected.push(match e {
+            JsonEvent::ValueString
+            | JsonEvent::ValueInt
+            | JsonEvent::ValueFloat
+            | JsonEvent::ValueTrue
+            | JsonEvent::ValueFalse
+            | JsonEvent::ValueNull => JsonEvent::Scalar,
+            e => e,
+        });
+    }
+    assert_eq!(events, expected);
+
+    assert_eq!(keys, vec!["a", "b\n", "c", "d"]);
+    assert_eq!(
+        pointers,
+        vec!["/a", "/b\n/0", "/b\n/1", "/b\n/2", "/b\n/3", "/b\n/4", "/b\n/5", "/c/d", "", ""]
+    );
+
+    // invalid input is still detected
+    let mut parser =
+        JsonParser::new_with_options(SliceJsonFeeder::new(br#"{"a": "b\x"}"#), options);
+    let r = loop {
+        match parser.next_event() {
+            Ok(Some(_)) => {}
+            r => break r,
+        }
+    };
+    assert!(matches!(r, Err(ParserError::SyntaxError)));
+}
+
+/// Test that [`JsonParser::match_field()`] finds the current field name
+#[test]
+fn match_field() {
+    let json = r#"{"a": "b", "c": {"b": 1, "a\u00e4": 2, "": 3}, "x": ["a"]}"#;
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+    let candidates = ["a", "b", "aä", ""];
+
+    let mut matches = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        let m = parser.match_field(&candidates);
+        if e == JsonEvent::FieldName {
+            matches.push(m);
+        } else {
+            assert_eq!(m, None);
+        }
+    }
+    assert_eq!(
+        matches,
+        vec![Some(0), None, Some(1), Some(2), Some(3), None]
+    );
+    assert_eq!(parser.match_field(&[]), None);
+}
+
+/// Test that [`JsonParser::current_bool()`] returns the value of booleans
+/// and an error for other events
+#[test]
+fn current_bool() {
+    let json = r#"[true, false, null, "true", 1]"#;
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+
+    let mut bools = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::ValueTrue | JsonEvent::ValueFalse => {
+                bools.push(parser.current_bool().unwrap())
+            }
+            _ => assert!(matches!(
+                parser.current_bool(),
+                Err(InvalidBoolValueError(n)) if n == e
+            )),
+        }
+    }
+
+    assert_eq!(bools, vec![true, false]);
+}
+
+/// Parse a JSON text sequence and return the generated events as well as
+/// the number of records that could not be parsed
+fn parse_json_seq(json: &[u8]) -> (Vec<JsonEvent>, usize) {
+    parse_json_seq_with_options(json, JsonParserOptionsBuilder::default())
+}
+
+/// Like [`parse_json_seq()`] but with additional options
+fn parse_json_seq_with_options(
+    json: &[u8],
+    options: JsonParserOptionsBuilder,
+) -> (Vec<JsonEvent>, usize) {
+    let options = options.with_json_seq(true).build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+
+    let mut events = Vec::new();
+    let mut errors = 0;
+    let mut i: usize = 0;
+    loop {
+        match parser.next_event() {
+            Ok(Some(JsonEvent::NeedMoreInput)) => {
+                i += parser.feeder.push_bytes(&json[i..]);
+                if i == json.len() {
+                    parser.feeder.done();
+                }
+            }
+            Ok(Some(e)) => events.push(e),
+            Ok(None) => break,
+            Err(_) => {
+                errors += 1;
+                parser.skip_to_next_record();
+            }
+        }
+    }
+
+    (events, errors)
+}
+
+/// Test if a JSON text sequence can be parsed
+#[test]
+fn json_seq() {
+    let json = b"\x1E{\"a\":1}\n\x1E[2]\n\x1E\"3\"\n\x1E4\n\x1Etrue\n";
+    let (events, errors) = parse_json_seq(json);
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::ValueInt,
+            JsonEvent::EndObject,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+            JsonEvent::ValueString,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueTrue,
+        ]
+    );
+    assert_eq!(errors, 0);
+}
+
+/// Test that truncated records in a JSON text sequence are reported and
+/// that the parser is able to continue with the next record
+#[test]
+fn json_seq_truncated() {
+    // a number directly followed by a record separator is truncated
+    let json = b"\x1E1\x1E2\n";
+    let (events, errors) = parse_json_seq(json);
+    assert_eq!(events, vec![JsonEvent::ValueInt]);
+    assert_eq!(errors, 1);
+
+    // incomplete objects
+    let json = b"\x1E{\"a\":\n\x1E[1]\n\x1E{\"b\"\x1E\"c\"\n";
+    let (events, errors) = parse_json_seq(json);
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::ValueString,
+        ]
+    );
+    assert_eq!(errors, 2);
+
+    // a malformed number does not affect the next record
+    let json = b"\x1E[1__0]\n\x1E[2]\n";
+    let (events, errors) = parse_json_seq_with_options(
+        json,
+        JsonParserOptionsBuilder::default().with_underscore_separators(true),
+    );
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+    assert_eq!(errors, 1);
+}
+
+/// Test that the offset of trailing data after the top-level value is
+/// reported
+#[test]
+fn trailing_data() {
+    assert!(matches!(
+        parse_fail(br#"{"a":1}   X"#),
+        ParserError::TrailingData(10)
+    ));
+    assert!(matches!(parse_fail(b"[1]]"), ParserError::TrailingData(3)));
+    assert!(matches!(parse_fail(b" 12,"), ParserError::TrailingData(3)));
+    assert!(matches!(
+        parse_fail(b"true\n\tnull"),
+        ParserError::TrailingData(6)
+    ));
+
+    // errors inside the value are still syntax errors
+    assert!(matches!(parse_fail(b"[1]2]"), ParserError::TrailingData(3)));
+    assert!(matches!(parse_fail(b"[1 2]"), ParserError::SyntaxError));
+    assert!(matches!(parse_fail(b"12x"), ParserError::SyntaxError));
+}
+
+/// Test that white space after the top-level value is accepted in
+/// non-streaming mode but other values are not
+#[test]
+fn trailing_whitespace() {
+    for value in [r#"{"a":1}"#, "[1]", r#""a""#, "1", "1.5", "true", "null"] {
+        for ws in ["\n", "\r\n", "  ", "\n\n\n", "\t \r\n\r\n ", " \n"] {
+            let json = format!("{}{}", value, ws);
+            assert_json_eq(value, &parse(&json));
+            assert!(actson::validate_str(&json).is_ok(), "{:?}", json);
+
+            // feed the input byte by byte
+            let mut parser = JsonParser::new(PushJsonFeeder::new());
+            let mut i = 0;
+            while let Some(e) = parser.next_event().unwrap() {
+                if e == JsonEvent::NeedMoreInput {
+                    if i < json.len() {
+                        i += parser.feeder.push_bytes(&json.as_bytes()[i..i + 1]);
+                    } else {
+                        parser.feeder.done();
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(matches!(
+        parse_fail(b"{\"a\":1}\n{\"b\":2}"),
+        ParserError::TrailingData(8)
+    ));
+    assert!(matches!(
+        parse_fail(b"{\"a\":1}\r\n\r\n{\"b\":2}\r\n"),
+        ParserError::TrailingData(11)
+    ));
+    assert!(matches!(
+        parse_fail(b"1\n2\n"),
+        ParserError::TrailingData(2)
+    ));
+}
+
+/// Test that [`actson::validate_str`] accepts exactly one complete JSON value
+#[test]
+fn validate_str() {
+    assert!(actson::validate_str(r#"{"name": "Elvis", "age": [1, 2.5, null]}"#).is_ok());
+    assert!(actson::validate_str("  5  ").is_ok());
+    assert!(matches!(
+        actson::validate_str(r#"{"name": "Elvis""#),
+        Err(ParserError::NoMoreInput)
+    ));
+    assert!(matches!(
+        actson::validate_str("[1, 2] 3"),
+        Err(ParserError::TrailingData(7))
+    ));
+}
+
+/// Test that [`JsonParser::current_container_count()`] counts the completed
+/// values in the innermost container, even if the input arrives byte by byte
+#[test]
+fn container_count() {
+    let json = r#"{"a":1,"b":{"c":[true,null,{}],"d":"e"},"f":[]}"#.as_bytes();
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+
+    let mut i = 0;
+    let mut counts = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            if i < json.len() {
+                parser.feeder.push_bytes(&json[i..i + 1]);
+                i += 1;
+            } else {
+                parser.feeder.done();
+            }
+        } else {
+            counts.push((e, parser.current_container_count()));
+        }
+    }
+
+    assert_eq!(
+        counts,
+        vec![
+            (JsonEvent::StartObject, 0),
+            (JsonEvent::FieldName, 0),
+            (JsonEvent::ValueInt, 1),
+            (JsonEvent::FieldName, 1),
+            (JsonEvent::StartObject, 0),
+            (JsonEvent::FieldName, 0),
+            (JsonEvent::StartArray, 0),
+            (JsonEvent::ValueTrue, 1),
+            (JsonEvent::ValueNull, 2),
+            (JsonEvent::StartObject, 0),
+            (JsonEvent::EndObject, 3),
+            (JsonEvent::EndArray, 1),
+            (JsonEvent::FieldName, 1),
+            (JsonEvent::ValueString, 2),
+            (JsonEvent::EndObject, 2),
+            (JsonEvent::FieldName, 2),
+            (JsonEvent::StartArray, 0),
+            (JsonEvent::EndArray, 3),
+            (JsonEvent::EndObject, 0),
+        ]
+    );
+}
+
+/// Parse the given JSON text byte by byte with the given options and return
+/// the generated events
+fn parse_events_with_options(
+    json: &[u8],
+    options: JsonParserOptions,
+) -> Result<Vec<JsonEvent>, ParserError> {
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+
+    let mut i = 0;
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event()? {
+        if e == JsonEvent::NeedMoreInput {
+            if i < json.len() {
+                parser.feeder.push_bytes(&json[i..i + 1]);
+                i += 1;
+            } else {
+                parser.feeder.done();
+            }
+        } else {
+            events.push(e);
+        }
+    }
+
+    Ok(events)
+}
+
+/// Test that values exceeding the maximum depth can be skipped
+#[test]
+fn skip_beyond_max_depth() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(3)
+        .with_skip_beyond_max_depth(true)
+        .build();
+
+    let json = r#"[{"a":{"b":"]}\"[{","c":[[]]},"d":[1]},{}]"#;
+    assert_eq!(
+        parse_events_with_options(json.as_bytes(), options).unwrap(),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndObject,
+            JsonEvent::StartObject,
+            JsonEvent::EndObject,
+            JsonEvent::EndArray,
+        ]
+    );
+
+    // the whole top-level value can be skipped
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(1)
+        .with_skip_beyond_max_depth(true)
+        .build();
+    assert_eq!(
+        parse_events_with_options(b"[1,2,[3]]  ", options.clone()).unwrap(),
+        vec![JsonEvent::TruncatedValue]
+    );
+
+    // the skipped value must end
+    assert!(matches!(
+        parse_events_with_options(b"[1,2,[3]", options.clone()),
+        Err(ParserError::NoMoreInput)
+    ));
+
+    // brackets in the skipped value must match
+    assert!(matches!(
+        parse_events_with_options(b"[[1}]", options.clone()),
+        Err(ParserError::SyntaxError)
+    ));
+    assert!(matches!(
+        parse_events_with_options(br#"[{"a":1]}"#, options.clone()),
+        Err(ParserError::SyntaxError)
+    ));
+
+    // the skipped value must not contain illegal bytes
+    assert!(matches!(
+        parse_events_with_options(b"[\"\x01\"]", options),
+        Err(ParserError::IllegalCharacter { byte: 1, offset: 2 })
+    ));
+
+    // without the option, exceeding the maximum depth is an error
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(1)
+        .build();
+    assert!(matches!(
+        parse_events_with_options(b"[1]", options),
+        Err(ParserError::DepthLimitExceeded(DepthLimit::Total(1)))
+    ));
+}
+
+/// Test the documented default depth limit and that the limit can be removed
+#[test]
+fn default_max_depth() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    let parse = |json: &str, options| {
+        let mut parser =
+            JsonParser::new_with_options(SliceJsonFeeder::new(json.as_bytes()), options);
+        while parser.next_event()?.is_some() {}
+        Ok::<_, ParserError>(())
+    };
+
+    let options = JsonParserOptionsBuilder::default().build();
+    assert_eq!(options.max_depth(), DEFAULT_MAX_DEPTH);
+    assert_eq!(DEFAULT_MAX_DEPTH, 2048);
+    assert!(parse(&nested(200), options.clone()).is_ok());
+    assert!(parse(&nested(DEFAULT_MAX_DEPTH - 1), options.clone()).is_ok());
+    assert!(matches!(
+        parse(&nested(DEFAULT_MAX_DEPTH), options),
+        Err(ParserError::DepthLimitExceeded(DepthLimit::Total(
+            DEFAULT_MAX_DEPTH
+        )))
+    ));
+
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(16)
+        .with_unlimited_depth()
+        .build();
+    assert_eq!(options.max_depth(), usize::MAX);
+    assert!(parse(&nested(10000), options).is_ok());
+}
+
+/// Test that arrays and objects can have separate depth limits
+#[test]
+fn max_array_and_object_depth() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_array_depth(2)
+        .with_max_object_depth(1)
+        .build();
+    assert!(parse_events_with_options(br#"[[{"a": [], "b": {}}], {}]"#, options.clone()).is_err());
+    assert!(parse_events_with_options(br#"[[{"a": 1}], {"b": [1]}]"#, options.clone()).is_ok());
+    assert!(parse_events_with_options(br#"{"a": [[1, 2], [3]]}"#, options.clone()).is_ok());
+    assert!(matches!(
+        parse_events_with_options(b"[[[1]]]", options.clone()),
+        Err(ParserError::DepthLimitExceeded(DepthLimit::Array(2)))
+    ));
+    assert!(matches!(
+        parse_events_with_options(br#"[{"a": {"b": 1}}]"#, options),
+        Err(ParserError::DepthLimitExceeded(DepthLimit::Object(1)))
+    ));
+
+    // the most restrictive limit applies
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(3)
+        .with_max_array_depth(5)
+        .build();
+    assert!(matches!(
+        parse_events_with_options(b"[[[1]]]", options),
+        Err(ParserError::DepthLimitExceeded(DepthLimit::Total(3)))
+    ));
+
+    // exceeding objects and arrays can be skipped
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_array_depth(1)
+        .with_skip_beyond_max_depth(true)
+        .build();
+    assert_eq!(
+        parse_events_with_options(br#"{"a": [1, [2, 3]], "b": [[]]}"#, options).unwrap(),
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndArray,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndArray,
+            JsonEvent::EndObject,
+        ]
+    );
+    assert_eq!(DepthLimit::Array(1).to_string(), "maximum array depth of 1");
+}
+
+/// Test that objects and arrays exceeding a depth limit can be clamped
+#[test]
+fn clamp_depth() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(3)
+        .with_clamp_depth(true)
+        .build();
+
+    // values in objects are reported as truncated
+    let json = r#"[{"a":{"b":[1,{"c":2}],"d":{}},"e":[3]},4]"#;
+    assert_eq!(
+        parse_events_with_options(json.as_bytes(), options.clone()).unwrap(),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndObject,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+
+    // values in arrays are flattened
+    assert_eq!(
+        parse_events_with_options(br#"[[1,[2,{"a":[3]}]],4]"#, options).unwrap(),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+
+    // the option takes precedence over skipping
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(2)
+        .with_clamp_depth(true)
+        .with_skip_beyond_max_depth(true)
+        .build();
+    assert_eq!(
+        parse_events_with_options(br#"[1,[2,{"a":[]}],3]"#, options.clone()).unwrap(),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+
+    // clamped values are still validated
+    assert!(matches!(
+        parse_events_with_options(b"[1,[2}]", options.clone()),
+        Err(ParserError::SyntaxError)
+    ));
+    assert!(matches!(
+        parse_events_with_options(b"[1,[2]", options),
+        Err(ParserError::NoMoreInput)
+    ));
+
+    // separate array and object limits can be clamped too
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_array_depth(1)
+        .with_clamp_depth(true)
+        .build();
+    assert_eq!(
+        parse_events_with_options(br#"{"a": [1, [2, {"b": [3]}]]}"#, options).unwrap(),
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+            JsonEvent::EndObject,
+        ]
+    );
+
+    // the path never exceeds the limit
+    let parse_paths = |json: &[u8]| {
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(json),
+            JsonParserOptionsBuilder::default()
+                .with_max_depth(2)
+                .with_clamp_depth(true)
+                .with_path_tracking(true)
+                .build(),
+        );
+        let mut paths = Vec::new();
+        while let Some(e) = parser.next_event().unwrap() {
+            paths.push((e, parser.current_path().to_vec()));
+        }
+        paths
+    };
+    let key = |k: &str| vec![PathSegment::Key(k.to_string())];
+    assert_eq!(
+        parse_paths(br#"{"a":{"b":1,"c":[]},"d":[2,{"e":3}]}"#),
+        vec![
+            (JsonEvent::StartObject, vec![]),
+            (JsonEvent::FieldName, key("a")),
+            (JsonEvent::TruncatedValue, key("a")),
+            (JsonEvent::FieldName, key("d")),
+            (JsonEvent::TruncatedValue, key("d")),
+            (JsonEvent::EndObject, vec![]),
+        ]
+    );
+    let index = |i: usize| vec![PathSegment::Index(i)];
+    assert_eq!(
+        parse_paths(br#"[[1,{"e":3}],{"a":[2]}]"#),
+        vec![
+            (JsonEvent::StartArray, vec![]),
+            (JsonEvent::ValueInt, index(0)),
+            (JsonEvent::ValueInt, index(1)),
+            (JsonEvent::ValueInt, index(2)),
+            (JsonEvent::EndArray, vec![]),
+        ]
+    );
+
+    // writers only see well-formed values
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(2)
+        .with_clamp_depth(true)
+        .build();
+    let mut parser =
+        JsonParser::new_with_options(SliceJsonFeeder::new(br#"{"a":[1,2],"b":3}"#), options);
+    let mut writer = JsonWriter::new(Vec::new());
+    while let Some(e) = parser.next_event().unwrap() {
+        writer.write_event(e, &parser).unwrap();
+    }
+    assert_eq!(writer.into_inner(), br#"{"a":null,"b":3}"#);
+}
+
+/// Test that clamped objects and arrays that cannot be flattened because
+/// they are values of fields or top-level values are truncated
+#[test]
+fn clamp_depth_truncated() {
+    let options = |max_depth| {
+        JsonParserOptionsBuilder::default()
+            .with_max_depth(max_depth)
+            .with_clamp_depth(true)
+            .with_streaming(true)
+            .build()
+    };
+
+    // the contents of a truncated value are lost, even if they are arrays
+    assert_eq!(
+        parse_events_with_options(br#"{"a":[1,{"b":2}],"c":{"d":[3]}}"#, options(2)).unwrap(),
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndObject,
+        ]
+    );
+
+    // top-level objects and arrays are truncated, other values are not
+    assert_eq!(
+        parse_events_with_options(b"{} [1] 2", options(1)).unwrap(),
+        vec![
+            JsonEvent::TruncatedValue,
+            JsonEvent::TruncatedValue,
+            JsonEvent::ValueInt,
+        ]
+    );
+
+    // the contents of truncated values are still validated
+    assert!(matches!(
+        parse_events_with_options(br#"{"a":{"b":}}"#, options(2)),
+        Err(ParserError::SyntaxError)
+    ));
+    assert!(matches!(
+        parse_events_with_options(br#"{"a":{"b":1}"#, options(2)),
+        Err(ParserError::NoMoreInput)
+    ));
+}
+
+/// Parse the given input byte by byte in tail mode and return the events
+/// and the offset after the last complete top-level value
+fn parse_tail(json: &[u8]) -> (Vec<JsonEvent>, usize) {
+    let options = JsonParserOptionsBuilder::default().with_tail(true).build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+    let mut i = 0;
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            if i < json.len() {
+                parser.feeder.push_bytes(&json[i..i + 1]);
+                i += 1;
+            } else {
+                parser.feeder.done();
+            }
+        } else {
+            events.push(e);
+        }
+    }
+    (events, parser.last_value_end())
+}
+
+/// Test that an incomplete top-level value at the end of the input is
+/// ignored in tail mode and that parsing can be resumed after the last
+/// complete value
+#[test]
+fn tail() {
+    for (json, end) in [
+        (&b""[..], 0),
+        (b"{\"a\": 1}\n", 8),
+        (b"{\"a\": 1}\n{", 8),
+        (b"{\"a\": 1}\n{\"b\": [1, {\"c", 8),
+        (b"{\"a\": 1} 12", 8),
+        (b"{\"a\": 1} 12 ", 12),
+        (b"{\"a\": 1} 1.5e", 8),
+        (b"1 tru", 2),
+        (b"1 true", 6),
+        (b"1 \"abc", 2),
+        (b"1 \"abc\\", 2),
+        (b"1[2]{", 4),
+    ] {
+        let (events, last_value_end) = parse_tail(json);
+        assert_eq!(last_value_end, end, "{}", String::from_utf8_lossy(json));
+
+        // the events of the complete values are the same as in streaming
+        // mode if the input is cut at the returned offset
+        let complete = parse_events_with_options(
+            &json[..end],
+            JsonParserOptionsBuilder::default()
+                .with_streaming(true)
+                .with_allow_empty_input(true)
+                .build(),
+        )
+        .unwrap();
+        assert_eq!(events[..complete.len()], complete[..]);
+    }
+
+    // tail mode implies streaming mode, so an incomplete first value is not
+    // dropped silently but reported through the offset
+    assert_eq!(
+        parse_tail(b"[1, 2"),
+        (vec![JsonEvent::StartArray, JsonEvent::ValueInt], 0)
+    );
+    assert_eq!(
+        parse_tail(b" [1] [2"),
+        (
+            vec![
+                JsonEvent::StartArray,
+                JsonEvent::ValueInt,
+                JsonEvent::EndArray,
+                JsonEvent::StartArray,
+            ],
+            4
+        )
+    );
+
+    // without tail mode, an incomplete value is still an error
+    assert!(matches!(
+        parse_events_with_options(
+            b"{\"a\": 1}\n{",
+            JsonParserOptionsBuilder::default()
+                .with_streaming(true)
+                .build()
+        ),
+        Err(ParserError::NoMoreInput)
+    ));
+
+    // syntax errors are still reported in tail mode
+    let mut parser = JsonParser::new_with_options(
+        SliceJsonFeeder::new(b"{\"a\": 1} [1}"),
+        JsonParserOptionsBuilder::default()
+            .with_streaming(true)
+            .with_tail(true)
+            .build(),
+    );
+    let err = loop {
+        if let Err(e) = parser.next_event() {
+            break e;
+        }
+    };
+    assert!(matches!(err, ParserError::SyntaxError));
+}
+
+/// Test that a leading line starting with `#` is skipped if enabled
+#[test]
+fn leading_shebang() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_leading_shebang(true)
+        .build();
+    let expected = vec![
+        JsonEvent::StartObject,
+        JsonEvent::FieldName,
+        JsonEvent::ValueInt,
+        JsonEvent::EndObject,
+    ];
+    for json in [
+        &b"#!/usr/bin/env tool\n{\"a\": 1}"[..],
+        b"# comment with {[\"\r\n{\"a\": 1}",
+        b"#\n\n  {\"a\": 1}\n",
+        b"{\"a\": 1}",
+    ] {
+        assert_eq!(
+            parse_events_with_options(json, options.clone()).unwrap(),
+            expected,
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // only the very first byte may start the line
+    assert!(matches!(
+        parse_events_with_options(b" #!tool\n{}", options.clone()),
+        Err(ParserError::SyntaxError)
+    ));
+    assert!(matches!(
+        parse_events_with_options(b"#!tool\n# more\n{}", options.clone()),
+        Err(ParserError::SyntaxError)
+    ));
+
+    // a file that only consists of the line is empty
+    assert!(matches!(
+        parse_events_with_options(b"#!tool", options),
+        Err(ParserError::EmptyInput)
+    ));
+
+    // the option is disabled by default
+    assert!(matches!(
+        parse_events_with_options(b"#!tool\n{}", JsonParserOptions::default()),
+        Err(ParserError::SyntaxError)
+    ));
+}
+
+/// Test that a JSONP callback wrapper is skipped if enabled
+#[test]
+fn jsonp() {
+    let options = JsonParserOptionsBuilder::default().with_jsonp(true).build();
+    let object = vec![
+        JsonEvent::StartObject,
+        JsonEvent::FieldName,
+        JsonEvent::ValueInt,
+        JsonEvent::EndObject,
+    ];
+    for json in [
+        &b"callback({\"a\": 1});"[..],
+        b"callback({\"a\": 1})",
+        b"  jQuery_123$.cb ( {\"a\": 1} ) ;\n",
+        b"{\"a\": 1}",
+    ] {
+        assert_eq!(
+            parse_events_with_options(json, options.clone()).unwrap(),
+            object,
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // scalars, including literals that look like callback names
+    for (json, expected) in [
+        (&b"cb(12)"[..], JsonEvent::ValueInt),
+        (b"cb(-1.5e3);", JsonEvent::ValueFloat),
+        (b"cb(true)", JsonEvent::ValueTrue),
+        (b"cb(\"a)\")", JsonEvent::ValueString),
+        (b"null", JsonEvent::ValueNull),
+        (b" false ", JsonEvent::ValueFalse),
+        (b"12", JsonEvent::ValueInt),
+    ] {
+        assert_eq!(
+            parse_events_with_options(json, options.clone()).unwrap(),
+            vec![expected],
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // the callback name is available after the opening parenthesis
+    let json = b"my.callback([1, 2]);";
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json), options.clone());
+    assert_eq!(parser.jsonp_callback(), None);
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert_eq!(parser.jsonp_callback(), Some("my.callback"));
+    while parser.next_event().unwrap().is_some() {}
+    assert_eq!(parser.jsonp_callback(), Some("my.callback"));
+
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"[1]"), options.clone());
+    while parser.next_event().unwrap().is_some() {}
+    assert_eq!(parser.jsonp_callback(), None);
+
+    // the wrapper may be split across buffers at any position
+    let json = b"cb ( [1, 23] ) ;";
+    for i in 0..=json.len() {
+        let events = parse_chunks(&[&json[..i], &json[i..]], options.clone()).unwrap();
+        assert_eq!(events.len(), 4, "split at {i}");
+        assert_eq!(events[2], (JsonEvent::ValueInt, "23".to_string()));
+    }
+
+    // invalid wrappers
+    for (json, err) in [
+        (&b"cb({}"[..], ParserError::NoMoreInput),
+        (b"cb(", ParserError::NoMoreInput),
+        (b"cb()", ParserError::SyntaxError),
+        (b"cb({}));", ParserError::TrailingData(6)),
+        (b"cb({});;", ParserError::TrailingData(7)),
+        (b"cb({}) x", ParserError::TrailingData(7)),
+        (b"cb {}", ParserError::SyntaxError),
+        (b"1cb({})", ParserError::SyntaxError),
+        (b"cb.({})", ParserError::SyntaxError),
+        (b"nul l", ParserError::SyntaxError),
+        (b"falsex", ParserError::SyntaxError),
+        (b"true]", ParserError::TrailingData(4)),
+        (b"[1)]", ParserError::SyntaxError),
+    ] {
+        let r = parse_events_with_options(json, options.clone());
+        assert_eq!(
+            r.as_ref().map_err(|e| e.to_string()),
+            Err(err.to_string()),
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // the option is disabled by default
+    assert!(matches!(
+        parse_events_with_options(b"cb({})", JsonParserOptions::default()),
+        Err(ParserError::SyntaxError)
+    ));
+}
+
+/// Test that [`JsonParser::next_event_blocking_on()`] calls the closure
+/// whenever the parser needs more input and passes its errors through
+#[test]
+fn next_event_blocking_on() {
+    let json = br#"{"a": [1, "bc", null]}"#;
+    let mut i = 0;
+    let mut calls = 0;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    let mut events = Vec::new();
+    while let Some(event) = parser
+        .next_event_blocking_on(|feeder| {
+            calls += 1;
+            if i < json.len() {
+                i += feeder.push_bytes(&json[i..i + 1]);
+            } else {
+                feeder.done();
+            }
+            Ok::<_, ParserError>(())
+        })
+        .unwrap()
+    {
+        events.push(event);
+    }
+    assert_eq!(calls, json.len() + 1);
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::ValueString,
+            JsonEvent::ValueNull,
+            JsonEvent::EndArray,
+            JsonEvent::EndObject,
+        ]
+    );
+
+    #[derive(Debug)]
+    enum RefillError {
+        Parse(ParserError),
+        Closed,
+    }
+
+    impl From<ParserError> for RefillError {
+        fn from(e: ParserError) -> Self {
+            RefillError::Parse(e)
+        }
+    }
+
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    assert!(matches!(
+        parser.next_event_blocking_on(|_| Err(RefillError::Closed)),
+        Err(RefillError::Closed)
+    ));
+
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1}"));
+    assert_eq!(
+        parser
+            .next_event_blocking_on(|_| Ok::<_, RefillError>(()))
+            .unwrap(),
+        Some(JsonEvent::StartArray)
+    );
+    assert!(matches!(
+        parser.next_event_blocking_on(|_| Ok::<_, RefillError>(())),
+        Err(RefillError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Test that [`JsonParser::collect_keys_on()`] returns the field names of
+/// the current object and leaves the parser after the object
+#[test]
+fn collect_keys_on() {
+    let json = br#"[{"a": {"x": 1}, "b": [[], {"y": 2}], "": null, "a": "dup"}, {}, 3]"#;
+    let mut i = 0;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    let mut refill = |feeder: &mut PushJsonFeeder| {
+        if i < json.len() {
+            i += feeder.push_bytes(&json[i..i + 1]);
+        } else {
+            feeder.done();
+        }
+        Ok::<_, CollectKeysError>(())
+    };
+
+    let mut keys = Vec::new();
+    let mut events = Vec::new();
+    while let Some(event) = parser.next_event_blocking_on(&mut refill).unwrap() {
+        events.push(event);
+        if event == JsonEvent::StartObject {
+            keys.push(parser.collect_keys_on(&mut refill).unwrap());
+        }
+    }
+    assert_eq!(keys, [vec!["a", "b", "", "a"], vec![]]);
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::StartObject,
+            JsonEvent::StartObject,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+
+    // the current event must be the start of an object
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[{}]"));
+    parser.next_event().unwrap();
+    assert!(matches!(
+        parser.collect_keys_on(|_| Ok::<_, CollectKeysError>(())),
+        Err(CollectKeysError::Parse(ParserError::UnexpectedEvent {
+            expected: JsonEventKind::Object,
+            found: Some(JsonEvent::StartArray),
+        }))
+    ));
+
+    // errors inside skipped values are reported
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"{"a": [1}, "b": 2}"#));
+    parser.next_event().unwrap();
+    assert!(matches!(
+        parser.collect_keys_on(|_| Ok::<_, CollectKeysError>(())),
+        Err(CollectKeysError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Test that the sign of zero is preserved by `current_float()` and
+/// `current_raw_number()`
+#[test]
+fn negative_zero() {
+    let json = r#"[-0, 0, -0.0, 0.0, -0e0, -0E-2]"#;
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+    let mut numbers = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        if matches!(event, JsonEvent::ValueInt | JsonEvent::ValueFloat) {
+            let f = parser.current_float().unwrap();
+            assert_eq!(f, 0.0);
+            numbers.push((
+                parser.current_raw_number().unwrap().to_string(),
+                f.is_sign_negative(),
+            ));
+        }
+    }
+    assert_eq!(
+        numbers,
+        vec![
+            ("-0".to_string(), true),
+            ("0".to_string(), false),
+            ("-0.0".to_string(), true),
+            ("0.0".to_string(), false),
+            ("-0e0".to_string(), true),
+            ("-0E-2".to_string(), true),
+        ]
+    );
+}
+
+/// Test that the original text of all scalar values can be retrieved, even if
+/// the input is fed byte by byte
+#[test]
+fn raw_values() {
+    let json = r#"{"a\"b":["\ud83d\ude00\n",-0.5E+2,0,true,false,null,"\u00e4ä",{}],"c":1e5}"#;
+    let options = JsonParserOptionsBuilder::default()
+        .with_raw_values(true)
+        .build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+
+    // rebuild the input from the raw values
+    let mut i = 0;
+    let mut out = String::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => {
+                if i < json.len() {
+                    i += parser.feeder.push_bytes(&json.as_bytes()[i..i + 1]);
+                } else {
+                    parser.feeder.done();
+                }
+            }
+            JsonEvent::EndObject => out.push('}'),
+            JsonEvent::EndArray => out.push(']'),
+            _ => {
+                if !out.is_empty() && !out.ends_with(['{', '[', ':']) {
+                    out.push(',');
+                }
+                match e {
+                    JsonEvent::StartObject => out.push('{'),
+                    JsonEvent::StartArray => out.push('['),
+                    JsonEvent::FieldName => {
+                        out.push_str(parser.current_raw().unwrap());
+                        out.push(':');
+                    }
+                    _ => out.push_str(parser.current_raw().unwrap()),
+                }
+            }
+        }
+    }
+    assert_eq!(out, json);
+
+    // top-level values in streaming mode
+    let options = JsonParserOptionsBuilder::default()
+        .with_raw_values(true)
+        .with_streaming(true)
+        .build();
+    let mut parser =
+        JsonParser::new_with_options(SliceJsonFeeder::new(br#"1 -2.0"x"true[null]3"#), options);
+    let mut raw = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e != JsonEvent::StartArray && e != JsonEvent::EndArray {
+            raw.push(parser.current_raw().unwrap().to_string());
+        }
+    }
+    assert_eq!(raw, ["1", "-2.0", "\"x\"", "true", "null", "3"]);
+
+    // raw values are disabled by default
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[true]"));
+    parser.next_event().unwrap();
+    parser.next_event().unwrap();
+    assert_eq!(parser.current_raw().unwrap(), "");
+}
+
+/// A buffer pool that counts how many buffers are in use
+#[derive(Default)]
+struct CountingPool {
+    allocated: AtomicUsize,
+    returned: Mutex<Vec<Vec<u8>>>,
+}
+
+impl BufferPool for CountingPool {
+    fn alloc_buf(&self) -> Vec<u8> {
+        self.allocated.fetch_add(1, Ordering::SeqCst);
+        b"garbage".to_vec()
+    }
+
+    fn return_buf(&self, buf: Vec<u8>) {
+        self.returned.lock().unwrap().push(buf);
+    }
+}
+
+/// Test that the parser takes its buffer from a buffer pool and returns it
+#[test]
+fn buffer_pool() {
+    let pool = Arc::new(CountingPool::default());
+    let json = r#"{"name": "Elvis", "born": 1935, "songs": ["Hound Dog"]}"#;
+
+    let parser = JsonParser::new(PushJsonFeeder::new()).with_buffer_pool(pool.clone());
+    let mut clone = parser.clone();
+    assert_eq!(pool.allocated.load(Ordering::SeqCst), 2);
+    drop(parser);
+    assert_eq!(pool.returned.lock().unwrap().len(), 1);
+
+    // the buffer is cleared and works like the default one
+    assert_eq!(parse_with_parser(json, &mut clone), parse(json));
+    drop(clone);
+
+    let returned = pool.returned.lock().unwrap();
+    assert_eq!(returned.len(), 2);
+    assert!(returned[1].capacity() >= "Hound Dog".len());
+    assert_eq!(pool.allocated.load(Ordering::SeqCst), 2);
+}
+
+/// Test that strings and field names can be limited in bytes and characters
+#[test]
+fn max_string_length() {
+    let bytes = |n| {
+        JsonParserOptionsBuilder::default()
+            .with_max_string_length(n)
+            .build()
+    };
+    let chars = |n| {
+        JsonParserOptionsBuilder::default()
+            .with_max_string_chars(n)
+            .build()
+    };
+
+    // "\u00e4ö😀\ud83d\ude00" has 4 characters and 12 bytes
+    let json = r#"{"a": "\u00e4ö😀\ud83d\ude00", "bcdef": "x"}"#.as_bytes();
+    assert!(parse_events_with_options(json, bytes(12)).is_ok());
+    assert!(parse_events_with_options(json, chars(5)).is_ok());
+    assert!(matches!(
+        parse_events_with_options(json, bytes(11)),
+        Err(ParserError::StringTooLong(StringLimit::Bytes(11)))
+    ));
+    assert!(matches!(
+        parse_events_with_options(json, chars(3)),
+        Err(ParserError::StringTooLong(StringLimit::Chars(3)))
+    ));
+
+    // field names are limited too
+    assert!(matches!(
+        parse_events_with_options(json, chars(4)),
+        Err(ParserError::StringTooLong(StringLimit::Chars(4)))
+    ));
+
+    // the more restrictive limit wins
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_string_length(11)
+        .with_max_string_chars(5)
+        .build();
+    assert!(matches!(
+        parse_events_with_options(json, options),
+        Err(ParserError::StringTooLong(StringLimit::Bytes(11)))
+    ));
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_string_length(100)
+        .with_max_string_chars(3)
+        .build();
+    let err = parse_events_with_options(json, options).unwrap_err();
+    assert!(matches!(
+        err,
+        ParserError::StringTooLong(StringLimit::Chars(3))
+    ));
+    assert_eq!(err.category(), ErrorCategory::Limit);
+    assert_eq!(
+        err.to_string(),
+        "maximum string length of 3 characters exceeded"
+    );
+
+    // the error is returned as soon as the limit has been exceeded
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), bytes(3));
+    parser.feeder.push_bytes(br#"["abcd"#);
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::StringTooLong(StringLimit::Bytes(3)))
+    ));
+
+    // consumed parts of a streaming string count as well
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_streaming_strings(true)
+            .with_max_string_length(3)
+            .build(),
+    );
+    parser.feeder.push_bytes(br#""ab"#);
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::NeedMoreInput));
+    assert_eq!(parser.string_chars().collect::<String>(), "ab");
+    parser.feeder.push_bytes(br#"cd""#);
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::StringTooLong(StringLimit::Bytes(3)))
+    ));
+
+    // string values count in structure-only mode too, although they are
+    // not kept in memory
+    for structure_only in [false, true] {
+        let options = |n| {
+            JsonParserOptionsBuilder::default()
+                .with_structure_only(structure_only)
+                .with_max_string_length(n)
+                .build()
+        };
+        let json = r#"["abc", "ä\n"]"#.as_bytes();
+        assert!(parse_events_with_options(json, options(3)).is_ok());
+        assert!(matches!(
+            parse_events_with_options(json, options(2)),
+            Err(ParserError::StringTooLong(StringLimit::Bytes(2)))
+        ));
+
+        let mut parser =
+            JsonParser::new_with_options(SliceJsonFeeder::new(br#"["aaaaaaaaaa"]"#), options(3));
+        assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+        assert!(matches!(
+            parser.next_event(),
+            Err(ParserError::StringTooLong(StringLimit::Bytes(3)))
+        ));
+        assert_eq!(parser.parsed_bytes(), 6);
+    }
+}
+
+/// Test that a clone of a parser can be used to backtrack
+#[test]
+fn clone_and_backtrack() {
+    let json = r#"{"a":"b","c":[1,2.5]}"#.as_bytes();
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(&json[..10]);
+
+    let mut checkpoint = None;
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            if checkpoint.is_none() {
+                // take a snapshot in the middle of the second field name
+                checkpoint = Some(parser.clone());
+                parser.feeder.push_bytes(&json[10..]);
+                parser.feeder.done();
+            }
+        } else {
+            events.push(e);
+        }
+    }
+
+    // restore the snapshot and feed the rest of the input again
+    let mut parser = checkpoint.unwrap();
+    parser.feeder.push_bytes(&json[10..]);
+    parser.feeder.done();
+    let mut events2 = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::FieldName {
+            assert_eq!(parser.current_str().unwrap(), "c");
+        }
+        events2.push(e);
+    }
+
+    assert_eq!(events[3..], events2[..]);
+}
+
+/// Test that [`DepthFilter`] collapses deeply nested values
+#[test]
+fn depth_filter() {
+    let json = r#"[1,{"a":[2,{"b":3}],"c":{}},[[4]],5]"#.as_bytes();
+
+    let filter_events = |max_depth: usize| {
+        let mut filter = DepthFilter::new(JsonParser::new(PushJsonFeeder::new()), max_depth);
+        let mut i = 0;
+        let mut events = Vec::new();
+        while let Some(e) = filter.next_event().unwrap() {
+            if e == JsonEvent::NeedMoreInput {
+                if i < json.len() {
+                    filter.parser.feeder.push_bytes(&json[i..i + 1]);
+                    i += 1;
+                } else {
+                    filter.parser.feeder.done();
+                }
+            } else {
+                events.push(e);
+            }
+        }
+        events
+    };
+
+    assert_eq!(filter_events(0), vec![JsonEvent::TruncatedValue]);
+    assert_eq!(
+        filter_events(1),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::TruncatedValue,
+            JsonEvent::TruncatedValue,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+    assert_eq!(
+        filter_events(2),
+        vec![
+            JsonEvent::StartArray,
+            JsonEvent::ValueInt,
+            JsonEvent::StartObject,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::FieldName,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndObject,
+            JsonEvent::StartArray,
+            JsonEvent::TruncatedValue,
+            JsonEvent::EndArray,
+            JsonEvent::ValueInt,
+            JsonEvent::EndArray,
+        ]
+    );
+    assert_eq!(filter_events(4).len(), 22);
+}
+
+/// Test that floats can be parsed to `f32`
+#[test]
+fn current_f32() {
+    let json = r#"[1.5, -0.1, 3.4028235e38, 1e39, -1e39, 1e-50, 7]"#;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(json.as_bytes());
+    parser.feeder.done();
+
+    let mut values = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::ValueFloat || e == JsonEvent::ValueInt {
+            values.push(parser.current_f32().ok());
+        }
+    }
+
+    assert_eq!(
+        values,
+        vec![
+            Some(1.5),
+            Some(-0.1),
+            Some(f32::MAX),
+            None,
+            None,
+            Some(0.0),
+            Some(7.0)
+        ]
+    );
+}
+
+/// Test that empty input and input only consisting of white space is an error
+/// by default but can be allowed
+#[test]
+fn empty_input() {
+    for json in ["", "   ", "\n\n"] {
+        assert!(matches!(
+            parse_events_with_options(json.as_bytes(), JsonParserOptions::default()),
+            Err(ParserError::EmptyInput)
+        ));
+
+        let options = JsonParserOptionsBuilder::default()
+            .with_allow_empty_input(true)
+            .build();
+        assert_eq!(
+            parse_events_with_options(json.as_bytes(), options.clone()).unwrap(),
+            vec![]
+        );
+
+        let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+        parser.feeder.push_bytes(json.as_bytes());
+        parser.feeder.done();
+        assert_eq!(parser.next_event().unwrap(), None);
+        assert!(matches!(parser.next_event(), Err(ParserError::NoMoreInput)));
+    }
+}
+
+/// Test that the parser in streaming mode only returns `None` after the
+/// feeder is done
+#[test]
+fn streaming_end_of_stream() {
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_streaming(true)
+            .build(),
+    );
+
+    parser.feeder.push_bytes(br#"{"a":1} [2]"#);
+    let mut events = Vec::new();
+    loop {
+        match parser.next_event().unwrap() {
+            Some(JsonEvent::NeedMoreInput) => break,
+            Some(e) => events.push(e),
+            None => panic!("stream must not end before the feeder is done"),
+        }
+    }
+    assert_eq!(events.len(), 7);
+
+    // without more input, the parser keeps asking for more
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::NeedMoreInput));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::NeedMoreInput));
+
+    // a trailing number is only complete at the end of the input
+    parser.feeder.push_bytes(b" 3");
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::NeedMoreInput));
+
+    parser.feeder.done();
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert_eq!(parser.current_int::<i32>().unwrap(), 3);
+    assert_eq!(parser.next_event().unwrap(), None);
+    assert!(matches!(parser.next_event(), Err(ParserError::NoMoreInput)));
+}
+
+/// Test that a stream ending in the middle of a value is an error
+#[test]
+fn streaming_incomplete_value() {
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_streaming(true)
+            .build(),
+    );
+    parser.feeder.push_bytes(b"1 [2");
+    parser.feeder.done();
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert!(matches!(parser.next_event(), Err(ParserError::NoMoreInput)));
+}
+
+/// Parse the given chunks one after the other, pushing the next chunk
+/// only when the parser asks for more input
+fn parse_chunks(
+    chunks: &[&[u8]],
+    options: JsonParserOptions,
+) -> Result<Vec<(JsonEvent, String)>, ParserError> {
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+    let mut chunks = chunks.iter();
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event()? {
+        match e {
+            JsonEvent::NeedMoreInput => match chunks.next() {
+                Some(c) => assert_eq!(parser.feeder.push_bytes(c), c.len()),
+                None => parser.feeder.done(),
+            },
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => {
+                events.push((e, parser.current_raw_number().unwrap().to_string()))
+            }
+            _ => events.push((e, String::new())),
+        }
+    }
+    Ok(events)
+}
+
+/// Test that numbers are reassembled correctly if they are split across
+/// buffers at any position and that white space between two parts of a
+/// number is not ignored
+#[test]
+fn numbers_split_across_buffers() {
+    let strict = JsonParserOptionsBuilder::default().build();
+    let streaming = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .build();
+
+    for number in [
+        "0", "-0", "7", "-123", "1.5", "-0.25", "10.125", "1e5", "1E+5", "-2e-10", "3.5E-07",
+        "0e0", "-0.0e+00",
+    ] {
+        let kind = if number.contains(['.', 'e', 'E']) {
+            JsonEvent::ValueFloat
+        } else {
+            JsonEvent::ValueInt
+        };
+        let expected = vec![(kind, number.to_string())];
+        let in_array = vec![
+            (JsonEvent::StartArray, String::new()),
+            (kind, number.to_string()),
+            (JsonEvent::EndArray, String::new()),
+        ];
+
+        for i in 0..=number.len() {
+            let (a, b) = number.as_bytes().split_at(i);
+            for options in [strict.clone(), streaming.clone()] {
+                assert_eq!(
+                    parse_chunks(&[a, b], options.clone()).unwrap(),
+                    expected,
+                    "{a:?} {b:?}"
+                );
+                assert_eq!(
+                    parse_chunks(&[b"[", a, b, b"]"], options).unwrap(),
+                    in_array,
+                    "{a:?} {b:?}"
+                );
+            }
+
+            // every byte in its own buffer
+            if i == 0 {
+                let bytes = number.as_bytes().chunks(1).collect::<Vec<_>>();
+                assert_eq!(
+                    parse_chunks(&bytes, strict.clone()).unwrap(),
+                    expected,
+                    "{number}"
+                );
+            }
+
+            // white space between two parts of a number
+            if i > 0 && i < number.len() {
+                assert!(
+                    parse_chunks(&[a, b" ", b], strict.clone()).is_err(),
+                    "{a:?} {b:?}"
+                );
+                assert!(
+                    parse_chunks(&[b"[", a, b" ", b, b"]"], strict.clone()).is_err(),
+                    "{a:?} {b:?}"
+                );
+            }
+        }
+    }
+
+    // in streaming mode, white space separates two values
+    assert_eq!(
+        parse_chunks(&[b"1", b" ", b"2"], streaming.clone()).unwrap(),
+        vec![
+            (JsonEvent::ValueInt, "1".to_string()),
+            (JsonEvent::ValueInt, "2".to_string()),
+        ]
+    );
+    assert!(matches!(
+        parse_chunks(&[b"1 ", b".5"], streaming),
+        Err(ParserError::SyntaxError)
+    ));
+    assert!(matches!(
+        parse_chunks(&[b"1", b" ", b".5"], strict),
+        Err(ParserError::TrailingData(2))
+    ));
+}
+
+/// Test that the number of top-level values can be limited
+#[test]
+fn max_values() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .with_max_values(3)
+        .build();
+
+    let events = parse_events_with_options(br#"1 {"a": [2]} "b"  "#, options.clone()).unwrap();
+    assert_eq!(events.len(), 8);
+
+    for json in [&b"1 2 3 4"[..], b"1 2 3 [4]", b"[] {} 3 {}", b"1 2 3 null"] {
+        assert!(
+            matches!(
+                parse_events_with_options(json, options.clone()),
+                Err(ParserError::TooManyValues(3))
+            ),
+            "{}",
+            std::str::from_utf8(json).unwrap()
+        );
+    }
+
+    // the limit also applies without streaming
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_values(0)
+        .build();
+    let err = parse_events_with_options(b"1", options).unwrap_err();
+    assert!(matches!(err, ParserError::TooManyValues(0)));
+    assert_eq!(err.category(), ErrorCategory::Limit);
+}
+
+/// Test that the path to the current value is tracked correctly
+#[test]
+fn path_tracking() {
+    let json = r#"{"a":[1,{"b/c":null},[]],"d~":{"e":"f"},"g":2}"#.as_bytes();
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_path_tracking(true)
+            .build(),
+    );
+
+    let mut i = 0;
+    let mut pointers = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            if i < json.len() {
+                parser.feeder.push_bytes(&json[i..i + 1]);
+                i += 1;
+            } else {
+                parser.feeder.done();
+            }
+        } else {
+            pointers.push(parser.current_pointer());
+        }
+    }
+
+    assert_eq!(
+        pointers,
+        vec![
+            "",
+            "/a",
+            "/a",
+            "/a/0",
+            "/a/1",
+            "/a/1/b~1c",
+            "/a/1/b~1c",
+            "/a/1",
+            "/a/2",
+            "/a/2",
+            "/a",
+            "/d~0",
+            "/d~0",
+            "/d~0/e",
+            "/d~0/e",
+            "/d~0",
+            "/g",
+            "/g",
+            ""
+        ]
+    );
+    assert_eq!(parser.current_path(), &[]);
+}
+
+/// Test that the stack of open objects and arrays is reported correctly
+#[test]
+fn container_stack() {
+    let json = br#"[{"a": [1, {"b": 2}]}, 3]"#;
+    for path_tracking in [true, false] {
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(json),
+            JsonParserOptionsBuilder::default()
+                .with_path_tracking(path_tracking)
+                .build(),
+        );
+        let field = |f| if path_tracking { Some(f) } else { None };
+
+        let expected = [
+            vec![
+                ContainerFrame::Array { count: 0 },
+                ContainerFrame::Object {
+                    count: 0,
+                    field: field("a"),
+                },
+                ContainerFrame::Array { count: 1 },
+            ],
+            vec![
+                ContainerFrame::Array { count: 0 },
+                ContainerFrame::Object {
+                    count: 0,
+                    field: field("a"),
+                },
+                ContainerFrame::Array { count: 1 },
+                ContainerFrame::Object {
+                    count: 1,
+                    field: field("b"),
+                },
+            ],
+            vec![ContainerFrame::Array { count: 2 }],
+        ];
+
+        assert_eq!(parser.container_stack().count(), 0);
+        let mut i = 0;
+        while let Some(e) = parser.next_event().unwrap() {
+            if e == JsonEvent::ValueInt {
+                assert_eq!(parser.container_stack().collect::<Vec<_>>(), expected[i]);
+                i += 1;
+            }
+        }
+        assert_eq!(i, expected.len());
+        assert_eq!(parser.container_stack().count(), 0);
+    }
+}
+
+/// Test that [`JsonParser::context()`] describes the parser's position
+#[test]
+fn context() {
+    let json = r#"{"a": [1, {"b": "x\u0079"}], "c": true}"#;
+    for path_tracking in [false, true] {
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(json.as_bytes()),
+            JsonParserOptionsBuilder::default()
+                .with_path_tracking(path_tracking)
+                .build(),
+        );
+
+        let context = parser.context();
+        assert_eq!(context.event(), JsonEvent::NeedMoreInput);
+        assert_eq!(context.depth(), 0);
+        assert_eq!(context.container(), None);
+        assert_eq!(context.offset(), 0);
+
+        let mut summary = Vec::new();
+        while let Some(e) = parser.next_event().unwrap() {
+            let context = parser.context();
+            assert_eq!(context.event(), e);
+            assert_eq!(context.depth(), parser.container_stack().count());
+            assert_eq!(context.container(), parser.container_stack().last());
+            assert_eq!(context.offset(), parser.parsed_bytes());
+            assert_eq!(context.path().is_some(), path_tracking);
+            if path_tracking {
+                assert_eq!(context.pointer().unwrap(), parser.current_pointer());
+            } else {
+                assert_eq!(context.pointer(), None);
+            }
+            summary.push(format!(
+                "{:?} {} {}",
+                context.key(),
+                String::from_utf8_lossy(context.bytes()),
+                context.depth()
+            ));
+        }
+
+        let key = |k: &'static str| if path_tracking { Some(k) } else { None };
+        let expected = [
+            (None, "", 1),
+            (Some("a"), "a", 1),
+            (None, "", 2),
+            (None, "1", 2),
+            (None, "", 3),
+            (Some("b"), "b", 3),
+            (key("b"), "xy", 3),
+            (None, "", 2),
+            (key("a"), "", 1),
+            (Some("c"), "c", 1),
+            (key("c"), "", 1),
+            (None, "", 0),
+        ]
+        .map(|(k, b, d)| format!("{k:?} {b} {d}"));
+        assert_eq!(summary, expected);
+    }
+}
+
+/// Test that values can be skipped
+#[test]
+fn skip_value() {
+    let json = r#"[{"a":[1,[2]],"b":3,"c":{"d":4}},[5],6]"#.as_bytes();
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+
+    let mut i = 0;
+    let mut events = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => {
+                if i < json.len() {
+                    parser.feeder.push_bytes(&json[i..i + 1]);
+                    i += 1;
+                } else {
+                    parser.feeder.done();
+                }
+                continue;
+            }
+            JsonEvent::FieldName => {
+                let name = parser.current_str().unwrap();
+                if name == "a" || name == "b" {
+                    parser.skip_value();
+                }
+            }
+            JsonEvent::StartArray if parser.current_container_count() == 0 && i > 1 => {
+                parser.skip_value();
+            }
+            _ => {}
+        }
+        events.push((e, parser.current_container_count()));
+    }
+
+    assert_eq!(
+        events,
+        vec![
+            (JsonEvent::StartArray, 0),
+            (JsonEvent::StartObject, 0),
+            (JsonEvent::FieldName, 0),
+            (JsonEvent::FieldName, 1),
+            (JsonEvent::FieldName, 2),
+            (JsonEvent::StartObject, 0),
+            (JsonEvent::FieldName, 0),
+            (JsonEvent::ValueInt, 1),
+            (JsonEvent::EndObject, 3),
+            (JsonEvent::EndObject, 1),
+            (JsonEvent::StartArray, 0),
+            (JsonEvent::ValueInt, 3),
+            (JsonEvent::EndArray, 0),
+        ]
+    );
+}
+
+/// Test that the rest of a top-level value can be skipped from any position
+#[test]
+fn skip_to_value_end() {
+    let json = br#"{"a": [1, "x", {"b": [2]}], "c": "]"} 3 [{}]"#;
+    let options = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .with_path_tracking(true)
+        .build();
+
+    // skip after the n-th event and return the remaining events
+    let skip_after = |n: usize| {
+        let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options.clone());
+        let mut events = vec![];
+        let mut i = 0;
+        let mut count = 0;
+        while let Some(e) = parser.next_event().unwrap() {
+            if e == JsonEvent::NeedMoreInput {
+                if i < json.len() {
+                    i += parser.feeder.push_bytes(&json[i..i + 1]);
+                } else {
+                    parser.feeder.done();
+                }
+                continue;
+            }
+            count += 1;
+            if count == n {
+                parser.skip_to_value_end();
+            } else if count > n {
+                events.push(e);
+                assert!(parser.current_path().len() <= 1);
+            }
+        }
+        events
+    };
+
+    let rest = vec![
+        JsonEvent::ValueInt,
+        JsonEvent::StartArray,
+        JsonEvent::StartObject,
+        JsonEvent::EndObject,
+        JsonEvent::EndArray,
+    ];
+    for n in 1..=15 {
+        assert_eq!(skip_after(n), rest, "skip after {} events", n);
+    }
+
+    // no effect outside of objects and arrays
+    assert_eq!(skip_after(16), &rest[1..]);
+}
+
+/// Test that Base64-encoded string values can be decoded incrementally
+#[test]
+fn decode_base64() {
+    let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+
+    // encode without an external crate
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut b64 = String::new();
+    for group in data.chunks(3) {
+        let mut acc = 0u32;
+        for (i, b) in group.iter().enumerate() {
+            acc |= (*b as u32) << (16 - i * 8);
+        }
+        for i in 0..=group.len() {
+            b64.push(CHARS[(acc >> (18 - i * 6) & 0x3f) as usize] as char);
+        }
+    }
+    let json = format!(r#"{{"name": "x", "data": "{}", "more": 1}}"#, b64);
+
+    for streaming_strings in [false, true] {
+        for chunk_size in [1, 3, 7, 64, 1024] {
+            let mut parser = JsonParser::new_with_options(
+                PushJsonFeeder::new(),
+                JsonParserOptionsBuilder::default()
+                    .with_streaming_strings(streaming_strings)
+                    .build(),
+            );
+            let mut decoded = vec![];
+            let mut max_buffered = 0;
+            let mut i = 0;
+            let mut in_data = false;
+            while let Some(e) = parser.next_event().unwrap() {
+                match e {
+                    JsonEvent::NeedMoreInput => {
+                        if in_data {
+                            parser
+                                .decode_base64_into(Base64Alphabet::UrlSafe, &mut decoded)
+                                .unwrap();
+                            max_buffered = max_buffered.max(parser.current_str().unwrap().len());
+                        }
+                        if i < json.len() {
+                            let end = json.len().min(i + chunk_size);
+                            i += parser.feeder.push_bytes(&json.as_bytes()[i..end]);
+                        } else {
+                            parser.feeder.done();
+                        }
+                    }
+                    JsonEvent::FieldName => {
+                        in_data = parser.current_str().unwrap() == "data";
+                        assert_eq!(
+                            parser
+                                .decode_base64_into(Base64Alphabet::UrlSafe, &mut decoded)
+                                .unwrap(),
+                            0
+                        );
+                    }
+                    JsonEvent::ValueString if in_data => {
+                        parser
+                            .decode_base64_into(Base64Alphabet::UrlSafe, &mut decoded)
+                            .unwrap();
+                        in_data = false;
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(decoded, data);
+            if streaming_strings {
+                assert!(max_buffered < chunk_size + 4);
+            }
+        }
+    }
+
+    // invalid characters
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(br#""ab+/""#));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+    assert!(matches!(
+        parser.decode_base64_into(Base64Alphabet::UrlSafe, &mut vec![]),
+        Err(Base64Error::InvalidCharacter('+'))
+    ));
+}
+
+/// Test that parsing can be resumed from a saved state at every event
+#[test]
+fn save_and_restore_state() {
+    let json = br#"{"a": [1, 2.5, "x", {"b": null}], "c": true} [3] "d" 4 5 [[[6], 7]]"#;
+    let default = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .with_path_tracking(true)
+        .with_position_tracking(true);
+    let all_options = [
+        default.clone().build(),
+        default
+            .clone()
+            .with_max_depth(2)
+            .with_clamp_depth(true)
+            .build(),
+        default
+            .with_max_depth(2)
+            .with_skip_beyond_max_depth(true)
+            .build(),
+    ];
+
+    // collect events together with the pointers and columns they occur at
+    let collect = |parser: &mut JsonParser<SliceJsonFeeder>| {
+        let mut events = vec![];
+        while let Some(e) = parser.next_event().unwrap() {
+            events.push((e, parser.current_pointer(), parser.current_column()));
+        }
+        events
+    };
+
+    for options in all_options {
+        let expected = collect(&mut JsonParser::new_with_options(
+            SliceJsonFeeder::new(json),
+            options.clone(),
+        ));
+
+        for n in 0..expected.len() {
+            let mut parser =
+                JsonParser::new_with_options(SliceJsonFeeder::new(json), options.clone());
+            for _ in 0..n {
+                parser.next_event().unwrap();
+            }
+            let state = parser.save_state();
+
+            #[cfg(feature = "serde")]
+            let state: actson::parser::ParserState =
+                serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+            let rest = &json[state.parsed_bytes()..];
+            let mut parser =
+                JsonParser::new_with_options(SliceJsonFeeder::new(rest), options.clone());
+            parser.restore_state(state).unwrap();
+            assert_eq!(
+                collect(&mut parser),
+                expected[n..],
+                "resume after {} events",
+                n
+            );
+        }
+    }
+}
+
+/// Test that inconsistent states are rejected instead of making the parser
+/// panic later
+#[cfg(feature = "serde")]
+#[test]
+fn restore_invalid_state() {
+    use actson::parser::{InvalidStateError, ParserState};
+
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(br#"{"a": [1]}"#));
+    while parser.next_event().unwrap() != Some(JsonEvent::StartArray) {}
+    let state = serde_json::to_value(parser.save_state()).unwrap();
+
+    let restore = |field: &str, value: Value| {
+        let mut state = state.clone();
+        state[field] = value;
+        let state: ParserState = serde_json::from_value(state).unwrap();
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(b"1]}"));
+        let r = parser.restore_state(state);
+        // the parser must remain usable
+        while let Ok(Some(_)) = parser.next_event() {}
+        r
+    };
+
+    assert_eq!(restore("state", Value::from(6)), Ok(()));
+    assert_eq!(
+        restore("state", Value::from(-1)),
+        Err(InvalidStateError::UnknownState(-1))
+    );
+    assert_eq!(
+        restore("state", Value::from(31)),
+        Err(InvalidStateError::UnknownState(31))
+    );
+    assert_eq!(
+        restore("stack", serde_json::json!([])),
+        Err(InvalidStateError::InvalidStack)
+    );
+    assert_eq!(
+        restore("stack", serde_json::json!([1, 3, 7])),
+        Err(InvalidStateError::InvalidStack)
+    );
+    assert_eq!(
+        restore("stack", serde_json::json!([1, 3])),
+        Err(InvalidStateError::DepthMismatch)
+    );
+    assert_eq!(
+        restore("array_depth", Value::from(2)),
+        Err(InvalidStateError::DepthMismatch)
+    );
+    assert_eq!(
+        restore("clamped_depth", Value::from(3)),
+        Err(InvalidStateError::DepthMismatch)
+    );
+    assert_eq!(
+        restore("containers", serde_json::json!([])),
+        Err(InvalidStateError::ContainerMismatch)
+    );
+    assert_eq!(
+        restore(
+            "containers",
+            serde_json::json!([
+                {"is_object": true, "count": 0},
+                {"is_object": true, "count": 0}
+            ])
+        ),
+        Err(InvalidStateError::ContainerMismatch)
+    );
+    assert_eq!(
+        restore("pending_events", serde_json::json!(["EndObject"])),
+        Err(InvalidStateError::EventMismatch)
+    );
+    assert_eq!(
+        restore("current_event", serde_json::json!("FieldName")),
+        Err(InvalidStateError::EventMismatch)
+    );
+    assert_eq!(
+        restore("skip_until", Value::from(3)),
+        Err(InvalidStateError::InvalidSkip)
+    );
+
+    // events that do not fit the stack are rejected
+    let mut parser = JsonParser::new_with_options(
+        SliceJsonFeeder::new(br#"{"a": 1}"#),
+        JsonParserOptionsBuilder::default()
+            .with_path_tracking(true)
+            .build(),
+    );
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartObject));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::FieldName));
+    let mut state = serde_json::to_value(parser.save_state()).unwrap();
+    let mut modified = state.clone();
+    modified["pending_events"] = serde_json::json!(["EndArray", "EndArray"]);
+    modified["containers"] = serde_json::json!([]);
+    modified["path"] = serde_json::json!([]);
+    assert_eq!(
+        parser.restore_state(serde_json::from_value(modified).unwrap()),
+        Err(InvalidStateError::EventMismatch)
+    );
+    state["path"] = serde_json::json!([]);
+    assert_eq!(
+        parser.restore_state(serde_json::from_value(state.clone()).unwrap()),
+        Err(InvalidStateError::PathMismatch)
+    );
+    state["path"] = serde_json::json!([{"Index": 0}]);
+    assert_eq!(
+        parser.restore_state(serde_json::from_value(state).unwrap()),
+        Err(InvalidStateError::PathMismatch)
+    );
+}
+
+/// Test that events can be collected in batches
+#[test]
+fn drain_into() {
+    let json = br#"{"a": [1, -2.5e3, "x", {}], "b": null} [true]"#;
+    let options = JsonParserOptionsBuilder::default()
+        .with_streaming(true)
+        .build();
+
+    let mut expected = vec![];
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(json), options.clone());
+    while let Some(e) = parser.next_event().unwrap() {
+        expected.push(e);
+    }
+
+    for capacity in [1, 2, 5, 100] {
+        for max in [1, 3, usize::MAX] {
+            let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options.clone());
+            let mut batch = EventBatch::new(capacity);
+            let mut collected = vec![];
+            let mut i = 0;
+            while let Some(e) = parser.drain_into(&mut batch, max).unwrap() {
+                assert!(batch.len() <= capacity.min(max));
+                collected.extend(batch.drain());
+                if e == JsonEvent::NeedMoreInput {
+                    if i < json.len() {
+                        i += parser.feeder.push_bytes(&json[i..json.len().min(i + 4)]);
+                    } else {
+                        parser.feeder.done();
+                    }
+                }
+            }
+            assert!(batch.is_empty());
+
+            assert_eq!(
+                collected.iter().map(|e| e.event).collect::<Vec<_>>(),
+                expected
+            );
+            let values = collected
+                .iter()
+                .filter_map(|e| e.value.as_deref().map(|v| (v, e.depth)))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                values,
+                vec![("a", 1), ("1", 2), ("-2.5e3", 2), ("x", 2), ("b", 1)]
+            );
+            let depths = collected.iter().map(|e| e.depth).collect::<Vec<_>>();
+            assert_eq!(depths, vec![1, 1, 2, 2, 2, 2, 3, 3, 2, 1, 1, 1, 1, 1, 1]);
+        }
+    }
+
+    // nothing happens if the batch is full
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1, 2]"));
+    let mut batch = EventBatch::new(1);
+    assert_eq!(
+        parser.drain_into(&mut batch, 10).unwrap(),
+        Some(JsonEvent::StartArray)
+    );
+    assert!(batch.is_full());
+    assert_eq!(
+        parser.drain_into(&mut batch, 10).unwrap(),
+        Some(JsonEvent::StartArray)
+    );
+    assert_eq!(batch.pop().unwrap().event, JsonEvent::StartArray);
+    assert_eq!(
+        parser.drain_into(&mut batch, 10).unwrap(),
+        Some(JsonEvent::ValueInt)
+    );
+}
+
+/// Test that empty field names and field names matching a predicate can be
+/// rejected
+#[test]
+fn reject_keys() {
+    let json = r#"{"a": {"b": 1, "": 2}}"#;
+
+    // empty keys are valid by default
+    assert_json_eq(json, &parse(json));
+    let json = json.as_bytes();
+
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_reject_empty_keys(true)
+            .with_path_tracking(true)
+            .build(),
+    );
+    let err = parse_fail_with_parser(json, &mut parser);
+    assert!(matches!(err, ParserError::EmptyKey(15)));
+    assert_eq!(err.category(), ErrorCategory::Syntax);
+    assert_eq!(&json[15..17], b"\"\"");
+    assert_eq!(parser.current_pointer(), "/a/");
+
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_reject_keys(Arc::new(|k| k.contains('.')))
+            .with_path_tracking(true)
+            .build(),
+    );
+    let err = parse_fail_with_parser(br#"{"a": 1, "b": {"": 1, "c.d": 2}}"#, &mut parser);
+    assert!(matches!(err, ParserError::KeyRejected(k) if k.as_str() == "c.d"));
+    assert_eq!(parser.current_pointer(), "/b/c.d");
+
+    // the predicate sees decoded field names and can capture state
+    let denied = String::from("\u{e9}");
+    let options = JsonParserOptionsBuilder::default()
+        .with_reject_keys(Arc::new(move |k| k == denied))
+        .build();
+    let err = parse_fail_with_parser(
+        br#"{"\u00e9": 1}"#,
+        &mut JsonParser::new_with_options(PushJsonFeeder::new(), options.clone()),
+    );
+    assert!(matches!(err, ParserError::KeyRejected(k) if k.as_str() == "\u{e9}"));
+    assert!(options.reject_keys().is_some_and(|f| f("\u{e9}")));
+
+    // string values are not affected
+    let json = r#"{"a": ["", "c.d"]}"#;
+    assert_json_eq(
+        json,
+        &parse_with_parser(
+            json,
+            &mut JsonParser::new_with_options(
+                PushJsonFeeder::new(),
+                JsonParserOptionsBuilder::default()
+                    .with_reject_empty_keys(true)
+                    .with_reject_keys(Arc::new(|k| k.contains('.')))
+                    .build(),
+            ),
+        ),
+    );
+}
+
+/// Test that numbers can be accepted, rejected, or rewritten by a hook
+#[test]
+fn number_hook() {
+    fn hook(n: &str) -> NumberDecision {
+        match n {
+            "13" => NumberDecision::Reject,
+            "1e2" => NumberDecision::Rewrite("100".to_string()),
+            "7" => NumberDecision::Rewrite("7.5".to_string()),
+            _ => NumberDecision::Accept,
+        }
+    }
+
+    let options = JsonParserOptionsBuilder::default()
+        .with_number_hook(Arc::new(hook))
+        .with_raw_values(true)
+        .build();
+    assert!(options
+        .number_hook()
+        .is_some_and(|f| f("13") == NumberDecision::Reject));
+
+    // feed byte by byte and collect numbers and their raw text
+    let json = br#"{"a": [1e2, 7, 2.5], "b": "13", "c": 7}"#;
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options.clone());
+    let mut i = 0;
+    let mut numbers = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => {
+                if i < json.len() {
+                    i += parser.feeder.push_bytes(&json[i..i + 1]);
+                } else {
+                    parser.feeder.done();
+                }
+            }
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => numbers.push((
+                e,
+                parser.current_raw_number().unwrap().to_string(),
+                parser.current_raw().unwrap().to_string(),
+            )),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        numbers,
+        vec![
+            (JsonEvent::ValueInt, "100".to_string(), "1e2".to_string()),
+            (JsonEvent::ValueFloat, "7.5".to_string(), "7".to_string()),
+            (JsonEvent::ValueFloat, "2.5".to_string(), "2.5".to_string()),
+            (JsonEvent::ValueFloat, "7.5".to_string(), "7".to_string()),
+        ]
+    );
+
+    // rejected numbers cause an error, also at the end of the input
+    let err = parse_fail_with_parser(
+        b"[1, 13]",
+        &mut JsonParser::new_with_options(PushJsonFeeder::new(), options.clone()),
+    );
+    assert!(matches!(&err, ParserError::NumberRejected(n) if n.as_str() == "13"));
+    assert_eq!(err.category(), ErrorCategory::Syntax);
+
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"13"), options.clone());
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::NumberRejected(n)) if n.as_str() == "13"
+    ));
+
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"1e2"), options);
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert_eq!(parser.current_int::<i32>().unwrap(), 100);
+}
+
+/// Test that a number hook receives tokens in other number formats before
+/// the grammar rejects them and that rewritten numbers are validated
+#[test]
+fn number_hook_dialect() {
+    fn hook(n: &str) -> NumberDecision {
+        if let Some(hex) = n.strip_prefix("0x") {
+            match i64::from_str_radix(hex, 16) {
+                Ok(v) => NumberDecision::Rewrite(v.to_string()),
+                Err(_) => NumberDecision::Reject,
+            }
+        } else if let Some(frac) = n.strip_prefix('.') {
+            NumberDecision::Rewrite(format!("0.{frac}"))
+        } else if n == "+1" {
+            NumberDecision::Rewrite("1.".to_string())
+        } else {
+            NumberDecision::Accept
+        }
+    }
+
+    let options = JsonParserOptionsBuilder::default()
+        .with_number_hook(Arc::new(hook))
+        .with_underscore_separators(true)
+        .with_raw_values(true)
+        .build();
+
+    let json = b"[0x1F, .5, 1_000, 0x10]";
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options.clone());
+    let mut i = 0;
+    let mut numbers = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => {
+                if i < json.len() {
+                    i += parser.feeder.push_bytes(&json[i..i + 1]);
+                } else {
+                    parser.feeder.done();
+                }
+            }
+            JsonEvent::ValueInt | JsonEvent::ValueFloat => numbers.push((
+                e,
+                parser.current_raw_number().unwrap().to_string(),
+                parser.current_raw().unwrap().to_string(),
+            )),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        numbers,
+        vec![
+            (JsonEvent::ValueInt, "31".to_string(), "0x1F".to_string()),
+            (JsonEvent::ValueFloat, "0.5".to_string(), ".5".to_string()),
+            (JsonEvent::ValueInt, "1000".to_string(), "1_000".to_string()),
+            (JsonEvent::ValueInt, "16".to_string(), "0x10".to_string()),
+        ]
+    );
+
+    // a top-level number at the end of the input
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b".25"), options.clone());
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueFloat));
+    assert_eq!(parser.current_float().unwrap(), 0.25);
+    assert_eq!(parser.next_event().unwrap(), None);
+
+    // the hook can reject tokens in other formats
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"[0xZZ]"), options.clone());
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::NumberRejected(n)) if n.as_str() == "0xZZ"
+    ));
+
+    // rewritten numbers must be valid JSON numbers
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"[+1]"), options.clone());
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::NumberRejected(n)) if n.as_str() == "+1"
+    ));
+
+    // accepted tokens are still checked by the grammar
+    let mut parser =
+        JsonParser::new_with_options(SliceJsonFeeder::new(b"[1, 01]"), options.clone());
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::StartArray));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert!(matches!(
+        parser.next_event(),
+        Err(ParserError::MalformedNumber(5))
+    ));
+
+    let mut parser = JsonParser::new_with_options(SliceJsonFeeder::new(b"1x"), options);
+    assert!(matches!(parser.next_event(), Err(ParserError::SyntaxError)));
+}
+
+/// Test that a hook that accepts every number does not change how the input
+/// is parsed
+#[test]
+fn number_hook_accept_all() {
+    let tokens = Arc::new(Mutex::new(Vec::new()));
+    let collected = tokens.clone();
+    let hook = move |n: &str| {
+        collected.lock().unwrap().push(n.to_string());
+        NumberDecision::Accept
+    };
+
+    for json in [
+        &b"1true"[..],
+        b"1 2-3",
+        b"-0.5e3null 7false",
+        b"[1true]",
+        b"[1x]",
+        b"[1.x]",
+        b"01",
+        b"[-]",
+    ] {
+        let events = |builder: JsonParserOptionsBuilder| {
+            let mut parser = JsonParser::new_with_options(
+                SliceJsonFeeder::new(json),
+                builder.with_streaming(true).build(),
+            );
+            let mut events = Vec::new();
+            loop {
+                match parser.next_event() {
+                    Ok(Some(e)) => events.push(Ok(e)),
+                    Ok(None) => return events,
+                    Err(e) => {
+                        events.push(Err(e.to_string()));
+                        return events;
+                    }
+                }
+            }
+        };
+        assert_eq!(
+            events(JsonParserOptionsBuilder::default().with_number_hook(Arc::new(hook.clone()))),
+            events(JsonParserOptionsBuilder::default()),
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // the hook receives valid numbers as the grammar delimits them and
+    // invalid ones as a whole
+    assert_eq!(
+        tokens.lock().unwrap()[..5],
+        ["1", "1", "2-3", "-0.5e3", "7"]
+    );
+}
+
+/// Test that only events returned to the caller are counted
+#[test]
+fn event_count() {
+    let json = br#"{"a": [1, {"b": 2}], "c": "d"}"#;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    let mut count = 0;
+    let mut i = 0;
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            assert_eq!(parser.event_count(), count);
+            if i < json.len() {
+                i += parser.feeder.push_bytes(&json[i..i + 1]);
+            } else {
+                parser.feeder.done();
+            }
+            continue;
+        }
+        count += 1;
+        assert_eq!(parser.event_count(), count);
+        if e == JsonEvent::FieldName && parser.current_str().unwrap() == "a" {
+            // the skipped array is not counted
+            parser.skip_value();
+        }
+    }
+    assert_eq!(count, 5);
+    assert_eq!(parser.event_count(), 5);
+
+    // errors are not counted
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parse_fail_with_parser(b"[1, }", &mut parser);
+    assert!(parser.next_event().is_err());
+    assert_eq!(parser.event_count(), 2);
+}
+
+/// Test that the profiling counters are updated
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics() {
+    let json = br#"{"s": "a\"b", "n": -1.5e3, "t": [true, null]}"#;
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    let mut need_more_input = 0;
+    let mut i = 0;
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            need_more_input += 1;
+            if i < json.len() {
+                i += parser.feeder.push_bytes(&json[i..i + 1]);
+            } else {
+                parser.feeder.done();
+            }
+        }
+    }
+
+    let metrics = parser.metrics();
+    assert_eq!(metrics.need_more_input, need_more_input);
+    assert_eq!(metrics.string_bytes, 15);
+    assert_eq!(metrics.number_bytes, 6);
+    assert_eq!(metrics.whitespace_bytes, 6);
+    assert_eq!(metrics.other_bytes, 18);
+    assert_eq!(
+        metrics.string_bytes
+            + metrics.number_bytes
+            + metrics.whitespace_bytes
+            + metrics.other_bytes,
+        parser.parsed_bytes() as u64
+    );
+
+    // bytes skipped in bulk in structure-only mode are counted too
+    let mut parser = JsonParser::new_with_options(
+        SliceJsonFeeder::new(json),
+        JsonParserOptionsBuilder::default()
+            .with_structure_only(true)
+            .build(),
+    );
+    while parser.next_event().unwrap().is_some() {}
+    let structure_metrics = parser.metrics();
+    assert_eq!(structure_metrics.string_bytes, metrics.string_bytes);
+    assert_eq!(structure_metrics.number_bytes, metrics.number_bytes);
+    assert_eq!(structure_metrics.whitespace_bytes, metrics.whitespace_bytes);
+    assert_eq!(structure_metrics.other_bytes, metrics.other_bytes);
+}
+
+/// A `tracing` subscriber that collects all events as strings
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct CollectingSubscriber(Arc<Mutex<Vec<String>>>);
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for CollectingSubscriber {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        struct Visitor(String);
+        impl tracing::field::Visit for Visitor {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                self.0 += &format!(" {}={:?}", field.name(), value);
+            }
+        }
+        let meta = event.metadata();
+        let mut visitor = Visitor(format!("{} {}", meta.level(), meta.target()));
+        event.record(&mut visitor);
+        self.0.lock().unwrap().push(visitor.0);
+    }
+
+    fn enter(&self, _: &tracing::span::Id) {}
+
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+/// Test that `tracing` events are emitted for parser events and errors
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_events() {
+    let subscriber = CollectingSubscriber::default();
+    let logs = subscriber.0.clone();
+    tracing::subscriber::with_default(subscriber, || {
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(b"[1]"));
+        while parser.next_event().unwrap().is_some() {}
+
+        let mut parser = JsonParser::new(SliceJsonFeeder::new(b"{\"a\" 1}"));
+        while parser.next_event().is_ok() {}
+        assert!(parser.next_event().is_err());
+
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(b"[1 2]"),
+            JsonParserOptionsBuilder::default()
+                .with_lenient_structure(true)
+                .build(),
+        );
+        while parser.next_event().unwrap().is_some() {}
+    });
+
+    let logs = logs.lock().unwrap();
+    assert_eq!(
+        logs[..4],
+        [
+            "TRACE actson message=JSON event event=StartArray depth=1 offset=1",
+            "TRACE actson message=JSON event event=ValueInt depth=1 offset=3",
+            "TRACE actson message=JSON event event=EndArray depth=0 offset=3",
+            "TRACE actson message=end of JSON text depth=0 offset=3",
+        ]
+    );
+
+    // the error is only logged once
+    let errors = logs
+        .iter()
+        .filter(|l| l.starts_with("ERROR"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            "ERROR actson message=invalid JSON text error=syntax error: the parsed text is not valid JSON category=Syntax depth=1 offset=6"
+        ]
+    );
+
+    let warnings = logs
+        .iter()
+        .filter(|l| l.starts_with("WARN"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        ["WARN actson message=recovered from invalid JSON text error=missing comma at offset 3 depth=1 offset=4"]
+    );
+}
+
+/// Test that field names are interned
+#[test]
+fn intern_field_names() {
+    let json = r#"[{"a":1,"b":2},{"b":3,"a":4},{"c":{"a":5}}]"#;
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_intern_field_names(true)
+            .build(),
+    );
+    parser.feeder.push_bytes(json.as_bytes());
+    parser.feeder.done();
+
+    let mut names = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::FieldName {
+            let n = parser.current_field_interned().unwrap();
+            assert_eq!(&*n, parser.current_str().unwrap());
+            names.push(n);
+        }
+    }
+
+    assert_eq!(names.len(), 6);
+    assert!(std::sync::Arc::ptr_eq(&names[0], &names[3]));
+    assert!(std::sync::Arc::ptr_eq(&names[0], &names[5]));
+    assert!(std::sync::Arc::ptr_eq(&names[1], &names[2]));
+    assert!(!std::sync::Arc::ptr_eq(&names[0], &names[1]));
+
+    // interning is disabled by default
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(json.as_bytes());
+    parser.feeder.done();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::FieldName {
+            assert!(parser.current_field_interned().is_none());
+        }
+    }
+}
+
+/// Test that lines and columns are tracked correctly
+#[test]
+fn position_tracking() {
+    let json = "[\"ä😀\",\r\n\"x\",\r\r1,\n\n 2 ]";
+    let options = JsonParserOptionsBuilder::default()
+        .with_position_tracking(true)
+        .build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+    parser.feeder.push_bytes(json.as_bytes());
+    parser.feeder.done();
+
+    let mut positions = Vec::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        positions.push((
+            e,
+            parser.current_line(),
+            parser.current_column(),
+            parser.current_column_utf16(),
+        ));
+    }
+
+    assert_eq!(
+        positions,
+        vec![
+            (JsonEvent::StartArray, 0, 1, 1),
+            (JsonEvent::ValueString, 0, 5, 6),
+            (JsonEvent::ValueString, 1, 3, 3),
+            (JsonEvent::ValueInt, 3, 2, 2),
+            (JsonEvent::ValueInt, 5, 3, 3),
+            (JsonEvent::EndArray, 5, 4, 4),
+        ]
+    );
+
+    // characters that are put back in streaming mode are not counted twice
+    let options = JsonParserOptionsBuilder::default()
+        .with_position_tracking(true)
+        .with_streaming(true)
+        .build();
+    let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options);
+    parser.feeder.push_bytes(b"1\"a\"");
+    parser.feeder.done();
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueInt));
+    assert_eq!(parser.current_column(), parser.parsed_bytes());
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+    assert_eq!(parser.current_column(), 4);
+
+    // positions are not tracked by default
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(b"\n\n[1]");
+    parser.feeder.done();
+    while parser.next_event().unwrap().is_some() {}
+    assert_eq!(parser.current_line(), 0);
+}
+
+/// Test that string values can be consumed incrementally, even if escape
+/// sequences and multi-byte characters are split across chunks
+#[test]
+fn string_chars() {
+    let json = r#"{"k\u00e9y": "a\u00e9\n€\ud83d\ude00b", "n": "xyz"}"#;
+    let mut parser = JsonParser::new_with_options(
+        PushJsonFeeder::new(),
+        JsonParserOptionsBuilder::default()
+            .with_streaming_strings(true)
+            .build(),
+    );
+
+    let mut values = Vec::new();
+    let mut field_names = Vec::new();
+    let mut current = String::new();
+    let mut max_part = 0;
+    let mut i = 0;
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => {
+                let part = parser.string_chars().collect::<String>();
+                max_part = max_part.max(part.len());
+                current.push_str(&part);
+                if i < json.len() {
+                    i += parser.feeder.push_bytes(&json.as_bytes()[i..i + 1]);
+                } else {
+                    parser.feeder.done();
+                }
+            }
+            JsonEvent::FieldName => field_names.push(parser.current_str().unwrap().to_string()),
+            JsonEvent::ValueString => {
+                current.push_str(parser.current_str().unwrap());
+                values.push(std::mem::take(&mut current));
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(field_names, vec!["k\u{e9}y", "n"]);
+    assert_eq!(values, vec!["a\u{e9}\n€\u{1f600}b", "xyz"]);
+    assert!(max_part <= 4);
+}
+
+/// Test that the number of characters in a string is computed correctly,
+/// even if the string is fed byte by byte or consumed incrementally
+#[test]
+fn str_char_len() {
+    let json = r#"["", "abc", "\"\\\/\b\f\n\r\t", "\u0041\u00e4\u20ac", "äö€😀",
+        "\ud83d\ude00\uD83D\uDE00x", "1 2 3", {"k\u00e9y😀": 1}]"#;
+
+    // characters are only counted during parsing if there is a limit
+    for (streaming_strings, max_chars) in [
+        (false, None),
+        (true, None),
+        (false, Some(100)),
+        (true, Some(100)),
+    ] {
+        let mut builder =
+            JsonParserOptionsBuilder::default().with_streaming_strings(streaming_strings);
+        if let Some(max_chars) = max_chars {
+            builder = builder.with_max_string_chars(max_chars);
+        }
+        let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), builder.build());
+
+        let mut lens = Vec::new();
+        let mut current = String::new();
+        let mut i = 0;
+        while let Some(e) = parser.next_event().unwrap() {
+            match e {
+                JsonEvent::NeedMoreInput => {
+                    current.extend(parser.string_chars());
+                    if i < json.len() {
+                        i += parser.feeder.push_bytes(&json.as_bytes()[i..i + 1]);
+                    } else {
+                        parser.feeder.done();
+                    }
+                }
+                JsonEvent::FieldName | JsonEvent::ValueString => {
+                    current.push_str(parser.current_str().unwrap());
+                    let len = std::mem::take(&mut current).chars().count();
+                    assert_eq!(parser.current_str_char_len(), Some(len));
+                    lens.push(len);
+                }
+                _ => assert_eq!(parser.current_str_char_len(), None),
+            }
+        }
+
+        assert_eq!(lens, vec![0, 3, 8, 3, 4, 3, 5, 4]);
+    }
+
+    // the number of characters is updated if a string is normalized
+    #[cfg(feature = "unicode-normalization")]
+    {
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(r#""cafe\u0301""#.as_bytes()),
+            JsonParserOptionsBuilder::default()
+                .with_nfc_strings(true)
+                .build(),
+        );
+        assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::ValueString));
+        assert_eq!(parser.current_str_char_len(), Some(4));
+    }
+}
+
+/// Test that [`JsonParser::expect()`] accepts all events a value of the
+/// expected kind can start with
+#[test]
+fn expect_kind() {
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    assert_eq!(
+        parser.expect(JsonEventKind::Object).unwrap(),
+        Some(JsonEvent::NeedMoreInput)
+    );
+    parser.feeder.push_bytes(br#"{"a": [1, 2.5, 3, false]}"#);
+    parser.feeder.done();
+    assert_eq!(
+        parser.expect(JsonEventKind::Object).unwrap(),
+        Some(JsonEvent::StartObject)
+    );
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::FieldName));
+    assert_eq!(
+        parser.expect(JsonEventKind::Array).unwrap(),
+        Some(JsonEvent::StartArray)
+    );
+    assert_eq!(
+        parser.expect(JsonEventKind::Number).unwrap(),
+        Some(JsonEvent::ValueInt)
+    );
+    assert_eq!(
+        parser.expect(JsonEventKind::Number).unwrap(),
+        Some(JsonEvent::ValueFloat)
+    );
+    assert_eq!(
+        parser.expect(JsonEventKind::Integer).unwrap(),
+        Some(JsonEvent::ValueInt)
+    );
+    let err = parser.expect(JsonEventKind::Null).unwrap_err();
+    assert!(matches!(
+        err,
+        ParserError::UnexpectedEvent {
+            expected: JsonEventKind::Null,
+            found: Some(JsonEvent::ValueFalse)
+        }
+    ));
+    assert_eq!(err.category(), ErrorCategory::Syntax);
+    assert_eq!(
+        err.to_string(),
+        "expected Null value but found Some(ValueFalse)"
+    );
+    assert!(matches!(
+        parser.expect(JsonEventKind::Boolean),
+        Err(ParserError::UnexpectedEvent {
+            expected: JsonEventKind::Boolean,
+            found: Some(JsonEvent::EndArray)
+        })
+    ));
+    assert_eq!(parser.next_event().unwrap(), Some(JsonEvent::EndObject));
+    assert!(matches!(
+        parser.expect(JsonEventKind::Object),
+        Err(ParserError::UnexpectedEvent {
+            expected: JsonEventKind::Object,
+            found: None
+        })
+    ));
+}
+
+/// Test that exponents and fractions can be disallowed separately
+#[test]
+fn disallow_exponents_and_fractions() {
+    let no_exponents = JsonParserOptionsBuilder::default()
+        .with_disallow_exponents(true)
+        .build();
+    let no_fractions = JsonParserOptionsBuilder::default()
+        .with_disallow_fractions(true)
+        .build();
+
+    let json = b"[1, -0.5, 20]";
+    assert!(parse_events_with_options(json, no_exponents.clone()).is_ok());
+    assert!(matches!(
+        parse_events_with_options(json, no_fractions.clone()),
+        Err(ParserError::FractionNotAllowed(6))
+    ));
+
+    let json = b"[1, 2E3, 0e-1]";
+    assert!(parse_events_with_options(json, no_fractions).is_ok());
+    assert!(matches!(
+        parse_events_with_options(json, no_exponents.clone()),
+        Err(ParserError::ExponentNotAllowed(5))
+    ));
+    let err = parse_events_with_options(b"1.5e3", no_exponents.clone()).unwrap_err();
+    assert!(matches!(err, ParserError::ExponentNotAllowed(3)));
+    assert_eq!(
+        err.to_string(),
+        "number in exponent notation not allowed at byte 3"
+    );
+
+    // 'e' is still allowed in strings and keywords
+    assert!(parse_events_with_options(br#"{"e": [1.0, false]}"#, no_exponents).is_ok());
+
+    // both are allowed by default
+    assert!(parse_events_with_options(b"[1.5e3]", JsonParserOptions::default()).is_ok());
+}
+
+/// Test that integers outside the safe range can be rejected
+#[test]
+fn safe_integers() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_safe_integers(true)
+        .build();
+
+    let json = b"[0, -9007199254740991, 9007199254740991, 1e300, 9007199254740992.0]";
+    assert!(parse_events_with_options(json, options.clone()).is_ok());
+
+    for (json, n) in [
+        ("9007199254740992", "9007199254740992"),
+        ("-9007199254740992", "-9007199254740992"),
+        ("[1, 18446744073709551616]", "18446744073709551616"),
+        (
+            r#"{"a": 99999999999999999999999}"#,
+            "99999999999999999999999",
+        ),
+    ] {
+        assert!(
+            matches!(
+                parse_events_with_options(json.as_bytes(), options.clone()),
+                Err(ParserError::UnsafeInteger(i)) if i.as_str() == n
+            ),
+            "{json}"
+        );
+    }
+
+    // the input is valid JSON that exceeds a limit the caller opted into
+    let err = parse_events_with_options(b"9007199254740992", options.clone()).unwrap_err();
+    assert_eq!(err.category(), ErrorCategory::Limit);
+
+    // all integers are allowed by default
+    assert!(parse_events_with_options(b"9007199254740992", JsonParserOptions::default()).is_ok());
+}
+
+/// Test that underscores between digits are accepted if enabled, even if
+/// the input is fed byte by byte
+#[test]
+fn underscore_separators() {
+    let options = JsonParserOptionsBuilder::default()
+        .with_underscore_separators(true)
+        .with_raw_values(true)
+        .build();
+
+    let parse = |json: &[u8]| -> Result<Vec<(String, String)>, ParserError> {
+        let mut parser = JsonParser::new_with_options(PushJsonFeeder::new(), options.clone());
+        let mut i = 0;
+        let mut numbers = Vec::new();
+        while let Some(e) = parser.next_event()? {
+            match e {
+                JsonEvent::NeedMoreInput => {
+                    if i < json.len() {
+                        i += parser.feeder.push_bytes(&json[i..i + 1]);
+                    } else {
+                        parser.feeder.done();
+                    }
+                }
+                JsonEvent::ValueInt | JsonEvent::ValueFloat => numbers.push((
+                    parser.current_raw_number().unwrap().to_string(),
+                    parser.current_raw().unwrap().to_string(),
+                )),
+                _ => {}
+            }
+        }
+        Ok(numbers)
+    };
+
+    assert_eq!(
+        parse(b"[1_000, -2_5.0_1e1_0, 0.1_2, 1e+0_1, 10]").unwrap(),
+        vec![
+            ("1000".to_string(), "1_000".to_string()),
+            ("-25.01e10".to_string(), "-2_5.0_1e1_0".to_string()),
+            ("0.12".to_string(), "0.1_2".to_string()),
+            ("1e+01".to_string(), "1e+0_1".to_string()),
+            ("10".to_string(), "10".to_string()),
+        ]
+    );
+    assert_eq!(parse(b"1_2_3").unwrap()[0].0, "123");
+
+    for (json, offset) in [
+        (&b"[1__0]"[..], 3),
+        (b"[1_]", 2),
+        (b"1_", 1),
+        (b"[1_ ]", 2),
+        (b"[1_.5]", 2),
+        (b"[1._5]", 3),
+        (b"[1e_5]", 3),
+        (b"[1e+_5]", 4),
+        (b"[-_1]", 2),
+        (b"[0_1]", 2),
+        (b"[_1]", 1),
+        (b"_1", 0),
+        (br#"{"a": _1}"#, 6),
+        (b"[1, _1]", 4),
+    ] {
+        assert!(
+            matches!(parse(json), Err(ParserError::MalformedNumber(o)) if o == offset),
+            "{}",
+            String::from_utf8_lossy(json)
+        );
+    }
+
+    // underscores outside of numbers are still invalid
+    assert!(matches!(parse(b"{_: 1}"), Err(ParserError::SyntaxError)));
+    assert!(matches!(
+        parse(br#"{"a"_: 1}"#),
+        Err(ParserError::SyntaxError)
+    ));
+
+    // underscores are rejected by default
+    assert!(parse_events_with_options(b"1_000", JsonParserOptions::default()).is_err());
+}
+
+/// Test that a missing comma or colon can be tolerated
+#[test]
+fn lenient_structure() {
+    let strict = JsonParserOptionsBuilder::default().build();
+    let lenient = JsonParserOptionsBuilder::default()
+        .with_lenient_structure(true)
+        .build();
+
+    for (broken, fixed) in [
+        ("[1 2]", "[1, 2]"),
+        ("[1.5 -2e3 0 true]", "[1.5, -2e3, 0, true]"),
+        (r#"["a""b"[]{}null]"#, r#"["a", "b", [], {}, null]"#),
+        (
+            r#"{"a" 1 "b" {"c" "d"} "e": [] "f" false}"#,
+            r#"{"a": 1, "b": {"c": "d"}, "e": [], "f": false}"#,
+        ),
+        (r#"[{"a": 1}{"b": 2}]"#, r#"[{"a": 1}, {"b": 2}]"#),
+    ] {
+        assert!(
+            matches!(
+                parse_events_with_options(broken.as_bytes(), strict.clone()),
+                Err(ParserError::SyntaxError)
+            ),
+            "{broken}"
+        );
+        assert_eq!(
+            parse_events_with_options(broken.as_bytes(), lenient.clone()).unwrap(),
+            parse_events_with_options(fixed.as_bytes(), strict.clone()).unwrap(),
+            "{broken}"
+        );
+    }
+
+    // valid JSON is not affected
+    let json = r#"{"a": [1, 2, {"b": null}], "c": "d"}"#;
+    assert_eq!(
+        parse_events_with_options(json.as_bytes(), lenient.clone()).unwrap(),
+        parse_events_with_options(json.as_bytes(), strict).unwrap()
+    );
+
+    // other errors cannot be recovered
+    for json in [
+        "[01]",
+        "[1-2]",
+        "[1,,2]",
+        "[1 2",
+        r#"{"a": 1 2}"#,
+        r#"{"a" }"#,
+        r#"{1: 2}"#,
+        r#"{"a" "b" "c"}"#,
+        "1 2",
+    ] {
+        assert!(
+            parse_events_with_options(json.as_bytes(), lenient.clone()).is_err(),
+            "{json}"
+        );
+    }
+
+    // recovered errors are reported
+    let recovered = Arc::new(Mutex::new(Vec::new()));
+    let r = Arc::clone(&recovered);
+    let options = JsonParserOptionsBuilder::default()
+        .with_lenient_structure(true)
+        .with_recovery_hook(Arc::new(move |err: &RecoveredError, pointer: &str| {
+            r.lock().unwrap().push((*err, pointer.to_string()));
+        }))
+        .with_path_tracking(true)
+        .build();
+    let json = r#"{"a" [1 2], "b": {"c": 3 "d": 4}} "#;
+    parse_events_with_options(json.as_bytes(), options).unwrap();
+    assert_eq!(
+        *recovered.lock().unwrap(),
+        vec![
+            (RecoveredError::MissingColon(5), String::new()),
+            (RecoveredError::MissingComma(8), "/a".to_string()),
+            (RecoveredError::MissingComma(25), "/b".to_string()),
+        ]
+    );
+}
+
+/// Test that strings and field names can be normalized to NFC
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn nfc_strings() {
+    let json = r#"{"e\u0301":"cafe\u0301","n":1e0}"#;
+    let parse = |nfc: bool| {
+        let mut parser = JsonParser::new_with_options(
+            PushJsonFeeder::new(),
+            JsonParserOptionsBuilder::default()
+                .with_nfc_strings(nfc)
+                .with_path_tracking(true)
+                .build(),
+        );
+        parser.feeder.push_bytes(json.as_bytes());
+        parser.feeder.done();
+        let mut strs = Vec::new();
+        while let Some(e) = parser.next_event().unwrap() {
+            match e {
+                JsonEvent::FieldName | JsonEvent::ValueString => {
+                    strs.push(parser.current_str().unwrap().to_string())
+                }
+                JsonEvent::ValueFloat => {
+                    strs.push(parser.current_raw_number().unwrap().to_string());
+                    strs.push(parser.current_pointer());
+                }
+                _ => {}
+            }
+        }
+        strs
+    };
+
+    assert_eq!(parse(true), vec!["\u{e9}", "caf\u{e9}", "n", "1e0", "/n"]);
+    assert_eq!(
+        parse(false),
+        vec!["e\u{301}", "cafe\u{301}", "n", "1e0", "/n"]
+    );
+}
+
+/// Test that the parser's state can be printed for debugging purposes
+#[test]
+fn debug() {
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    parser.feeder.push_bytes(br#"{"a":[""#);
+    parser.feeder.push_bytes(&[b'x'; 40]);
+    while parser.next_event().unwrap() != Some(JsonEvent::NeedMoreInput) {}
+
+    let s = format!("{:?}", parser);
+    assert!(s.starts_with("JsonParser { current_event: StartArray, state: \"string\", depth: 2, parsed_bytes: 47, streaming: false, current_token: \"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx...\", feeder: "), "{s}");
+}
+
+/// Test that errors are categorized correctly
+#[test]
+fn error_category() {
+    let category = |json: &[u8]| parse_fail(json).category();
+    assert_eq!(category(b"[\"a\x01\"]"), ErrorCategory::Syntax);
+    assert_eq!(category(b"[1 2]"), ErrorCategory::Syntax);
+    assert_eq!(category(b"{\"a\": [1, 2"), ErrorCategory::Syntax);
+    assert_eq!(category(b"  "), ErrorCategory::Syntax);
+
+    // errors reported by the feeder are I/O errors
+    struct FailingFeeder;
+    impl JsonFeeder for FailingFeeder {
+        fn has_input(&self) -> bool {
+            false
+        }
+
+        fn is_done(&self) -> bool {
+            false
+        }
+
+        fn next_input(&mut self) -> Option<u8> {
+            None
+        }
+
+        fn check_error(&mut self) -> Result<(), FeederError> {
+            Err(FeederError::Io(std::io::ErrorKind::BrokenPipe.into()))
+        }
+    }
+    let err = JsonParser::new(FailingFeeder).next_event().unwrap_err();
+    assert!(matches!(&err, ParserError::Feeder(e) if matches!(**e, FeederError::Io(_))));
+    assert_eq!(err.category(), ErrorCategory::Io);
+}
+
+/// Test that parser errors stay small, so returning them does not slow down
+/// the parser. Strings are boxed. Depth and string limits are not, so they
+/// can still be matched directly.
+#[test]
+fn error_size() {
+    assert!(std::mem::size_of::<ParserError>() <= 24);
+}
+
+/// Test that numbers not written in canonical form are reported
+#[test]
+fn noncanonical_numbers() {
+    let parse = |json: &[u8], rules: CanonicalNumberRules| {
+        let found = Arc::new(Mutex::new(Vec::new()));
+        let f = Arc::clone(&found);
+        let mut parser = JsonParser::new_with_options(
+            SliceJsonFeeder::new(json),
+            JsonParserOptionsBuilder::default()
+                .with_noncanonical_number_hook(Arc::new(move |number, pointer| {
+                    f.lock().unwrap().push(format!("{pointer}: {number}"));
+                }))
+                .with_canonical_number_rules(rules)
+                .with_path_tracking(true)
+                .build(),
+        );
+        while parser.next_event().unwrap().is_some() {}
+        drop(parser);
+        Arc::try_unwrap(found).unwrap().into_inner().unwrap()
+    };
+
+    let json = br#"{"a": [1.500, 1E5, 1e+5, 1e05, 1.5, 10, 0.0, -2e-5], "b": 1.0E+07}"#;
+    assert_eq!(
+        parse(json, CanonicalNumberRules::default()),
+        [
+            "/a/0: 1.500",
+            "/a/1: 1E5",
+            "/a/2: 1e+5",
+            "/a/3: 1e05",
+            "/a/6: 0.0",
+            "/b: 1.0E+07"
+        ]
+    );
+
+    let mut rules = CanonicalNumberRules::default();
+    rules.lowercase_exponent = false;
+    rules.no_trailing_zeros = false;
+    assert_eq!(
+        parse(json, rules),
+        ["/a/2: 1e+5", "/a/3: 1e05", "/b: 1.0E+07"]
+    );
+
+    // top-level numbers are only reported at the end of the input
+    assert_eq!(parse(b"2.50", CanonicalNumberRules::default()), [": 2.50"]);
+}
+
+/// Parse a JSON string and write it back with a [`JsonWriter`]
+fn rewrite(json: &str, sorted_keys: bool) -> String {
+    let mut parser = JsonParser::new(SliceJsonFeeder::new(json.as_bytes()));
+    let mut writer = JsonWriter::new(Vec::new()).with_sorted_keys(sorted_keys);
+    while let Some(event) = parser.next_event().unwrap() {
+        writer.write_event(event, &parser).unwrap();
+    }
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+/// Test that values are written back correctly
+#[test]
+fn writer_roundtrip() {
+    let json = r#"{"a":[1,-2.5e3,true,false,null,"x"],"b":{},"c":[],"d":[[{}]]}"#;
+    assert_eq!(rewrite(json, false), json);
+    assert_eq!(rewrite("  42 ", false), "42");
+}
+
+/// Test that strings are escaped
+#[test]
+fn writer_escape() {
+    let json = r#"["a\"b\\c\/d\n\u0001ä"]"#;
+    assert_eq!(rewrite(json, false), "[\"a\\\"b\\\\c/d\\n\\u0001\u{e4}\"]");
+}
+
+/// Test that keys are sorted
+#[test]
+fn writer_sorted_keys() {
+    let json = r#"{"z":[{"y":1,"x":2},3],"ä":0,"a":{"c":{},"b":[]},"a":1}"#;
+    assert_eq!(
+        rewrite(json, true),
+        r#"{"a":{"b":[],"c":{}},"a":1,"z":[{"x":2,"y":1},3],"ä":0}"#
+    );
+    assert_eq!(
+        rewrite(r#"[{"b":1,"a":2},{}]"#, true),
+        r#"[{"a":2,"b":1},{}]"#
+    );
+}
+
+/// Parse the given JSON text and return all errors the validator reports
+fn validate_events(validator: &mut SimpleValidator, json: &str) -> Vec<ValidationError> {
+    let mut parser = JsonParser::new_with_options(
+        SliceJsonFeeder::new(json.as_bytes()),
+        JsonParserOptionsBuilder::default()
+            .with_path_tracking(true)
+            .build(),
+    );
+    let mut errors = vec![];
+    while let Some(event) = parser.next_event().unwrap() {
+        if let Err(e) = validator.on_event(event, &parser) {
+            errors.push(e);
+        }
+    }
+    errors
+}
+
+/// Get the JSON pointers of the given validation errors
+fn error_pointers(errors: &[ValidationError]) -> Vec<&str> {
+    errors.iter().map(|e| e.pointer.as_str()).collect()
+}
+
+/// Test that [`SimpleValidator`] checks the types of values
+#[test]
+fn validator_types() {
+    let mut validator = SimpleValidator::new()
+        .with_type("/a", ValueType::Number)
+        .with_type("/b", ValueType::Integer)
+        .with_type("/c/1", ValueType::String)
+        .with_type("/d", ValueType::Array)
+        .with_type("/e", ValueType::Boolean);
+
+    let errors = validate_events(
+        &mut validator,
+        r#"{"a": 1, "b": 1.5, "c": [1, "x"], "d": {}, "e": null}"#,
+    );
+    assert_eq!(error_pointers(&errors), vec!["/b", "/d", "/e"]);
+}
+
+/// Test that [`SimpleValidator`] checks required fields
+#[test]
+fn validator_required() {
+    let mut validator = SimpleValidator::new()
+        .with_required("", ["a", "b"])
+        .with_required("/c", ["x"]);
+
+    let errors = validate_events(&mut validator, r#"{"b": {"a": 1}, "c": {"x": 1}}"#);
+    assert_eq!(error_pointers(&errors), vec![""]);
+    assert_eq!(errors[0].message, "missing required fields: a");
+
+    let errors = validate_events(&mut validator, r#"{"a": 1, "b": 2, "c": {"y": 1}}"#);
+    assert_eq!(error_pointers(&errors), vec!["/c"]);
+}
+
+/// Test that [`SimpleValidator`] checks the range of numbers
+#[test]
+fn validator_minimum_maximum() {
+    let mut validator = SimpleValidator::new()
+        .with_minimum("/0", 0.0)
+        .with_maximum("/0", 10.0)
+        .with_minimum("/1", 0.5);
+
+    assert!(validate_events(&mut validator, "[0, 0.5]").is_empty());
+    assert!(validate_events(&mut validator, "[10, 1e3]").is_empty());
+    assert_eq!(
+        error_pointers(&validate_events(&mut validator, "[-1, 0.4]")),
+        vec!["/0", "/1"]
+    );
+    assert_eq!(
+        error_pointers(&validate_events(&mut validator, "[10.5]")),
+        vec!["/0"]
+    );
+}
+
+/// Convert the given JSON text to CSV with the given columns and options
+fn convert_to_csv(json: &str, columns: &[&str], options: CsvOptions) -> Result<String, CsvError> {
+    let mut out = Vec::new();
+    to_csv_with_options(json.as_bytes(), &mut out, columns, options)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+/// Test that cells are quoted and escaped according to RFC 4180
+#[test]
+fn csv_quoting() {
+    let json = r#"[{"a": "x,y", "b": "line\r\nbreak", "c": "\"q\"", "d": " s "}]"#;
+    assert_eq!(
+        convert_to_csv(json, &["a", "b", "c", "d"], CsvOptions::default()).unwrap(),
+        "a,b,c,d\r\n\"x,y\",\"line\r\nbreak\",\"\"\"q\"\"\", s \r\n"
+    );
+
+    // the header is quoted too
+    assert_eq!(
+        convert_to_csv("[]", &["a,b", "c"], CsvOptions::default()).unwrap(),
+        "\"a,b\",c\r\n"
+    );
+
+    // a comma does not need quotes in a TSV file
+    let options = CsvOptions::default().with_delimiter(b'\t');
+    assert_eq!(
+        convert_to_csv(json, &["a", "d"], options).unwrap(),
+        "a\td\r\nx,y\t s \r\n"
+    );
+}
+
+/// Test that values of all types are converted
+#[test]
+fn csv_values() {
+    let json = r#"[
+        {"s": "ä", "i": -12, "f": 1.50e3, "t": true, "n": null},
+        {"f": false, "s": {"x": [1, {"y": "\"z\""}]}, "i": []}
+    ]"#;
+    assert_eq!(
+        convert_to_csv(json, &["s", "i", "f", "t", "n"], CsvOptions::default()).unwrap(),
+        "s,i,f,t,n\r\n\
+         \u{e4},-12,1.50e3,true,\r\n\
+         \"{\"\"x\"\":[1,{\"\"y\"\":\"\"\\\"\"z\\\"\"\"\"}]}\",[],false,,\r\n"
+    );
+}
+
+/// Test that missing, unknown, and duplicate fields are handled
+#[test]
+fn csv_fields() {
+    let json = r#"[{"b": {"deep": [[]]}, "a": 1, "a": 2}, {}, {"c": 3}]"#;
+    let options = CsvOptions::default().with_header(false);
+    assert_eq!(
+        convert_to_csv(json, &["a", "c", "a"], options).unwrap(),
+        "2,,2\r\n,,\r\n,3,\r\n"
+    );
+    assert_eq!(convert_to_csv(json, &[], options).unwrap(), "\r\n\r\n\r\n");
+}
+
+/// Test that invalid input is rejected
+#[test]
+fn csv_invalid() {
+    let options = CsvOptions::default();
+    for json in [r#"{"a": 1}"#, "[1]", "[[]]", r#"[{"a": 1}, null]"#, "5"] {
+        assert!(matches!(
+            convert_to_csv(json, &["a"], options),
+            Err(CsvError::NotAnArrayOfObjects)
+        ));
+    }
+    assert!(matches!(
+        convert_to_csv(r#"[{"a": 1}"#, &["a"], options),
+        Err(CsvError::Parse(ParserError::NoMoreInput))
+    ));
+    assert!(matches!(
+        convert_to_csv(r#"[{"a": 1}] x"#, &["a"], options),
+        Err(CsvError::Parse(_))
+    ));
+    for d in [b'"', b'\n', b'\r', 0xff] {
+        assert!(matches!(
+            convert_to_csv("[]", &["a"], options.with_delimiter(d)),
+            Err(CsvError::InvalidDelimiter(_))
+        ));
+    }
+}
+
+/// Test that the input is read incrementally
+#[test]
+fn csv_incremental() {
+    let mut json = String::from("[");
+    for i in 0..1000 {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str(&format!(r#"{{"id": {i}, "name": "n{i}", "skip": [{i}]}}"#));
+    }
+    json.push(']');
+
+    // the input is larger than the reader's buffer
+    let mut out = Vec::new();
+    to_csv(json.as_bytes(), &mut out, &["name", "id"]).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let lines = out.split_terminator("\r\n").collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1001);
+    assert_eq!(lines[0], "name,id");
+    assert_eq!(lines[1000], "n999,999");
+}
+
+/// Create a [`TypeMismatch`] for the given pointer and kinds
+fn type_mismatch(pointer: &str, expected: JsonEventKind, found: JsonEventKind) -> TypeMismatch {
+    TypeMismatch {
+        pointer: pointer.to_string(),
+        expected,
+        found,
+    }
+}
+
+/// Test that values of all kinds are checked
+#[test]
+fn type_check_kinds() {
+    let types = HashMap::from([
+        ("o", JsonEventKind::Object),
+        ("a", JsonEventKind::Array),
+        ("s", JsonEventKind::String),
+        ("n", JsonEventKind::Number),
+        ("i", JsonEventKind::Integer),
+        ("b", JsonEventKind::Boolean),
+        ("z", JsonEventKind::Null),
+    ]);
+
+    let json = r#"{"o": {"x": 1}, "a": [[]], "s": "", "n": 1, "i": 2, "b": true, "z": null}"#;
+    assert_eq!(
+        check_top_level_types(json.as_bytes(), &types).unwrap(),
+        vec![]
+    );
+
+    let json = r#"{"o": [], "a": {}, "s": 1, "n": "1", "i": 2.5, "b": null, "z": false}"#;
+    assert_eq!(
+        check_top_level_types(json.as_bytes(), &types).unwrap(),
+        vec![
+            type_mismatch("/o", JsonEventKind::Object, JsonEventKind::Array),
+            type_mismatch("/a", JsonEventKind::Array, JsonEventKind::Object),
+            type_mismatch("/s", JsonEventKind::String, JsonEventKind::Integer),
+            type_mismatch("/n", JsonEventKind::Number, JsonEventKind::String),
+            type_mismatch("/i", JsonEventKind::Integer, JsonEventKind::Number),
+            type_mismatch("/b", JsonEventKind::Boolean, JsonEventKind::Null),
+            type_mismatch("/z", JsonEventKind::Null, JsonEventKind::Boolean),
+        ]
+    );
+}
+
+/// Test that only top-level fields are checked and that each occurrence
+/// of a duplicate field is checked
+#[test]
+fn type_check_top_level_only() {
+    let types = HashMap::from([("a/b", JsonEventKind::String), ("c", JsonEventKind::Null)]);
+    let json = r#"{"x": {"a/b": 1, "c": 2}, "a/b": 3, "c": null, "a/b": "4", "a/b": [5]}"#;
+    assert_eq!(
+        check_top_level_types(json.as_bytes(), &types).unwrap(),
+        vec![
+            type_mismatch("/a~1b", JsonEventKind::String, JsonEventKind::Integer),
+            type_mismatch("/a~1b", JsonEventKind::String, JsonEventKind::Array),
+        ]
+    );
+}
+
+/// Test that invalid input is rejected
+#[test]
+fn type_check_invalid() {
+    let types = HashMap::from([("a", JsonEventKind::String)]);
+    for json in ["[]", "1", r#""a""#] {
+        assert!(matches!(
+            check_top_level_types(json.as_bytes(), &types),
+            Err(TypeCheckError::NotAnObject)
+        ));
+    }
+    assert!(matches!(
+        check_top_level_types(r#"{"a": 1"#.as_bytes(), &types),
+        Err(TypeCheckError::Parse(ParserError::NoMoreInput))
+    ));
+    assert!(matches!(
+        check_top_level_types(r#"{"b": [1 2], "a": 1}"#.as_bytes(), &types),
+        Err(TypeCheckError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Collect the results of [`unique_scalars()`] for the given JSON text
+fn unique_values(json: &str) -> Vec<Result<OwnedScalar, UniqueScalarsError>> {
+    unique_scalars(json.as_bytes()).collect()
+}
+
+/// Test that only the first occurrence of each value is yielded
+#[test]
+fn unique_scalars_deduplicate() {
+    let json = r#"[false, "", 0, -0, "0", false, true, "", 0e0, "\"", null, null]"#;
+    let values = unique_values(json)
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            OwnedScalar::Bool(false),
+            OwnedScalar::String("".to_string()),
+            OwnedScalar::Number("0".to_string()),
+            OwnedScalar::Number("-0".to_string()),
+            OwnedScalar::String("0".to_string()),
+            OwnedScalar::Bool(true),
+            OwnedScalar::Number("0e0".to_string()),
+            OwnedScalar::String("\"".to_string()),
+            OwnedScalar::Null,
+        ]
+    );
+    assert!(unique_values("[]").is_empty());
+}
+
+/// Test that a large array with few distinct values can be processed
+#[test]
+fn unique_scalars_large() {
+    let json = format!(
+        "[{}]",
+        (0..100_000)
+            .map(|i| format!("\"v{}\"", i % 7))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    assert_eq!(unique_values(&json).len(), 7);
+}
+
+/// Test that the iterator ends after the first error
+#[test]
+fn unique_scalars_invalid() {
+    for (json, len) in [
+        (r#"{"a": 1}"#, 1),
+        ("1", 1),
+        ("[1, [2], 3]", 2),
+        ("[{}, 1]", 1),
+    ] {
+        let r = unique_values(json);
+        assert_eq!(r.len(), len);
+        assert!(matches!(
+            r.last(),
+            Some(Err(UniqueScalarsError::NotAnArrayOfScalars))
+        ));
+    }
+
+    let r = unique_values("[1, 2, 1, 3");
+    assert_eq!(r.len(), 4);
+    assert!(matches!(
+        r[3],
+        Err(UniqueScalarsError::Parse(ParserError::NoMoreInput))
+    ));
+
+    let r = unique_values("[1] 2");
+    assert_eq!(r.len(), 2);
+    assert!(matches!(
+        r[1],
+        Err(UniqueScalarsError::Parse(ParserError::TrailingData(4)))
+    ));
+}
+
+/// Test that arrays of the expected type can be parsed
+#[test]
+fn parse_vec_valid() {
+    assert_eq!(
+        parse_vec_i64("[0, -1, 9223372036854775807, -9223372036854775808]".as_bytes()).unwrap(),
+        vec![0, -1, i64::MAX, i64::MIN]
+    );
+    assert_eq!(
+        parse_vec_string(r#"["", "aä", "\"b\""]"#.as_bytes()).unwrap(),
+        vec!["", "aä", "\"b\""]
+    );
+    assert!(parse_vec_i64("[]".as_bytes()).unwrap().is_empty());
+    assert!(parse_vec_string(" [ ] ".as_bytes()).unwrap().is_empty());
+}
+
+/// Test that elements of the wrong kind are reported with their pointer
+#[test]
+fn parse_vec_unexpected_element() {
+    for (json, pointer, found) in [
+        ("[1, 2, \"3\"]", "/2", JsonEventKind::String),
+        ("[1e3]", "/0", JsonEventKind::Number),
+        ("[1, [2]]", "/1", JsonEventKind::Array),
+        ("[{}]", "/0", JsonEventKind::Object),
+        ("[null]", "/0", JsonEventKind::Null),
+    ] {
+        let err = parse_vec_i64(json.as_bytes()).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                ParseVecError::UnexpectedElement { pointer: p, expected: JsonEventKind::Integer, found: f }
+                    if p == pointer && *f == found
+            ),
+            "{json}: {err}"
+        );
+    }
+
+    let err = parse_vec_string(r#"["a", true]"#.as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "expected String at `/1' but found Boolean");
+}
+
+/// Test that invalid input is rejected
+#[test]
+fn parse_vec_invalid() {
+    assert!(matches!(
+        parse_vec_i64(r#"{"a": 1}"#.as_bytes()),
+        Err(ParseVecError::NotAnArray)
+    ));
+    assert!(matches!(
+        parse_vec_string(r#""a""#.as_bytes()),
+        Err(ParseVecError::NotAnArray)
+    ));
+    assert!(matches!(
+        parse_vec_i64("[9223372036854775808]".as_bytes()),
+        Err(ParseVecError::InvalidIntValue(_))
+    ));
+    assert!(matches!(
+        parse_vec_i64("[1, 2".as_bytes()),
+        Err(ParseVecError::Parse(ParserError::NoMoreInput))
+    ));
+}
+
+/// Test that all values of a nested JSON text can be visited
+#[test]
+fn cursor_navigate() {
+    let json = br#"[1, {"a": [], "b": {}, "c": [true, null]}, "x", 2.5]"#;
+    let mut cursor = JsonCursor::new(json).unwrap();
+    assert_eq!(cursor.kind(), Some(JsonEventKind::Array));
+    assert_eq!(cursor.key(), None);
+    assert_eq!(cursor.depth(), 0);
+
+    assert!(cursor.enter().unwrap());
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Number("1")));
+    assert!(matches!(cursor.enter(), Err(CursorError::NotAContainer)));
+
+    assert!(cursor.next_sibling().unwrap());
+    assert!(cursor.enter().unwrap());
+    assert_eq!(cursor.depth(), 2);
+    assert_eq!(cursor.key(), Some("a"));
+
+    // empty array
+    assert!(!cursor.enter().unwrap());
+    assert_eq!(cursor.value().unwrap(), None);
+    assert_eq!(cursor.key(), None);
+    cursor.exit().unwrap();
+    assert_eq!(cursor.key(), Some("a"));
+    assert!(matches!(cursor.enter(), Err(CursorError::ContainerLeft)));
+
+    // skip empty object
+    assert!(cursor.next_sibling().unwrap());
+    assert_eq!(cursor.key(), Some("b"));
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Object));
+
+    assert!(cursor.next_sibling().unwrap());
+    assert_eq!(cursor.key(), Some("c"));
+    assert!(cursor.enter().unwrap());
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Bool(true)));
+    assert!(cursor.next_sibling().unwrap());
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Null));
+    assert!(!cursor.next_sibling().unwrap());
+    assert!(!cursor.next_sibling().unwrap());
+    cursor.exit().unwrap();
+    assert!(!cursor.next_sibling().unwrap());
+    cursor.exit().unwrap();
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Object));
+    assert_eq!(cursor.depth(), 1);
+
+    assert!(cursor.next_sibling().unwrap());
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::String("x")));
+    assert!(cursor.next_sibling().unwrap());
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Number("2.5")));
+    cursor.exit().unwrap();
+    assert_eq!(cursor.depth(), 0);
+    assert!(matches!(cursor.exit(), Err(CursorError::NotInContainer)));
+    assert!(!cursor.next_sibling().unwrap());
+    assert_eq!(cursor.value().unwrap(), None);
+}
+
+/// Test that the cursor can be reset to navigate backwards
+#[test]
+fn cursor_reset() {
+    let json = br#"{"a": {"b": 1}, "c": 2}"#;
+    let mut cursor = JsonCursor::new(json).unwrap();
+    cursor.enter().unwrap();
+    cursor.next_sibling().unwrap();
+    assert_eq!(cursor.key(), Some("c"));
+
+    cursor.reset().unwrap();
+    assert_eq!(cursor.depth(), 0);
+    cursor.enter().unwrap();
+    cursor.enter().unwrap();
+    assert_eq!(cursor.key(), Some("b"));
+    assert_eq!(cursor.value().unwrap(), Some(CursorValue::Number("1")));
+}
+
+/// Test that invalid JSON texts are reported, even in skipped values
+#[test]
+fn cursor_invalid() {
+    assert!(matches!(
+        JsonCursor::new(b""),
+        Err(CursorError::Parse(ParserError::EmptyInput))
+    ));
+
+    let mut cursor = JsonCursor::new(br#"[{"a": [1 2]}, 3]"#).unwrap();
+    cursor.enter().unwrap();
+    assert!(matches!(
+        cursor.next_sibling(),
+        Err(CursorError::Parse(ParserError::SyntaxError))
+    ));
+
+    let mut cursor = JsonCursor::new(b"[1] 2").unwrap();
+    assert!(matches!(
+        cursor.next_sibling(),
+        Err(CursorError::Parse(ParserError::TrailingData(4)))
+    ));
+}
+
+/// Flatten the given JSON text with the given separator
+fn flattened_pairs(json: &str, separator: char) -> Vec<(String, OwnedScalar)> {
+    flatten(json.as_bytes(), separator)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Test that nested objects and arrays are flattened
+#[test]
+fn flatten_nested() {
+    let json = r#"{"a": [{"b": null}, [1.5, "x"], {}, []], "c": {"": false}, "d/e": -1}"#;
+    assert_eq!(
+        flattened_pairs(json, '/'),
+        vec![
+            ("a/0/b".to_string(), OwnedScalar::Null),
+            ("a/1/0".to_string(), OwnedScalar::Number("1.5".to_string())),
+            ("a/1/1".to_string(), OwnedScalar::String("x".to_string())),
+            ("c/".to_string(), OwnedScalar::Bool(false)),
+            ("d/e".to_string(), OwnedScalar::Number("-1".to_string())),
+        ]
+    );
+}
+
+/// Test that top-level scalars have an empty path and that empty
+/// containers do not produce pairs
+#[test]
+fn flatten_top_level() {
+    assert_eq!(
+        flattened_pairs(r#""x""#, '.'),
+        vec![(String::new(), OwnedScalar::String("x".to_string()))]
+    );
+    assert!(flattened_pairs("{}", '.').is_empty());
+    assert!(flattened_pairs("[[], {}]", '.').is_empty());
+}
+
+/// Test that the iterator ends after the first error
+#[test]
+fn flatten_invalid() {
+    let r = flatten(r#"{"a": 1, "b": }"#.as_bytes(), '.').collect::<Vec<_>>();
+    assert_eq!(r.len(), 2);
+    assert!(matches!(
+        r[1],
+        Err(FlattenError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Compute the canonical hash of the given JSON text
+fn canonical_hash_of(json: &str) -> u64 {
+    canonical_hash(json.as_bytes(), DefaultHasher::new()).unwrap()
+}
+
+/// Test that texts that only differ in their formatting have the same
+/// hash and that other texts have different hashes
+#[test]
+fn canonical_hash_equal() {
+    let a = canonical_hash_of(r#"{"b": {"d": [1.0, 2e0], "c": null}, "a": "A"}"#);
+    let b = canonical_hash_of(r#"{"a":"A","b":{"c":null,"d":[1,2]}}"#);
+    let c = canonical_hash_of(r#"{"a":"A","b":{"c":null,"d":[2,1]}}"#);
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(canonical_hash_of(" 1e2 "), canonical_hash_of("100"));
+    assert_ne!(canonical_hash_of("[]"), canonical_hash_of("{}"));
+}
+
+/// Test that invalid input is rejected
+#[test]
+fn canonical_hash_invalid() {
+    assert!(matches!(
+        canonical_hash(r#"{"a": }"#.as_bytes(), DefaultHasher::new()),
+        Err(CanonicalHashError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Create a [`RecordedEvent`] to compare against
+fn recorded(event: JsonEvent, value: Option<&str>, depth: usize) -> RecordedEvent {
+    RecordedEvent {
+        event,
+        value: value.map(String::from),
+        depth,
+    }
+}
+
+/// Test that events are recorded with their values and depths
+#[test]
+fn trace_record() {
+    let json = br#"{"a": [1, {"b": "c"}, []], "d": null}"#;
+    let trace = EventTrace::from_slice(json).unwrap();
+    assert_eq!(
+        trace.into_events(),
+        vec![
+            recorded(JsonEvent::StartObject, None, 0),
+            recorded(JsonEvent::FieldName, Some("a"), 1),
+            recorded(JsonEvent::StartArray, None, 1),
+            recorded(JsonEvent::ValueInt, Some("1"), 2),
+            recorded(JsonEvent::StartObject, None, 2),
+            recorded(JsonEvent::FieldName, Some("b"), 3),
+            recorded(JsonEvent::ValueString, Some("c"), 3),
+            recorded(JsonEvent::EndObject, None, 2),
+            recorded(JsonEvent::StartArray, None, 2),
+            recorded(JsonEvent::EndArray, None, 2),
+            recorded(JsonEvent::EndArray, None, 1),
+            recorded(JsonEvent::FieldName, Some("d"), 1),
+            recorded(JsonEvent::ValueNull, None, 1),
+            recorded(JsonEvent::EndObject, None, 0),
+        ]
+    );
+
+    assert!(matches!(
+        EventTrace::from_slice(b"[1}"),
+        Err(RecordError::Parse(ParserError::SyntaxError))
+    ));
+}
+
+/// Test that `NeedMoreInput` is not recorded
+#[test]
+fn trace_need_more_input() {
+    let json = b"[true, 2e3]";
+    let mut parser = JsonParser::new(PushJsonFeeder::new());
+    let mut trace = EventTrace::new();
+    let mut i = 0;
+    while let Some(event) = parser.next_event().unwrap() {
+        if event == JsonEvent::NeedMoreInput {
+            if i < json.len() {
+                i += parser.feeder.push_bytes(&json[i..i + 1]);
+            } else {
+                parser.feeder.done();
+            }
+        }
+        trace.record(event, &parser).unwrap();
+    }
+    assert_eq!(trace, EventTrace::from_slice(json).unwrap());
+    assert_eq!(trace.events().len(), 4);
+}
+
+/// Test that a trace can be serialized and deserialized
+#[cfg(feature = "serde")]
+#[test]
+fn trace_serde() {
+    let trace = EventTrace::from_slice(br#"{"a": 1}"#).unwrap();
+    let json = serde_json::to_string(&trace).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"[{"event":"StartObject","value":null,"depth":0},"#,
+            r#"{"event":"FieldName","value":"a","depth":1},"#,
+            r#"{"event":"ValueInt","value":"1","depth":1},"#,
+            r#"{"event":"EndObject","value":null,"depth":0}]"#
+        )
+    );
+    assert_eq!(serde_json::from_str::<EventTrace>(&json).unwrap(), trace);
+}
+
+/// A record filled by [`item_mapper()`]
+#[derive(Debug, Default, PartialEq)]
+struct MappedItem {
+    id: i64,
+    tags: Vec<String>,
+    price: Option<f64>,
+}
+
+/// Create a [`RecordMapper`] that fills [`MappedItem`]s
+fn item_mapper() -> RecordMapper<MappedItem> {
+    RecordMapper::new()
+        .with_field("/id", |r: &mut MappedItem, v| {
+            if let Scalar::Int(i) = v {
+                r.id = i;
+            }
+        })
+        .unwrap()
+        .with_field("/tags/0", |r: &mut MappedItem, v| {
+            if let Scalar::Str(s) = v {
+                r.tags.push(s.to_string());
+            }
+        })
+        .unwrap()
+        .with_field("/tags/1", |r: &mut MappedItem, v| {
+            if let Scalar::Str(s) = v {
+                r.tags.push(s.to_string());
+            }
+        })
+        .unwrap()
+        .with_field("/price", |r: &mut MappedItem, v| match v {
+            Scalar::Int(i) => r.price = Some(i as f64),
+            Scalar::Float(f) => r.price = Some(f),
+            _ => r.price = None,
+        })
+        .unwrap()
+}
+
+/// Map the given JSON text to [`MappedItem`]s
+fn mapped_items(json: &str) -> Vec<MappedItem> {
+    item_mapper()
+        .map(json.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Test that the elements of a top-level array are mapped
+#[test]
+fn mapper_array() {
+    let json = r#"[
+        {"id": 1, "tags": ["a", "b", "c"], "price": 1.5, "extra": {"id": 99}},
+        {"price": null, "id": 2},
+        {},
+        [1, 2],
+        "x"
+    ]"#;
+    assert_eq!(
+        mapped_items(json),
+        vec![
+            MappedItem {
+                id: 1,
+                tags: vec!["a".to_string(), "b".to_string()],
+                price: Some(1.5),
+            },
+            MappedItem {
+                id: 2,
+                ..Default::default()
+            },
+            MappedItem::default(),
+            MappedItem::default(),
+            MappedItem::default(),
+        ]
+    );
+    assert!(mapped_items("[]").is_empty());
+}
+
+/// Test that a stream of top-level values is mapped
+#[test]
+fn mapper_stream() {
+    let json = "{\"id\": 1}\n{\"id\": 2, \"price\": 3}\n[{\"id\": 3}]\n";
+    assert_eq!(
+        mapped_items(json),
+        vec![
+            MappedItem {
+                id: 1,
+                ..Default::default()
+            },
+            MappedItem {
+                id: 2,
+                price: Some(3.0),
+                ..Default::default()
+            },
+            MappedItem {
+                id: 3,
+                ..Default::default()
+            },
+        ]
+    );
+}
+
+/// Test that a pointer referring to the whole record is supported
+#[test]
+fn mapper_whole_record() {
+    let mut mapper = RecordMapper::new()
+        .with_field("", |r: &mut String, v| {
+            if let Scalar::Str(s) = v {
+                *r = s.to_string();
+            }
+        })
+        .unwrap();
+    let r = mapper
+        .map(r#"["a", {"b": "c"}, "d"]"#.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(r, vec!["a", "", "d"]);
+}
+
+/// Test that errors are reported
+#[test]
+fn mapper_invalid() {
+    assert!(matches!(
+        RecordMapper::new().with_field("id", |_: &mut MappedItem, _| {}),
+        Err(MapperError::InvalidPointer(p)) if p == "id"
+    ));
+
+    let mut mapper = item_mapper();
+    let r = mapper
+        .map(r#"[{"id": 1}, {"id": }]"#.as_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(r.len(), 2);
+    assert_eq!(r[0].as_ref().unwrap().id, 1);
+    assert!(matches!(
+        r[1],
+        Err(MapperError::Parse(ParserError::SyntaxError))
+    ));
+}
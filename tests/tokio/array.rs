@@ -0,0 +1,148 @@
+use std::future::poll_fn;
+use std::pin::{pin, Pin};
+
+use futures_core::Stream;
+use serde_json::Value;
+use tokio::io::{AsyncWriteExt, BufReader};
+
+use actson::options::JsonParserOptionsBuilder;
+use actson::parser::ParserError;
+use actson::serde_json::{IntoSerdeValueError, ValueOptions};
+use actson::tokio::{array_elements, ArrayElements, AsyncBufReaderJsonFeeder};
+use actson::JsonParser;
+
+/// Collect all items of the given stream
+async fn collect<S: Stream>(mut stream: Pin<&mut S>) -> Vec<S::Item> {
+    let mut r = Vec::new();
+    while let Some(item) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        r.push(item);
+    }
+    r
+}
+
+/// Test that the elements of a top-level array can be deserialized
+#[tokio::test]
+async fn elements() {
+    let json = br#"[1, 2, 3, -4]"#;
+    let elements = pin!(array_elements::<i64, _>(&json[..]));
+    let elements = collect(elements).await;
+    assert_eq!(
+        elements.into_iter().map(|e| e.unwrap()).collect::<Vec<_>>(),
+        vec![1, 2, 3, -4]
+    );
+
+    let json = br#"[{"a": [1, {}]}, [], "x", null]"#;
+    let elements = pin!(array_elements::<Value, _>(&json[..]));
+    let elements = collect(elements).await;
+    assert_eq!(elements.len(), 4);
+    assert_eq!(elements[0].as_ref().unwrap()["a"][0], 1);
+
+    let json = b"[]";
+    let elements = pin!(array_elements::<Value, _>(&json[..]));
+    assert!(collect(elements).await.is_empty());
+}
+
+/// Test that the stream either ends or continues after an invalid element
+#[tokio::test]
+async fn invalid_elements() {
+    let json = br#"[1, "x", [2, [3]], {"a": 1e400}, 5]"#;
+
+    let elements = pin!(array_elements::<i64, _>(&json[..]));
+    let elements = collect(elements).await;
+    assert_eq!(elements.len(), 2);
+    assert!(matches!(elements[0], Ok(1)));
+    assert!(matches!(
+        elements[1],
+        Err(IntoSerdeValueError::Deserialize(_))
+    ));
+
+    let elements = pin!(array_elements::<i64, _>(&json[..]).with_skip_invalid(true));
+    let elements = collect(elements).await;
+    assert_eq!(elements.len(), 5);
+    assert!(matches!(elements[0], Ok(1)));
+    assert!(matches!(
+        elements[1],
+        Err(IntoSerdeValueError::Deserialize(_))
+    ));
+    assert!(matches!(
+        elements[2],
+        Err(IntoSerdeValueError::Deserialize(_))
+    ));
+    assert!(matches!(
+        elements[3],
+        Err(IntoSerdeValueError::NumberOutOfRange(_))
+    ));
+    assert!(matches!(elements[4], Ok(5)));
+}
+
+/// Test that elements exceeding a clamped depth limit can be deserialized
+#[tokio::test]
+async fn clamped_elements() {
+    let json = br#"[{"a": [1, 2]}, [3, [4]]]"#;
+    let options = JsonParserOptionsBuilder::default()
+        .with_max_depth(3)
+        .with_clamp_depth(true)
+        .build();
+    let feeder = AsyncBufReaderJsonFeeder::new(BufReader::new(&json[..]));
+    let parser = JsonParser::new_with_options(feeder, options);
+    let elements = pin!(ArrayElements::<Value, _>::new(
+        parser,
+        ValueOptions::default()
+    ));
+    let elements = collect(elements).await;
+    assert_eq!(
+        elements.into_iter().map(|e| e.unwrap()).collect::<Vec<_>>(),
+        vec![serde_json::json!({"a": null}), serde_json::json!([3, 4]),]
+    );
+}
+
+/// Test that syntax errors end the stream
+#[tokio::test]
+async fn syntax_errors() {
+    let json = br#"{"a": 1}"#;
+    let elements = pin!(array_elements::<Value, _>(&json[..]).with_skip_invalid(true));
+    let elements = collect(elements).await;
+    assert!(matches!(
+        elements[..],
+        [Err(IntoSerdeValueError::Parse(
+            ParserError::UnexpectedEvent { .. }
+        ))]
+    ));
+
+    let json = br#"[1, 2,, 3]"#;
+    let elements = pin!(array_elements::<i64, _>(&json[..]).with_skip_invalid(true));
+    let elements = collect(elements).await;
+    assert_eq!(elements.len(), 3);
+    assert!(matches!(
+        elements[2],
+        Err(IntoSerdeValueError::Parse(ParserError::SyntaxError))
+    ));
+
+    let json = br#"[1] 2"#;
+    let elements = pin!(array_elements::<i64, _>(&json[..]));
+    let elements = collect(elements).await;
+    assert_eq!(elements.len(), 2);
+    assert!(matches!(
+        elements[1],
+        Err(IntoSerdeValueError::Parse(ParserError::TrailingData(4)))
+    ));
+}
+
+/// Test that elements are yielded as soon as they have been read
+#[tokio::test]
+async fn incremental() {
+    let (mut writer, reader) = tokio::io::duplex(64);
+    let mut elements = pin!(array_elements::<Value, _>(reader));
+
+    writer.write_all(br#"[1, {"a": 2}, "#).await.unwrap();
+    let e = poll_fn(|cx| elements.as_mut().poll_next(cx)).await;
+    assert_eq!(e.unwrap().unwrap(), 1);
+    let e = poll_fn(|cx| elements.as_mut().poll_next(cx)).await;
+    assert_eq!(e.unwrap().unwrap()["a"], 2);
+
+    writer.write_all(b"true]").await.unwrap();
+    drop(writer);
+    let rest = collect(elements).await;
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].as_ref().unwrap(), &Value::Bool(true));
+}
@@ -3,7 +3,8 @@ use tokio::fs::File;
 use tokio::io::{AsyncReadExt, BufReader};
 
 use crate::prettyprinter::PrettyPrinter;
-use actson::feeder::JsonFeeder;
+use actson::feeder::{FeederError, JsonFeeder};
+use actson::parser::Scalar;
 use actson::tokio::AsyncBufReaderJsonFeeder;
 use actson::{JsonEvent, JsonParser};
 
@@ -79,3 +80,124 @@ async fn parse_from_file() {
     let am: Value = serde_json::from_str(actual).unwrap();
     assert_eq!(em, am);
 }
+
+/// Test that a parser can be created from a file path
+#[tokio::test]
+async fn from_path() {
+    let mut parser = JsonParser::from_path_async("tests/fixtures/pass1.txt")
+        .await
+        .unwrap();
+    let mut prettyprinter = PrettyPrinter::new();
+    while let Some(e) = parser.next_event().unwrap() {
+        if e == JsonEvent::NeedMoreInput {
+            parser.feeder.fill_buf().await.unwrap();
+        }
+        prettyprinter.on_event(e, &parser).unwrap();
+    }
+    let expected: Value =
+        serde_json::from_str(&std::fs::read_to_string("tests/fixtures/pass1.txt").unwrap())
+            .unwrap();
+    let actual: Value = serde_json::from_str(prettyprinter.get_result()).unwrap();
+    assert_eq!(expected, actual);
+
+    assert!(
+        JsonParser::from_path_async("tests/fixtures/does-not-exist.txt")
+            .await
+            .is_err()
+    );
+}
+
+/// Test that [`AsyncBufReaderJsonFeeder`] refuses to read more than the
+/// configured maximum number of bytes
+#[tokio::test]
+async fn max_bytes() {
+    let len = tokio::fs::metadata("tests/fixtures/pass1.txt")
+        .await
+        .unwrap()
+        .len() as usize;
+
+    let file = File::open("tests/fixtures/pass1.txt").await.unwrap();
+    let reader = BufReader::with_capacity(32, file);
+    let feeder = AsyncBufReaderJsonFeeder::new_with_max_bytes(reader, len - 1);
+    let mut parser = JsonParser::new(feeder);
+    loop {
+        let e = parser.next_event().unwrap();
+        if e == Some(JsonEvent::NeedMoreInput) {
+            if let Err(err) = parser.feeder.fill_buf().await {
+                assert!(matches!(err, FeederError::InputTooLarge(n) if n == len - 1));
+                break;
+            }
+        }
+        assert!(e.is_some(), "input should have been too large");
+    }
+}
+
+/// Test that [`AsyncBufReaderJsonFeeder::into_remainder()`] returns the bytes
+/// that have not been consumed by the parser
+#[tokio::test]
+async fn into_remainder() {
+    let input = br#"{"a": [1, 2]}NEXT FRAME"#;
+    let reader = BufReader::with_capacity(8, &input[..]);
+    let feeder = AsyncBufReaderJsonFeeder::new(reader);
+    let mut parser = JsonParser::new(feeder);
+    loop {
+        match parser.next_event().unwrap() {
+            Some(JsonEvent::NeedMoreInput) => parser.feeder.fill_buf().await.unwrap(),
+            Some(JsonEvent::EndObject) => break,
+            _ => {}
+        }
+    }
+
+    let (mut reader, mut rest) = parser.feeder.into_remainder();
+    reader.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, b"NEXT FRAME");
+}
+
+/// Test that [`JsonParser::fold_all_numbers()`] reads the whole input
+#[tokio::test]
+async fn fold_all_numbers() {
+    let json = br#"{"a": [1, 2, {"b": 3.5}], "c": "4", "d": -0.5, "e": 9007199254740993}"#;
+    let reader = BufReader::with_capacity(4, &json[..]);
+    let mut parser = JsonParser::new(AsyncBufReaderJsonFeeder::new(reader));
+    let (ints, sum) = parser
+        .fold_all_numbers((0, 0.0), |(ints, sum), n| match n {
+            Scalar::Int(i) => (ints + i, sum),
+            Scalar::Float(f) => (ints, sum + f),
+            _ => (ints, sum),
+        })
+        .await
+        .unwrap();
+    assert_eq!(ints, 9007199254740996);
+    assert_eq!(sum, 3.0);
+}
+
+/// Test that [`JsonParser::next_event_async()`] never returns
+/// [`JsonEvent::NeedMoreInput`]
+#[tokio::test]
+async fn next_event_async() {
+    let json = br#"{"a": [1, 2, {"b": 3.5}], "c": "4"}"#;
+    let reader = BufReader::with_capacity(3, &json[..]);
+    let mut parser = JsonParser::new(AsyncBufReaderJsonFeeder::new(reader));
+    let mut count = 0;
+    while let Some(event) = parser.next_event_async().await.unwrap() {
+        assert_ne!(event, JsonEvent::NeedMoreInput);
+        count += 1;
+    }
+    assert_eq!(count, 13);
+}
+
+/// Test that the parser reports its progress if the total length of the
+/// input is known
+#[tokio::test]
+async fn progress() {
+    let mut parser = JsonParser::from_path_async("tests/fixtures/pass1.txt")
+        .await
+        .unwrap();
+    assert_eq!(parser.progress(), Some(0.0));
+    while parser.next_event_async().await.unwrap().is_some() {}
+    assert_eq!(parser.progress(), Some(1.0));
+
+    let reader = BufReader::new(&b"[1]"[..]);
+    let parser = JsonParser::new(AsyncBufReaderJsonFeeder::new(reader));
+    assert_eq!(parser.progress(), None);
+}
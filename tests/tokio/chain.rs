@@ -0,0 +1,107 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+use actson::feeder::{FeederError, JsonFeeder};
+use actson::tokio::AsyncChainJsonFeeder;
+use actson::{JsonEvent, JsonParser};
+
+/// A reader that always fails
+struct FailingReader;
+
+impl AsyncRead for FailingReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::Error::other("broken")))
+    }
+}
+
+/// Parse everything from the given feeder and return the field names and
+/// string values
+async fn parse_strings<T>(feeder: AsyncChainJsonFeeder<T>) -> Result<Vec<String>, FeederError>
+where
+    T: AsyncRead + Unpin,
+{
+    let mut parser = JsonParser::new(feeder);
+    let mut strings = vec![];
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => parser.feeder.fill_buf().await?,
+            JsonEvent::FieldName | JsonEvent::ValueString => {
+                strings.push(parser.current_str().unwrap().to_string())
+            }
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// Test that tokens can straddle the boundaries between readers and that
+/// empty readers are skipped
+#[tokio::test]
+async fn straddle() {
+    let parts: Vec<&[u8]> = vec![
+        b"",
+        br#"{"na"#,
+        br#"me": "#,
+        b"",
+        b"",
+        br#""El"#,
+        br#"vis"}"#,
+    ];
+    let feeder = AsyncChainJsonFeeder::new(parts);
+    assert_eq!(parse_strings(feeder).await.unwrap(), vec!["name", "Elvis"]);
+
+    // a number at the end of one reader is continued in the next one
+    let parts: [&[u8]; 3] = [b"[12", b"34", b"]"];
+    let mut parser = JsonParser::new(AsyncChainJsonFeeder::new(parts));
+    let mut numbers = vec![];
+    while let Some(e) = parser.next_event().unwrap() {
+        match e {
+            JsonEvent::NeedMoreInput => parser.feeder.fill_buf().await.unwrap(),
+            JsonEvent::ValueInt => numbers.push(parser.current_int::<i32>().unwrap()),
+            _ => {}
+        }
+    }
+    assert_eq!(numbers, vec![1234]);
+    assert_eq!(parser.feeder.current_index(), 2);
+}
+
+/// Test that the feeder is only done after the last reader is exhausted
+#[tokio::test]
+async fn done() {
+    let parts: [&[u8]; 2] = [b"1", b""];
+    let mut feeder = AsyncChainJsonFeeder::new(parts);
+    assert!(!feeder.is_done());
+    feeder.fill_buf().await.unwrap();
+    assert_eq!(feeder.next_input(), Some(b'1'));
+    assert!(!feeder.is_done());
+    feeder.fill_buf().await.unwrap();
+    assert!(!feeder.has_input());
+    assert!(feeder.is_done());
+
+    let feeder = AsyncChainJsonFeeder::<&[u8]>::new([]);
+    assert!(feeder.is_done());
+}
+
+/// Test that I/O errors report the index of the reader that failed
+#[tokio::test]
+async fn reader_error() {
+    let readers: Vec<Box<dyn AsyncRead + Unpin>> = vec![
+        Box::new(&br#"{"a": "#[..]),
+        Box::new(FailingReader),
+        Box::new(&b"1}"[..]),
+    ];
+    let err = parse_strings(AsyncChainJsonFeeder::new(readers))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        FeederError::Reader { index: 1, ref source } if source.to_string() == "broken"
+    ));
+}
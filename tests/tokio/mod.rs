@@ -1 +1,4 @@
+#[cfg(feature = "serde_json")]
+mod array;
 mod asyncbufreader;
+mod chain;